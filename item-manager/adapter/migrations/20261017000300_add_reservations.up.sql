@@ -0,0 +1,23 @@
+CREATE TABLE IF NOT EXISTS reservations (
+  reservation_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  item_id UUID NOT NULL,
+  user_id UUID NOT NULL,
+  reserved_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  ready_at TIMESTAMP(3) WITH TIME ZONE,
+  expires_at TIMESTAMP(3) WITH TIME ZONE,
+
+  UNIQUE (item_id, user_id),
+
+  FOREIGN KEY (item_id) REFERENCES items(item_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE,
+
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS reservations_item_id_reserved_at_idx
+  ON reservations(item_id, reserved_at);
+
+CREATE INDEX IF NOT EXISTS reservations_user_id_idx ON reservations(user_id);
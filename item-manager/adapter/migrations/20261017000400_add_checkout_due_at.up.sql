@@ -0,0 +1,24 @@
+ALTER TABLE checkouts ADD COLUMN IF NOT EXISTS due_at TIMESTAMP(3) WITH TIME ZONE;
+ALTER TABLE returned_checkouts ADD COLUMN IF NOT EXISTS due_at TIMESTAMP(3) WITH TIME ZONE;
+
+-- Backfill existing loans with the default loan periods
+UPDATE checkouts AS c
+SET due_at = c.checked_out_at + CASE i.category
+    WHEN 'laptop' THEN INTERVAL '7 days'
+    ELSE INTERVAL '14 days'
+  END
+FROM items AS i
+WHERE i.item_id = c.item_id;
+
+UPDATE returned_checkouts AS rc
+SET due_at = rc.checked_out_at + CASE i.category
+    WHEN 'laptop' THEN INTERVAL '7 days'
+    ELSE INTERVAL '14 days'
+  END
+FROM items AS i
+WHERE i.item_id = rc.item_id;
+
+ALTER TABLE checkouts ALTER COLUMN due_at SET NOT NULL;
+ALTER TABLE returned_checkouts ALTER COLUMN due_at SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS checkouts_due_at_idx ON checkouts(due_at);
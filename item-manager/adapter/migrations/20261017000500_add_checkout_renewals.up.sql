@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS checkout_renewals (
+  renewal_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  -- Refers to either an active or a returned checkout, as the row moves on return
+  checkout_id UUID NOT NULL,
+  item_id UUID NOT NULL,
+  user_id UUID NOT NULL,
+  renewed_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  previous_due_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+  due_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+
+  FOREIGN KEY (item_id) REFERENCES items(item_id)
+    ON UPDATE CASCADE
+    ON DELETE RESTRICT,
+
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE RESTRICT
+);
+
+CREATE INDEX IF NOT EXISTS checkout_renewals_checkout_id_idx ON checkout_renewals(checkout_id);
@@ -0,0 +1,12 @@
+DROP INDEX IF EXISTS laptops_mac_address_idx;
+DROP INDEX IF EXISTS books_isbn_idx;
+DROP INDEX IF EXISTS books_author_idx;
+DROP INDEX IF EXISTS items_location_idx;
+
+DROP INDEX IF EXISTS books_isbn_trgm_idx;
+DROP INDEX IF EXISTS books_author_trgm_idx;
+DROP INDEX IF EXISTS items_location_trgm_idx;
+DROP INDEX IF EXISTS items_description_trgm_idx;
+DROP INDEX IF EXISTS items_name_trgm_idx;
+
+DROP INDEX IF EXISTS items_search_idx;
@@ -0,0 +1,16 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS items_search_idx ON items USING GIN (
+  to_tsvector('simple'::regconfig, name || ' ' || description || ' ' || COALESCE(location, ''))
+);
+
+CREATE INDEX IF NOT EXISTS items_name_trgm_idx ON items USING GIN (name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS items_description_trgm_idx ON items USING GIN (description gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS items_location_trgm_idx ON items USING GIN (location gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS books_author_trgm_idx ON books USING GIN (author gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS books_isbn_trgm_idx ON books USING GIN (isbn gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS items_location_idx ON items(location);
+CREATE INDEX IF NOT EXISTS books_author_idx ON books(author);
+CREATE INDEX IF NOT EXISTS books_isbn_idx ON books(isbn);
+CREATE INDEX IF NOT EXISTS laptops_mac_address_idx ON laptops(mac_address);
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS audit_logs (
+  audit_log_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  -- Not a foreign key, so entries outlive the users they mention
+  actor_id UUID,
+  action VARCHAR(255) NOT NULL,
+  target_type VARCHAR(255) NOT NULL CHECK (target_type IN ('item', 'user', 'checkout')),
+  target_id UUID NOT NULL,
+  before JSONB,
+  after JSONB,
+  created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3)
+);
+
+CREATE INDEX IF NOT EXISTS audit_logs_created_at_idx ON audit_logs(created_at);
+CREATE INDEX IF NOT EXISTS audit_logs_actor_id_idx ON audit_logs(actor_id);
+CREATE INDEX IF NOT EXISTS audit_logs_target_idx ON audit_logs(target_type, target_id);
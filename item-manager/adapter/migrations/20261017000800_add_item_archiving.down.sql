@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS items_active_created_at_idx;
+ALTER TABLE items DROP COLUMN IF EXISTS archive_reason;
+ALTER TABLE items DROP COLUMN IF EXISTS archived_at;
@@ -0,0 +1,7 @@
+ALTER TABLE items ADD COLUMN IF NOT EXISTS archived_at TIMESTAMP(3) WITH TIME ZONE;
+ALTER TABLE items ADD COLUMN IF NOT EXISTS archive_reason VARCHAR(1024);
+
+-- Listings only ever scan the items that are still in circulation
+CREATE INDEX IF NOT EXISTS items_active_created_at_idx
+  ON items(created_at)
+  WHERE archived_at IS NULL;
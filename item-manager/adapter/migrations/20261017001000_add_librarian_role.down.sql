@@ -0,0 +1,6 @@
+-- Deleting the role would cascade to its users, so demote them first.
+UPDATE users
+SET role_id = (SELECT role_id FROM roles WHERE name = 'User')
+WHERE role_id = (SELECT role_id FROM roles WHERE name = 'Librarian');
+
+DELETE FROM roles WHERE name = 'Librarian';
@@ -0,0 +1,2 @@
+-- Permissions are granted per role in code; the row only has to exist so users can be assigned it.
+INSERT INTO roles(name) VALUES ('Librarian') ON CONFLICT DO NOTHING;
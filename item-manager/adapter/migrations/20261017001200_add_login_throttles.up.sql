@@ -0,0 +1,7 @@
+-- Failed login attempts per subject, where a subject is `email:<address>` or `ip:<address>`
+CREATE TABLE IF NOT EXISTS login_throttles (
+  subject VARCHAR(512) PRIMARY KEY,
+  failed_attempts INTEGER NOT NULL,
+  last_failed_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+  locked_until TIMESTAMP(3) WITH TIME ZONE
+);
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS invitations (
+  invitation_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  code_hash VARCHAR(255) NOT NULL UNIQUE,
+  role_id UUID NOT NULL,
+  max_uses INTEGER NOT NULL CHECK (max_uses > 0),
+  uses INTEGER NOT NULL DEFAULT 0 CHECK (uses <= max_uses),
+  expires_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+  created_by UUID,
+  created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+
+  FOREIGN KEY (role_id) REFERENCES roles(role_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE,
+  FOREIGN KEY (created_by) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE SET NULL
+);
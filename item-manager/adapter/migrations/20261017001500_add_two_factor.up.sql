@@ -0,0 +1,41 @@
+-- `confirmed_at` stays NULL until the user has proven their authenticator app works
+CREATE TABLE IF NOT EXISTS user_totp_secrets (
+  user_id UUID PRIMARY KEY,
+  secret VARCHAR(255) NOT NULL,
+  confirmed_at TIMESTAMP(3) WITH TIME ZONE,
+  -- The last accepted time step, so a code cannot be replayed within its window
+  last_used_step BIGINT,
+  created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+-- Codes are deleted once used, which keeps them single-use
+CREATE TABLE IF NOT EXISTS totp_recovery_codes (
+  totp_recovery_code_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  user_id UUID NOT NULL,
+  code_hash VARCHAR(255) NOT NULL UNIQUE,
+
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS totp_recovery_codes_user_id_idx ON totp_recovery_codes(user_id);
+
+-- A login whose password was verified but whose second factor is still outstanding
+CREATE TABLE IF NOT EXISTS login_challenges (
+  login_challenge_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  user_id UUID NOT NULL,
+  token_hash VARCHAR(255) NOT NULL UNIQUE,
+  setup_required BOOLEAN NOT NULL,
+  failed_attempts INTEGER NOT NULL DEFAULT 0,
+  expires_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+  created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
@@ -0,0 +1,24 @@
+-- A sign-in sent to the identity provider, kept until its callback arrives. The PKCE verifier
+-- and nonce never leave the server.
+CREATE TABLE IF NOT EXISTS oidc_login_requests (
+  state_hash VARCHAR(255) PRIMARY KEY,
+  nonce VARCHAR(255) NOT NULL,
+  pkce_verifier VARCHAR(255) NOT NULL,
+  expires_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+  created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3)
+);
+
+-- Identities at the provider, by issuer and subject, that sign in as a local user
+CREATE TABLE IF NOT EXISTS user_identities (
+  issuer VARCHAR(255) NOT NULL,
+  subject VARCHAR(255) NOT NULL,
+  user_id UUID NOT NULL,
+  created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+
+  PRIMARY KEY (issuer, subject),
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS user_identities_user_id_idx ON user_identities(user_id);
@@ -0,0 +1,13 @@
+-- Hashes of earlier passwords, so recent ones cannot be chosen again
+CREATE TABLE IF NOT EXISTS password_history (
+  password_history_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  user_id UUID NOT NULL,
+  password_hash VARCHAR(255) NOT NULL,
+  created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS password_history_user_id_idx ON password_history(user_id, created_at);
@@ -0,0 +1,7 @@
+DELETE FROM audit_logs WHERE target_type = 'tag';
+ALTER TABLE audit_logs DROP CONSTRAINT IF EXISTS audit_logs_target_type_check;
+ALTER TABLE audit_logs ADD CONSTRAINT audit_logs_target_type_check
+  CHECK (target_type IN ('item', 'user', 'checkout'));
+
+DROP TABLE IF EXISTS item_tags;
+DROP TABLE IF EXISTS tags;
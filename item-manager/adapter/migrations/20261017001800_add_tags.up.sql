@@ -0,0 +1,33 @@
+CREATE TABLE IF NOT EXISTS tags (
+  tag_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  name VARCHAR(64) NOT NULL,
+  created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3)
+);
+
+-- Names are matched without regard to case, so "JOI prep" and "joi prep" are one tag
+CREATE UNIQUE INDEX IF NOT EXISTS tags_name_key ON tags(LOWER(name));
+
+CREATE TRIGGER tags_updated_at_trigger
+  BEFORE UPDATE ON tags FOR EACH ROW
+  EXECUTE PROCEDURE set_updated_at();
+
+CREATE TABLE IF NOT EXISTS item_tags (
+  item_id UUID NOT NULL,
+  tag_id UUID NOT NULL,
+  created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+
+  PRIMARY KEY (item_id, tag_id),
+  FOREIGN KEY (item_id) REFERENCES items(item_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE,
+  FOREIGN KEY (tag_id) REFERENCES tags(tag_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS item_tags_tag_id_idx ON item_tags(tag_id);
+
+ALTER TABLE audit_logs DROP CONSTRAINT IF EXISTS audit_logs_target_type_check;
+ALTER TABLE audit_logs ADD CONSTRAINT audit_logs_target_type_check
+  CHECK (target_type IN ('item', 'user', 'checkout', 'tag'));
@@ -0,0 +1,18 @@
+DELETE FROM audit_logs WHERE target_type = 'custom_category';
+ALTER TABLE audit_logs DROP CONSTRAINT IF EXISTS audit_logs_target_type_check;
+ALTER TABLE audit_logs ADD CONSTRAINT audit_logs_target_type_check
+  CHECK (target_type IN ('item', 'user', 'checkout', 'tag'));
+
+ALTER TABLE items DROP CONSTRAINT IF EXISTS items_custom_attributes_check;
+ALTER TABLE items DROP CONSTRAINT IF EXISTS items_category_check;
+-- Custom items keep their history as general items
+UPDATE items SET category = 'general' WHERE category = 'custom';
+ALTER TABLE items ADD CONSTRAINT items_category_check
+  CHECK (category IN ('general', 'book', 'laptop'));
+
+DROP INDEX IF EXISTS items_custom_category_id_idx;
+ALTER TABLE items
+  DROP COLUMN IF EXISTS attributes,
+  DROP COLUMN IF EXISTS custom_category_id;
+
+DROP TABLE IF EXISTS custom_categories;
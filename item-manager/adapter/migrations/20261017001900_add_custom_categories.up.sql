@@ -0,0 +1,39 @@
+CREATE TABLE IF NOT EXISTS custom_categories (
+  custom_category_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  key VARCHAR(64) NOT NULL UNIQUE,
+  name VARCHAR(255) NOT NULL,
+  -- Attribute definitions, validated by the application before they are stored
+  attributes JSONB NOT NULL DEFAULT '[]'::jsonb,
+  created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3)
+);
+
+CREATE TRIGGER custom_categories_updated_at_trigger
+  BEFORE UPDATE ON custom_categories FOR EACH ROW
+  EXECUTE PROCEDURE set_updated_at();
+
+ALTER TABLE items
+  ADD COLUMN IF NOT EXISTS custom_category_id UUID,
+  ADD COLUMN IF NOT EXISTS attributes JSONB;
+
+ALTER TABLE items ADD CONSTRAINT items_custom_category_id_fkey
+  FOREIGN KEY (custom_category_id) REFERENCES custom_categories(custom_category_id)
+    ON UPDATE CASCADE
+    ON DELETE RESTRICT;
+
+ALTER TABLE items DROP CONSTRAINT IF EXISTS items_category_check;
+ALTER TABLE items ADD CONSTRAINT items_category_check
+  CHECK (category IN ('general', 'book', 'laptop', 'custom'));
+
+-- Custom items, and only those, carry a category and their attribute values
+ALTER TABLE items ADD CONSTRAINT items_custom_attributes_check
+  CHECK (
+    (category = 'custom') = (custom_category_id IS NOT NULL)
+    AND (category = 'custom') = (attributes IS NOT NULL)
+  );
+
+CREATE INDEX IF NOT EXISTS items_custom_category_id_idx ON items(custom_category_id);
+
+ALTER TABLE audit_logs DROP CONSTRAINT IF EXISTS audit_logs_target_type_check;
+ALTER TABLE audit_logs ADD CONSTRAINT audit_logs_target_type_check
+  CHECK (target_type IN ('item', 'user', 'checkout', 'tag', 'custom_category'));
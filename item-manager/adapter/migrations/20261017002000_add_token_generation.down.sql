@@ -0,0 +1,7 @@
+ALTER TABLE users
+  ADD COLUMN tokens_revoked_before TIMESTAMP(3) WITH TIME ZONE;
+
+UPDATE users SET tokens_revoked_before = CURRENT_TIMESTAMP(3) WHERE token_generation > 0;
+
+ALTER TABLE users
+  DROP COLUMN token_generation;
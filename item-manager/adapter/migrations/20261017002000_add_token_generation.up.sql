@@ -0,0 +1,11 @@
+-- Access tokens carry the generation they were issued under, which replaces
+-- comparing their whole-second issue time against a revocation timestamp.
+ALTER TABLE users
+  ADD COLUMN token_generation INTEGER NOT NULL DEFAULT 0;
+
+-- Tokens issued before this migration have generation 0, so users who already
+-- revoked their tokens start at 1 and have to sign in again.
+UPDATE users SET token_generation = 1 WHERE tokens_revoked_before IS NOT NULL;
+
+ALTER TABLE users
+  DROP COLUMN tokens_revoked_before;
@@ -0,0 +1,49 @@
+use std::str::FromStr;
+
+use kernel::model::{
+    audit::{AuditAction, AuditLog, AuditTargetType},
+    id::{AuditLogId, UserId},
+};
+use shared::error::AppError;
+use sqlx::types::chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+pub struct AuditLogRow {
+    pub audit_log_id: AuditLogId,
+    pub actor_id: Option<UserId>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<AuditLogRow> for AuditLog {
+    type Error = AppError;
+
+    fn try_from(value: AuditLogRow) -> Result<Self, Self::Error> {
+        let AuditLogRow {
+            audit_log_id,
+            actor_id,
+            action,
+            target_type,
+            target_id,
+            before,
+            after,
+            created_at,
+        } = value;
+        Ok(AuditLog {
+            id: audit_log_id,
+            actor_id,
+            action: AuditAction::from_str(&action)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            target_type: AuditTargetType::from_str(&target_type)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            target_id,
+            before,
+            after,
+            created_at,
+        })
+    }
+}
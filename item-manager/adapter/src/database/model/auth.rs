@@ -1,14 +1,19 @@
-use chrono::{Duration, Utc};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use shared::error::{AppError, AppResult};
 use std::str::FromStr;
+use uuid::Uuid;
 
 use kernel::model::{auth::AccessToken, id::UserId};
 
 pub struct UserItem {
     pub user_id: UserId,
     pub password_hash: String,
+    pub deactivated: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -16,6 +21,48 @@ pub struct Claims {
     pub sub: String, // subject (user_id)
     pub exp: i64,    // expiration time
     pub iat: i64,    // issued at
+    pub jti: String, // token id, used for revocation
+    /// The user's token generation at issue time; revoking every token of the
+    /// user moves the generation on.
+    #[serde(rename = "gen", default)]
+    pub generation: i32,
+}
+
+impl Claims {
+    pub fn user_id(&self) -> AppResult<UserId> {
+        UserId::from_str(&self.sub).map_err(|e| AppError::ConversionEntityError(e.to_string()))
+    }
+
+    pub fn token_id(&self) -> AppResult<Uuid> {
+        Uuid::parse_str(&self.jti).map_err(|e| AppError::ConversionEntityError(e.to_string()))
+    }
+}
+
+pub struct TokenStateRow {
+    pub revoked: bool,
+    pub deactivated: bool,
+    pub token_generation: i32,
+}
+
+pub struct RefreshTokenRow {
+    pub refresh_token_id: Uuid,
+    pub family_id: Uuid,
+    pub user_id: UserId,
+    pub expires_at: DateTime<Utc>,
+    pub rotated_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Generates a random, URL-safe token for credentials that are stored only as a hash.
+pub fn generate_opaque_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Opaque tokens carry enough entropy that a fast digest is sufficient, unlike passwords.
+pub fn hash_opaque_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
 }
 
 pub struct JwtSecret(String);
@@ -25,7 +72,12 @@ impl JwtSecret {
         Self(secret)
     }
 
-    pub fn create_token(&self, user_id: UserId, ttl: u64) -> AppResult<AccessToken> {
+    pub fn create_token(
+        &self,
+        user_id: UserId,
+        generation: i32,
+        ttl: u64,
+    ) -> AppResult<AccessToken> {
         let expiration = Utc::now()
             .checked_add_signed(Duration::seconds(ttl as i64))
             .ok_or_else(|| {
@@ -36,6 +88,8 @@ impl JwtSecret {
             sub: user_id.to_string(),
             exp: expiration.timestamp(),
             iat: Utc::now().timestamp(),
+            jti: Uuid::new_v4().to_string(),
+            generation,
         };
 
         let token = encode(
@@ -48,17 +102,13 @@ impl JwtSecret {
         Ok(AccessToken(token))
     }
 
-    pub fn verify_token(&self, token: &AccessToken) -> AppResult<Option<UserId>> {
+    pub fn verify_token(&self, token: &AccessToken) -> AppResult<Option<Claims>> {
         match decode::<Claims>(
             &token.0,
             &DecodingKey::from_secret(self.0.as_bytes()),
             &Validation::default(),
         ) {
-            Ok(token_data) => {
-                let user_id = UserId::from_str(&token_data.claims.sub)
-                    .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
-                Ok(Some(user_id))
-            }
+            Ok(token_data) => Ok(Some(token_data.claims)),
             Err(err) => match err.kind() {
                 jsonwebtoken::errors::ErrorKind::ExpiredSignature => Ok(None),
                 _ => Err(AppError::UnauthenticatedError),
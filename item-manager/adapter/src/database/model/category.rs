@@ -0,0 +1,23 @@
+use kernel::model::{
+    category::{AttributeDefinition, CustomCategory},
+    id::CustomCategoryId,
+};
+use sqlx::types::Json;
+
+pub struct CustomCategoryRow {
+    pub custom_category_id: CustomCategoryId,
+    pub key: String,
+    pub name: String,
+    pub attributes: Json<Vec<AttributeDefinition>>,
+}
+
+impl From<CustomCategoryRow> for CustomCategory {
+    fn from(value: CustomCategoryRow) -> Self {
+        CustomCategory {
+            id: value.custom_category_id,
+            key: value.key,
+            name: value.name,
+            attributes: value.attributes.0,
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use kernel::model::{
-    checkout::Checkout,
+    checkout::{Checkout, CheckoutRenewal},
     id::{CheckoutId, ItemId, UserId},
 };
 use sqlx::types::chrono::{DateTime, Utc};
@@ -15,6 +15,7 @@ pub struct CheckoutRow {
     pub item_id: ItemId,
     pub user_id: UserId,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
 }
 
 impl From<CheckoutRow> for Checkout {
@@ -23,28 +24,60 @@ impl From<CheckoutRow> for Checkout {
             id: value.checkout_id,
             checked_out_by: value.user_id,
             checked_out_at: value.checked_out_at,
+            due_at: value.due_at,
             returned_at: None,
             item_id: value.item_id,
+            renewals: Vec::new(),
         }
     }
 }
 
-pub struct ReturnedCheckoutRow {
+pub struct CheckoutHistoryRow {
     pub checkout_id: CheckoutId,
     pub item_id: ItemId,
     pub user_id: UserId,
     pub checked_out_at: DateTime<Utc>,
-    pub returned_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+    pub returned_at: Option<DateTime<Utc>>,
 }
 
-impl From<ReturnedCheckoutRow> for Checkout {
-    fn from(value: ReturnedCheckoutRow) -> Self {
+impl From<CheckoutHistoryRow> for Checkout {
+    fn from(value: CheckoutHistoryRow) -> Self {
         Checkout {
             id: value.checkout_id,
             checked_out_by: value.user_id,
             checked_out_at: value.checked_out_at,
-            returned_at: Some(value.returned_at),
+            due_at: value.due_at,
+            returned_at: value.returned_at,
             item_id: value.item_id,
+            renewals: Vec::new(),
+        }
+    }
+}
+
+pub struct RenewalStateRow {
+    pub user_id: UserId,
+    pub category: String,
+    pub due_at: DateTime<Utc>,
+    pub renewal_count: i64,
+    pub reserved: bool,
+}
+
+pub struct CheckoutRenewalRow {
+    pub checkout_id: CheckoutId,
+    pub user_id: UserId,
+    pub renewed_at: DateTime<Utc>,
+    pub previous_due_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+}
+
+impl From<CheckoutRenewalRow> for CheckoutRenewal {
+    fn from(value: CheckoutRenewalRow) -> Self {
+        CheckoutRenewal {
+            renewed_by: value.user_id,
+            renewed_at: value.renewed_at,
+            previous_due_at: value.previous_due_at,
+            due_at: value.due_at,
         }
     }
 }
@@ -0,0 +1,36 @@
+use std::str::FromStr;
+
+use kernel::model::{
+    id::{InvitationId, UserId},
+    invitation::Invitation,
+    role::Role,
+};
+use shared::error::AppError;
+use sqlx::types::chrono::{DateTime, Utc};
+
+pub struct InvitationRow {
+    pub invitation_id: InvitationId,
+    pub role_name: String,
+    pub max_uses: i32,
+    pub uses: i32,
+    pub expires_at: DateTime<Utc>,
+    pub created_by: Option<UserId>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<InvitationRow> for Invitation {
+    type Error = AppError;
+
+    fn try_from(value: InvitationRow) -> Result<Self, Self::Error> {
+        Ok(Invitation {
+            id: value.invitation_id,
+            role: Role::from_str(&value.role_name)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            max_uses: value.max_uses as u32,
+            uses: value.uses as u32,
+            expires_at: value.expires_at,
+            created_by: value.created_by,
+            created_at: value.created_at,
+        })
+    }
+}
@@ -1,7 +1,9 @@
 use kernel::model::{
+    category::CustomCategoryRef,
     checkout::SimpleCheckout,
-    id::{CheckoutId, ItemId, UserId},
-    item::{Item, book, general, laptop},
+    id::{CheckoutId, CustomCategoryId, ItemId, TagId, UserId},
+    item::{Item, ItemArchive, book, custom, general, laptop},
+    tag::Tag,
     user::CheckoutUser,
 };
 use shared::error::{AppError, AppResult};
@@ -15,10 +17,20 @@ pub struct ItemRow {
     pub author: Option<String>,
     pub isbn: Option<String>,
     pub mac_address: Option<mac_address::MacAddress>,
+    pub custom_category_id: Option<CustomCategoryId>,
+    pub custom_category_key: Option<String>,
+    pub custom_category_name: Option<String>,
+    pub attributes: Option<serde_json::Value>,
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub archive_reason: Option<String>,
 }
 
 impl ItemRow {
-    pub fn into_item(self, checkout: Option<SimpleCheckout>) -> AppResult<Item> {
+    pub fn into_item(self, checkout: Option<SimpleCheckout>, tags: Vec<Tag>) -> AppResult<Item> {
+        let archive = self.archived_at.map(|archived_at| ItemArchive {
+            archived_at,
+            reason: self.archive_reason,
+        });
         match self.category.as_str() {
             "general" => Ok(Item::General(general::GeneralItem {
                 id: self.item_id,
@@ -26,6 +38,8 @@ impl ItemRow {
                 description: self.description,
                 location: self.location,
                 checkout,
+                archive,
+                tags,
             })),
             "book" => Ok(Item::Book(book::Book {
                 id: self.item_id,
@@ -39,6 +53,8 @@ impl ItemRow {
                 description: self.description,
                 location: self.location,
                 checkout,
+                archive,
+                tags,
             })),
             "laptop" => Ok(Item::Laptop(laptop::Laptop {
                 id: self.item_id,
@@ -49,14 +65,143 @@ impl ItemRow {
                 description: self.description,
                 location: self.location,
                 checkout,
+                archive,
+                tags,
             })),
+            "custom" => {
+                let category = match (
+                    self.custom_category_id,
+                    self.custom_category_key,
+                    self.custom_category_name,
+                ) {
+                    (Some(id), Some(key), Some(name)) => CustomCategoryRef { id, key, name },
+                    _ => {
+                        return Err(AppError::ConversionEntityError(
+                            "Custom item is missing its category".into(),
+                        ));
+                    }
+                };
+                let Some(serde_json::Value::Object(attributes)) = self.attributes else {
+                    return Err(AppError::ConversionEntityError(
+                        "Custom item is missing attributes".into(),
+                    ));
+                };
+                Ok(Item::Custom(custom::CustomItem {
+                    id: self.item_id,
+                    name: self.name,
+                    category,
+                    attributes,
+                    description: self.description,
+                    location: self.location,
+                    checkout,
+                    archive,
+                    tags,
+                }))
+            }
             _ => unreachable!("Invalid item category"),
         }
     }
 }
 
+pub struct ItemExportRow {
+    pub item_id: ItemId,
+    pub category: String,
+    pub name: String,
+    pub description: String,
+    pub location: Option<String>,
+    pub author: Option<String>,
+    pub isbn: Option<String>,
+    pub mac_address: Option<mac_address::MacAddress>,
+    pub custom_category_id: Option<CustomCategoryId>,
+    pub custom_category_key: Option<String>,
+    pub custom_category_name: Option<String>,
+    pub attributes: Option<serde_json::Value>,
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub archive_reason: Option<String>,
+    pub checkout_id: Option<CheckoutId>,
+    pub user_id: Option<UserId>,
+    pub user_name: Option<String>,
+    pub checked_out_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub due_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Parallel to `tag_names`, in alphabetical order.
+    pub tag_ids: Vec<TagId>,
+    pub tag_names: Vec<String>,
+}
+
+impl ItemExportRow {
+    pub fn into_item(self) -> AppResult<Item> {
+        let checkout = match (
+            self.checkout_id,
+            self.user_id,
+            self.user_name,
+            self.checked_out_at,
+            self.due_at,
+        ) {
+            (
+                Some(checkout_id),
+                Some(user_id),
+                Some(user_name),
+                Some(checked_out_at),
+                Some(due_at),
+            ) => Some(SimpleCheckout::from(ItemCheckoutRow {
+                checkout_id,
+                item_id: self.item_id,
+                user_id,
+                user_name,
+                checked_out_at,
+                due_at,
+            })),
+            _ => None,
+        };
+        let tags = self
+            .tag_ids
+            .into_iter()
+            .zip(self.tag_names)
+            .map(|(id, name)| Tag { id, name })
+            .collect();
+        ItemRow {
+            item_id: self.item_id,
+            category: self.category,
+            name: self.name,
+            description: self.description,
+            location: self.location,
+            author: self.author,
+            isbn: self.isbn,
+            mac_address: self.mac_address,
+            custom_category_id: self.custom_category_id,
+            custom_category_key: self.custom_category_key,
+            custom_category_name: self.custom_category_name,
+            attributes: self.attributes,
+            archived_at: self.archived_at,
+            archive_reason: self.archive_reason,
+        }
+        .into_item(checkout, tags)
+    }
+}
+
 pub struct PaginatedItemRow {
     pub id: ItemId,
+    pub key_text: Option<String>,
+    pub key_time: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// A row of an item listing query, which also carries the number of items
+/// matching the filter. An empty page is a single row without an item.
+pub struct ItemPageRow {
+    pub total: i64,
+    pub id: Option<ItemId>,
+    pub key_text: Option<String>,
+    pub key_time: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ItemPageRow {
+    pub fn into_paginated(self) -> Option<PaginatedItemRow> {
+        Some(PaginatedItemRow {
+            id: self.id?,
+            key_text: self.key_text,
+            key_time: self.key_time,
+        })
+    }
 }
 
 pub struct ItemCheckoutRow {
@@ -65,6 +210,7 @@ pub struct ItemCheckoutRow {
     pub user_id: UserId,
     pub user_name: String,
     pub checked_out_at: chrono::DateTime<chrono::Utc>,
+    pub due_at: chrono::DateTime<chrono::Utc>,
 }
 
 impl From<ItemCheckoutRow> for SimpleCheckout {
@@ -76,6 +222,7 @@ impl From<ItemCheckoutRow> for SimpleCheckout {
                 name: value.user_name,
             },
             checked_out_at: value.checked_out_at,
+            due_at: value.due_at,
         }
     }
 }
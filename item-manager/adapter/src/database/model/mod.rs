@@ -1,4 +1,13 @@
+pub mod audit;
 pub mod auth;
+pub mod category;
 pub mod checkout;
+pub mod invitation;
 pub mod item;
+pub mod oidc;
+pub mod password;
+pub mod personal_access_token;
+pub mod reservation;
+pub mod tag;
+pub mod two_factor;
 pub mod user;
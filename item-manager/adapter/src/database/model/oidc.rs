@@ -0,0 +1,13 @@
+use kernel::model::id::UserId;
+
+pub struct OidcLoginRequestRow {
+    pub nonce: String,
+    pub pkce_verifier: String,
+    pub expired: bool,
+}
+
+/// The account an identity signs in as.
+pub struct LinkedUserRow {
+    pub user_id: UserId,
+    pub deactivated: bool,
+}
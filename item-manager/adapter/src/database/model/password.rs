@@ -0,0 +1,131 @@
+use std::{collections::HashSet, sync::Arc};
+
+use anyhow::Context;
+use argon2::{
+    Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version,
+    password_hash::{SaltString, rand_core::OsRng},
+};
+use shared::{
+    config::{PasswordConfig, PasswordHashAlgorithm},
+    error::{AppError, AppResult},
+};
+
+const ARGON2_PREFIX: &str = "$argon2";
+
+/// Checks new passwords against the configured rules and hashes them with the configured
+/// algorithm. Hashes made with other settings still verify, so they can be upgraded on login.
+#[derive(Clone, Default)]
+pub struct PasswordPolicy {
+    config: PasswordConfig,
+    /// Lowercased, so a refused password cannot be used by changing its case.
+    denylist: Arc<HashSet<String>>,
+}
+
+impl PasswordPolicy {
+    pub fn new(config: PasswordConfig) -> anyhow::Result<Self> {
+        let denylist = match &config.denylist_file {
+            Some(path) => std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read the password denylist at {path}"))?
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_lowercase)
+                .collect(),
+            None => HashSet::new(),
+        };
+        // Parameters the library rejects should stop the server, not every password change.
+        if config.algorithm == PasswordHashAlgorithm::Argon2id {
+            argon2_params(&config)?;
+        }
+
+        Ok(Self {
+            config,
+            denylist: Arc::new(denylist),
+        })
+    }
+
+    /// How many recent passwords, including the current one, cannot be chosen again.
+    pub fn history(&self) -> u32 {
+        self.config.history
+    }
+
+    pub fn validate(&self, password: &str) -> AppResult<()> {
+        if password.chars().count() < self.config.min_length {
+            return Err(AppError::UnprocessableEntity(format!(
+                "The password must be at least {} characters long.",
+                self.config.min_length
+            )));
+        }
+        if self.denylist.contains(&password.to_lowercase()) {
+            return Err(AppError::UnprocessableEntity(
+                "The password is too common; choose another one.".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn hash(&self, password: &str) -> AppResult<String> {
+        match self.config.algorithm {
+            PasswordHashAlgorithm::Bcrypt => {
+                bcrypt::hash(password, self.config.bcrypt_cost).map_err(AppError::from)
+            }
+            PasswordHashAlgorithm::Argon2id => {
+                let params = argon2_params(&self.config)
+                    .map_err(|e| AppError::PasswordHashError(e.to_string()))?;
+                let salt = SaltString::generate(&mut OsRng);
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password(password.as_bytes(), &salt)
+                    .map(|hash| hash.to_string())
+                    .map_err(|e| AppError::PasswordHashError(e.to_string()))
+            }
+        }
+    }
+
+    /// Verifies against a hash of either algorithm, using the parameters stored in it.
+    pub fn verify(&self, password: &str, hash: &str) -> AppResult<bool> {
+        if !hash.starts_with(ARGON2_PREFIX) {
+            return bcrypt::verify(password, hash).map_err(AppError::from);
+        }
+        let parsed =
+            PasswordHash::new(hash).map_err(|e| AppError::PasswordHashError(e.to_string()))?;
+        match Argon2::default().verify_password(password.as_bytes(), &parsed) {
+            Ok(()) => Ok(true),
+            Err(argon2::password_hash::Error::Password) => Ok(false),
+            Err(e) => Err(AppError::PasswordHashError(e.to_string())),
+        }
+    }
+
+    /// Whether `hash` was made with another algorithm or other parameters than configured.
+    pub fn needs_rehash(&self, hash: &str) -> bool {
+        match self.config.algorithm {
+            PasswordHashAlgorithm::Bcrypt => {
+                // bcrypt hashes look like `$2b$12$...`, with the cost as the second field.
+                hash.starts_with(ARGON2_PREFIX)
+                    || hash.split('$').nth(2).and_then(|cost| cost.parse().ok())
+                        != Some(self.config.bcrypt_cost)
+            }
+            PasswordHashAlgorithm::Argon2id => {
+                let Ok(parsed) = PasswordHash::new(hash) else {
+                    return true;
+                };
+                let Ok(params) = Params::try_from(&parsed) else {
+                    return true;
+                };
+                parsed.algorithm != Algorithm::Argon2id.ident()
+                    || params.m_cost() != self.config.argon2_memory_kib
+                    || params.t_cost() != self.config.argon2_iterations
+                    || params.p_cost() != self.config.argon2_parallelism
+            }
+        }
+    }
+}
+
+fn argon2_params(config: &PasswordConfig) -> anyhow::Result<Params> {
+    Params::new(
+        config.argon2_memory_kib,
+        config.argon2_iterations,
+        config.argon2_parallelism,
+        None,
+    )
+    .map_err(|e| anyhow::anyhow!("Invalid Argon2 parameters: {e}"))
+}
@@ -0,0 +1,41 @@
+use kernel::model::{
+    id::{PersonalAccessTokenId, UserId},
+    personal_access_token::{PersonalAccessToken, TokenScope},
+};
+use shared::error::{AppError, AppResult};
+use sqlx::types::chrono::{DateTime, Utc};
+use std::str::FromStr;
+
+pub struct PersonalAccessTokenRow {
+    pub personal_access_token_id: PersonalAccessTokenId,
+    pub user_id: UserId,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<PersonalAccessTokenRow> for PersonalAccessToken {
+    type Error = AppError;
+
+    fn try_from(value: PersonalAccessTokenRow) -> Result<Self, Self::Error> {
+        let scopes = value
+            .scopes
+            .iter()
+            .map(|scope| {
+                TokenScope::from_str(scope)
+                    .map_err(|e| AppError::ConversionEntityError(e.to_string()))
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok(PersonalAccessToken {
+            id: value.personal_access_token_id,
+            user_id: value.user_id,
+            name: value.name,
+            scopes,
+            created_at: value.created_at,
+            expires_at: value.expires_at,
+            last_used_at: value.last_used_at,
+        })
+    }
+}
@@ -0,0 +1,42 @@
+use kernel::model::{
+    id::{ItemId, ReservationId, UserId},
+    reservation::Reservation,
+};
+use sqlx::types::chrono::{DateTime, Utc};
+
+pub struct ReservationRow {
+    pub reservation_id: ReservationId,
+    pub item_id: ItemId,
+    pub user_id: UserId,
+    pub reserved_at: DateTime<Utc>,
+    pub position: i64,
+    pub ready_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<ReservationRow> for Reservation {
+    fn from(value: ReservationRow) -> Self {
+        Reservation {
+            id: value.reservation_id,
+            item_id: value.item_id,
+            reserved_by: value.user_id,
+            reserved_at: value.reserved_at,
+            position: value.position,
+            ready_at: value.ready_at,
+            expires_at: value.expires_at,
+        }
+    }
+}
+
+pub struct ReservationHeadRow {
+    pub reservation_id: ReservationId,
+    pub user_id: UserId,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// The reservation an item is currently being held for.
+pub struct ReservationHold {
+    pub reservation_id: ReservationId,
+    pub user_id: UserId,
+    pub expires_at: DateTime<Utc>,
+}
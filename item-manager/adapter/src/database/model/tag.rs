@@ -0,0 +1,24 @@
+use kernel::model::{
+    id::{ItemId, TagId},
+    tag::Tag,
+};
+
+pub struct TagRow {
+    pub tag_id: TagId,
+    pub name: String,
+}
+
+impl From<TagRow> for Tag {
+    fn from(value: TagRow) -> Self {
+        Tag {
+            id: value.tag_id,
+            name: value.name,
+        }
+    }
+}
+
+pub struct ItemTagRow {
+    pub item_id: ItemId,
+    pub tag_id: TagId,
+    pub name: String,
+}
@@ -0,0 +1,88 @@
+use kernel::model::{id::UserId, two_factor::TotpSetup};
+use rand::RngCore;
+use shared::error::{AppError, AppResult};
+use totp_rs::{Algorithm, Secret, TOTP};
+use uuid::Uuid;
+
+/// Shown above the account in authenticator apps.
+const TOTP_ISSUER: &str = "procon-manager";
+/// Seconds per code, the period authenticator apps assume when none is given.
+const TOTP_STEP: u64 = 30;
+/// Codes of this many steps before or after the current one are accepted, to allow for drift
+/// between the server clock and the phone.
+const TOTP_SKEW: i64 = 1;
+
+pub struct TotpSecretRow {
+    pub secret: String,
+    pub confirmed: bool,
+    pub last_used_step: Option<i64>,
+}
+
+pub struct LoginChallengeRow {
+    pub login_challenge_id: Uuid,
+    pub user_id: UserId,
+    pub setup_required: bool,
+    pub failed_attempts: i32,
+}
+
+/// Generates a 160-bit secret, the length RFC 4226 recommends.
+pub fn generate_totp_setup(account_name: &str) -> AppResult<TotpSetup> {
+    let mut bytes = vec![0u8; 20];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let totp = build_totp(bytes, account_name)?;
+    Ok(TotpSetup {
+        secret: totp.get_secret_base32(),
+        otpauth_url: totp.get_url(),
+    })
+}
+
+/// Returns the time step `code` belongs to, if it is within the accepted window and later than
+/// `last_used_step`, so each code works only once.
+pub fn verify_totp(
+    secret: &str,
+    code: &str,
+    last_used_step: Option<i64>,
+) -> AppResult<Option<i64>> {
+    let bytes = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+    let totp = build_totp(bytes, "")?;
+    let current = chrono::Utc::now().timestamp() / TOTP_STEP as i64;
+
+    Ok((current - TOTP_SKEW..=current + TOTP_SKEW)
+        .filter(|step| last_used_step.is_none_or(|last| *step > last))
+        .find(|step| totp.check(code, *step as u64 * TOTP_STEP)))
+}
+
+fn build_totp(secret: Vec<u8>, account_name: &str) -> AppResult<TOTP> {
+    // The window is applied by `verify_totp`, so the library itself checks a single step.
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        0,
+        TOTP_STEP,
+        secret,
+        Some(TOTP_ISSUER.to_string()),
+        account_name.replace(':', ""),
+    )
+    .map_err(|e| AppError::ConversionEntityError(e.to_string()))
+}
+
+/// TOTP codes are six digits; anything else is treated as a recovery code.
+pub fn is_totp_code(code: &str) -> bool {
+    code.len() == 6 && code.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Generates a 64-bit recovery code formatted as `xxxx-xxxx-xxxx-xxxx`.
+pub fn generate_recovery_code() -> String {
+    let hex = format!("{:016x}", rand::thread_rng().next_u64());
+    [&hex[0..4], &hex[4..8], &hex[8..12], &hex[12..16]].join("-")
+}
+
+/// Recovery codes are compared without separators or case, as people tend to retype them.
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
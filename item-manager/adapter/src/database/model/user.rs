@@ -8,10 +8,38 @@ pub struct UserRow {
     pub name: String,
     pub email: String,
     pub role_name: String,
+    pub deactivated_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// A row of a user listing query, which also carries the number of users
+/// matching the filter. An empty page is a single row without a user.
+pub struct UserPageRow {
+    pub total: i64,
+    pub user_id: Option<UserId>,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub role_name: Option<String>,
+    pub deactivated_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl UserPageRow {
+    pub fn into_user_row(self) -> Option<UserRow> {
+        Some(UserRow {
+            user_id: self.user_id?,
+            name: self.name?,
+            email: self.email?,
+            role_name: self.role_name?,
+            deactivated_at: self.deactivated_at,
+            created_at: self.created_at?,
+            updated_at: self.updated_at?,
+        })
+    }
+}
+
 impl TryFrom<UserRow> for User {
     type Error = AppError;
 
@@ -21,6 +49,7 @@ impl TryFrom<UserRow> for User {
             name,
             email,
             role_name,
+            deactivated_at,
             ..
         } = value;
         Ok(User {
@@ -29,6 +58,7 @@ impl TryFrom<UserRow> for User {
             email,
             role: Role::from_str(role_name.as_str())
                 .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            deactivated_at,
         })
     }
 }
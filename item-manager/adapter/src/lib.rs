@@ -1,2 +1,4 @@
 pub mod database;
+pub mod mailer;
+pub mod oidc;
 pub mod repository;
@@ -0,0 +1,83 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use kernel::mailer::{Mail, Mailer};
+use shared::error::{AppError, AppResult};
+use uuid::Uuid;
+
+/// Writes each mail to its own file in `dir`, or only logs it when no directory is given.
+/// Meant for development and tests; mails contain secrets such as reset links.
+pub struct FileMailer {
+    from: String,
+    dir: Option<PathBuf>,
+}
+
+impl FileMailer {
+    pub fn new(from: String, dir: Option<PathBuf>) -> Self {
+        Self { from, dir }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, mail: Mail) -> AppResult<()> {
+        let Some(dir) = &self.dir else {
+            tracing::info!(to = %mail.to, subject = %mail.subject, body = %mail.body, "Mail");
+            return Ok(());
+        };
+
+        let now = Utc::now();
+        let content = format!(
+            "From: {}\r\nTo: {}\r\nDate: {}\r\nSubject: {}\r\n\r\n{}\r\n",
+            self.from,
+            mail.to,
+            now.to_rfc2822(),
+            mail.subject,
+            mail.body
+        );
+        let path = dir.join(format!(
+            "{}-{}.eml",
+            now.format("%Y%m%dT%H%M%S"),
+            Uuid::new_v4()
+        ));
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| AppError::MailError(e.to_string()))?;
+        tokio::fs::write(&path, content)
+            .await
+            .map_err(|e| AppError::MailError(e.to_string()))?;
+        tracing::info!(to = %mail.to, path = %path.display(), "Mail written to file");
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_mailer_writes_eml() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("mailer-test-{}", Uuid::new_v4()));
+        let mailer = FileMailer::new("noreply@example.com".into(), Some(dir.clone()));
+
+        mailer
+            .send(Mail {
+                to: "user@example.com".into(),
+                subject: "Hello".into(),
+                body: "Line one".into(),
+            })
+            .await?;
+
+        let mut entries = std::fs::read_dir(&dir)?.collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(entries.len(), 1);
+        let content = std::fs::read_to_string(entries.pop().unwrap().path())?;
+        assert!(content.contains("To: user@example.com\r\n"));
+        assert!(content.contains("Subject: Hello\r\n"));
+        assert!(content.ends_with("\r\n\r\nLine one\r\n"));
+
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,353 @@
+use async_trait::async_trait;
+use kernel::model::oidc::{OidcAuthorization, OidcIdentity, PendingOidcLogin};
+use kernel::oidc::OidcProvider;
+use openidconnect::{
+    AuthenticationFlow, AuthorizationCode, ClientId, ClientSecret, CsrfToken, EndpointMaybeSet,
+    EndpointNotSet, EndpointSet, IssuerUrl, Nonce, PkceCodeChallenge, PkceCodeVerifier,
+    RedirectUrl, RequestTokenError, Scope,
+    core::{CoreClient, CoreProviderMetadata, CoreResponseType},
+    reqwest,
+};
+use shared::{
+    config::OidcConfig,
+    error::{AppError, AppResult},
+};
+use tokio::sync::OnceCell;
+
+type DiscoveredClient = CoreClient<
+    EndpointSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointMaybeSet,
+    EndpointMaybeSet,
+>;
+
+/// Single sign-on through an OpenID Connect provider with the authorization code flow and PKCE.
+pub struct OpenIdConnectProvider {
+    issuer_url: IssuerUrl,
+    client_id: ClientId,
+    client_secret: Option<ClientSecret>,
+    redirect_url: RedirectUrl,
+    scopes: Vec<Scope>,
+    http_client: reqwest::Client,
+    /// Discovered on first use, so the server starts even while the provider is unreachable.
+    client: OnceCell<DiscoveredClient>,
+}
+
+impl OpenIdConnectProvider {
+    pub fn new(config: &OidcConfig) -> anyhow::Result<Self> {
+        // Following redirects would open the token request to SSRF.
+        let http_client = reqwest::ClientBuilder::new()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        Ok(Self {
+            issuer_url: IssuerUrl::new(config.issuer_url.clone())?,
+            client_id: ClientId::new(config.client_id.clone()),
+            client_secret: config.client_secret.clone().map(ClientSecret::new),
+            redirect_url: RedirectUrl::new(config.redirect_url.clone())?,
+            scopes: config.scopes.iter().cloned().map(Scope::new).collect(),
+            http_client,
+            client: OnceCell::new(),
+        })
+    }
+
+    async fn client(&self) -> AppResult<&DiscoveredClient> {
+        self.client
+            .get_or_try_init(|| async {
+                let metadata = CoreProviderMetadata::discover_async(
+                    self.issuer_url.clone(),
+                    &self.http_client,
+                )
+                .await
+                .map_err(|e| AppError::IdentityProviderError(e.to_string()))?;
+                Ok(CoreClient::from_provider_metadata(
+                    metadata,
+                    self.client_id.clone(),
+                    self.client_secret.clone(),
+                )
+                .set_redirect_uri(self.redirect_url.clone()))
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl OidcProvider for OpenIdConnectProvider {
+    async fn authorize(&self) -> AppResult<OidcAuthorization> {
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+        let (url, state, nonce) = self
+            .client()
+            .await?
+            .authorize_url(
+                AuthenticationFlow::<CoreResponseType>::AuthorizationCode,
+                CsrfToken::new_random,
+                Nonce::new_random,
+            )
+            .add_scopes(self.scopes.iter().cloned())
+            .set_pkce_challenge(pkce_challenge)
+            .url();
+
+        Ok(OidcAuthorization {
+            url: url.into(),
+            state: state.into_secret(),
+            nonce: nonce.secret().clone(),
+            pkce_verifier: pkce_verifier.into_secret(),
+        })
+    }
+
+    async fn exchange_code(
+        &self,
+        code: &str,
+        pending: PendingOidcLogin,
+    ) -> AppResult<OidcIdentity> {
+        let client = self.client().await?;
+        let response = client
+            .exchange_code(AuthorizationCode::new(code.to_string()))
+            .map_err(|e| AppError::IdentityProviderError(e.to_string()))?
+            .set_pkce_verifier(PkceCodeVerifier::new(pending.pkce_verifier))
+            .request_async(&self.http_client)
+            .await
+            .map_err(|e| match e {
+                RequestTokenError::ServerResponse(response) => {
+                    tracing::warn!(error = %response, "Identity provider rejected the code");
+                    AppError::UnauthenticatedError
+                }
+                e => AppError::IdentityProviderError(e.to_string()),
+            })?;
+
+        let id_token = response
+            .extra_fields()
+            .id_token()
+            .ok_or_else(|| AppError::IdentityProviderError("No ID token returned.".into()))?;
+        let claims = id_token
+            .claims(&client.id_token_verifier(), &Nonce::new(pending.nonce))
+            .map_err(|e| {
+                tracing::warn!(error = %e, "Rejected ID token");
+                AppError::UnauthenticatedError
+            })?;
+
+        Ok(OidcIdentity {
+            issuer: claims.issuer().to_string(),
+            subject: claims.subject().to_string(),
+            email: claims.email().map(|email| email.to_string()),
+            email_verified: claims.email_verified().unwrap_or(false),
+            name: claims
+                .name()
+                .and_then(|name| name.get(None))
+                .map(|name| name.to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use axum::{
+        Form, Json, Router,
+        extract::State,
+        http::StatusCode,
+        routing::{get, post},
+    };
+    use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+    use jsonwebtoken::{EncodingKey, Header, encode};
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+
+    const CLIENT_ID: &str = "procon-manager";
+    const CLIENT_SECRET: &str = "client-secret";
+    const CODE: &str = "authorization-code";
+
+    /// What the mock provider received with the authorization request.
+    #[derive(Default)]
+    struct MockProviderState {
+        issuer: String,
+        code_challenge: String,
+        nonce: String,
+    }
+
+    type SharedState = Arc<Mutex<MockProviderState>>;
+
+    /// Serves discovery and a token endpoint that signs ID tokens with the client secret.
+    async fn start_mock_provider() -> anyhow::Result<SharedState> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let issuer = format!("http://{}", listener.local_addr()?);
+        let state = Arc::new(Mutex::new(MockProviderState {
+            issuer,
+            ..Default::default()
+        }));
+
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route(
+                "/jwks",
+                get(|| async { Json(serde_json::json!({ "keys": [] })) }),
+            )
+            .route("/token", post(token))
+            .with_state(state.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        Ok(state)
+    }
+
+    async fn discovery(State(state): State<SharedState>) -> Json<serde_json::Value> {
+        let issuer = state.lock().unwrap().issuer.clone();
+        Json(serde_json::json!({
+            "issuer": issuer,
+            "authorization_endpoint": format!("{issuer}/authorize"),
+            "token_endpoint": format!("{issuer}/token"),
+            "jwks_uri": format!("{issuer}/jwks"),
+            "response_types_supported": ["code"],
+            "subject_types_supported": ["public"],
+            "id_token_signing_alg_values_supported": ["HS256"],
+        }))
+    }
+
+    async fn token(
+        State(state): State<SharedState>,
+        Form(form): Form<HashMap<String, String>>,
+    ) -> (StatusCode, Json<serde_json::Value>) {
+        let state = state.lock().unwrap();
+        let challenge = form
+            .get("code_verifier")
+            .map(|verifier| URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes())));
+        if form.get("code").map(String::as_str) != Some(CODE)
+            || challenge.as_deref() != Some(state.code_challenge.as_str())
+        {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "invalid_grant" })),
+            );
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let id_token = encode(
+            &Header::default(),
+            &serde_json::json!({
+                "iss": state.issuer,
+                "sub": "member-1",
+                "aud": CLIENT_ID,
+                "iat": now,
+                "exp": now + 300,
+                "nonce": state.nonce,
+                "email": "member@example.com",
+                "email_verified": true,
+                "name": "Member",
+            }),
+            &EncodingKey::from_secret(CLIENT_SECRET.as_bytes()),
+        )
+        .unwrap();
+        (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "access_token": "access-token",
+                "token_type": "Bearer",
+                "expires_in": 300,
+                "id_token": id_token,
+            })),
+        )
+    }
+
+    fn provider(issuer: &str) -> anyhow::Result<OpenIdConnectProvider> {
+        OpenIdConnectProvider::new(&OidcConfig {
+            issuer_url: issuer.into(),
+            client_id: CLIENT_ID.into(),
+            client_secret: Some(CLIENT_SECRET.into()),
+            redirect_url: "http://localhost:8080/auth/oidc/callback".into(),
+            scopes: vec!["email".into(), "profile".into()],
+            allow_signup: false,
+            link_by_email: true,
+        })
+    }
+
+    /// Starts a sign-in and has the mock provider remember it, as its login page would.
+    async fn authorize(
+        provider: &OpenIdConnectProvider,
+        state: &SharedState,
+    ) -> anyhow::Result<OidcAuthorization> {
+        let authorization = provider.authorize().await?;
+        let url = reqwest::Url::parse(&authorization.url)?;
+        let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+        assert_eq!(params["state"], authorization.state);
+        assert_eq!(params["code_challenge_method"], "S256");
+        assert_eq!(params["scope"], "openid email profile");
+
+        let mut state = state.lock().unwrap();
+        state.code_challenge = params["code_challenge"].clone();
+        state.nonce = params["nonce"].clone();
+        Ok(authorization)
+    }
+
+    #[tokio::test]
+    async fn test_sign_in_with_mock_provider() -> anyhow::Result<()> {
+        let state = start_mock_provider().await?;
+        let issuer = state.lock().unwrap().issuer.clone();
+        let provider = provider(&issuer)?;
+
+        let authorization = authorize(&provider, &state).await?;
+        let identity = provider
+            .exchange_code(
+                CODE,
+                PendingOidcLogin {
+                    nonce: authorization.nonce,
+                    pkce_verifier: authorization.pkce_verifier,
+                },
+            )
+            .await?;
+        assert_eq!(identity.issuer, issuer);
+        assert_eq!(identity.subject, "member-1");
+        assert_eq!(identity.email.as_deref(), Some("member@example.com"));
+        assert!(identity.email_verified);
+        assert_eq!(identity.name.as_deref(), Some("Member"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rejects_mismatched_verifier_and_nonce() -> anyhow::Result<()> {
+        let state = start_mock_provider().await?;
+        let issuer = state.lock().unwrap().issuer.clone();
+        let provider = provider(&issuer)?;
+
+        let authorization = authorize(&provider, &state).await?;
+        let res = provider
+            .exchange_code(
+                CODE,
+                PendingOidcLogin {
+                    nonce: authorization.nonce.clone(),
+                    pkce_verifier: "another-verifier-of-sufficient-length-0123456789".into(),
+                },
+            )
+            .await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+
+        let res = provider
+            .exchange_code(
+                CODE,
+                PendingOidcLogin {
+                    nonce: "another-nonce".into(),
+                    pkce_verifier: authorization.pkce_verifier,
+                },
+            )
+            .await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_provider() -> anyhow::Result<()> {
+        let provider = provider("http://127.0.0.1:9")?;
+
+        let res = provider.authorize().await;
+        assert!(matches!(res, Err(AppError::IdentityProviderError(_))));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,384 @@
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::{
+    audit::{AuditAction, AuditLog, AuditLogListOptions},
+    id::{CheckoutId, ItemId, UserId},
+    list::PaginatedList,
+};
+use kernel::repository::audit::AuditRepository;
+use shared::error::{AppError, AppResult};
+use uuid::Uuid;
+
+use crate::database::{ConnectionPool, model::audit::AuditLogRow};
+
+#[derive(new)]
+pub struct AuditRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl AuditRepository for AuditRepositoryImpl {
+    async fn find_all(&self, options: AuditLogListOptions) -> AppResult<PaginatedList<AuditLog>> {
+        let AuditLogListOptions {
+            limit,
+            offset,
+            actor_id,
+            action,
+            target_type,
+            target_id,
+            from,
+            to,
+        } = options;
+        let actor_id = actor_id.map(UserId::raw);
+        let action = action.as_ref().map(AsRef::as_ref);
+        let target_type = target_type.as_ref().map(AsRef::as_ref);
+
+        let total = sqlx::query_scalar!(
+            r#"
+                SELECT COUNT(*) AS "total!"
+                FROM audit_logs
+                WHERE ($1::uuid IS NULL OR actor_id = $1)
+                  AND ($2::text IS NULL OR action = $2)
+                  AND ($3::text IS NULL OR target_type = $3)
+                  AND ($4::uuid IS NULL OR target_id = $4)
+                  AND ($5::timestamptz IS NULL OR created_at >= $5)
+                  AND ($6::timestamptz IS NULL OR created_at < $6)
+            "#,
+            actor_id,
+            action,
+            target_type,
+            target_id,
+            from,
+            to,
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let rows: Vec<AuditLogRow> = sqlx::query_as!(
+            AuditLogRow,
+            r#"
+                SELECT
+                    audit_log_id,
+                    actor_id AS "actor_id?: UserId",
+                    action,
+                    target_type,
+                    target_id,
+                    before,
+                    after,
+                    created_at
+                FROM audit_logs
+                WHERE ($1::uuid IS NULL OR actor_id = $1)
+                  AND ($2::text IS NULL OR action = $2)
+                  AND ($3::text IS NULL OR target_type = $3)
+                  AND ($4::uuid IS NULL OR target_id = $4)
+                  AND ($5::timestamptz IS NULL OR created_at >= $5)
+                  AND ($6::timestamptz IS NULL OR created_at < $6)
+                ORDER BY created_at DESC, audit_log_id DESC
+                LIMIT $7
+                OFFSET $8
+            "#,
+            actor_id,
+            action,
+            target_type,
+            target_id,
+            from,
+            to,
+            limit,
+            offset,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let items = rows
+            .into_iter()
+            .map(AuditLog::try_from)
+            .collect::<AppResult<Vec<_>>>()?;
+
+        Ok(PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+            next_cursor: None,
+            prev_cursor: None,
+        })
+    }
+}
+
+/// An audit log entry to be written alongside the change it describes.
+#[derive(new)]
+pub(crate) struct AuditEntry {
+    actor_id: UserId,
+    action: AuditAction,
+    target_id: Uuid,
+    before: Option<serde_json::Value>,
+    after: Option<serde_json::Value>,
+}
+
+pub(crate) async fn record_audit(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    entry: AuditEntry,
+) -> AppResult<()> {
+    let target_type = entry.action.target_type();
+    sqlx::query!(
+        r#"
+            INSERT INTO audit_logs (actor_id, action, target_type, target_id, before, after)
+            VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+        entry.actor_id.raw(),
+        entry.action.as_ref(),
+        target_type.as_ref(),
+        entry.target_id,
+        entry.before,
+        entry.after,
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    Ok(())
+}
+
+/// Current state of an item, including its category-specific columns.
+pub(crate) async fn item_snapshot(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    item_id: ItemId,
+) -> AppResult<Option<serde_json::Value>> {
+    sqlx::query_scalar!(
+        r#"
+            SELECT
+                to_jsonb(i)
+                || COALESCE(to_jsonb(b) - 'item_id', '{}'::jsonb)
+                || COALESCE(to_jsonb(l) - 'item_id', '{}'::jsonb) AS "snapshot!"
+            FROM items AS i
+            LEFT JOIN books AS b USING(item_id)
+            LEFT JOIN laptops AS l USING(item_id)
+            WHERE i.item_id = $1
+        "#,
+        item_id.raw(),
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)
+}
+
+/// Current state of a user, without the password hash.
+pub(crate) async fn user_snapshot(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: UserId,
+) -> AppResult<Option<serde_json::Value>> {
+    sqlx::query_scalar!(
+        r#"
+            SELECT
+                (to_jsonb(u) - 'password_hash' - 'role_id')
+                || jsonb_build_object(
+                    'role', r.name,
+                    'two_factor_enabled', EXISTS (
+                        SELECT 1 FROM user_totp_secrets AS s
+                        WHERE s.user_id = u.user_id
+                          AND s.confirmed_at IS NOT NULL
+                    )
+                ) AS "snapshot!"
+            FROM users AS u
+            INNER JOIN roles AS r USING(role_id)
+            WHERE u.user_id = $1
+        "#,
+        user_id.raw(),
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)
+}
+
+/// Current state of a checkout, whether it is still active or already returned.
+pub(crate) async fn checkout_snapshot(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    checkout_id: CheckoutId,
+) -> AppResult<Option<serde_json::Value>> {
+    sqlx::query_scalar!(
+        r#"
+            SELECT to_jsonb(c) AS "snapshot!"
+            FROM checkouts AS c
+            WHERE c.checkout_id = $1
+            UNION ALL
+            SELECT to_jsonb(rc) AS "snapshot!"
+            FROM returned_checkouts AS rc
+            WHERE rc.checkout_id = $1
+        "#,
+        checkout_id.raw(),
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use chrono::Utc;
+    use kernel::{
+        model::{
+            audit::AuditTargetType,
+            checkout::event::{CreateCheckout, UpdateReturned},
+            item::{CreateItem, DeleteItem, UpdateItem},
+            role::Role,
+            user::event::UpdateUserRole,
+        },
+        repository::{checkout::CheckoutRepository, item::ItemRepository, user::UserRepository},
+    };
+    use shared::config::LoanConfig;
+
+    use super::*;
+    use crate::database::model::password::PasswordPolicy;
+    use crate::repository::{
+        checkout::CheckoutRepositoryImpl, item::ItemRepositoryImpl, user::UserRepositoryImpl,
+    };
+
+    #[sqlx::test(fixtures("common", "item"))]
+    async fn test_mutations_are_audited(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let repo = AuditRepositoryImpl::new(db.clone());
+        let item_repo = ItemRepositoryImpl::new(db.clone());
+        let user_repo = UserRepositoryImpl::new(db.clone(), PasswordPolicy::default());
+        let checkout_repo = CheckoutRepositoryImpl::new(db, 3600, LoanConfig::default());
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let user_id = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let item_id = ItemId::from_str("9890736e-a4e4-461a-a77d-eac3517ef113")?;
+
+        item_repo
+            .update(UpdateItem::General {
+                item_id,
+                requested_by: admin_id,
+                name: "renamed item".into(),
+                description: "test description".into(),
+                location: None,
+            })
+            .await?;
+        user_repo
+            .update_role(UpdateUserRole {
+                user_id,
+                role: Role::Admin,
+                requested_by: admin_id,
+            })
+            .await?;
+        checkout_repo
+            .create(CreateCheckout::new(
+                item_id,
+                user_id,
+                admin_id,
+                Utc::now(),
+                None,
+            ))
+            .await?;
+        let checkout_id = checkout_repo.find_unreturned_by_user_id(user_id).await?[0].id;
+        checkout_repo
+            .update_returned(UpdateReturned::new(
+                checkout_id,
+                item_id,
+                admin_id,
+                Role::Admin,
+                Utc::now(),
+            ))
+            .await?;
+
+        let all = repo
+            .find_all(AuditLogListOptions {
+                limit: 20,
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(all.total, 4);
+        assert!(all.items.iter().all(|log| log.actor_id == Some(admin_id)));
+        let entry = |action: AuditAction| {
+            all.items
+                .iter()
+                .find(|log| log.action == action)
+                .unwrap_or_else(|| panic!("{action:?} was not recorded"))
+        };
+
+        let updated = entry(AuditAction::ItemUpdated);
+        assert_eq!(updated.target_type, AuditTargetType::Item);
+        assert_eq!(updated.target_id, item_id.raw());
+        assert_eq!(updated.before.as_ref().unwrap()["name"], "test item");
+        assert_eq!(updated.after.as_ref().unwrap()["name"], "renamed item");
+
+        let role_change = entry(AuditAction::UserRoleChanged);
+        assert_eq!(role_change.before.as_ref().unwrap()["role"], "User");
+        assert_eq!(role_change.after.as_ref().unwrap()["role"], "Admin");
+        assert!(
+            role_change.after.as_ref().unwrap()["password_hash"].is_null(),
+            "password hashes must not be recorded"
+        );
+
+        let checked_out = entry(AuditAction::ItemCheckedOut);
+        assert_eq!(checked_out.target_id, checkout_id.raw());
+        assert!(checked_out.before.is_none());
+
+        let returned = entry(AuditAction::ItemReturned);
+        assert!(returned.before.as_ref().unwrap()["returned_at"].is_null());
+        assert!(returned.after.as_ref().unwrap()["returned_at"].is_string());
+
+        let checkouts = repo
+            .find_all(AuditLogListOptions {
+                limit: 20,
+                target_type: Some(AuditTargetType::Checkout),
+                target_id: Some(checkout_id.raw()),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(checkouts.total, 2);
+
+        let future = repo
+            .find_all(AuditLogListOptions {
+                limit: 20,
+                from: Some(Utc::now() + chrono::Duration::hours(1)),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(future.total, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_failed_mutations_are_not_audited(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let repo = AuditRepositoryImpl::new(db.clone());
+        let item_repo = ItemRepositoryImpl::new(db);
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        item_repo
+            .create(CreateItem::General {
+                requested_by: admin_id,
+                name: "Cable".into(),
+                description: "".into(),
+                location: None,
+            })
+            .await?;
+        let res = item_repo
+            .delete(DeleteItem {
+                item_id: ItemId::new(),
+                requested_by: admin_id,
+                reason: None,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        let logs = repo
+            .find_all(AuditLogListOptions {
+                limit: 20,
+                actor_id: Some(admin_id),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(logs.total, 1);
+        assert_eq!(logs.items[0].action, AuditAction::ItemCreated);
+        assert!(logs.items[0].before.is_none());
+        assert_eq!(logs.items[0].after.as_ref().unwrap()["name"], "Cable");
+
+        Ok(())
+    }
+}
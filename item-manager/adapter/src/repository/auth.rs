@@ -1,17 +1,30 @@
+use std::net::IpAddr;
+
 use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
 use derive_new::new;
 use kernel::{
     model::{
-        auth::{AccessToken, event::CreateToken},
+        auth::{AccessToken, RefreshToken, RotatedRefreshToken, event::CreateToken},
         id::UserId,
     },
     repository::auth::AuthRepository,
 };
-use shared::error::{AppError, AppResult};
+use shared::{
+    config::LoginThrottleConfig,
+    error::{AppError, AppResult},
+};
+use uuid::Uuid;
 
 use crate::database::{
     ConnectionPool,
-    model::auth::{JwtSecret, UserItem},
+    model::{
+        auth::{
+            JwtSecret, RefreshTokenRow, TokenStateRow, UserItem, generate_opaque_token,
+            hash_opaque_token,
+        },
+        password::PasswordPolicy,
+    },
 };
 
 #[derive(new)]
@@ -19,6 +32,9 @@ pub struct AuthRepositoryImpl {
     db: ConnectionPool,
     secret: JwtSecret,
     ttl: u64,
+    refresh_ttl: u64,
+    throttle: LoginThrottleConfig,
+    password_policy: PasswordPolicy,
 }
 
 #[async_trait]
@@ -27,41 +43,467 @@ impl AuthRepository for AuthRepositoryImpl {
         &self,
         access_token: &AccessToken,
     ) -> AppResult<Option<UserId>> {
-        self.secret.verify_token(access_token)
+        let Some(claims) = self.secret.verify_token(access_token)? else {
+            return Ok(None);
+        };
+        let user_id = claims.user_id()?;
+
+        let state = sqlx::query_as!(
+            TokenStateRow,
+            r#"
+                SELECT
+                    EXISTS (
+                        SELECT 1 FROM revoked_tokens WHERE jti = $2
+                    ) AS "revoked!",
+                    u.deactivated_at IS NOT NULL AS "deactivated!",
+                    u.token_generation
+                FROM users AS u
+                WHERE u.user_id = $1
+            "#,
+            user_id.raw(),
+            claims.token_id()?
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        // A missing user means the account was deleted, which revokes every token it held.
+        let Some(state) = state else {
+            return Ok(None);
+        };
+        if state.revoked || state.deactivated || claims.generation != state.token_generation {
+            return Ok(None);
+        }
+
+        Ok(Some(user_id))
     }
 
-    async fn verify_user(&self, email: &str, password: &str) -> AppResult<UserId> {
+    async fn verify_user(
+        &self,
+        email: &str,
+        password: &str,
+        client_ip: Option<IpAddr>,
+    ) -> AppResult<UserId> {
+        let email_subject = email_throttle_subject(email);
+        let mut subjects = vec![(email_subject.clone(), self.throttle.max_email_failures)];
+        if let Some(client_ip) = client_ip {
+            subjects.push((format!("ip:{client_ip}"), self.throttle.max_ip_failures));
+        }
+        // Rows are always locked in the same order so that concurrent attempts cannot deadlock.
+        subjects.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        // Every subject's row is created if needed and locked until the attempt is settled, so
+        // concurrent attempts are checked one at a time against the failures counted so far.
+        let mut tx = self.db.begin().await?;
+        let mut locked_until = None;
+        for (subject, _) in &subjects {
+            let subject_locked_until = sqlx::query_scalar!(
+                r#"
+                    INSERT INTO login_throttles (subject, failed_attempts, last_failed_at)
+                    VALUES ($1, 0, CURRENT_TIMESTAMP(3))
+                    ON CONFLICT (subject) DO UPDATE SET subject = EXCLUDED.subject
+                    RETURNING
+                        CASE
+                            WHEN locked_until > CURRENT_TIMESTAMP(3) THEN locked_until
+                        END AS locked_until;
+                "#,
+                subject
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+            locked_until = locked_until.max(subject_locked_until);
+        }
+
+        // Locked out subjects are refused before the password is even checked. Dropping the
+        // transaction discards any row created above.
+        if let Some(locked_until) = locked_until {
+            let remaining = (locked_until - Utc::now()).num_milliseconds();
+            return Err(AppError::TooManyRequests {
+                retry_after: (remaining.max(1) as u64).div_ceil(1000),
+            });
+        }
+
         let user_item = sqlx::query_as!(
             UserItem,
             r#"
-                SELECT user_id, password_hash FROM users
+                SELECT
+                    user_id,
+                    password_hash,
+                    deactivated_at IS NOT NULL AS "deactivated!"
+                FROM users
                 WHERE email = $1;
             "#,
             email
         )
-        .fetch_one(self.db.inner_ref())
+        .fetch_optional(&mut *tx)
         .await
-        .map_err(|e| match e {
-            sqlx::Error::RowNotFound => AppError::UnauthenticatedError,
-            other => AppError::SpecificOperationError(other),
-        })?;
-        let valid = bcrypt::verify(password, &user_item.password_hash)?;
-        if !valid {
-            return Err(AppError::UnauthenticatedError);
+        .map_err(AppError::SpecificOperationError)?;
+        let user_item = match user_item {
+            Some(item) if self.password_policy.verify(password, &item.password_hash)? => item,
+            _ => {
+                self.record_login_failure(&mut tx, &subjects).await?;
+                tx.commit().await.map_err(AppError::TransactionError)?;
+                return Err(AppError::UnauthenticatedError);
+            }
+        };
+
+        // Only the email counter is cleared, so a client cannot reset its IP counter by
+        // signing in to an account of its own. IP rows that only existed for locking go too.
+        let subject_keys: Vec<String> = subjects.iter().map(|(key, _)| key.clone()).collect();
+        sqlx::query!(
+            r#"
+                DELETE FROM login_throttles
+                WHERE subject = $1
+                   OR (subject = ANY($2) AND failed_attempts = 0);
+            "#,
+            email_subject,
+            &subject_keys
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        // The plain password is only available now, so this is when a hash made with outdated
+        // settings can be replaced. A concurrent password change takes precedence.
+        if self.password_policy.needs_rehash(&user_item.password_hash) {
+            sqlx::query!(
+                r#"
+                    UPDATE users
+                    SET password_hash = $2
+                    WHERE user_id = $1
+                      AND password_hash = $3;
+                "#,
+                user_item.user_id.raw(),
+                self.password_policy.hash(password)?,
+                user_item.password_hash
+            )
+            .execute(self.db.inner_ref())
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+        }
+
+        // Only reported once the password matched, so the account state is not disclosed.
+        if user_item.deactivated {
+            return Err(AppError::ForbiddenOperation(
+                "This account has been deactivated.".into(),
+            ));
         }
         Ok(user_item.user_id)
     }
 
     async fn create_token(&self, event: CreateToken) -> AppResult<AccessToken> {
-        self.secret.create_token(event.user_id, self.ttl)
+        let generation = sqlx::query_scalar!(
+            r#"
+                SELECT token_generation FROM users WHERE user_id = $1
+            "#,
+            event.user_id.raw()
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| AppError::EntityNotFound("specified user not found".into()))?;
+
+        self.secret
+            .create_token(event.user_id, generation, self.ttl)
+    }
+
+    async fn revoke_token(&self, access_token: &AccessToken) -> AppResult<()> {
+        // Expired or malformed tokens are already unusable, so there is nothing to record.
+        let Ok(Some(claims)) = self.secret.verify_token(access_token) else {
+            return Ok(());
+        };
+        let expires_at = DateTime::<Utc>::from_timestamp(claims.exp, 0).ok_or_else(|| {
+            AppError::ConversionEntityError("Invalid token expiration".to_string())
+        })?;
+
+        let mut tx = self.db.begin().await?;
+
+        sqlx::query!(
+            r#"
+                DELETE FROM revoked_tokens WHERE expires_at < CURRENT_TIMESTAMP(3);
+            "#
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO revoked_tokens (jti, user_id, expires_at)
+                SELECT $1, user_id, $3 FROM users WHERE user_id = $2
+                ON CONFLICT (jti) DO NOTHING;
+            "#,
+            claims.token_id()?,
+            claims.user_id()?.raw(),
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn create_refresh_token(&self, event: CreateToken) -> AppResult<RefreshToken> {
+        let mut tx = self.db.begin().await?;
+
+        sqlx::query!(
+            r#"
+                DELETE FROM refresh_tokens WHERE expires_at < CURRENT_TIMESTAMP(3);
+            "#
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let refresh_token = self
+            .insert_refresh_token(&mut tx, Uuid::new_v4(), event.user_id)
+            .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(refresh_token)
+    }
+
+    async fn rotate_refresh_token(
+        &self,
+        refresh_token: &RefreshToken,
+    ) -> AppResult<RotatedRefreshToken> {
+        let mut tx = self.db.begin().await?;
+
+        let row = sqlx::query_as!(
+            RefreshTokenRow,
+            r#"
+                SELECT
+                    refresh_token_id,
+                    family_id,
+                    user_id,
+                    expires_at,
+                    rotated_at,
+                    revoked_at
+                FROM refresh_tokens
+                WHERE token_hash = $1
+                FOR UPDATE
+            "#,
+            hash_opaque_token(&refresh_token.0)
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or(AppError::UnauthenticatedError)?;
+
+        if row.revoked_at.is_some() {
+            return Err(AppError::UnauthenticatedError);
+        }
+
+        if row.rotated_at.is_some() {
+            // A rotated token can only be presented again if it was stolen, so the whole
+            // family is revoked to cut off whoever holds the newer tokens as well.
+            sqlx::query!(
+                r#"
+                    UPDATE refresh_tokens
+                    SET revoked_at = CURRENT_TIMESTAMP(3)
+                    WHERE family_id = $1
+                      AND revoked_at IS NULL;
+                "#,
+                row.family_id
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+            tx.commit().await.map_err(AppError::TransactionError)?;
+            return Err(AppError::UnauthenticatedError);
+        }
+
+        if row.expires_at <= Utc::now() {
+            return Err(AppError::UnauthenticatedError);
+        }
+
+        sqlx::query!(
+            r#"
+                UPDATE refresh_tokens
+                SET rotated_at = CURRENT_TIMESTAMP(3)
+                WHERE refresh_token_id = $1;
+            "#,
+            row.refresh_token_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let new_refresh_token = self
+            .insert_refresh_token(&mut tx, row.family_id, row.user_id)
+            .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(RotatedRefreshToken {
+            user_id: row.user_id,
+            refresh_token: new_refresh_token,
+        })
+    }
+
+    async fn revoke_refresh_token(&self, refresh_token: &RefreshToken) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+                UPDATE refresh_tokens
+                SET revoked_at = CURRENT_TIMESTAMP(3)
+                WHERE family_id = (
+                    SELECT family_id FROM refresh_tokens WHERE token_hash = $1
+                )
+                  AND revoked_at IS NULL;
+            "#,
+            hash_opaque_token(&refresh_token.0)
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(())
+    }
+}
+
+impl AuthRepositoryImpl {
+    /// Counts a failed login against each subject and locks out those over their limit.
+    async fn record_login_failure(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        subjects: &[(String, u32)],
+    ) -> AppResult<()> {
+        let failure_window = self.throttle.failure_window as f64;
+
+        // Rows held by attempts still in progress are left for a later cleanup.
+        sqlx::query!(
+            r#"
+                DELETE FROM login_throttles
+                WHERE subject IN (
+                    SELECT subject
+                    FROM login_throttles
+                    WHERE last_failed_at < CURRENT_TIMESTAMP(3) - make_interval(secs => $1)
+                      AND (locked_until IS NULL OR locked_until < CURRENT_TIMESTAMP(3))
+                    FOR UPDATE SKIP LOCKED
+                );
+            "#,
+            failure_window
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        for (subject, max_failures) in subjects {
+            let failed_attempts = sqlx::query_scalar!(
+                r#"
+                    INSERT INTO login_throttles (subject, failed_attempts, last_failed_at)
+                    VALUES ($1, 1, CURRENT_TIMESTAMP(3))
+                    ON CONFLICT (subject) DO UPDATE SET
+                        failed_attempts = CASE
+                            WHEN login_throttles.last_failed_at
+                                < CURRENT_TIMESTAMP(3) - make_interval(secs => $2)
+                            THEN 1
+                            ELSE login_throttles.failed_attempts + 1
+                        END,
+                        last_failed_at = CURRENT_TIMESTAMP(3)
+                    RETURNING failed_attempts;
+                "#,
+                subject,
+                failure_window
+            )
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+
+            let Some(lockout) =
+                lockout_seconds(&self.throttle, failed_attempts as u32, *max_failures)
+            else {
+                continue;
+            };
+            sqlx::query!(
+                r#"
+                    UPDATE login_throttles
+                    SET locked_until = CURRENT_TIMESTAMP(3) + make_interval(secs => $2)
+                    WHERE subject = $1;
+                "#,
+                subject,
+                lockout as f64
+            )
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+        }
+
+        Ok(())
+    }
+
+    async fn insert_refresh_token(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        family_id: Uuid,
+        user_id: UserId,
+    ) -> AppResult<RefreshToken> {
+        let expires_at = Utc::now()
+            .checked_add_signed(Duration::seconds(self.refresh_ttl as i64))
+            .ok_or_else(|| {
+                AppError::ConversionEntityError(
+                    "Failed to calculate refresh token expiration".to_string(),
+                )
+            })?;
+        let refresh_token = generate_opaque_token();
+
+        sqlx::query!(
+            r#"
+                INSERT INTO refresh_tokens (family_id, user_id, token_hash, expires_at)
+                VALUES ($1, $2, $3, $4);
+            "#,
+            family_id,
+            user_id.raw(),
+            hash_opaque_token(&refresh_token),
+            expires_at
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(RefreshToken(refresh_token))
     }
 }
 
+/// The base lockout once `max_failures` is reached, doubling with every further failure.
+fn lockout_seconds(
+    throttle: &LoginThrottleConfig,
+    failed_attempts: u32,
+    max_failures: u32,
+) -> Option<u64> {
+    let doublings = failed_attempts.checked_sub(max_failures)?;
+    let factor = 1u64.checked_shl(doublings).unwrap_or(u64::MAX);
+    Some(
+        throttle
+            .base_lockout
+            .saturating_mul(factor)
+            .min(throttle.max_lockout),
+    )
+}
+
+/// Throttle key for failed logins against an email address.
+pub(crate) fn email_throttle_subject(email: &str) -> String {
+    format!("email:{}", email.to_lowercase())
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
 
-    use kernel::{model::user::event::CreateUser, repository::user::UserRepository};
+    use kernel::{
+        model::user::event::{
+            CreateUser, DeactivateUser, DeleteUser, ReactivateUser, UnlockUser, UpdateUserPassword,
+        },
+        repository::user::UserRepository,
+    };
+
+    use shared::config::{PasswordConfig, PasswordHashAlgorithm};
 
     use crate::repository::user::UserRepositoryImpl;
 
@@ -69,10 +511,18 @@ mod tests {
 
     #[sqlx::test(fixtures("common"))]
     async fn test_verify_user(pool: sqlx::PgPool) -> anyhow::Result<()> {
-        let user_repo = UserRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let user_repo =
+            UserRepositoryImpl::new(ConnectionPool::new(pool.clone()), PasswordPolicy::default());
         let ttl = 3600; // 1 hour
         let secret = JwtSecret::new("test_secret".to_string());
-        let auth_repo = AuthRepositoryImpl::new(ConnectionPool::new(pool), secret, ttl);
+        let auth_repo = AuthRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            secret,
+            ttl,
+            ttl,
+            LoginThrottleConfig::default(),
+            PasswordPolicy::default(),
+        );
 
         // Create a test user
         let user = user_repo
@@ -80,35 +530,44 @@ mod tests {
                 name: "Auth Test User".into(),
                 email: "auth_test@example.com".into(),
                 password: "test_password".into(),
+                requested_by: Some(UserId::new()),
+                invitation_code: None,
             })
             .await?;
 
         // Test with correct credentials
         let result = auth_repo
-            .verify_user("auth_test@example.com", "test_password")
+            .verify_user("auth_test@example.com", "test_password", None)
             .await?;
         assert_eq!(result, user.id);
 
         // Test with incorrect password
         let result = auth_repo
-            .verify_user("auth_test@example.com", "wrong_password")
+            .verify_user("auth_test@example.com", "wrong_password", None)
             .await;
         assert!(matches!(result, Err(AppError::UnauthenticatedError)));
 
         // Test with non-existent email
         let result = auth_repo
-            .verify_user("nonexistent@example.com", "test_password")
+            .verify_user("nonexistent@example.com", "test_password", None)
             .await;
         assert!(matches!(result, Err(AppError::UnauthenticatedError)));
 
         Ok(())
     }
 
-    #[sqlx::test(fixtures("common"))]
+    #[sqlx::test(fixtures("common", "item"))]
     async fn test_token_operations(pool: sqlx::PgPool) -> anyhow::Result<()> {
         let secret = JwtSecret::new("test_secret".to_string());
         let ttl = 3600; // 1 hour
-        let auth_repo = AuthRepositoryImpl::new(ConnectionPool::new(pool), secret, ttl);
+        let auth_repo = AuthRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            secret,
+            ttl,
+            ttl,
+            LoginThrottleConfig::default(),
+            PasswordPolicy::default(),
+        );
 
         let user_id = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
 
@@ -122,4 +581,435 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test(fixtures("common", "item"))]
+    async fn test_revoke_token(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let secret = JwtSecret::new("test_secret".to_string());
+        let auth_repo = AuthRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            secret,
+            3600,
+            3600,
+            LoginThrottleConfig::default(),
+            PasswordPolicy::default(),
+        );
+
+        let user_id = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let revoked = auth_repo.create_token(CreateToken::new(user_id)).await?;
+        let other = auth_repo.create_token(CreateToken::new(user_id)).await?;
+
+        auth_repo.revoke_token(&revoked).await?;
+        // Revoking twice is harmless
+        auth_repo.revoke_token(&revoked).await?;
+
+        assert_eq!(auth_repo.fetch_user_id_from_token(&revoked).await?, None);
+        assert_eq!(
+            auth_repo.fetch_user_id_from_token(&other).await?,
+            Some(user_id)
+        );
+
+        // Malformed tokens are ignored
+        auth_repo
+            .revoke_token(&AccessToken("not-a-token".into()))
+            .await?;
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "item"))]
+    async fn test_refresh_token_rotation(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let secret = JwtSecret::new("test_secret".to_string());
+        let auth_repo = AuthRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            secret,
+            3600,
+            3600,
+            LoginThrottleConfig::default(),
+            PasswordPolicy::default(),
+        );
+
+        let user_id = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let first = auth_repo
+            .create_refresh_token(CreateToken::new(user_id))
+            .await?;
+
+        let rotated = auth_repo.rotate_refresh_token(&first).await?;
+        assert_eq!(rotated.user_id, user_id);
+        assert_ne!(rotated.refresh_token.0, first.0);
+
+        let second = auth_repo
+            .rotate_refresh_token(&rotated.refresh_token)
+            .await?;
+
+        // Presenting an already rotated token revokes the whole family
+        let reused = auth_repo.rotate_refresh_token(&first).await;
+        assert!(matches!(reused, Err(AppError::UnauthenticatedError)));
+        let latest = auth_repo.rotate_refresh_token(&second.refresh_token).await;
+        assert!(matches!(latest, Err(AppError::UnauthenticatedError)));
+
+        // Logging out revokes the family of the presented token only
+        let other = auth_repo
+            .create_refresh_token(CreateToken::new(user_id))
+            .await?;
+        let kept = auth_repo
+            .create_refresh_token(CreateToken::new(user_id))
+            .await?;
+        auth_repo.revoke_refresh_token(&other).await?;
+        let revoked = auth_repo.rotate_refresh_token(&other).await;
+        assert!(matches!(revoked, Err(AppError::UnauthenticatedError)));
+        auth_repo.rotate_refresh_token(&kept).await?;
+
+        let unknown = auth_repo
+            .rotate_refresh_token(&RefreshToken("unknown".into()))
+            .await;
+        assert!(matches!(unknown, Err(AppError::UnauthenticatedError)));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_password_change_and_deletion_revoke_tokens(
+        pool: sqlx::PgPool,
+    ) -> anyhow::Result<()> {
+        let user_repo =
+            UserRepositoryImpl::new(ConnectionPool::new(pool.clone()), PasswordPolicy::default());
+        let secret = JwtSecret::new("test_secret".to_string());
+        let auth_repo = AuthRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            secret,
+            3600,
+            3600,
+            LoginThrottleConfig::default(),
+            PasswordPolicy::default(),
+        );
+
+        let user = user_repo
+            .create(CreateUser {
+                name: "Revoke Test User".into(),
+                email: "revoke_test@example.com".into(),
+                password: "test_password".into(),
+                requested_by: Some(UserId::new()),
+                invitation_code: None,
+            })
+            .await?;
+        let token = auth_repo.create_token(CreateToken::new(user.id)).await?;
+        let refresh_token = auth_repo
+            .create_refresh_token(CreateToken::new(user.id))
+            .await?;
+
+        user_repo
+            .update_password(UpdateUserPassword {
+                user_id: user.id,
+                current_password: "test_password".into(),
+                new_password: "new_password".into(),
+            })
+            .await?;
+        assert_eq!(auth_repo.fetch_user_id_from_token(&token).await?, None);
+        let refreshed = auth_repo.rotate_refresh_token(&refresh_token).await;
+        assert!(matches!(refreshed, Err(AppError::UnauthenticatedError)));
+
+        // A token issued right after the change is valid
+        let token = auth_repo.create_token(CreateToken::new(user.id)).await?;
+        assert_eq!(
+            auth_repo.fetch_user_id_from_token(&token).await?,
+            Some(user.id)
+        );
+
+        user_repo
+            .delete(DeleteUser {
+                user_id: user.id,
+                requested_by: UserId::new(),
+            })
+            .await?;
+        assert_eq!(auth_repo.fetch_user_id_from_token(&token).await?, None);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_deactivated_user_cannot_sign_in(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let user_repo =
+            UserRepositoryImpl::new(ConnectionPool::new(pool.clone()), PasswordPolicy::default());
+        let secret = JwtSecret::new("test_secret".to_string());
+        let auth_repo = AuthRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            secret,
+            3600,
+            3600,
+            LoginThrottleConfig::default(),
+            PasswordPolicy::default(),
+        );
+
+        let user = user_repo
+            .create(CreateUser {
+                name: "Graduated User".into(),
+                email: "graduated@example.com".into(),
+                password: "test_password".into(),
+                requested_by: Some(UserId::new()),
+                invitation_code: None,
+            })
+            .await?;
+        let token = auth_repo.create_token(CreateToken::new(user.id)).await?;
+        let refresh_token = auth_repo
+            .create_refresh_token(CreateToken::new(user.id))
+            .await?;
+
+        user_repo
+            .deactivate(DeactivateUser {
+                user_id: user.id,
+                requested_by: UserId::new(),
+            })
+            .await?;
+        assert_eq!(auth_repo.fetch_user_id_from_token(&token).await?, None);
+        let refreshed = auth_repo.rotate_refresh_token(&refresh_token).await;
+        assert!(matches!(refreshed, Err(AppError::UnauthenticatedError)));
+        let result = auth_repo
+            .verify_user("graduated@example.com", "test_password", None)
+            .await;
+        assert!(matches!(result, Err(AppError::ForbiddenOperation(_))));
+        let result = auth_repo
+            .verify_user("graduated@example.com", "wrong_password", None)
+            .await;
+        assert!(matches!(result, Err(AppError::UnauthenticatedError)));
+
+        user_repo
+            .reactivate(ReactivateUser {
+                user_id: user.id,
+                requested_by: UserId::new(),
+            })
+            .await?;
+        let result = auth_repo
+            .verify_user("graduated@example.com", "test_password", None)
+            .await?;
+        assert_eq!(result, user.id);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_login_throttling(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let user_repo =
+            UserRepositoryImpl::new(ConnectionPool::new(pool.clone()), PasswordPolicy::default());
+        let secret = JwtSecret::new("test_secret".to_string());
+        let auth_repo = AuthRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            secret,
+            3600,
+            3600,
+            LoginThrottleConfig {
+                max_email_failures: 2,
+                max_ip_failures: 3,
+                base_lockout: 30,
+                max_lockout: 3600,
+                failure_window: 3600,
+            },
+            PasswordPolicy::default(),
+        );
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let attacker: Option<IpAddr> = Some("192.0.2.1".parse()?);
+        let user = user_repo
+            .create(CreateUser {
+                name: "Throttled User".into(),
+                email: "throttled@example.com".into(),
+                password: "test_password".into(),
+                requested_by: Some(admin_id),
+                invitation_code: None,
+            })
+            .await?;
+
+        // A success before the limit resets the email counter.
+        for _ in 0..2 {
+            let result = auth_repo
+                .verify_user("throttled@example.com", "wrong_password", None)
+                .await;
+            assert!(matches!(result, Err(AppError::UnauthenticatedError)));
+            auth_repo
+                .verify_user("throttled@example.com", "test_password", None)
+                .await?;
+        }
+
+        for _ in 0..2 {
+            let result = auth_repo
+                .verify_user("throttled@example.com", "wrong_password", None)
+                .await;
+            assert!(matches!(result, Err(AppError::UnauthenticatedError)));
+        }
+        // Even the right password is refused while locked out.
+        let result = auth_repo
+            .verify_user("throttled@example.com", "test_password", None)
+            .await;
+        assert!(matches!(
+            result,
+            Err(AppError::TooManyRequests { retry_after }) if (1..=30).contains(&retry_after)
+        ));
+
+        user_repo
+            .unlock(UnlockUser {
+                user_id: user.id,
+                requested_by: admin_id,
+            })
+            .await?;
+        auth_repo
+            .verify_user("throttled@example.com", "test_password", None)
+            .await?;
+        let result = user_repo
+            .unlock(UnlockUser {
+                user_id: user.id,
+                requested_by: admin_id,
+            })
+            .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+
+        // The IP counter catches guesses spread over several email addresses, even unknown ones.
+        for email in ["a@example.com", "b@example.com", "throttled@example.com"] {
+            let result = auth_repo
+                .verify_user(email, "wrong_password", attacker)
+                .await;
+            assert!(matches!(result, Err(AppError::UnauthenticatedError)));
+        }
+        let result = auth_repo
+            .verify_user("throttled@example.com", "test_password", attacker)
+            .await;
+        assert!(matches!(result, Err(AppError::TooManyRequests { .. })));
+        // Other clients are unaffected.
+        auth_repo
+            .verify_user("throttled@example.com", "test_password", None)
+            .await?;
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_login_throttling_concurrent_attempts(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let user_repo =
+            UserRepositoryImpl::new(ConnectionPool::new(pool.clone()), PasswordPolicy::default());
+        let auth_repo = std::sync::Arc::new(AuthRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            JwtSecret::new("test_secret".to_string()),
+            3600,
+            3600,
+            LoginThrottleConfig {
+                max_email_failures: 2,
+                max_ip_failures: 10,
+                base_lockout: 30,
+                max_lockout: 3600,
+                failure_window: 3600,
+            },
+            PasswordPolicy::default(),
+        ));
+        user_repo
+            .create(CreateUser {
+                name: "Throttled User".into(),
+                email: "throttled@example.com".into(),
+                password: "test_password".into(),
+                requested_by: Some(UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?),
+                invitation_code: None,
+            })
+            .await?;
+
+        // A burst of guesses gets no more password checks than the limit allows.
+        let client_ip: Option<IpAddr> = Some("192.0.2.1".parse()?);
+        let mut attempts = tokio::task::JoinSet::new();
+        for _ in 0..6 {
+            let auth_repo = auth_repo.clone();
+            attempts.spawn(async move {
+                auth_repo
+                    .verify_user("throttled@example.com", "wrong_password", client_ip)
+                    .await
+            });
+        }
+        let (mut rejected, mut throttled) = (0, 0);
+        for result in attempts.join_all().await {
+            match result {
+                Err(AppError::UnauthenticatedError) => rejected += 1,
+                Err(AppError::TooManyRequests { .. }) => throttled += 1,
+                other => panic!("unexpected result: {other:?}"),
+            }
+        }
+        assert_eq!((rejected, throttled), (2, 4));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lockout_backoff() {
+        let throttle = LoginThrottleConfig {
+            max_email_failures: 5,
+            max_ip_failures: 20,
+            base_lockout: 30,
+            max_lockout: 3600,
+            failure_window: 86_400,
+        };
+        let lockout = |failed_attempts| lockout_seconds(&throttle, failed_attempts, 5);
+        assert_eq!(lockout(4), None);
+        assert_eq!(lockout(5), Some(30));
+        assert_eq!(lockout(6), Some(60));
+        assert_eq!(lockout(8), Some(240));
+        assert_eq!(lockout(12), Some(3600));
+        assert_eq!(lockout(200), Some(3600));
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_verify_user_upgrades_password_hash(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let bcrypt = PasswordPolicy::new(PasswordConfig {
+            bcrypt_cost: 4,
+            ..Default::default()
+        })?;
+        let argon2 = PasswordPolicy::new(PasswordConfig {
+            algorithm: PasswordHashAlgorithm::Argon2id,
+            argon2_memory_kib: 1024,
+            argon2_iterations: 1,
+            ..Default::default()
+        })?;
+        let user_repo = UserRepositoryImpl::new(ConnectionPool::new(pool.clone()), bcrypt);
+        let auth_repo = AuthRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            JwtSecret::new("test_secret".to_string()),
+            3600,
+            3600,
+            LoginThrottleConfig::default(),
+            argon2.clone(),
+        );
+        let user = user_repo
+            .create(CreateUser {
+                name: "Rehash User".into(),
+                email: "rehash@example.com".into(),
+                password: "test_password".into(),
+                requested_by: None,
+                invitation_code: None,
+            })
+            .await?;
+        let password_hash = || {
+            sqlx::query_scalar!(
+                "SELECT password_hash FROM users WHERE user_id = $1",
+                user.id.raw()
+            )
+            .fetch_one(&pool)
+        };
+        let original = password_hash().await?;
+        assert!(argon2.needs_rehash(&original));
+
+        // A failed login leaves the hash alone.
+        let res = auth_repo
+            .verify_user("rehash@example.com", "wrong_password", None)
+            .await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+        assert_eq!(password_hash().await?, original);
+
+        auth_repo
+            .verify_user("rehash@example.com", "test_password", None)
+            .await?;
+        let upgraded = password_hash().await?;
+        assert!(upgraded.starts_with("$argon2id$"));
+        assert!(!argon2.needs_rehash(&upgraded));
+        assert_eq!(
+            auth_repo
+                .verify_user("rehash@example.com", "test_password", None)
+                .await?,
+            user.id
+        );
+        assert_eq!(password_hash().await?, upgraded);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,464 @@
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::{
+    audit::AuditAction,
+    category::{
+        AttributeDefinition, CustomCategory,
+        event::{CreateCustomCategory, DeleteCustomCategory, UpdateCustomCategory},
+        validate_definitions,
+    },
+    id::{CustomCategoryId, ItemId},
+};
+use kernel::repository::category::CustomCategoryRepository;
+use shared::error::{AppError, AppResult};
+use sqlx::types::Json;
+
+use crate::database::{ConnectionPool, model::category::CustomCategoryRow};
+use crate::repository::audit::{AuditEntry, record_audit};
+
+#[derive(new)]
+pub struct CustomCategoryRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl CustomCategoryRepository for CustomCategoryRepositoryImpl {
+    async fn create(&self, event: CreateCustomCategory) -> AppResult<CustomCategory> {
+        validate_definitions(&event.attributes)?;
+
+        let mut tx = self.db.begin().await?;
+
+        let row = sqlx::query_as!(
+            CustomCategoryRow,
+            r#"
+                INSERT INTO custom_categories (key, name, attributes)
+                VALUES ($1, $2, $3)
+                RETURNING
+                    custom_category_id AS "custom_category_id: CustomCategoryId",
+                    key,
+                    name,
+                    attributes AS "attributes: Json<Vec<AttributeDefinition>>"
+            "#,
+            event.key,
+            event.name.trim(),
+            Json(&event.attributes) as _
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("23505") => {
+                AppError::Conflict("A custom category with the same key already exists.".into())
+            }
+            _ => AppError::SpecificOperationError(e),
+        })?;
+        let category = CustomCategory::from(row);
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.requested_by,
+                AuditAction::CustomCategoryCreated,
+                category.id.raw(),
+                None,
+                Some(category_snapshot(&category)),
+            ),
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(category)
+    }
+
+    async fn find_all(&self) -> AppResult<Vec<CustomCategory>> {
+        let rows = sqlx::query_as!(
+            CustomCategoryRow,
+            r#"
+                SELECT
+                    custom_category_id AS "custom_category_id: CustomCategoryId",
+                    key,
+                    name,
+                    attributes AS "attributes: Json<Vec<AttributeDefinition>>"
+                FROM custom_categories
+                ORDER BY LOWER(name), key
+            "#
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(rows.into_iter().map(CustomCategory::from).collect())
+    }
+
+    async fn update(&self, event: UpdateCustomCategory) -> AppResult<CustomCategory> {
+        validate_definitions(&event.attributes)?;
+
+        let mut tx = self.db.begin().await?;
+
+        let before = find_custom_category(&mut tx, event.custom_category_id).await?;
+        let after = CustomCategory {
+            name: event.name.trim().to_string(),
+            attributes: event.attributes,
+            ..before.clone()
+        };
+
+        // Items are locked so none is created or changed against the old
+        // definitions while the new ones are checked.
+        let items = sqlx::query!(
+            r#"
+                SELECT
+                    item_id AS "item_id: ItemId",
+                    attributes AS "attributes!: Json<serde_json::Map<String, serde_json::Value>>"
+                FROM items
+                WHERE custom_category_id = $1
+                ORDER BY created_at, item_id
+                FOR UPDATE
+            "#,
+            event.custom_category_id.raw()
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        for item in items {
+            after.validate_attributes(item.attributes.0).map_err(|e| {
+                AppError::Conflict(format!(
+                    "The item ({}) does not match the new attributes: {e}",
+                    item.item_id
+                ))
+            })?;
+        }
+
+        sqlx::query!(
+            r#"
+                UPDATE custom_categories
+                SET
+                    name = $2,
+                    attributes = $3
+                WHERE custom_category_id = $1
+            "#,
+            event.custom_category_id.raw(),
+            after.name,
+            Json(&after.attributes) as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.requested_by,
+                AuditAction::CustomCategoryUpdated,
+                after.id.raw(),
+                Some(category_snapshot(&before)),
+                Some(category_snapshot(&after)),
+            ),
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(after)
+    }
+
+    async fn delete(&self, event: DeleteCustomCategory) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        let before = find_custom_category(&mut tx, event.custom_category_id).await?;
+        let in_use = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS(
+                    SELECT 1 FROM items WHERE custom_category_id = $1
+                ) AS "in_use!"
+            "#,
+            event.custom_category_id.raw()
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if in_use {
+            return Err(AppError::Conflict(format!(
+                "The custom category ({}) still has items.",
+                before.key
+            )));
+        }
+
+        sqlx::query!(
+            r#"
+                DELETE FROM custom_categories WHERE custom_category_id = $1
+            "#,
+            event.custom_category_id.raw()
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.requested_by,
+                AuditAction::CustomCategoryDeleted,
+                before.id.raw(),
+                Some(category_snapshot(&before)),
+                None,
+            ),
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+}
+
+async fn find_custom_category(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    custom_category_id: CustomCategoryId,
+) -> AppResult<CustomCategory> {
+    sqlx::query_as!(
+        CustomCategoryRow,
+        r#"
+            SELECT
+                custom_category_id AS "custom_category_id: CustomCategoryId",
+                key,
+                name,
+                attributes AS "attributes: Json<Vec<AttributeDefinition>>"
+            FROM custom_categories
+            WHERE custom_category_id = $1
+            FOR UPDATE
+        "#,
+        custom_category_id.raw()
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?
+    .map(CustomCategory::from)
+    .ok_or_else(|| AppError::EntityNotFound("specified custom category not found".into()))
+}
+
+/// Looks a category up by key for an item being stored in it. The row is
+/// share-locked, so its definitions cannot change before the item is written.
+pub(crate) async fn find_custom_category_by_key(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    key: &str,
+) -> AppResult<CustomCategory> {
+    sqlx::query_as!(
+        CustomCategoryRow,
+        r#"
+            SELECT
+                custom_category_id AS "custom_category_id: CustomCategoryId",
+                key,
+                name,
+                attributes AS "attributes: Json<Vec<AttributeDefinition>>"
+            FROM custom_categories
+            WHERE key = $1
+            FOR SHARE
+        "#,
+        key
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?
+    .map(CustomCategory::from)
+    .ok_or_else(|| AppError::UnprocessableEntity(format!("Unknown custom category: {key}")))
+}
+
+fn category_snapshot(category: &CustomCategory) -> serde_json::Value {
+    serde_json::json!({
+        "custom_category_id": category.id.raw(),
+        "key": category.key,
+        "name": category.name,
+        "attributes": category.attributes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use kernel::{
+        model::{category::AttributeType, id::UserId, item::CreateItem},
+        repository::item::ItemRepository,
+    };
+    use serde_json::json;
+
+    use crate::repository::item::ItemRepositoryImpl;
+
+    use super::*;
+
+    fn admin_id() -> UserId {
+        UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c").unwrap()
+    }
+
+    fn definition(key: &str, attribute_type: AttributeType, required: bool) -> AttributeDefinition {
+        AttributeDefinition {
+            key: key.into(),
+            label: key.replace('_', " "),
+            attribute_type,
+            required,
+            options: vec![],
+        }
+    }
+
+    fn create_monitor() -> CreateCustomCategory {
+        CreateCustomCategory {
+            key: "monitor".into(),
+            name: " Monitor ".into(),
+            attributes: vec![
+                definition("screen_size", AttributeType::Number, true),
+                definition("manual", AttributeType::Url, false),
+            ],
+            requested_by: admin_id(),
+        }
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_custom_categories(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = CustomCategoryRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+
+        let monitor = repo.create(create_monitor()).await?;
+        assert_eq!(monitor.name, "Monitor");
+        let res = repo.create(create_monitor()).await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        // Definitions are checked before anything is stored
+        let res = repo
+            .create(CreateCustomCategory {
+                key: "router".into(),
+                name: "Router".into(),
+                attributes: vec![
+                    definition("mac", AttributeType::MacAddress, true),
+                    definition("mac", AttributeType::String, false),
+                ],
+                requested_by: admin_id(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        let res = repo
+            .create(CreateCustomCategory {
+                key: "router".into(),
+                name: "Router".into(),
+                attributes: vec![definition("band", AttributeType::Enum, false)],
+                requested_by: admin_id(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        let keyboard = repo
+            .create(CreateCustomCategory {
+                key: "keyboard".into(),
+                name: "Keyboard".into(),
+                attributes: vec![],
+                requested_by: admin_id(),
+            })
+            .await?;
+        assert_eq!(
+            repo.find_all().await?,
+            vec![keyboard.clone(), monitor.clone()]
+        );
+
+        let updated = repo
+            .update(UpdateCustomCategory {
+                custom_category_id: monitor.id,
+                name: "Display".into(),
+                attributes: vec![definition("screen_size", AttributeType::Number, false)],
+                requested_by: admin_id(),
+            })
+            .await?;
+        assert_eq!(updated.key, "monitor");
+        assert_eq!(updated.name, "Display");
+        let res = repo
+            .update(UpdateCustomCategory {
+                custom_category_id: CustomCategoryId::new(),
+                name: "Display".into(),
+                attributes: vec![],
+                requested_by: admin_id(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        repo.delete(DeleteCustomCategory {
+            custom_category_id: keyboard.id,
+            requested_by: admin_id(),
+        })
+        .await?;
+        assert_eq!(repo.find_all().await?, vec![updated]);
+        let res = repo
+            .delete(DeleteCustomCategory {
+                custom_category_id: keyboard.id,
+                requested_by: admin_id(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        let actions = sqlx::query_scalar!(
+            r#"
+                SELECT action FROM audit_logs
+                WHERE target_id = $1
+                ORDER BY created_at
+            "#,
+            keyboard.id.raw()
+        )
+        .fetch_all(&pool)
+        .await?;
+        assert_eq!(
+            actions,
+            [
+                AuditAction::CustomCategoryCreated.as_ref(),
+                AuditAction::CustomCategoryDeleted.as_ref()
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_custom_category_with_items(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = CustomCategoryRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let item_repo = ItemRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let monitor = repo.create(create_monitor()).await?;
+
+        item_repo
+            .create(CreateItem::Custom {
+                requested_by: admin_id(),
+                name: "Monitor 1".into(),
+                custom_category: "monitor".into(),
+                attributes: json!({ "screen_size": 27 }).as_object().unwrap().clone(),
+                description: "".into(),
+                location: None,
+            })
+            .await?;
+
+        // The item has no `manual`, so making it required would break the item
+        let res = repo
+            .update(UpdateCustomCategory {
+                custom_category_id: monitor.id,
+                name: monitor.name.clone(),
+                attributes: vec![
+                    definition("screen_size", AttributeType::Number, true),
+                    definition("manual", AttributeType::Url, true),
+                ],
+                requested_by: admin_id(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+        // Dropping a definition the item still uses is refused as well
+        let res = repo
+            .update(UpdateCustomCategory {
+                custom_category_id: monitor.id,
+                name: monitor.name.clone(),
+                attributes: vec![],
+                requested_by: admin_id(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+        assert_eq!(repo.find_all().await?, vec![monitor.clone()]);
+
+        let res = repo
+            .delete(DeleteCustomCategory {
+                custom_category_id: monitor.id,
+                requested_by: admin_id(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        Ok(())
+    }
+}
@@ -1,23 +1,40 @@
+use std::{collections::HashMap, str::FromStr};
+
 use async_trait::async_trait;
+use chrono::Duration;
 use derive_new::new;
+use kernel::model::audit::AuditAction;
 use kernel::model::checkout::{
-    Checkout,
-    event::{CreateCheckout, UpdateReturned},
+    Checkout, CheckoutRenewal,
+    event::{CreateCheckout, RenewCheckout, UpdateReturned},
 };
 use kernel::model::id::{CheckoutId, ItemId, UserId};
-use kernel::model::role::Role;
+use kernel::model::item::ItemCategory;
+use kernel::model::list::{Cursor, CursorDirection, CursorListOptions, PaginatedList};
+use kernel::model::role::Permission;
 use kernel::repository::checkout::CheckoutRepository;
-use shared::error::{AppError, AppResult};
+use shared::{
+    config::LoanConfig,
+    error::{AppError, AppResult},
+};
 
 use crate::database::{
     ConnectionPool,
-    model::checkout::{CheckoutRow, CheckoutStateRow, ReturnedCheckoutRow},
+    model::checkout::{
+        CheckoutHistoryRow, CheckoutRenewalRow, CheckoutRow, CheckoutStateRow, RenewalStateRow,
+    },
     set_transaction_serializable,
 };
+use crate::repository::audit::{AuditEntry, checkout_snapshot, record_audit};
+use crate::repository::pagination::{KeysetPage, keyset_page};
+use crate::repository::reservation::refresh_item_hold;
+use crate::repository::{item::ensure_not_archived, user::ensure_user_active};
 
 #[derive(new)]
 pub struct CheckoutRepositoryImpl {
     db: ConnectionPool,
+    reservation_hold_ttl: u64,
+    loan: LoanConfig,
 }
 
 #[async_trait]
@@ -65,16 +82,66 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
             }
         }
 
+        ensure_not_archived(&mut tx, event.item_id).await?;
+        ensure_user_active(&mut tx, event.checked_out_by).await?;
+
+        // While the item is held for a reservation, only its holder may check it out
+        if let Some(hold) = refresh_item_hold(
+            &mut tx,
+            event.item_id,
+            event.checked_out_at,
+            self.reservation_hold_ttl,
+        )
+        .await?
+        {
+            if hold.user_id != event.checked_out_by {
+                return Err(AppError::Conflict(format!(
+                    "The item ({}) is reserved for another user until {}.",
+                    event.item_id, hold.expires_at
+                )));
+            }
+
+            sqlx::query!(
+                r#"
+                    DELETE FROM reservations WHERE reservation_id = $1;
+                "#,
+                hold.reservation_id.raw(),
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+        }
+
+        let due_at = match event.due_at {
+            Some(due_at) => due_at,
+            None => {
+                let category = sqlx::query_scalar!(
+                    r#"
+                        SELECT category FROM items WHERE item_id = $1;
+                    "#,
+                    event.item_id.raw()
+                )
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(AppError::SpecificOperationError)?;
+
+                event.checked_out_at + self.loan_period(&category)?
+            }
+        };
+
+        let checkout_id = CheckoutId::new();
         let res = sqlx::query!(
             r#"
                 INSERT INTO checkouts
-                (item_id, user_id, checked_out_at)
-                VALUES ($1, $2, $3)
+                (checkout_id, item_id, user_id, checked_out_at, due_at)
+                VALUES ($1, $2, $3, $4, $5)
                 ;
             "#,
+            checkout_id.raw(),
             event.item_id.raw(),
             event.checked_out_by.raw(),
             event.checked_out_at,
+            due_at,
         )
         .execute(&mut *tx)
         .await
@@ -86,6 +153,19 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
             ));
         }
 
+        let after = checkout_snapshot(&mut tx, checkout_id).await?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.requested_by,
+                AuditAction::ItemCheckedOut,
+                checkout_id.raw(),
+                None,
+                after,
+            ),
+        )
+        .await?;
+
         tx.commit().await.map_err(AppError::TransactionError)?;
 
         Ok(())
@@ -120,9 +200,13 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
                     checkout_id: Some(_c),
                     user_id: Some(u),
                     ..
-                }) if u != event.returned_by && event.returned_by_role != Role::Admin => {
+                }) if u != event.returned_by
+                    && !event
+                        .returned_by_role
+                        .has_permission(Permission::CheckoutsManageOthers) =>
+                {
                     return Err(AppError::ForbiddenOperation(format!(
-                        "Designated checkout (id({}), users({}), items({})) cannot be returned by non-admin user",
+                        "Designated checkout (id({}), users({}), items({})) cannot be returned by another user",
                         event.checkout_id, event.returned_by, event.item_id
                     )));
                 }
@@ -136,11 +220,13 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
             }
         }
 
+        let before = checkout_snapshot(&mut tx, event.checkout_id).await?;
+
         let res = sqlx::query!(
             r#"
                 INSERT INTO returned_checkouts
-                (checkout_id, item_id, user_id, checked_out_at, returned_at)
-                SELECT checkout_id, item_id, user_id, checked_out_at, $2
+                (checkout_id, item_id, user_id, checked_out_at, due_at, returned_at)
+                SELECT checkout_id, item_id, user_id, checked_out_at, due_at, $2
                 FROM checkouts
                 WHERE checkout_id = $1
                   AND item_id = $3
@@ -177,20 +263,170 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
             ));
         }
 
+        // Start the exclusive window for the head of the reservation queue, if any
+        refresh_item_hold(
+            &mut tx,
+            event.item_id,
+            event.returned_at,
+            self.reservation_hold_ttl,
+        )
+        .await?;
+
+        let after = checkout_snapshot(&mut tx, event.checkout_id).await?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.returned_by,
+                AuditAction::ItemReturned,
+                event.checkout_id.raw(),
+                before,
+                after,
+            ),
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn renew(&self, event: RenewCheckout) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        set_transaction_serializable(&mut tx).await?;
+
+        let state = sqlx::query_as!(
+            RenewalStateRow,
+            r#"
+                SELECT
+                c.user_id,
+                i.category,
+                c.due_at,
+                (
+                    SELECT COUNT(*) FROM checkout_renewals AS cr
+                    WHERE cr.checkout_id = c.checkout_id
+                ) AS "renewal_count!",
+                EXISTS(
+                    SELECT 1 FROM reservations AS r WHERE r.item_id = c.item_id
+                ) AS "reserved!"
+                FROM checkouts AS c
+                INNER JOIN items AS i USING(item_id)
+                WHERE c.checkout_id = $1
+                  AND c.item_id = $2
+                FOR UPDATE OF c;
+            "#,
+            event.checkout_id.raw(),
+            event.item_id.raw(),
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let state = match state {
+            None => {
+                return Err(AppError::EntityNotFound(format!(
+                    "Checkout ({}) for item ({}) not found.",
+                    event.checkout_id, event.item_id
+                )));
+            }
+            Some(state)
+                if state.user_id != event.renewed_by
+                    && !event
+                        .renewed_by_role
+                        .has_permission(Permission::CheckoutsManageOthers) =>
+            {
+                return Err(AppError::ForbiddenOperation(format!(
+                    "Designated checkout (id({}), users({}), items({})) cannot be renewed by another user",
+                    event.checkout_id, event.renewed_by, event.item_id
+                )));
+            }
+            Some(state) => state,
+        };
+
+        if state.renewal_count >= i64::from(self.loan.max_renewals) {
+            return Err(AppError::Conflict(format!(
+                "Checkout ({}) has already been renewed the maximum of {} times.",
+                event.checkout_id, self.loan.max_renewals
+            )));
+        }
+        if state.reserved {
+            return Err(AppError::Conflict(format!(
+                "The item ({}) has been reserved by another user and cannot be renewed.",
+                event.item_id
+            )));
+        }
+
+        // Extend from the current due date, or from now if the loan is already overdue
+        let due_at = match event.due_at {
+            Some(due_at) => due_at,
+            None => state.due_at.max(event.renewed_at) + self.loan_period(&state.category)?,
+        };
+
+        let before = checkout_snapshot(&mut tx, event.checkout_id).await?;
+
+        sqlx::query!(
+            r#"
+                UPDATE checkouts SET due_at = $2 WHERE checkout_id = $1;
+            "#,
+            event.checkout_id.raw(),
+            due_at,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO checkout_renewals
+                (checkout_id, item_id, user_id, renewed_at, previous_due_at, due_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ;
+            "#,
+            event.checkout_id.raw(),
+            event.item_id.raw(),
+            event.renewed_by.raw(),
+            event.renewed_at,
+            state.due_at,
+            due_at,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::NoRowsAffectedError(
+                "No renewal record has been created".into(),
+            ));
+        }
+
+        let after = checkout_snapshot(&mut tx, event.checkout_id).await?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.renewed_by,
+                AuditAction::CheckoutRenewed,
+                event.checkout_id.raw(),
+                before,
+                after,
+            ),
+        )
+        .await?;
+
         tx.commit().await.map_err(AppError::TransactionError)?;
 
         Ok(())
     }
 
     async fn find_unreturned_all(&self) -> AppResult<Vec<Checkout>> {
-        sqlx::query_as!(
+        let checkouts: Vec<Checkout> = sqlx::query_as!(
             CheckoutRow,
             r#"
                 SELECT
                 c.checkout_id,
                 c.item_id,
                 c.user_id,
-                c.checked_out_at
+                c.checked_out_at,
+                c.due_at
                 FROM checkouts AS c
                 ORDER BY c.checked_out_at ASC
                 ;
@@ -199,18 +435,45 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
         .fetch_all(self.db.inner_ref())
         .await
         .map(|rows| rows.into_iter().map(Checkout::from).collect())
-        .map_err(AppError::SpecificOperationError)
+        .map_err(AppError::SpecificOperationError)?;
+
+        self.with_renewals(checkouts).await
+    }
+
+    async fn find_overdue_all(&self) -> AppResult<Vec<Checkout>> {
+        let checkouts: Vec<Checkout> = sqlx::query_as!(
+            CheckoutRow,
+            r#"
+                SELECT
+                c.checkout_id,
+                c.item_id,
+                c.user_id,
+                c.checked_out_at,
+                c.due_at
+                FROM checkouts AS c
+                WHERE c.due_at < CURRENT_TIMESTAMP(3)
+                ORDER BY c.due_at ASC
+                ;
+            "#,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map(|rows| rows.into_iter().map(Checkout::from).collect())
+        .map_err(AppError::SpecificOperationError)?;
+
+        self.with_renewals(checkouts).await
     }
 
     async fn find_unreturned_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Checkout>> {
-        sqlx::query_as!(
+        let checkouts: Vec<Checkout> = sqlx::query_as!(
             CheckoutRow,
             r#"
                 SELECT
                 c.checkout_id,
                 c.item_id,
                 c.user_id,
-                c.checked_out_at
+                c.checked_out_at,
+                c.due_at
                 FROM checkouts AS c
                 WHERE c.user_id = $1
                 ORDER BY c.checked_out_at ASC
@@ -221,38 +484,121 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
         .fetch_all(self.db.inner_ref())
         .await
         .map(|rows| rows.into_iter().map(Checkout::from).collect())
-        .map_err(AppError::SpecificOperationError)
+        .map_err(AppError::SpecificOperationError)?;
+
+        self.with_renewals(checkouts).await
     }
 
-    async fn find_history_by_item_id(&self, item_id: ItemId) -> AppResult<Vec<Checkout>> {
-        let checkout: Option<Checkout> = self.find_unreturned_by_item_id(item_id).await?;
-        let mut checkout_histories: Vec<Checkout> = sqlx::query_as!(
-            ReturnedCheckoutRow,
+    async fn find_history_by_item_id(
+        &self,
+        item_id: ItemId,
+        options: CursorListOptions,
+    ) -> AppResult<PaginatedList<Checkout>> {
+        let CursorListOptions { limit, cursor } = options;
+        let backwards = cursor
+            .as_ref()
+            .is_some_and(|c| c.direction == CursorDirection::Before);
+        let (cursor_time, cursor_id) = match &cursor {
+            Some(c) => (c.key_time, Some(c.id)),
+            None => (None, None),
+        };
+
+        let total = sqlx::query_scalar!(
             r#"
                 SELECT
-                rc.checkout_id,
-                rc.item_id,
-                rc.user_id,
-                rc.checked_out_at,
-                rc.returned_at
-                FROM returned_checkouts AS rc
-                WHERE rc.item_id = $1
-                ORDER BY rc.checked_out_at DESC
+                    (SELECT COUNT(*) FROM checkouts WHERE item_id = $1)
+                    + (SELECT COUNT(*) FROM returned_checkouts WHERE item_id = $1)
+                    AS "total!"
             "#,
             item_id.raw()
         )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        // Newest first, so an `After` cursor scans towards older checkouts.
+        let rows: Vec<CheckoutHistoryRow> = sqlx::query_as!(
+            CheckoutHistoryRow,
+            r#"
+                WITH history AS (
+                    SELECT
+                        c.checkout_id,
+                        c.item_id,
+                        c.user_id,
+                        c.checked_out_at,
+                        c.due_at,
+                        NULL::timestamptz AS returned_at
+                    FROM checkouts AS c
+                    WHERE c.item_id = $1
+                    UNION ALL
+                    SELECT
+                        rc.checkout_id,
+                        rc.item_id,
+                        rc.user_id,
+                        rc.checked_out_at,
+                        rc.due_at,
+                        rc.returned_at
+                    FROM returned_checkouts AS rc
+                    WHERE rc.item_id = $1
+                )
+                SELECT
+                    h.checkout_id AS "checkout_id!",
+                    h.item_id AS "item_id!",
+                    h.user_id AS "user_id!",
+                    h.checked_out_at AS "checked_out_at!",
+                    h.due_at AS "due_at!",
+                    h.returned_at AS "returned_at?"
+                FROM history AS h
+                WHERE $4::uuid IS NULL
+                   OR (
+                        CASE WHEN $2 THEN (h.checked_out_at, h.checkout_id) > ($3::timestamptz, $4)
+                             ELSE (h.checked_out_at, h.checkout_id) < ($3::timestamptz, $4)
+                        END
+                   )
+                ORDER BY
+                    CASE WHEN $2 THEN h.checked_out_at END ASC,
+                    CASE WHEN $2 THEN h.checkout_id END ASC,
+                    CASE WHEN NOT $2 THEN h.checked_out_at END DESC,
+                    CASE WHEN NOT $2 THEN h.checkout_id END DESC
+                LIMIT $5
+            "#,
+            item_id.raw(),
+            backwards,
+            cursor_time,
+            cursor_id,
+            limit.map(|limit| limit + 1),
+        )
         .fetch_all(self.db.inner_ref())
         .await
-        .map_err(AppError::SpecificOperationError)?
-        .into_iter()
-        .map(Checkout::from)
-        .collect();
+        .map_err(AppError::SpecificOperationError)?;
 
-        if let Some(co) = checkout {
-            checkout_histories.insert(0, co);
-        }
+        let page_size = limit.unwrap_or(total);
+        let KeysetPage {
+            rows,
+            next_cursor,
+            prev_cursor,
+        } = keyset_page(rows, page_size, cursor.as_ref(), false, |row, direction| {
+            Cursor {
+                key_text: None,
+                key_time: Some(row.checked_out_at),
+                id: row.checkout_id.raw(),
+                direction,
+                sort: None,
+            }
+        });
+
+        let items = self
+            .with_renewals(rows.into_iter().map(Checkout::from).collect())
+            .await?;
 
-        Ok(checkout_histories)
+        Ok(PaginatedList {
+            total,
+            limit: page_size,
+            offset: 0,
+            items,
+            next_cursor,
+            prev_cursor,
+        })
     }
 }
 
@@ -272,26 +618,54 @@ fn map_sqlx_error_on_create(err: sqlx::Error, item_id: ItemId) -> AppError {
 }
 
 impl CheckoutRepositoryImpl {
-    async fn find_unreturned_by_item_id(&self, item_id: ItemId) -> AppResult<Option<Checkout>> {
-        let res = sqlx::query_as!(
-            CheckoutRow,
+    fn loan_period(&self, category: &str) -> AppResult<Duration> {
+        let days = match ItemCategory::from_str(category) {
+            Ok(ItemCategory::General | ItemCategory::Custom) => self.loan.general_days,
+            Ok(ItemCategory::Book) => self.loan.book_days,
+            Ok(ItemCategory::Laptop) => self.loan.laptop_days,
+            Err(_) => {
+                return Err(AppError::ConversionEntityError(format!(
+                    "Unknown item category: {category}"
+                )));
+            }
+        };
+        Ok(Duration::days(days as i64))
+    }
+
+    async fn with_renewals(&self, mut checkouts: Vec<Checkout>) -> AppResult<Vec<Checkout>> {
+        let checkout_ids: Vec<CheckoutId> = checkouts.iter().map(|c| c.id).collect();
+        let rows = sqlx::query_as!(
+            CheckoutRenewalRow,
             r#"
                 SELECT
-                c.checkout_id,
-                c.item_id,
-                c.user_id,
-                c.checked_out_at
-                FROM checkouts AS c
-                WHERE c.item_id = $1
+                cr.checkout_id,
+                cr.user_id,
+                cr.renewed_at,
+                cr.previous_due_at,
+                cr.due_at
+                FROM checkout_renewals AS cr
+                WHERE cr.checkout_id = ANY($1)
+                ORDER BY cr.renewed_at ASC
+                ;
             "#,
-            item_id.raw(),
+            &checkout_ids as _
         )
-        .fetch_optional(self.db.inner_ref())
+        .fetch_all(self.db.inner_ref())
         .await
-        .map_err(AppError::SpecificOperationError)?
-        .map(Checkout::from);
+        .map_err(AppError::SpecificOperationError)?;
 
-        Ok(res)
+        let mut renewals: HashMap<CheckoutId, Vec<CheckoutRenewal>> = HashMap::new();
+        for row in rows {
+            renewals
+                .entry(row.checkout_id)
+                .or_default()
+                .push(CheckoutRenewal::from(row));
+        }
+        for checkout in checkouts.iter_mut() {
+            checkout.renewals = renewals.remove(&checkout.id).unwrap_or_default();
+        }
+
+        Ok(checkouts)
     }
 }
 
@@ -300,14 +674,19 @@ mod tests {
     use std::str::FromStr;
 
     use chrono::Utc;
-    use kernel::{model::user::event::DeleteUser, repository::user::UserRepository};
+    use kernel::{
+        model::{reservation::event::CreateReservation, role::Role, user::event::DeleteUser},
+        repository::{reservation::ReservationRepository, user::UserRepository},
+    };
 
     use super::*;
-    use crate::repository::user::UserRepositoryImpl;
+    use crate::database::model::password::PasswordPolicy;
+    use crate::repository::{reservation::ReservationRepositoryImpl, user::UserRepositoryImpl};
 
     #[sqlx::test(fixtures("common", "item"))]
     async fn test_checkout_flow(pool: sqlx::PgPool) -> anyhow::Result<()> {
-        let repo = CheckoutRepositoryImpl::new(ConnectionPool::new(pool));
+        let repo =
+            CheckoutRepositoryImpl::new(ConnectionPool::new(pool), 3600, LoanConfig::default());
 
         // Test basic checkout flow
         let item_id = ItemId::from_str("9890736e-a4e4-461a-a77d-eac3517ef113")?;
@@ -318,7 +697,9 @@ mod tests {
         let event = CreateCheckout {
             item_id,
             checked_out_by: user_id,
+            requested_by: user_id,
             checked_out_at: checkout_time,
+            due_at: None,
         };
         repo.create(event).await?;
 
@@ -332,7 +713,10 @@ mod tests {
         assert!(checkout.returned_at.is_none());
 
         // Test initial checkout history
-        let history = repo.find_history_by_item_id(item_id).await?;
+        let history = repo
+            .find_history_by_item_id(item_id, CursorListOptions::default())
+            .await?
+            .items;
         assert_eq!(history.len(), 1);
         assert_eq!(history[0].item_id, item_id);
         assert!(history[0].returned_at.is_none());
@@ -349,7 +733,10 @@ mod tests {
         repo.update_returned(event).await?;
 
         // Verify checkout is now in history
-        let history = repo.find_history_by_item_id(item_id).await?;
+        let history = repo
+            .find_history_by_item_id(item_id, CursorListOptions::default())
+            .await?
+            .items;
         assert_eq!(history.len(), 1);
         assert_eq!(history[0].item_id, item_id);
 
@@ -364,8 +751,13 @@ mod tests {
     async fn test_checkout_history_prevents_user_deletion(
         pool: sqlx::PgPool,
     ) -> anyhow::Result<()> {
-        let checkout_repo = CheckoutRepositoryImpl::new(ConnectionPool::new(pool.clone()));
-        let user_repo = UserRepositoryImpl::new(ConnectionPool::new(pool));
+        let checkout_repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            3600,
+            LoanConfig::default(),
+        );
+        let user_repo =
+            UserRepositoryImpl::new(ConnectionPool::new(pool), PasswordPolicy::default());
         let item_id = ItemId::from_str("9890736e-a4e4-461a-a77d-eac3517ef113")?;
         let user_id = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
 
@@ -373,7 +765,9 @@ mod tests {
             .create(CreateCheckout {
                 item_id,
                 checked_out_by: user_id,
+                requested_by: user_id,
                 checked_out_at: Utc::now(),
+                due_at: None,
             })
             .await?;
 
@@ -388,7 +782,12 @@ mod tests {
             })
             .await?;
 
-        let delete_result = user_repo.delete(DeleteUser { user_id }).await;
+        let delete_result = user_repo
+            .delete(DeleteUser {
+                user_id,
+                requested_by: UserId::new(),
+            })
+            .await;
         assert!(
             matches!(delete_result, Err(AppError::Conflict(_))),
             "{delete_result:?}"
@@ -399,7 +798,8 @@ mod tests {
 
     #[sqlx::test(fixtures("common", "item"))]
     async fn test_checkout_errors(pool: sqlx::PgPool) -> anyhow::Result<()> {
-        let repo = CheckoutRepositoryImpl::new(ConnectionPool::new(pool));
+        let repo =
+            CheckoutRepositoryImpl::new(ConnectionPool::new(pool), 3600, LoanConfig::default());
         let item_id = ItemId::from_str("9890736e-a4e4-461a-a77d-eac3517ef113")?;
         let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
         let user_id2 = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5")?;
@@ -409,7 +809,9 @@ mod tests {
         let event = CreateCheckout {
             item_id,
             checked_out_by: user_id1,
+            requested_by: user_id1,
             checked_out_at: checkout_time,
+            due_at: None,
         };
         repo.create(event).await?;
 
@@ -417,7 +819,9 @@ mod tests {
         let event = CreateCheckout {
             item_id,
             checked_out_by: user_id2,
+            requested_by: user_id2,
             checked_out_at: checkout_time,
+            due_at: None,
         };
         assert!(repo.create(event).await.is_err());
 
@@ -426,7 +830,9 @@ mod tests {
         let event = CreateCheckout {
             item_id: non_existent_item_id,
             checked_out_by: user_id1,
+            requested_by: user_id1,
             checked_out_at: checkout_time,
+            due_at: None,
         };
         assert!(repo.create(event).await.is_err());
 
@@ -448,7 +854,11 @@ mod tests {
     async fn test_return_with_mismatched_item_id_is_rejected(
         pool: sqlx::PgPool,
     ) -> anyhow::Result<()> {
-        let repo = CheckoutRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            3600,
+            LoanConfig::default(),
+        );
         let admin_user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
 
         let item_ids = sqlx::query_scalar::<_, ItemId>(
@@ -468,7 +878,9 @@ mod tests {
         let event = CreateCheckout {
             item_id: item_id1,
             checked_out_by: admin_user_id,
+            requested_by: admin_user_id,
             checked_out_at: Utc::now(),
+            due_at: None,
         };
         repo.create(event).await?;
 
@@ -493,7 +905,8 @@ mod tests {
 
     #[sqlx::test(fixtures("common", "item"))]
     async fn test_admin_can_return_any_item(pool: sqlx::PgPool) -> anyhow::Result<()> {
-        let repo = CheckoutRepositoryImpl::new(ConnectionPool::new(pool));
+        let repo =
+            CheckoutRepositoryImpl::new(ConnectionPool::new(pool), 3600, LoanConfig::default());
         let item_id = ItemId::from_str("9890736e-a4e4-461a-a77d-eac3517ef113")?;
         let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?; // Regular user who checks out
         let admin_user_id = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5")?; // Admin user who returns
@@ -503,7 +916,9 @@ mod tests {
         let event = CreateCheckout {
             item_id,
             checked_out_by: user_id1,
+            requested_by: user_id1,
             checked_out_at: checkout_time,
+            due_at: None,
         };
         repo.create(event).await?;
 
@@ -529,7 +944,10 @@ mod tests {
         assert_eq!(unreturned_after.len(), 0);
 
         // Verify it appears in history
-        let history = repo.find_history_by_item_id(item_id).await?;
+        let history = repo
+            .find_history_by_item_id(item_id, CursorListOptions::default())
+            .await?
+            .items;
         assert_eq!(history.len(), 1);
         assert!(history[0].returned_at.is_some());
 
@@ -538,7 +956,8 @@ mod tests {
 
     #[sqlx::test(fixtures("common", "item"))]
     async fn test_find_operations(pool: sqlx::PgPool) -> anyhow::Result<()> {
-        let repo = CheckoutRepositoryImpl::new(ConnectionPool::new(pool));
+        let repo =
+            CheckoutRepositoryImpl::new(ConnectionPool::new(pool), 3600, LoanConfig::default());
         let item_id = ItemId::from_str("9890736e-a4e4-461a-a77d-eac3517ef113")?;
         let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
         let user_id2 = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5")?;
@@ -548,7 +967,9 @@ mod tests {
         let event = CreateCheckout {
             item_id,
             checked_out_by: user_id1,
+            requested_by: user_id1,
             checked_out_at: checkout_time,
+            due_at: None,
         };
         repo.create(event).await?;
 
@@ -577,7 +998,9 @@ mod tests {
         let event = CreateCheckout {
             item_id,
             checked_out_by: user_id1,
+            requested_by: user_id1,
             checked_out_at: Utc::now(),
+            due_at: None,
         };
         repo.create(event).await?;
 
@@ -594,7 +1017,10 @@ mod tests {
         repo.update_returned(event).await?;
 
         // Verify history contains both checkouts in correct order
-        let history = repo.find_history_by_item_id(item_id).await?;
+        let history = repo
+            .find_history_by_item_id(item_id, CursorListOptions::default())
+            .await?
+            .items;
         assert_eq!(history.len(), 2);
         assert_eq!(history[0].item_id, item_id); // Most recent first
         assert_eq!(history[1].item_id, item_id);
@@ -603,4 +1029,280 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test(fixtures("common", "item"))]
+    async fn test_history_cursor_pagination(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo =
+            CheckoutRepositoryImpl::new(ConnectionPool::new(pool), 3600, LoanConfig::default());
+        let item_id = ItemId::from_str("9890736e-a4e4-461a-a77d-eac3517ef113")?;
+        let user_id = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let now = Utc::now();
+
+        // Four returned checkouts and one that is still out, oldest first
+        let mut checkout_ids = Vec::new();
+        for hours in (0..5).rev() {
+            repo.create(CreateCheckout::new(
+                item_id,
+                user_id,
+                user_id,
+                now - Duration::hours(hours),
+                None,
+            ))
+            .await?;
+            let checkout_id = repo.find_unreturned_by_user_id(user_id).await?[0].id;
+            checkout_ids.push(checkout_id);
+            if hours > 0 {
+                repo.update_returned(UpdateReturned::new(
+                    checkout_id,
+                    item_id,
+                    user_id,
+                    Role::User,
+                    now - Duration::hours(hours) + Duration::minutes(30),
+                ))
+                .await?;
+            }
+        }
+        checkout_ids.reverse();
+        let ids =
+            |page: &PaginatedList<Checkout>| page.items.iter().map(|c| c.id).collect::<Vec<_>>();
+        let page = |cursor: Option<Cursor>| CursorListOptions {
+            limit: Some(2),
+            cursor,
+        };
+
+        let first = repo.find_history_by_item_id(item_id, page(None)).await?;
+        assert_eq!(first.total, 5);
+        assert_eq!(ids(&first), checkout_ids[0..2]);
+        assert!(first.items[0].returned_at.is_none());
+        assert!(first.prev_cursor.is_none());
+
+        let second = repo
+            .find_history_by_item_id(item_id, page(first.next_cursor))
+            .await?;
+        assert_eq!(ids(&second), checkout_ids[2..4]);
+
+        let last = repo
+            .find_history_by_item_id(item_id, page(second.next_cursor))
+            .await?;
+        assert_eq!(ids(&last), checkout_ids[4..]);
+        assert!(last.next_cursor.is_none());
+
+        let back = repo
+            .find_history_by_item_id(item_id, page(last.prev_cursor))
+            .await?;
+        assert_eq!(ids(&back), checkout_ids[2..4]);
+        let back = repo
+            .find_history_by_item_id(item_id, page(back.prev_cursor))
+            .await?;
+        assert_eq!(ids(&back), checkout_ids[0..2]);
+        assert!(back.prev_cursor.is_none());
+        assert!(back.next_cursor.is_some());
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "item"))]
+    async fn test_due_date_and_overdue(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let loan = LoanConfig {
+            general_days: 3,
+            ..LoanConfig::default()
+        };
+        let repo = CheckoutRepositoryImpl::new(ConnectionPool::new(pool), 3600, loan);
+        let item_id = ItemId::from_str("9890736e-a4e4-461a-a77d-eac3517ef113")?;
+        let user_id = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+
+        // The default loan period of the item's category applies
+        let checked_out_at = Utc::now() - Duration::days(5);
+        repo.create(CreateCheckout::new(
+            item_id,
+            user_id,
+            user_id,
+            checked_out_at,
+            None,
+        ))
+        .await?;
+
+        let checkout = repo.find_unreturned_by_user_id(user_id).await?.remove(0);
+        assert_eq!(checkout.due_at - checkout.checked_out_at, Duration::days(3));
+        assert!(checkout.is_overdue(Utc::now()));
+
+        let overdue = repo.find_overdue_all().await?;
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(overdue[0].id, checkout.id);
+
+        // The due date is kept in the history once returned
+        repo.update_returned(UpdateReturned::new(
+            checkout.id,
+            item_id,
+            user_id,
+            Role::User,
+            Utc::now(),
+        ))
+        .await?;
+        let history = repo
+            .find_history_by_item_id(item_id, CursorListOptions::default())
+            .await?
+            .items;
+        assert_eq!(history[0].due_at, checkout.due_at);
+        assert!(!history[0].is_overdue(Utc::now()));
+        assert!(repo.find_overdue_all().await?.is_empty());
+
+        // An explicit due date overrides the default
+        let due_at = Utc::now() + Duration::days(30);
+        repo.create(CreateCheckout::new(
+            item_id,
+            user_id,
+            user_id,
+            Utc::now(),
+            Some(due_at),
+        ))
+        .await?;
+        let checkout = repo.find_unreturned_by_user_id(user_id).await?.remove(0);
+        assert!((checkout.due_at - due_at).num_milliseconds().abs() <= 1);
+        assert!(repo.find_overdue_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "item"))]
+    async fn test_renewal(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let loan = LoanConfig {
+            general_days: 7,
+            max_renewals: 1,
+            ..LoanConfig::default()
+        };
+        let repo = CheckoutRepositoryImpl::new(ConnectionPool::new(pool.clone()), 3600, loan);
+        let item_id = ItemId::from_str("9890736e-a4e4-461a-a77d-eac3517ef113")?;
+        let user_id = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let other_user_id = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5")?;
+
+        repo.create(CreateCheckout::new(
+            item_id,
+            user_id,
+            user_id,
+            Utc::now(),
+            None,
+        ))
+        .await?;
+        let checkout = repo.find_unreturned_by_user_id(user_id).await?.remove(0);
+
+        // Only the borrower or an admin can renew
+        let res = repo
+            .renew(RenewCheckout::new(
+                checkout.id,
+                item_id,
+                other_user_id,
+                Role::User,
+                Utc::now(),
+                None,
+            ))
+            .await;
+        assert!(
+            matches!(res, Err(AppError::ForbiddenOperation(_))),
+            "{res:?}"
+        );
+
+        repo.renew(RenewCheckout::new(
+            checkout.id,
+            item_id,
+            user_id,
+            Role::User,
+            Utc::now(),
+            None,
+        ))
+        .await?;
+
+        let renewed = repo.find_unreturned_by_user_id(user_id).await?.remove(0);
+        assert_eq!(renewed.id, checkout.id);
+        assert_eq!(renewed.due_at - checkout.due_at, Duration::days(7));
+        assert_eq!(renewed.renewals.len(), 1);
+        assert_eq!(renewed.renewals[0].renewed_by, user_id);
+        assert_eq!(renewed.renewals[0].previous_due_at, checkout.due_at);
+        assert_eq!(renewed.renewals[0].due_at, renewed.due_at);
+
+        // The renewal limit applies
+        let res = repo
+            .renew(RenewCheckout::new(
+                checkout.id,
+                item_id,
+                user_id,
+                Role::User,
+                Utc::now(),
+                None,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))), "{res:?}");
+
+        // Renewals stay in the history after the return
+        repo.update_returned(UpdateReturned::new(
+            checkout.id,
+            item_id,
+            user_id,
+            Role::User,
+            Utc::now(),
+        ))
+        .await?;
+        let history = repo
+            .find_history_by_item_id(item_id, CursorListOptions::default())
+            .await?
+            .items;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].due_at, renewed.due_at);
+        assert_eq!(history[0].renewals.len(), 1);
+
+        // Returned checkouts can no longer be renewed
+        let res = repo
+            .renew(RenewCheckout::new(
+                checkout.id,
+                item_id,
+                user_id,
+                Role::User,
+                Utc::now(),
+                None,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))), "{res:?}");
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "item"))]
+    async fn test_renewal_refused_while_reserved(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            3600,
+            LoanConfig::default(),
+        );
+        let reservation_repo = ReservationRepositoryImpl::new(ConnectionPool::new(pool), 3600);
+        let item_id = ItemId::from_str("9890736e-a4e4-461a-a77d-eac3517ef113")?;
+        let user_id = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let other_user_id = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5")?;
+
+        repo.create(CreateCheckout::new(
+            item_id,
+            user_id,
+            user_id,
+            Utc::now(),
+            None,
+        ))
+        .await?;
+        reservation_repo
+            .create(CreateReservation::new(item_id, other_user_id, Utc::now()))
+            .await?;
+
+        let checkout = repo.find_unreturned_by_user_id(user_id).await?.remove(0);
+        let res = repo
+            .renew(RenewCheckout::new(
+                checkout.id,
+                item_id,
+                user_id,
+                Role::User,
+                Utc::now(),
+                None,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))), "{res:?}");
+
+        Ok(())
+    }
 }
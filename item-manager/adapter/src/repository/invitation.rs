@@ -0,0 +1,246 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::{
+    id::{InvitationId, UserId},
+    invitation::{
+        CreatedInvitation, Invitation,
+        event::{CreateInvitation, DeleteInvitation},
+    },
+    role::Role,
+};
+use kernel::repository::invitation::InvitationRepository;
+use shared::error::{AppError, AppResult};
+
+use crate::database::{
+    ConnectionPool,
+    model::{
+        auth::{generate_opaque_token, hash_opaque_token},
+        invitation::InvitationRow,
+    },
+};
+
+#[derive(new)]
+pub struct InvitationRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl InvitationRepository for InvitationRepositoryImpl {
+    async fn create(&self, event: CreateInvitation) -> AppResult<CreatedInvitation> {
+        let code = generate_opaque_token();
+
+        let row = sqlx::query_as!(
+            InvitationRow,
+            r#"
+                WITH inserted AS (
+                    INSERT INTO invitations (code_hash, role_id, max_uses, expires_at, created_by)
+                    SELECT $1, role_id, $3, $4, $5 FROM roles WHERE name = $2
+                    RETURNING *
+                )
+                SELECT
+                    i.invitation_id AS "invitation_id: InvitationId",
+                    r.name AS role_name,
+                    i.max_uses,
+                    i.uses,
+                    i.expires_at,
+                    i.created_by AS "created_by?: UserId",
+                    i.created_at
+                FROM inserted AS i
+                INNER JOIN roles AS r USING(role_id)
+            "#,
+            hash_opaque_token(&code),
+            event.role.as_ref(),
+            event.max_uses as i32,
+            event.expires_at,
+            event.requested_by.raw(),
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(CreatedInvitation {
+            invitation: row.try_into()?,
+            code,
+        })
+    }
+
+    async fn find_all(&self) -> AppResult<Vec<Invitation>> {
+        sqlx::query_as!(
+            InvitationRow,
+            r#"
+                SELECT
+                    i.invitation_id AS "invitation_id: InvitationId",
+                    r.name AS role_name,
+                    i.max_uses,
+                    i.uses,
+                    i.expires_at,
+                    i.created_by AS "created_by?: UserId",
+                    i.created_at
+                FROM invitations AS i
+                INNER JOIN roles AS r USING(role_id)
+                ORDER BY i.created_at DESC, i.invitation_id DESC
+            "#,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(Invitation::try_from)
+        .collect()
+    }
+
+    async fn delete(&self, event: DeleteInvitation) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM invitations WHERE invitation_id = $1
+            "#,
+            event.invitation_id.raw(),
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(format!(
+                "Invitation ({}) not found.",
+                event.invitation_id
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Counts a use of the invitation and returns the role it grants. Fails with
+/// `UnprocessableEntity` when the code is unknown, expired or used up.
+pub(crate) async fn redeem_invitation(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    code: &str,
+) -> AppResult<Role> {
+    let role_name = sqlx::query_scalar!(
+        r#"
+            UPDATE invitations AS i
+            SET uses = i.uses + 1
+            FROM roles AS r
+            WHERE i.code_hash = $1
+              AND i.expires_at > CURRENT_TIMESTAMP(3)
+              AND i.uses < i.max_uses
+              AND r.role_id = i.role_id
+            RETURNING r.name
+        "#,
+        hash_opaque_token(code),
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?
+    .ok_or_else(|| {
+        AppError::UnprocessableEntity("The invitation code is invalid, expired or used up.".into())
+    })?;
+
+    Role::from_str(&role_name).map_err(|e| AppError::ConversionEntityError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use kernel::{model::user::event::CreateUser, repository::user::UserRepository};
+
+    use crate::database::model::password::PasswordPolicy;
+    use crate::repository::user::UserRepositoryImpl;
+
+    use super::*;
+
+    fn signup(email: &str, code: &str) -> CreateUser {
+        CreateUser {
+            name: "New Member".into(),
+            email: email.into(),
+            password: "password".into(),
+            requested_by: None,
+            invitation_code: Some(code.into()),
+        }
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_invitations(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = InvitationRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let user_repo =
+            UserRepositoryImpl::new(ConnectionPool::new(pool.clone()), PasswordPolicy::default());
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        let created = repo
+            .create(CreateInvitation {
+                role: Role::Librarian,
+                max_uses: 2,
+                expires_at: Utc::now() + Duration::days(7),
+                requested_by: admin_id,
+            })
+            .await?;
+        assert_eq!(created.invitation.role, Role::Librarian);
+        assert_eq!(created.invitation.uses, 0);
+        assert_eq!(created.invitation.created_by, Some(admin_id));
+
+        let user = user_repo
+            .create(signup("first@example.com", &created.code))
+            .await?;
+        assert_eq!(user.role, Role::Librarian);
+        let actor = sqlx::query_scalar!(
+            "SELECT actor_id FROM audit_logs WHERE target_id = $1",
+            user.id.raw()
+        )
+        .fetch_one(&pool)
+        .await?;
+        assert_eq!(actor, Some(user.id.raw()));
+
+        // A rejected signup does not use up the code.
+        let result = user_repo
+            .create(signup("first@example.com", &created.code))
+            .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        assert_eq!(repo.find_all().await?[0].uses, 1);
+
+        user_repo
+            .create(signup("second@example.com", &created.code))
+            .await?;
+        let result = user_repo
+            .create(signup("third@example.com", &created.code))
+            .await;
+        assert!(matches!(result, Err(AppError::UnprocessableEntity(_))));
+        let result = user_repo
+            .create(signup("third@example.com", "unknown-code"))
+            .await;
+        assert!(matches!(result, Err(AppError::UnprocessableEntity(_))));
+
+        let expired = repo
+            .create(CreateInvitation {
+                role: Role::User,
+                max_uses: 1,
+                expires_at: Utc::now() - Duration::seconds(1),
+                requested_by: admin_id,
+            })
+            .await?;
+        let result = user_repo
+            .create(signup("third@example.com", &expired.code))
+            .await;
+        assert!(matches!(result, Err(AppError::UnprocessableEntity(_))));
+
+        let invitations = repo.find_all().await?;
+        assert_eq!(invitations.len(), 2);
+        assert_eq!(invitations[0].id, expired.invitation.id);
+        assert_eq!(invitations[1].uses, 2);
+
+        repo.delete(DeleteInvitation {
+            invitation_id: expired.invitation.id,
+        })
+        .await?;
+        let result = repo
+            .delete(DeleteInvitation {
+                invitation_id: expired.invitation.id,
+            })
+            .await;
+        assert!(matches!(result, Err(AppError::EntityNotFound(_))));
+
+        Ok(())
+    }
+}
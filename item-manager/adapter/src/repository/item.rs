@@ -3,16 +3,31 @@ use std::str::FromStr;
 
 use async_trait::async_trait;
 use derive_new::new;
-use kernel::model::id::ItemId;
-use kernel::model::item::{CreateItem, DeleteItem, ItemCategory, UpdateItem};
-use kernel::model::list::{ListOptions, PaginatedList};
-use kernel::model::{checkout::SimpleCheckout, item::Item};
-use kernel::repository::item::ItemRepository;
+use kernel::model::audit::AuditAction;
+use kernel::model::id::{CheckoutId, CustomCategoryId, ItemId, TagId, UserId};
+use kernel::model::item::{CreateItem, DeleteItem, ItemCategory, RestoreItem, UpdateItem};
+use kernel::model::list::{
+    Cursor, CursorDirection, CursorSort, ItemFilter, ListOptions, PaginatedList, SortOrder,
+    TagMatch,
+};
+use kernel::model::{checkout::SimpleCheckout, item::Item, tag::Tag};
+use kernel::repository::item::{ItemRepository, ItemStream};
+use serde_json::{Map, Value};
 use shared::error::{AppError, AppResult};
+use tokio::sync::mpsc;
+use tokio_stream::{StreamExt, wrappers::ReceiverStream};
 
 use crate::database::ConnectionPool;
-use crate::database::model::item::{ItemCheckoutRow, ItemRow, PaginatedItemRow};
+use crate::database::model::item::{ItemCheckoutRow, ItemExportRow, ItemPageRow, ItemRow};
+use crate::database::model::tag::ItemTagRow;
 use crate::database::set_transaction_serializable;
+use crate::repository::audit::{AuditEntry, item_snapshot, record_audit};
+use crate::repository::category::find_custom_category_by_key;
+use crate::repository::like_pattern;
+use crate::repository::pagination::{KeysetPage, keyset_page};
+
+/// Rows buffered between the export query and a slow consumer.
+const EXPORT_BUFFER: usize = 64;
 
 #[derive(new)]
 pub struct ItemRepositoryImpl {
@@ -22,76 +37,22 @@ pub struct ItemRepositoryImpl {
 #[async_trait]
 impl ItemRepository for ItemRepositoryImpl {
     async fn create(&self, event: CreateItem) -> AppResult<()> {
-        let category = event.as_ref();
+        let mut tx = self.db.begin().await?;
+        set_transaction_serializable(&mut tx).await?;
 
-        let (name, description, location) = match &event {
-            CreateItem::General {
-                name,
-                description,
-                location,
-            }
-            | CreateItem::Book {
-                name,
-                description,
-                location,
-                ..
-            }
-            | CreateItem::Laptop {
-                name,
-                description,
-                location,
-                ..
-            } => (name, description, location),
-        };
+        insert_item(&mut tx, &event).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
 
+        Ok(())
+    }
+
+    async fn create_many(&self, events: Vec<CreateItem>) -> AppResult<()> {
         let mut tx = self.db.begin().await?;
         set_transaction_serializable(&mut tx).await?;
 
-        let item_id = sqlx::query!(
-            r#"
-            INSERT INTO items (name, description, location, category)
-            VALUES ($1, $2, $3, $4)
-            RETURNING item_id
-        "#,
-            name,
-            description,
-            location.as_deref(),
-            category
-        )
-        .fetch_one(&mut *tx)
-        .await
-        .map_err(AppError::SpecificOperationError)?
-        .item_id;
-
-        match &event {
-            CreateItem::Book { author, isbn, .. } => {
-                sqlx::query!(
-                    r#"
-                        INSERT INTO books (item_id, author, isbn)
-                        VALUES ($1, $2, $3)
-                    "#,
-                    item_id,
-                    author,
-                    isbn,
-                )
-                .execute(&mut *tx)
-                .await
-                .map_err(AppError::SpecificOperationError)?;
-            }
-            CreateItem::Laptop { mac_address, .. } => {
-                sqlx::query!(
-                    r#"
-                        INSERT INTO laptops (item_id, mac_address)
-                        VALUES ($1, $2)
-                    "#,
-                    item_id,
-                    mac_address,
-                )
-                .execute(&mut *tx)
-                .await
-                .map_err(AppError::SpecificOperationError)?;
-            }
-            _ => {}
+        for event in &events {
+            insert_item(&mut tx, event).await?;
         }
 
         tx.commit().await.map_err(AppError::TransactionError)?;
@@ -103,43 +64,215 @@ impl ItemRepository for ItemRepositoryImpl {
         let ListOptions {
             limit,
             offset,
+            cursor,
             category,
+            filter,
+            sort,
+            order,
         } = options;
         let category_param = category.map(|value| value.as_ref().to_string());
+        let ItemFilter {
+            q,
+            author,
+            isbn,
+            mac_address,
+            location,
+            available,
+            include_archived,
+            tags,
+            tag_match,
+            custom_category,
+        } = filter;
+        let q_pattern = q.as_deref().map(like_pattern);
+        // Deduplicated, so matching every tag means matching as many as were requested.
+        let mut tags = tags
+            .iter()
+            .map(|tag| tag.trim().to_lowercase())
+            .filter(|tag| !tag.is_empty())
+            .collect::<Vec<_>>();
+        tags.sort();
+        tags.dedup();
+        let match_all_tags = tag_match == TagMatch::All;
+
+        let backwards = cursor
+            .as_ref()
+            .is_some_and(|c| c.direction == CursorDirection::Before);
+        let scan_ascending = (order == SortOrder::Asc) != backwards;
+        let offset = if cursor.is_some() { 0 } else { offset };
+        let (cursor_text, cursor_time, cursor_id) = match &cursor {
+            Some(c) => (c.key_text.as_deref(), c.key_time, Some(c.id)),
+            None => (None, None, None),
+        };
 
-        let total = sqlx::query_scalar!(
-            r#"
-                SELECT COUNT(*) AS "total!"
-                FROM items AS i
-                WHERE $1::text IS NULL OR i.category = $1
-            "#,
-            category_param.as_deref()
-        )
-        .fetch_one(self.db.inner_ref())
-        .await
-        .map_err(AppError::SpecificOperationError)?;
-
-        let rows: Vec<PaginatedItemRow> = sqlx::query_as!(
-            PaginatedItemRow,
+        // `filtered` holds every item matching the filter and is both counted
+        // and paged, so the two cannot disagree. Rows are ordered by
+        // (key is NULL, key, item_id), with NULL keys last. A `Before` cursor
+        // scans that order in reverse and the page is flipped back afterwards.
+        // An empty page still yields one row, which only carries the total.
+        let rows: Vec<ItemPageRow> = sqlx::query_as!(
+            ItemPageRow,
             r#"
+                WITH filtered AS (
+                    SELECT
+                        i.item_id,
+                        CASE $15
+                            WHEN 'name' THEN i.name
+                            WHEN 'category' THEN i.category
+                            WHEN 'location' THEN i.location
+                        END AS key_text,
+                        CASE $15
+                            WHEN 'created_at' THEN i.created_at
+                            WHEN 'updated_at' THEN i.updated_at
+                            WHEN 'checked_out_at' THEN c.checked_out_at
+                        END AS key_time
+                    FROM items AS i
+                    LEFT JOIN books AS b ON i.item_id = b.item_id
+                    LEFT JOIN laptops AS l ON i.item_id = l.item_id
+                    LEFT JOIN checkouts AS c ON i.item_id = c.item_id
+                    WHERE ($3::text IS NULL OR i.category = $3)
+                      AND (
+                        $4::text IS NULL
+                        OR to_tsvector('simple'::regconfig, i.name || ' ' || i.description || ' ' || COALESCE(i.location, ''))
+                            @@ websearch_to_tsquery('simple'::regconfig, $4)
+                        OR i.name ILIKE $5
+                        OR i.description ILIKE $5
+                        OR i.location ILIKE $5
+                        OR b.author ILIKE $5
+                        OR b.isbn ILIKE $5
+                      )
+                      AND ($6::text IS NULL OR b.author = $6)
+                      AND ($7::text IS NULL OR b.isbn = $7)
+                      AND ($8::macaddr IS NULL OR l.mac_address = $8)
+                      AND ($9::text IS NULL OR i.location = $9)
+                      AND ($10::bool IS NULL OR (c.checkout_id IS NULL) = $10)
+                      AND ($11 OR i.archived_at IS NULL)
+                      AND (
+                        cardinality($12::text[]) = 0
+                        OR (
+                            SELECT COUNT(*)
+                            FROM item_tags AS it
+                            INNER JOIN tags AS t USING(tag_id)
+                            WHERE it.item_id = i.item_id
+                              AND LOWER(t.name) = ANY($12)
+                        ) >= CASE WHEN $13 THEN cardinality($12) ELSE 1 END
+                      )
+                      AND (
+                        $14::text IS NULL
+                        OR EXISTS (
+                            SELECT 1 FROM custom_categories AS cc
+                            WHERE cc.custom_category_id = i.custom_category_id
+                              AND cc.key = $14
+                        )
+                      )
+                ),
+                ranked AS (
+                    SELECT
+                        item_id,
+                        key_text,
+                        key_time,
+                        (key_text IS NULL AND key_time IS NULL) AS key_null
+                    FROM filtered
+                ),
+                scanned AS (
+                    SELECT
+                        r.item_id,
+                        r.key_text,
+                        r.key_time,
+                        ROW_NUMBER() OVER (
+                            ORDER BY
+                                CASE WHEN $17 THEN r.key_null END ASC,
+                                CASE WHEN NOT $17 THEN r.key_null END DESC,
+                                CASE WHEN $16 THEN r.key_text END ASC,
+                                CASE WHEN NOT $16 THEN r.key_text END DESC,
+                                CASE WHEN $16 THEN r.key_time END ASC,
+                                CASE WHEN NOT $16 THEN r.key_time END DESC,
+                                CASE WHEN $16 THEN r.item_id END ASC,
+                                CASE WHEN NOT $16 THEN r.item_id END DESC
+                        ) AS position
+                    FROM ranked AS r
+                    WHERE $20::uuid IS NULL
+                       OR (
+                            CASE WHEN $17 THEN r.key_null > ($18::text IS NULL AND $19::timestamptz IS NULL)
+                                 ELSE r.key_null < ($18::text IS NULL AND $19::timestamptz IS NULL)
+                            END
+                       )
+                       OR (
+                            r.key_null = ($18::text IS NULL AND $19::timestamptz IS NULL)
+                            AND (
+                                CASE WHEN $16 THEN r.key_text > $18 ELSE r.key_text < $18 END
+                                OR (
+                                    r.key_text IS NOT DISTINCT FROM $18
+                                    AND (
+                                        CASE WHEN $16 THEN r.key_time > $19 ELSE r.key_time < $19 END
+                                        OR (
+                                            r.key_time IS NOT DISTINCT FROM $19
+                                            AND CASE WHEN $16 THEN r.item_id > $20 ELSE r.item_id < $20 END
+                                        )
+                                    )
+                                )
+                            )
+                       )
+                )
                 SELECT
-                    i.item_id AS id
-                FROM items AS i
-                WHERE $3::text IS NULL OR i.category = $3
-                ORDER BY i.created_at DESC
-                LIMIT $1
-                OFFSET $2
+                    total.count AS "total!",
+                    s.item_id AS "id?: ItemId",
+                    s.key_text AS "key_text?",
+                    s.key_time AS "key_time?"
+                FROM (SELECT COUNT(*) FROM filtered) AS total
+                LEFT JOIN scanned AS s ON s.position > $2 AND s.position <= $2 + $1
+                ORDER BY s.position
             "#,
-            limit,
+            limit + 1,
             offset,
-            category_param.as_deref()
+            category_param.as_deref(),
+            q.as_deref(),
+            q_pattern.as_deref(),
+            author.as_deref(),
+            isbn.as_deref(),
+            mac_address,
+            location.as_deref(),
+            available,
+            include_archived,
+            &tags,
+            match_all_tags,
+            custom_category.as_deref(),
+            sort.as_ref(),
+            scan_ascending,
+            !backwards,
+            cursor_text,
+            cursor_time,
+            cursor_id,
         )
         .fetch_all(self.db.inner_ref())
         .await
         .map_err(AppError::SpecificOperationError)?;
+        let total = rows.first().map_or(0, |row| row.total);
+        let rows = rows
+            .into_iter()
+            .filter_map(ItemPageRow::into_paginated)
+            .collect::<Vec<_>>();
+
+        let KeysetPage {
+            rows,
+            next_cursor,
+            prev_cursor,
+        } = keyset_page(
+            rows,
+            limit,
+            cursor.as_ref(),
+            offset > 0,
+            |row, direction| Cursor {
+                key_text: row.key_text.clone(),
+                key_time: row.key_time,
+                id: row.id.raw(),
+                direction,
+                sort: Some(CursorSort::new(sort, order)),
+            },
+        );
 
         let item_ids = rows.into_iter().map(|row| row.id).collect::<Vec<ItemId>>();
         let mut checkouts = self.find_checkouts(&item_ids).await?;
+        let mut item_tags = self.find_tags(&item_ids).await?;
 
         let rows: Vec<ItemRow> = sqlx::query_as!(
             ItemRow,
@@ -152,12 +285,19 @@ impl ItemRepository for ItemRepositoryImpl {
                     i.location AS location,
                     b.author AS "author?",
                     b.isbn AS "isbn?",
-                    l.mac_address AS "mac_address?"
-                FROM items AS i
+                    l.mac_address AS "mac_address?",
+                    i.custom_category_id AS "custom_category_id: CustomCategoryId",
+                    cc.key AS "custom_category_key?",
+                    cc.name AS "custom_category_name?",
+                    i.attributes,
+                    i.archived_at,
+                    i.archive_reason
+                FROM UNNEST($1::uuid[]) WITH ORDINALITY AS ids(item_id, ord)
+                JOIN items AS i ON i.item_id = ids.item_id
                 LEFT JOIN books b ON i.item_id = b.item_id
                 LEFT JOIN laptops l ON i.item_id = l.item_id
-                WHERE i.item_id IN (SELECT * FROM UNNEST($1::uuid[]))
-                ORDER BY i.created_at DESC
+                LEFT JOIN custom_categories cc ON i.custom_category_id = cc.custom_category_id
+                ORDER BY ids.ord
             "#,
             &item_ids as _
         )
@@ -169,7 +309,8 @@ impl ItemRepository for ItemRepositoryImpl {
             .into_iter()
             .map(|row| {
                 let checkout = checkouts.remove(&row.item_id);
-                row.into_item(checkout)
+                let tags = item_tags.remove(&row.item_id).unwrap_or_default();
+                row.into_item(checkout, tags)
             })
             .collect::<AppResult<Vec<_>>>()?;
 
@@ -178,9 +319,80 @@ impl ItemRepository for ItemRepositoryImpl {
             limit,
             offset,
             items,
+            next_cursor,
+            prev_cursor,
         })
     }
 
+    fn stream_all(&self) -> ItemStream {
+        let pool = self.db.clone();
+        let (tx, rx) = mpsc::channel(EXPORT_BUFFER);
+
+        // The query borrows the pool, so it runs in its own task and hands rows
+        // over through a bounded channel. Dropping the stream stops the task.
+        tokio::spawn(async move {
+            let mut rows = sqlx::query_as!(
+                ItemExportRow,
+                r#"
+                    SELECT
+                        i.item_id,
+                        i.category,
+                        i.name,
+                        i.description,
+                        i.location,
+                        b.author AS "author?",
+                        b.isbn AS "isbn?",
+                        l.mac_address AS "mac_address?",
+                        i.custom_category_id AS "custom_category_id: CustomCategoryId",
+                        cc.key AS "custom_category_key?",
+                        cc.name AS "custom_category_name?",
+                        i.attributes,
+                        i.archived_at,
+                        i.archive_reason,
+                        c.checkout_id AS "checkout_id?: CheckoutId",
+                        u.user_id AS "user_id?: UserId",
+                        u.name AS "user_name?",
+                        c.checked_out_at AS "checked_out_at?",
+                        c.due_at AS "due_at?",
+                        ARRAY(
+                            SELECT t.tag_id
+                            FROM item_tags AS it
+                            INNER JOIN tags AS t USING(tag_id)
+                            WHERE it.item_id = i.item_id
+                            ORDER BY LOWER(t.name), t.tag_id
+                        ) AS "tag_ids!: Vec<TagId>",
+                        ARRAY(
+                            SELECT t.name
+                            FROM item_tags AS it
+                            INNER JOIN tags AS t USING(tag_id)
+                            WHERE it.item_id = i.item_id
+                            ORDER BY LOWER(t.name), t.tag_id
+                        ) AS "tag_names!"
+                    FROM items AS i
+                    LEFT JOIN books AS b ON i.item_id = b.item_id
+                    LEFT JOIN laptops AS l ON i.item_id = l.item_id
+                    LEFT JOIN custom_categories AS cc ON i.custom_category_id = cc.custom_category_id
+                    LEFT JOIN checkouts AS c ON i.item_id = c.item_id
+                    LEFT JOIN users AS u ON c.user_id = u.user_id
+                    ORDER BY i.created_at, i.item_id
+                "#
+            )
+            .fetch(pool.inner_ref());
+
+            while let Some(row) = rows.next().await {
+                let item = row
+                    .map_err(AppError::SpecificOperationError)
+                    .and_then(ItemExportRow::into_item);
+                let failed = item.is_err();
+                if tx.send(item).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Box::pin(ReceiverStream::new(rx))
+    }
+
     async fn find_by_id(&self, item_id: ItemId) -> AppResult<Option<Item>> {
         let row: Option<ItemRow> = sqlx::query_as!(
             ItemRow,
@@ -193,10 +405,17 @@ impl ItemRepository for ItemRepositoryImpl {
                     i.location AS location,
                     b.author AS "author?",
                     b.isbn AS "isbn?",
-                    l.mac_address AS "mac_address?"
+                    l.mac_address AS "mac_address?",
+                    i.custom_category_id AS "custom_category_id: CustomCategoryId",
+                    cc.key AS "custom_category_key?",
+                    cc.name AS "custom_category_name?",
+                    i.attributes,
+                    i.archived_at,
+                    i.archive_reason
                 FROM items AS i
                 LEFT JOIN books b ON i.item_id = b.item_id
                 LEFT JOIN laptops l ON i.item_id = l.item_id
+                LEFT JOIN custom_categories cc ON i.custom_category_id = cc.custom_category_id
                 WHERE i.item_id = $1
                 ORDER BY i.created_at DESC
             "#,
@@ -209,7 +428,12 @@ impl ItemRepository for ItemRepositoryImpl {
         match row {
             Some(row) => {
                 let checkout = self.find_checkouts(&[item_id]).await?.remove(&item_id);
-                row.into_item(checkout).map(Some)
+                let tags = self
+                    .find_tags(&[item_id])
+                    .await?
+                    .remove(&item_id)
+                    .unwrap_or_default();
+                row.into_item(checkout, tags).map(Some)
             }
             None => Ok(None),
         }
@@ -223,6 +447,7 @@ impl ItemRepository for ItemRepositoryImpl {
                 name,
                 description,
                 location,
+                ..
             } => (item_id, name, description, location),
             UpdateItem::Book {
                 item_id,
@@ -237,12 +462,28 @@ impl ItemRepository for ItemRepositoryImpl {
                 description,
                 location,
                 ..
+            }
+            | UpdateItem::Custom {
+                item_id,
+                name,
+                description,
+                location,
+                ..
             } => (item_id, name, description, location),
         };
 
         let mut tx = self.db.begin().await?;
         set_transaction_serializable(&mut tx).await?;
 
+        let (custom_category_id, attributes) = match &event {
+            UpdateItem::Custom {
+                custom_category,
+                attributes,
+                ..
+            } => custom_attributes(&mut tx, custom_category, attributes.clone()).await?,
+            _ => (None, None),
+        };
+
         // Get current category
         let current = sqlx::query!(
             r#"
@@ -258,6 +499,7 @@ impl ItemRepository for ItemRepositoryImpl {
         .ok_or_else(|| AppError::EntityNotFound("specified item not found".into()))?;
 
         let current_category = ItemCategory::from_str(&current.category).unwrap();
+        let before = item_snapshot(&mut tx, *item_id).await?;
 
         // Clean up old category data if category changed
         if current_category != new_category {
@@ -286,7 +528,7 @@ impl ItemRepository for ItemRepositoryImpl {
                     .await
                     .map_err(AppError::SpecificOperationError)?;
                 }
-                ItemCategory::General => {}
+                ItemCategory::General | ItemCategory::Custom => {}
             }
         }
 
@@ -298,7 +540,9 @@ impl ItemRepository for ItemRepositoryImpl {
                     name = $1,
                     description = $2,
                     location = $3,
-                    category = $4
+                    category = $4,
+                    custom_category_id = $6,
+                    attributes = $7
                 WHERE item_id = $5
             "#,
             name,
@@ -306,6 +550,8 @@ impl ItemRepository for ItemRepositoryImpl {
             location.as_deref(),
             new_category.as_ref(),
             item_id.raw(),
+            custom_category_id.map(CustomCategoryId::raw),
+            attributes,
         )
         .execute(&mut *tx)
         .await
@@ -381,40 +627,295 @@ impl ItemRepository for ItemRepositoryImpl {
             _ => {}
         }
 
+        let after = item_snapshot(&mut tx, *item_id).await?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.requested_by(),
+                AuditAction::ItemUpdated,
+                item_id.raw(),
+                before,
+                after,
+            ),
+        )
+        .await?;
+
         tx.commit().await.map_err(AppError::TransactionError)?;
 
         Ok(())
     }
 
     async fn delete(&self, event: DeleteItem) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        set_transaction_serializable(&mut tx).await?;
+
+        let state = sqlx::query!(
+            r#"
+                SELECT
+                    i.archived_at,
+                    EXISTS(
+                        SELECT 1 FROM checkouts AS c WHERE c.item_id = i.item_id
+                    ) AS "checked_out!"
+                FROM items AS i
+                WHERE i.item_id = $1
+            "#,
+            event.item_id.raw(),
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| AppError::EntityNotFound("specified item not found".into()))?;
+
+        if state.archived_at.is_some() {
+            return Err(AppError::Conflict(format!(
+                "The item ({}) has already been archived.",
+                event.item_id
+            )));
+        }
+        if state.checked_out {
+            return Err(AppError::Conflict(format!(
+                "The item ({}) is checked out and cannot be archived.",
+                event.item_id
+            )));
+        }
+
+        let before = item_snapshot(&mut tx, event.item_id).await?;
+
+        sqlx::query!(
+            r#"
+                UPDATE items
+                SET
+                    archived_at = CURRENT_TIMESTAMP(3),
+                    archive_reason = $2
+                WHERE item_id = $1
+            "#,
+            event.item_id.raw(),
+            event.reason,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        // Nobody can check an archived item out, so its queue is dropped
+        sqlx::query!(
+            r#"
+                DELETE FROM reservations WHERE item_id = $1
+            "#,
+            event.item_id.raw(),
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let after = item_snapshot(&mut tx, event.item_id).await?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.requested_by,
+                AuditAction::ItemDeleted,
+                event.item_id.raw(),
+                before,
+                after,
+            ),
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn restore(&self, event: RestoreItem) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        let before = item_snapshot(&mut tx, event.item_id)
+            .await?
+            .ok_or_else(|| AppError::EntityNotFound("specified item not found".into()))?;
+
         let res = sqlx::query!(
             r#"
-                DELETE FROM items
+                UPDATE items
+                SET
+                    archived_at = NULL,
+                    archive_reason = NULL
                 WHERE item_id = $1
+                  AND archived_at IS NOT NULL
             "#,
             event.item_id.raw(),
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
-        .map_err(map_sqlx_error_on_delete)?;
+        .map_err(AppError::SpecificOperationError)?;
 
         if res.rows_affected() < 1 {
-            return Err(AppError::EntityNotFound("specified item not found".into()));
+            return Err(AppError::Conflict(format!(
+                "The item ({}) is not archived.",
+                event.item_id
+            )));
         }
 
+        let after = item_snapshot(&mut tx, event.item_id).await?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.requested_by,
+                AuditAction::ItemRestored,
+                event.item_id.raw(),
+                Some(before),
+                after,
+            ),
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
         Ok(())
     }
 }
 
-fn map_sqlx_error_on_delete(err: sqlx::Error) -> AppError {
-    match &err {
-        sqlx::Error::Database(db_err)
-            if matches!(db_err.code().as_deref(), Some("23001" | "23503")) =>
-        {
-            AppError::Conflict("Cannot delete item because checkout history references it.".into())
+/// Fails with `Conflict` when the item has been archived and so cannot be
+/// checked out or reserved.
+pub(crate) async fn ensure_not_archived(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    item_id: ItemId,
+) -> AppResult<()> {
+    let archived = sqlx::query_scalar!(
+        r#"
+            SELECT archived_at IS NOT NULL AS "archived!"
+            FROM items
+            WHERE item_id = $1
+        "#,
+        item_id.raw(),
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    if archived == Some(true) {
+        return Err(AppError::Conflict(format!(
+            "The item ({item_id}) has been archived."
+        )));
+    }
+
+    Ok(())
+}
+
+async fn insert_item(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    event: &CreateItem,
+) -> AppResult<()> {
+    let category = event.as_ref();
+
+    let (name, description, location) = match event {
+        CreateItem::General {
+            name,
+            description,
+            location,
+            ..
+        }
+        | CreateItem::Book {
+            name,
+            description,
+            location,
+            ..
         }
-        _ => AppError::SpecificOperationError(err),
+        | CreateItem::Laptop {
+            name,
+            description,
+            location,
+            ..
+        }
+        | CreateItem::Custom {
+            name,
+            description,
+            location,
+            ..
+        } => (name, description, location),
+    };
+    let (custom_category_id, attributes) = match event {
+        CreateItem::Custom {
+            custom_category,
+            attributes,
+            ..
+        } => custom_attributes(tx, custom_category, attributes.clone()).await?,
+        _ => (None, None),
+    };
+
+    let item_id = sqlx::query!(
+        r#"
+        INSERT INTO items (name, description, location, category, custom_category_id, attributes)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING item_id
+    "#,
+        name,
+        description,
+        location.as_deref(),
+        category,
+        custom_category_id.map(CustomCategoryId::raw),
+        attributes,
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?
+    .item_id;
+
+    match event {
+        CreateItem::Book { author, isbn, .. } => {
+            sqlx::query!(
+                r#"
+                    INSERT INTO books (item_id, author, isbn)
+                    VALUES ($1, $2, $3)
+                "#,
+                item_id,
+                author,
+                isbn,
+            )
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+        }
+        CreateItem::Laptop { mac_address, .. } => {
+            sqlx::query!(
+                r#"
+                    INSERT INTO laptops (item_id, mac_address)
+                    VALUES ($1, $2)
+                "#,
+                item_id,
+                mac_address,
+            )
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+        }
+        _ => {}
     }
+
+    let item_id = ItemId::from(item_id);
+    let after = item_snapshot(tx, item_id).await?;
+    record_audit(
+        tx,
+        AuditEntry::new(
+            event.requested_by(),
+            AuditAction::ItemCreated,
+            item_id.raw(),
+            None,
+            after,
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Category and validated attribute values of a custom item, ready to be stored.
+async fn custom_attributes(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    custom_category: &str,
+    attributes: Map<String, Value>,
+) -> AppResult<(Option<CustomCategoryId>, Option<Value>)> {
+    let category = find_custom_category_by_key(tx, custom_category).await?;
+    let attributes = category.validate_attributes(attributes)?;
+    Ok((Some(category.id), Some(Value::Object(attributes))))
 }
 
 impl ItemRepositoryImpl {
@@ -430,7 +931,8 @@ impl ItemRepositoryImpl {
                     c.item_id,
                     u.user_id,
                     u.name AS user_name,
-                    c.checked_out_at
+                    c.checked_out_at,
+                    c.due_at
                 FROM checkouts AS c
                 INNER JOIN users AS u USING(user_id)
                 WHERE item_id = ANY($1)
@@ -447,6 +949,36 @@ impl ItemRepositoryImpl {
 
         Ok(res)
     }
+
+    /// Tags of each item, in alphabetical order.
+    async fn find_tags(&self, item_ids: &[ItemId]) -> AppResult<HashMap<ItemId, Vec<Tag>>> {
+        let rows = sqlx::query_as!(
+            ItemTagRow,
+            r#"
+                SELECT
+                    it.item_id,
+                    t.tag_id,
+                    t.name
+                FROM item_tags AS it
+                INNER JOIN tags AS t USING(tag_id)
+                WHERE it.item_id = ANY($1)
+                ORDER BY LOWER(t.name), t.tag_id
+            "#,
+            item_ids as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let mut tags: HashMap<ItemId, Vec<Tag>> = HashMap::new();
+        for row in rows {
+            tags.entry(row.item_id).or_default().push(Tag {
+                id: row.tag_id,
+                name: row.name,
+            });
+        }
+        Ok(tags)
+    }
 }
 
 #[cfg(test)]
@@ -456,16 +988,26 @@ mod tests {
     use chrono::Utc;
     use kernel::{
         model::{
+            category::{AttributeDefinition, AttributeType, event::CreateCustomCategory},
             checkout::event::{CreateCheckout, UpdateReturned},
             id::UserId,
             item::general::GeneralItem,
+            list::{Cursor, ItemSort, SortOrder},
             role::Role,
+            tag::event::{AttachTag, CreateTag},
+        },
+        repository::{
+            category::CustomCategoryRepository, checkout::CheckoutRepository, tag::TagRepository,
         },
-        repository::checkout::CheckoutRepository,
     };
     use mac_address::MacAddress;
 
-    use crate::repository::{checkout::CheckoutRepositoryImpl, item::ItemRepositoryImpl};
+    use crate::repository::{
+        category::CustomCategoryRepositoryImpl, checkout::CheckoutRepositoryImpl,
+        item::ItemRepositoryImpl, tag::TagRepositoryImpl,
+    };
+
+    use shared::config::LoanConfig;
 
     use super::*;
 
@@ -477,6 +1019,7 @@ mod tests {
         let original_description = "Test Description".to_string();
 
         let create_event = CreateItem::General {
+            requested_by: UserId::new(),
             name: original_name.clone(),
             description: original_description.clone(),
             location: Some("Shelf A".into()),
@@ -487,6 +1030,7 @@ mod tests {
             limit: 20,
             offset: 0,
             category: Some(ItemCategory::General),
+            ..Default::default()
         };
 
         let res = repo.find_all(options).await?;
@@ -525,6 +1069,7 @@ mod tests {
         let original_isbn = "1234567890123".to_string();
 
         let create_event = CreateItem::Book {
+            requested_by: UserId::new(),
             name: original_name.clone(),
             description: original_description.clone(),
             location: None,
@@ -537,6 +1082,7 @@ mod tests {
             limit: 20,
             offset: 0,
             category: Some(ItemCategory::Book),
+            ..Default::default()
         };
 
         let res = repo.find_all(options).await?;
@@ -569,6 +1115,7 @@ mod tests {
         let original_mac_address = MacAddress::from_str("00:00:00:00:00:00")?;
 
         let create_event = CreateItem::Laptop {
+            requested_by: UserId::new(),
             name: original_name.clone(),
             description: original_description.clone(),
             location: None,
@@ -580,6 +1127,7 @@ mod tests {
             limit: 20,
             offset: 0,
             category: Some(ItemCategory::Laptop),
+            ..Default::default()
         };
 
         let res = repo.find_all(options).await?;
@@ -613,6 +1161,7 @@ mod tests {
 
         let update_event = UpdateItem::General {
             item_id: general_item.id,
+            requested_by: UserId::new(),
             name: "Updated Name".into(),
             description: "Updated Description".into(),
             location: Some("Updated Shelf".into()),
@@ -644,6 +1193,7 @@ mod tests {
 
             let update_event = UpdateItem::Book {
                 item_id,
+                requested_by: UserId::new(),
                 name: "Updated Book Name".into(),
                 description: "Updated Book Description".into(),
                 location: Some("Book Shelf".into()),
@@ -673,6 +1223,7 @@ mod tests {
             let mac_address = MacAddress::from_str("00:00:00:00:00:00")?;
             let update_event = UpdateItem::Laptop {
                 item_id,
+                requested_by: UserId::new(),
                 name: "Updated Laptop Name".into(),
                 description: "Updated Laptop Description".into(),
                 location: Some("Laptop Locker".into()),
@@ -699,6 +1250,7 @@ mod tests {
 
             let update_event = UpdateItem::General {
                 item_id,
+                requested_by: UserId::new(),
                 name: "Final General Name".into(),
                 description: "Final General Description".into(),
                 location: None,
@@ -717,17 +1269,684 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test(fixtures("common", "item"))]
+    async fn test_custom_items(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = ItemRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let category_repo = CustomCategoryRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let definition = |key: &str, attribute_type, required| AttributeDefinition {
+            key: key.into(),
+            label: key.into(),
+            attribute_type,
+            required,
+            options: vec![],
+        };
+        let router = category_repo
+            .create(CreateCustomCategory {
+                key: "router".into(),
+                name: "Router".into(),
+                attributes: vec![
+                    definition("mac", AttributeType::MacAddress, true),
+                    definition("admin_url", AttributeType::Url, false),
+                    definition("purchased_on", AttributeType::Date, false),
+                ],
+                requested_by: UserId::new(),
+            })
+            .await?;
+        let attributes = |value: serde_json::Value| value.as_object().unwrap().clone();
+        let create = |custom_category: &str, attributes| CreateItem::Custom {
+            requested_by: UserId::new(),
+            name: "Router 1".into(),
+            custom_category: custom_category.into(),
+            attributes,
+            description: "".into(),
+            location: None,
+        };
+
+        repo.create(create(
+            "router",
+            attributes(serde_json::json!({
+                "mac": "AA-BB-CC-DD-EE-0F",
+                "admin_url": " http://192.168.0.1 ",
+                "purchased_on": "",
+            })),
+        ))
+        .await?;
+
+        for invalid in [
+            serde_json::json!({ "mac": "not a mac" }),
+            serde_json::json!({ "mac": "AA:BB:CC:DD:EE:0F", "admin_url": "ftp://router" }),
+            serde_json::json!({ "mac": "AA:BB:CC:DD:EE:0F", "purchased_on": "2024/04/10" }),
+            serde_json::json!({ "mac": "AA:BB:CC:DD:EE:0F", "ports": 4 }),
+            serde_json::json!({ "admin_url": "http://192.168.0.1" }),
+        ] {
+            let res = repo.create(create("router", attributes(invalid))).await;
+            assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        }
+        let res = repo.create(create("switch", Map::new())).await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        let options = |custom_category: &str| ListOptions {
+            limit: 20,
+            offset: 0,
+            filter: ItemFilter {
+                custom_category: Some(custom_category.into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let res = repo.find_all(options("router")).await?;
+        assert_eq!(res.items.len(), 1);
+        let Item::Custom(ref item) = res.items[0] else {
+            panic!("Expected item to be Custom");
+        };
+        assert_eq!(item.category, router.to_ref());
+        assert_eq!(
+            serde_json::Value::Object(item.attributes.clone()),
+            serde_json::json!({
+                "mac": "AA:BB:CC:DD:EE:0F",
+                "admin_url": "http://192.168.0.1/",
+            })
+        );
+        assert!(repo.find_all(options("monitor")).await?.items.is_empty());
+
+        // A general item becomes a custom one and back again
+        let item_id = ItemId::from_str("9890736e-a4e4-461a-a77d-eac3517ef113")?;
+        repo.update(UpdateItem::Custom {
+            item_id,
+            requested_by: UserId::new(),
+            name: "Router 2".into(),
+            custom_category: "router".into(),
+            attributes: attributes(serde_json::json!({ "mac": "00:11:22:33:44:55" })),
+            description: "".into(),
+            location: None,
+        })
+        .await?;
+        let Some(Item::Custom(item)) = repo.find_by_id(item_id).await? else {
+            panic!("Expected item to be Custom");
+        };
+        assert_eq!(item.name, "Router 2");
+        assert_eq!(item.category.key, "router");
+
+        repo.update(UpdateItem::General {
+            item_id,
+            requested_by: UserId::new(),
+            name: "General".into(),
+            description: "".into(),
+            location: None,
+        })
+        .await?;
+        let row = sqlx::query!(
+            r#"
+                SELECT custom_category_id, attributes FROM items WHERE item_id = $1
+            "#,
+            item_id.raw()
+        )
+        .fetch_one(&pool)
+        .await?;
+        assert!(row.custom_category_id.is_none());
+        assert!(row.attributes.is_none());
+
+        Ok(())
+    }
+
     #[sqlx::test(fixtures("common", "item"))]
     async fn test_delete_item(pool: sqlx::PgPool) -> anyhow::Result<()> {
         let repo = ItemRepositoryImpl::new(ConnectionPool::new(pool.clone()));
         let item_id = ItemId::from_str("9890736e-a4e4-461a-a77d-eac3517ef113")?;
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let list = |include_archived: bool| ListOptions {
+            limit: 20,
+            filter: ItemFilter {
+                include_archived,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
 
         let item = repo.find_by_id(item_id).await?;
         assert!(item.is_some());
 
-        repo.delete(DeleteItem { item_id }).await?;
-        let item = repo.find_by_id(item_id).await?;
-        assert!(item.is_none());
+        repo.delete(DeleteItem {
+            item_id,
+            requested_by: admin_id,
+            reason: Some("broken".into()),
+        })
+        .await?;
+
+        // Archived items are still resolvable by id but hidden from listings.
+        let Some(Item::General(item)) = repo.find_by_id(item_id).await? else {
+            panic!("Expected the archived item to be found");
+        };
+        let archive = item.archive.expect("item should be archived");
+        assert_eq!(archive.reason.as_deref(), Some("broken"));
+        assert_eq!(repo.find_all(list(false)).await?.total, 0);
+        assert_eq!(repo.find_all(list(true)).await?.total, 1);
+
+        let res = repo
+            .delete(DeleteItem {
+                item_id,
+                requested_by: admin_id,
+                reason: None,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        repo.restore(RestoreItem {
+            item_id,
+            requested_by: admin_id,
+        })
+        .await?;
+        let Some(Item::General(item)) = repo.find_by_id(item_id).await? else {
+            panic!("Expected the restored item to be found");
+        };
+        assert!(item.archive.is_none());
+        assert_eq!(repo.find_all(list(false)).await?.total, 1);
+
+        let res = repo
+            .restore(RestoreItem {
+                item_id,
+                requested_by: admin_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "item"))]
+    async fn test_archived_item_cannot_be_checked_out(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let repo = ItemRepositoryImpl::new(db.clone());
+        let checkout_repo = CheckoutRepositoryImpl::new(db, 3600, LoanConfig::default());
+        let item_id = ItemId::from_str("9890736e-a4e4-461a-a77d-eac3517ef113")?;
+        let user_id = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+
+        checkout_repo
+            .create(CreateCheckout::new(
+                item_id,
+                user_id,
+                user_id,
+                Utc::now(),
+                None,
+            ))
+            .await?;
+        let res = repo
+            .delete(DeleteItem {
+                item_id,
+                requested_by: user_id,
+                reason: None,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        let checkout_id = checkout_repo.find_unreturned_by_user_id(user_id).await?[0].id;
+        checkout_repo
+            .update_returned(UpdateReturned::new(
+                checkout_id,
+                item_id,
+                user_id,
+                Role::User,
+                Utc::now(),
+            ))
+            .await?;
+        repo.delete(DeleteItem {
+            item_id,
+            requested_by: user_id,
+            reason: None,
+        })
+        .await?;
+
+        let res = checkout_repo
+            .create(CreateCheckout::new(
+                item_id,
+                user_id,
+                user_id,
+                Utc::now(),
+                None,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_create_many_is_all_or_nothing(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = ItemRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let general = |name: &str| CreateItem::General {
+            requested_by: UserId::new(),
+            name: name.into(),
+            description: "".into(),
+            location: None,
+        };
+
+        repo.create_many(vec![
+            general("Cable"),
+            CreateItem::Book {
+                requested_by: UserId::new(),
+                name: "Book".into(),
+                author: "Author".into(),
+                isbn: "1234567890123".into(),
+                description: "".into(),
+                location: Some("Shelf A".into()),
+            },
+            CreateItem::Laptop {
+                requested_by: UserId::new(),
+                name: "Laptop".into(),
+                mac_address: MacAddress::new([0, 1, 2, 3, 4, 5]),
+                description: "".into(),
+                location: None,
+            },
+        ])
+        .await?;
+        let all = ListOptions {
+            limit: 10,
+            ..Default::default()
+        };
+        assert_eq!(repo.find_all(all).await?.total, 3);
+
+        // A row the database rejects rolls back the rows before it
+        let res = repo
+            .create_many(vec![general("Adapter"), general(&"x".repeat(256))])
+            .await;
+        assert!(matches!(res, Err(AppError::SpecificOperationError(_))));
+        let all = ListOptions {
+            limit: 10,
+            ..Default::default()
+        };
+        assert_eq!(repo.find_all(all).await?.total, 3);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "item_list"))]
+    async fn test_stream_all(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = ItemRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let checkout_repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            3600,
+            LoanConfig::default(),
+        );
+        let Item::Book(book) = &repo
+            .find_all(ListOptions {
+                limit: 1,
+                filter: ItemFilter {
+                    q: Some("book007".into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .await?
+            .items[0]
+        else {
+            panic!("Expected item to be Book");
+        };
+        checkout_repo
+            .create(CreateCheckout::new(
+                book.id,
+                UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?,
+                UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?,
+                Utc::now(),
+                None,
+            ))
+            .await?;
+
+        let items = repo.stream_all().collect::<AppResult<Vec<_>>>().await?;
+        assert_eq!(items.len(), 50);
+        // Oldest first
+        assert!(matches!(&items[0], Item::Laptop(l) if l.name == "laptop010"));
+        assert!(matches!(&items[49], Item::General(i) if i.name == "item001"));
+
+        let checked_out = items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Book(b) => b.checkout.as_ref().map(|c| (b.id, c)),
+                Item::General(i) => i.checkout.as_ref().map(|c| (i.id, c)),
+                Item::Laptop(l) => l.checkout.as_ref().map(|c| (l.id, c)),
+                Item::Custom(l) => l.checkout.as_ref().map(|c| (l.id, c)),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(checked_out.len(), 1);
+        assert_eq!(checked_out[0].0, book.id);
+        assert_eq!(checked_out[0].1.checked_out_by.name, "Eleazar Fig");
+
+        // Archived items are exported along with their archive state
+        let Item::General(retired) = &items[49] else {
+            panic!("Expected item to be General");
+        };
+        repo.delete(DeleteItem {
+            item_id: retired.id,
+            requested_by: UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?,
+            reason: Some("broken".into()),
+        })
+        .await?;
+        let items = repo.stream_all().collect::<AppResult<Vec<_>>>().await?;
+        assert_eq!(items.len(), 50);
+        assert!(matches!(
+            &items[49],
+            Item::General(i) if i.id == retired.id
+                && i.archive.as_ref().and_then(|a| a.reason.as_deref()) == Some("broken")
+        ));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "item_list"))]
+    async fn test_list_sorting(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = ItemRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let sorted = |sort: ItemSort, order: SortOrder, limit: i64, offset: i64| ListOptions {
+            limit,
+            offset,
+            sort,
+            order,
+            ..Default::default()
+        };
+        let names = |items: &[Item]| {
+            items
+                .iter()
+                .map(|item| match item {
+                    Item::General(i) => i.name.clone(),
+                    Item::Book(b) => b.name.clone(),
+                    Item::Laptop(l) => l.name.clone(),
+                    Item::Custom(l) => l.name.clone(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let res = repo
+            .find_all(sorted(ItemSort::Name, SortOrder::Asc, 3, 0))
+            .await?;
+        assert_eq!(names(&res.items), ["book001", "book002", "book003"]);
+        let res = repo
+            .find_all(sorted(ItemSort::Name, SortOrder::Asc, 3, 10))
+            .await?;
+        assert_eq!(names(&res.items), ["item001", "item002", "item003"]);
+        let res = repo
+            .find_all(sorted(ItemSort::Name, SortOrder::Desc, 2, 0))
+            .await?;
+        assert_eq!(names(&res.items), ["laptop010", "laptop009"]);
+
+        // The default keeps the newest items first
+        let res = repo
+            .find_all(sorted(Default::default(), Default::default(), 2, 0))
+            .await?;
+        assert_eq!(names(&res.items), ["item001", "item002"]);
+
+        // Pages over a column with many ties neither skip nor repeat items
+        let mut seen = std::collections::HashSet::new();
+        for offset in (0..50).step_by(7) {
+            let res = repo
+                .find_all(sorted(ItemSort::Category, SortOrder::Asc, 7, offset))
+                .await?;
+            for item in &res.items {
+                let id = match item {
+                    Item::General(i) => i.id,
+                    Item::Book(b) => b.id,
+                    Item::Laptop(l) => l.id,
+                    Item::Custom(l) => l.id,
+                };
+                assert!(seen.insert(id));
+            }
+        }
+        assert_eq!(seen.len(), 50);
+
+        // A page past the end is empty but still counts the matching items
+        let res = repo
+            .find_all(sorted(ItemSort::Name, SortOrder::Asc, 5, 50))
+            .await?;
+        assert!(res.items.is_empty());
+        assert_eq!(res.total, 50);
+
+        // Items that are not checked out come last in either direction
+        let checkout_repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            3600,
+            LoanConfig::default(),
+        );
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let all = repo
+            .find_all(sorted(ItemSort::Name, SortOrder::Asc, 50, 0))
+            .await?;
+        for (item, minutes) in [(&all.items[20], 10), (&all.items[30], 5)] {
+            let Item::General(item) = item else {
+                panic!("Expected item to be General");
+            };
+            checkout_repo
+                .create(CreateCheckout::new(
+                    item.id,
+                    user_id,
+                    user_id,
+                    Utc::now() - chrono::Duration::minutes(minutes),
+                    None,
+                ))
+                .await?;
+        }
+
+        let res = repo
+            .find_all(sorted(ItemSort::CheckedOutAt, SortOrder::Desc, 3, 0))
+            .await?;
+        assert_eq!(names(&res.items)[..2], ["item021", "item011"]);
+        let checked_out = match &res.items[2] {
+            Item::General(i) => i.checkout.is_some(),
+            Item::Book(b) => b.checkout.is_some(),
+            Item::Laptop(l) => l.checkout.is_some(),
+            Item::Custom(l) => l.checkout.is_some(),
+        };
+        assert!(!checked_out);
+        let res = repo
+            .find_all(sorted(ItemSort::CheckedOutAt, SortOrder::Asc, 3, 0))
+            .await?;
+        assert_eq!(names(&res.items)[..2], ["item011", "item021"]);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "item_list"))]
+    async fn test_list_cursor_pagination(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = ItemRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let item_id = |item: &Item| match item {
+            Item::General(i) => i.id,
+            Item::Book(b) => b.id,
+            Item::Laptop(l) => l.id,
+            Item::Custom(l) => l.id,
+        };
+
+        let checkout_repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            3600,
+            LoanConfig::default(),
+        );
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let all = repo
+            .find_all(ListOptions {
+                limit: 50,
+                ..Default::default()
+            })
+            .await?;
+        for (minutes, item) in all.items.iter().step_by(5).enumerate() {
+            checkout_repo
+                .create(CreateCheckout::new(
+                    item_id(item),
+                    user_id,
+                    user_id,
+                    Utc::now() - chrono::Duration::minutes(minutes as i64),
+                    None,
+                ))
+                .await?;
+        }
+
+        for (sort, order) in [
+            (ItemSort::Name, SortOrder::Asc),
+            (ItemSort::Category, SortOrder::Desc),
+            (ItemSort::CheckedOutAt, SortOrder::Desc),
+            (ItemSort::CheckedOutAt, SortOrder::Asc),
+        ] {
+            let options = |cursor: Option<Cursor>| ListOptions {
+                limit: 7,
+                cursor,
+                sort,
+                order,
+                ..Default::default()
+            };
+            let expected = repo
+                .find_all(ListOptions {
+                    limit: 50,
+                    ..options(None)
+                })
+                .await?
+                .items
+                .iter()
+                .map(item_id)
+                .collect::<Vec<_>>();
+
+            // Walk forward to the end, then back to the start
+            let mut pages = vec![repo.find_all(options(None)).await?];
+            while let Some(cursor) = pages.last().unwrap().next_cursor.clone() {
+                pages.push(repo.find_all(options(Some(cursor))).await?);
+            }
+            assert_eq!(pages.len(), 8);
+            let forward = pages
+                .iter()
+                .flat_map(|page| page.items.iter().map(item_id))
+                .collect::<Vec<_>>();
+            assert_eq!(forward, expected, "{sort:?} {order:?}");
+
+            let mut page = pages.pop().unwrap();
+            while let Some(cursor) = page.prev_cursor.clone() {
+                page = repo.find_all(options(Some(cursor))).await?;
+                let expected_page = pages.pop().unwrap();
+                assert_eq!(
+                    page.items.iter().map(item_id).collect::<Vec<_>>(),
+                    expected_page.items.iter().map(item_id).collect::<Vec<_>>(),
+                );
+            }
+            assert!(pages.is_empty());
+        }
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "item_list"))]
+    async fn test_list_search_and_field_filters(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = ItemRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let search = |filter: ItemFilter| ListOptions {
+            limit: 100,
+            offset: 0,
+            filter,
+            ..Default::default()
+        };
+
+        // Substring match on the name
+        let res = repo
+            .find_all(search(ItemFilter {
+                q: Some("book003".into()),
+                ..Default::default()
+            }))
+            .await?;
+        assert_eq!(res.total, 1);
+        let Item::Book(book) = &res.items[0] else {
+            panic!("Expected item to be Book");
+        };
+        assert_eq!(book.name, "book003");
+
+        // Substring match on the book author
+        let res = repo
+            .find_all(search(ItemFilter {
+                q: Some("AUTHOR005".into()),
+                ..Default::default()
+            }))
+            .await?;
+        assert_eq!(res.total, 1);
+
+        // Full-text match regardless of word order
+        let res = repo
+            .find_all(search(ItemFilter {
+                q: Some("description001 laptop".into()),
+                ..Default::default()
+            }))
+            .await?;
+        assert_eq!(res.total, 1);
+        assert!(matches!(&res.items[0], Item::Laptop(l) if l.name == "laptop001"));
+
+        // LIKE wildcards in the query are matched literally
+        let res = repo
+            .find_all(search(ItemFilter {
+                q: Some("%".into()),
+                ..Default::default()
+            }))
+            .await?;
+        assert_eq!(res.total, 0);
+
+        // Exact field filters
+        let res = repo
+            .find_all(search(ItemFilter {
+                author: Some("author001".into()),
+                ..Default::default()
+            }))
+            .await?;
+        assert_eq!(res.total, 1);
+
+        let res = repo
+            .find_all(search(ItemFilter {
+                isbn: Some("0000000000002".into()),
+                ..Default::default()
+            }))
+            .await?;
+        assert_eq!(res.total, 1);
+
+        let res = repo
+            .find_all(search(ItemFilter {
+                author: Some("author".into()),
+                ..Default::default()
+            }))
+            .await?;
+        assert_eq!(res.total, 0);
+
+        // Availability depends on the active checkouts
+        let checkout_repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            3600,
+            LoanConfig::default(),
+        );
+        let Item::Book(book) = &repo
+            .find_all(search(ItemFilter {
+                q: Some("book001".into()),
+                ..Default::default()
+            }))
+            .await?
+            .items[0]
+        else {
+            panic!("Expected item to be Book");
+        };
+        checkout_repo
+            .create(CreateCheckout::new(
+                book.id,
+                UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?,
+                UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?,
+                Utc::now(),
+                None,
+            ))
+            .await?;
+
+        let res = repo
+            .find_all(search(ItemFilter {
+                available: Some(false),
+                ..Default::default()
+            }))
+            .await?;
+        assert_eq!(res.total, 1);
+        assert!(matches!(&res.items[0], Item::Book(b) if b.id == book.id));
+
+        let res = repo
+            .find_all(ListOptions {
+                category: Some(ItemCategory::Book),
+                ..search(ItemFilter {
+                    available: Some(true),
+                    ..Default::default()
+                })
+            })
+            .await?;
+        assert_eq!(res.total, 9);
 
         Ok(())
     }
@@ -742,6 +1961,7 @@ mod tests {
                 limit: 100,
                 offset: 0,
                 category: None,
+                ..Default::default()
             })
             .await?;
         assert_eq!(res.total, 50); // 30 general + 10 books + 10 laptops
@@ -752,6 +1972,7 @@ mod tests {
                 limit: 10,
                 offset: 0,
                 category: None,
+                ..Default::default()
             })
             .await?;
         assert_eq!(res.limit, 10);
@@ -767,6 +1988,7 @@ mod tests {
                 limit: 10,
                 offset: 10,
                 category: None,
+                ..Default::default()
             })
             .await?;
         assert_eq!(res.limit, 10);
@@ -783,6 +2005,7 @@ mod tests {
                 limit: 100,
                 offset: 0,
                 category: Some(ItemCategory::General),
+                ..Default::default()
             })
             .await?;
         assert_eq!(res.total, 30);
@@ -797,6 +2020,7 @@ mod tests {
                 limit: 100,
                 offset: 0,
                 category: Some(ItemCategory::Book),
+                ..Default::default()
             })
             .await?;
         assert_eq!(res.total, 10);
@@ -807,6 +2031,7 @@ mod tests {
                 limit: 100,
                 offset: 0,
                 category: Some(ItemCategory::Laptop),
+                ..Default::default()
             })
             .await?;
         assert_eq!(res.total, 10);
@@ -818,6 +2043,7 @@ mod tests {
                 limit: 10,
                 offset: 100,
                 category: None,
+                ..Default::default()
             })
             .await?;
         assert_eq!(res.total, 50);
@@ -826,6 +2052,119 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test(fixtures("common", "item_list"))]
+    async fn test_list_tag_filters(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = ItemRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let tag_repo = TagRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        let kit = tag_repo
+            .create(CreateTag {
+                name: "contest kit".into(),
+                requested_by: admin_id,
+            })
+            .await?;
+        let prep = tag_repo
+            .create(CreateTag {
+                name: "JOI prep".into(),
+                requested_by: admin_id,
+            })
+            .await?;
+        let mut ids = HashMap::new();
+        for (name, tag) in [
+            ("book001", &kit),
+            ("book001", &prep),
+            ("book002", &kit),
+            ("laptop001", &prep),
+        ] {
+            let item_id = ItemId::from(
+                sqlx::query_scalar!("SELECT item_id FROM items WHERE name = $1", name)
+                    .fetch_one(&pool)
+                    .await?,
+            );
+            ids.insert(name, item_id);
+            tag_repo
+                .attach(AttachTag {
+                    item_id,
+                    tag_id: tag.id,
+                    requested_by: admin_id,
+                })
+                .await?;
+        }
+
+        let names = |res: PaginatedList<Item>| {
+            let mut names = res
+                .items
+                .iter()
+                .map(|item| match item {
+                    Item::Book(b) => b.name.clone(),
+                    Item::General(i) => i.name.clone(),
+                    Item::Laptop(l) => l.name.clone(),
+                    Item::Custom(l) => l.name.clone(),
+                })
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+        let search = |tags: &[&str], tag_match: TagMatch| ListOptions {
+            limit: 100,
+            offset: 0,
+            filter: ItemFilter {
+                tags: tags.iter().map(|t| t.to_string()).collect(),
+                tag_match,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Tag names match regardless of case
+        let res = repo
+            .find_all(search(&["Contest Kit"], TagMatch::Any))
+            .await?;
+        assert_eq!(res.total, 2);
+        assert_eq!(names(res), ["book001", "book002"]);
+
+        let res = repo
+            .find_all(search(&["contest kit", "joi prep"], TagMatch::Any))
+            .await?;
+        assert_eq!(res.total, 3);
+        assert_eq!(names(res), ["book001", "book002", "laptop001"]);
+
+        let res = repo
+            .find_all(search(&["contest kit", "joi prep"], TagMatch::All))
+            .await?;
+        assert_eq!(res.total, 1);
+        assert_eq!(names(res), ["book001"]);
+
+        // Repeating a tag does not change what `all` requires
+        let res = repo
+            .find_all(search(&["contest kit", "Contest kit"], TagMatch::All))
+            .await?;
+        assert_eq!(res.total, 2);
+
+        let res = repo
+            .find_all(search(&["contest kit", "unknown"], TagMatch::All))
+            .await?;
+        assert_eq!(res.total, 0);
+
+        // Items carry their tags in alphabetical order
+        let Some(Item::Book(book)) = repo.find_by_id(ids["book001"]).await? else {
+            panic!("Expected item to be Book");
+        };
+        assert_eq!(book.tags, vec![kit.clone(), prep.clone()]);
+        let items = repo.stream_all().collect::<AppResult<Vec<_>>>().await?;
+        let laptop = items
+            .iter()
+            .find_map(|item| match item {
+                Item::Laptop(l) if l.name == "laptop001" => Some(l),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(laptop.tags, vec![prep]);
+
+        Ok(())
+    }
+
     #[sqlx::test(fixtures("common"))]
     async fn test_missing_category_details_are_rejected(pool: sqlx::PgPool) -> anyhow::Result<()> {
         let repo = ItemRepositoryImpl::new(ConnectionPool::new(pool.clone()));
@@ -859,6 +2198,7 @@ mod tests {
         let update_result = repo
             .update(UpdateItem::General {
                 item_id: non_existent_id,
+                requested_by: UserId::new(),
                 name: "New Name".into(),
                 description: "New Description".into(),
                 location: None,
@@ -870,6 +2210,8 @@ mod tests {
         let delete_result = repo
             .delete(DeleteItem {
                 item_id: non_existent_id,
+                requested_by: UserId::new(),
+                reason: None,
             })
             .await;
         assert!(matches!(delete_result, Err(AppError::EntityNotFound(_))));
@@ -888,6 +2230,7 @@ mod tests {
         let create_task1 = task::spawn(async move {
             repo1
                 .create(CreateItem::General {
+                    requested_by: UserId::new(),
                     name: "Concurrent Item".into(),
                     description: "Description 1".into(),
                     location: None,
@@ -898,6 +2241,7 @@ mod tests {
         let create_task2 = task::spawn(async move {
             repo2
                 .create(CreateItem::General {
+                    requested_by: UserId::new(),
                     name: "Concurrent Item".into(),
                     description: "Description 2".into(),
                     location: None,
@@ -916,6 +2260,7 @@ mod tests {
                 limit: 10,
                 offset: 0,
                 category: Some(ItemCategory::General),
+                ..Default::default()
             })
             .await?;
 
@@ -936,7 +2281,11 @@ mod tests {
     #[sqlx::test(fixtures("common", "item"))]
     async fn test_item_checkout(pool: sqlx::PgPool) -> anyhow::Result<()> {
         let item_repo = ItemRepositoryImpl::new(ConnectionPool::new(pool.clone()));
-        let checkout_repo = CheckoutRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let checkout_repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            3600,
+            LoanConfig::default(),
+        );
 
         let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
         let user_id2 = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5")?;
@@ -946,6 +2295,7 @@ mod tests {
                 limit: 20,
                 offset: 0,
                 category: None,
+                ..Default::default()
             })
             .await?;
 
@@ -954,6 +2304,7 @@ mod tests {
             Item::General(item) => (item.id, item.checkout.as_ref()),
             Item::Book(item) => (item.id, item.checkout.as_ref()),
             Item::Laptop(item) => (item.id, item.checkout.as_ref()),
+            Item::Custom(item) => (item.id, item.checkout.as_ref()),
         };
         assert!(checkout.is_none());
 
@@ -962,7 +2313,9 @@ mod tests {
                 .create(CreateCheckout {
                     item_id,
                     checked_out_by: user_id1,
+                    requested_by: user_id1,
                     checked_out_at: Utc::now(),
+                    due_at: None,
                 })
                 .await?;
 
@@ -971,6 +2324,7 @@ mod tests {
                 Item::General(item) => item.checkout.as_ref(),
                 Item::Book(item) => item.checkout.as_ref(),
                 Item::Laptop(item) => item.checkout.as_ref(),
+                Item::Custom(item) => item.checkout.as_ref(),
             };
             assert!(checkout.is_some());
             let co = checkout.unwrap();
@@ -991,6 +2345,7 @@ mod tests {
                 Item::General(item) => item.checkout.as_ref(),
                 Item::Book(item) => item.checkout.as_ref(),
                 Item::Laptop(item) => item.checkout.as_ref(),
+                Item::Custom(item) => item.checkout.as_ref(),
             };
             assert!(checkout.is_none());
         }
@@ -1000,7 +2355,9 @@ mod tests {
                 .create(CreateCheckout {
                     item_id,
                     checked_out_by: user_id2,
+                    requested_by: user_id2,
                     checked_out_at: Utc::now(),
+                    due_at: None,
                 })
                 .await?;
 
@@ -1009,6 +2366,7 @@ mod tests {
                 Item::General(item) => item.checkout.as_ref(),
                 Item::Book(item) => item.checkout.as_ref(),
                 Item::Laptop(item) => item.checkout.as_ref(),
+                Item::Custom(item) => item.checkout.as_ref(),
             };
             assert!(checkout.is_some());
             let co = checkout.unwrap();
@@ -1029,12 +2387,20 @@ mod tests {
                 Item::General(item) => item.checkout.as_ref(),
                 Item::Book(item) => item.checkout.as_ref(),
                 Item::Laptop(item) => item.checkout.as_ref(),
+                Item::Custom(item) => item.checkout.as_ref(),
             };
             assert!(checkout.is_none());
         }
 
-        let delete_result = item_repo.delete(DeleteItem { item_id }).await;
-        assert!(matches!(delete_result, Err(AppError::Conflict(_))));
+        let delete_result = item_repo
+            .delete(DeleteItem {
+                item_id,
+                requested_by: UserId::new(),
+                reason: None,
+            })
+            .await;
+        // Returned checkouts are kept as history and do not block archiving.
+        assert!(delete_result.is_ok());
 
         Ok(())
     }
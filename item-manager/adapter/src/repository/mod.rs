@@ -1,5 +1,24 @@
+pub mod audit;
 pub mod auth;
+pub mod category;
 pub mod checkout;
 pub mod health;
+pub mod invitation;
 pub mod item;
+pub mod oidc;
+mod pagination;
+pub mod password_reset;
+pub mod personal_access_token;
+pub mod reservation;
+pub mod tag;
+pub mod two_factor;
 pub mod user;
+
+/// Builds an `ILIKE` pattern matching `query` anywhere, with its wildcards escaped.
+pub(crate) fn like_pattern(query: &str) -> String {
+    let escaped = query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{escaped}%")
+}
@@ -0,0 +1,404 @@
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use derive_new::new;
+use kernel::model::{
+    audit::AuditAction,
+    id::UserId,
+    oidc::{
+        OidcIdentity, PendingOidcLogin,
+        event::{CreateOidcLoginRequest, SignInWithOidc},
+    },
+    role::Role,
+};
+use kernel::repository::oidc::OidcRepository;
+use shared::error::{AppError, AppResult};
+
+use crate::database::{
+    ConnectionPool,
+    model::{
+        auth::{generate_opaque_token, hash_opaque_token},
+        oidc::{LinkedUserRow, OidcLoginRequestRow},
+        password::PasswordPolicy,
+    },
+};
+use crate::repository::audit::{AuditEntry, record_audit, user_snapshot};
+
+/// How long, in seconds, the provider may take to send the browser back.
+const LOGIN_REQUEST_TTL: i64 = 10 * 60;
+
+#[derive(new)]
+pub struct OidcRepositoryImpl {
+    db: ConnectionPool,
+    /// Create accounts for identities that match none.
+    allow_signup: bool,
+    /// Link identities to the account with the same verified email address.
+    link_by_email: bool,
+    password_policy: PasswordPolicy,
+}
+
+#[async_trait]
+impl OidcRepository for OidcRepositoryImpl {
+    async fn create_login_request(&self, event: CreateOidcLoginRequest) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        sqlx::query!(
+            r#"
+                DELETE FROM oidc_login_requests WHERE expires_at < CURRENT_TIMESTAMP(3)
+            "#
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        sqlx::query!(
+            r#"
+                INSERT INTO oidc_login_requests (state_hash, nonce, pkce_verifier, expires_at)
+                VALUES ($1, $2, $3, $4)
+            "#,
+            hash_opaque_token(&event.state),
+            event.nonce,
+            event.pkce_verifier,
+            Utc::now() + Duration::seconds(LOGIN_REQUEST_TTL)
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn take_login_request(&self, state: &str) -> AppResult<Option<PendingOidcLogin>> {
+        let row = sqlx::query_as!(
+            OidcLoginRequestRow,
+            r#"
+                DELETE FROM oidc_login_requests
+                WHERE state_hash = $1
+                RETURNING
+                    nonce,
+                    pkce_verifier,
+                    expires_at <= CURRENT_TIMESTAMP(3) AS "expired!"
+            "#,
+            hash_opaque_token(state)
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(row.filter(|row| !row.expired).map(|row| PendingOidcLogin {
+            nonce: row.nonce,
+            pkce_verifier: row.pkce_verifier,
+        }))
+    }
+
+    async fn sign_in(&self, event: SignInWithOidc) -> AppResult<UserId> {
+        let identity = event.identity;
+        let mut tx = self.db.begin().await?;
+
+        let linked = sqlx::query_as!(
+            LinkedUserRow,
+            r#"
+                SELECT
+                    u.user_id AS "user_id: UserId",
+                    u.deactivated_at IS NOT NULL AS "deactivated!"
+                FROM user_identities AS i
+                INNER JOIN users AS u USING(user_id)
+                WHERE i.issuer = $1
+                  AND i.subject = $2
+            "#,
+            identity.issuer,
+            identity.subject
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        // An unverified address may belong to someone else, so it neither links nor creates.
+        let verified_email = identity.email.clone().filter(|_| identity.email_verified);
+        let user = match (linked, verified_email) {
+            (Some(user), _) => user,
+            (None, Some(email)) => {
+                let existing = if self.link_by_email {
+                    find_user_by_email(&mut tx, &email).await?
+                } else {
+                    None
+                };
+                match existing {
+                    Some(user) => {
+                        link_identity(&mut tx, user.user_id, &identity).await?;
+                        record_audit(
+                            &mut tx,
+                            AuditEntry::new(
+                                user.user_id,
+                                AuditAction::UserIdentityLinked,
+                                user.user_id.raw(),
+                                None,
+                                Some(serde_json::json!({
+                                    "issuer": identity.issuer,
+                                    "subject": identity.subject,
+                                })),
+                            ),
+                        )
+                        .await?;
+                        user
+                    }
+                    None if self.allow_signup => {
+                        create_user(&mut tx, &self.password_policy, &identity, &email).await?
+                    }
+                    None => return Err(AppError::UnauthenticatedError),
+                }
+            }
+            (None, None) => return Err(AppError::UnauthenticatedError),
+        };
+
+        if user.deactivated {
+            return Err(AppError::ForbiddenOperation(
+                "This account has been deactivated.".into(),
+            ));
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(user.user_id)
+    }
+}
+
+async fn find_user_by_email(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    email: &str,
+) -> AppResult<Option<LinkedUserRow>> {
+    sqlx::query_as!(
+        LinkedUserRow,
+        r#"
+            SELECT
+                user_id AS "user_id: UserId",
+                deactivated_at IS NOT NULL AS "deactivated!"
+            FROM users
+            WHERE email = $1
+            FOR UPDATE
+        "#,
+        email
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)
+}
+
+async fn link_identity(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: UserId,
+    identity: &OidcIdentity,
+) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+            INSERT INTO user_identities (issuer, subject, user_id)
+            VALUES ($1, $2, $3)
+        "#,
+        identity.issuer,
+        identity.subject,
+        user_id.raw()
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    Ok(())
+}
+
+/// Creates a member for a new identity. Its password is random, so until one is set with a
+/// password reset the account can only be used through the provider.
+async fn create_user(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    password_policy: &PasswordPolicy,
+    identity: &OidcIdentity,
+    email: &str,
+) -> AppResult<LinkedUserRow> {
+    let user_id = UserId::new();
+    let name = identity.name.as_deref().unwrap_or(email);
+    let res = sqlx::query!(
+        r#"
+            INSERT INTO users(user_id, name, email, password_hash, role_id)
+            SELECT $1, $2, $3, $4, role_id FROM roles WHERE name = $5
+            ON CONFLICT (email) DO NOTHING
+        "#,
+        user_id.raw(),
+        name,
+        email,
+        password_policy.hash(&generate_opaque_token())?,
+        Role::User.as_ref()
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+    // The address belongs to an account the identity may not be linked to.
+    if res.rows_affected() < 1 {
+        return Err(AppError::UnauthenticatedError);
+    }
+    link_identity(tx, user_id, identity).await?;
+
+    let after = user_snapshot(tx, user_id).await?;
+    record_audit(
+        tx,
+        AuditEntry::new(
+            user_id,
+            AuditAction::UserCreated,
+            user_id.raw(),
+            None,
+            after,
+        ),
+    )
+    .await?;
+
+    Ok(LinkedUserRow {
+        user_id,
+        deactivated: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn identity(subject: &str, email: &str, email_verified: bool) -> SignInWithOidc {
+        SignInWithOidc {
+            identity: OidcIdentity {
+                issuer: "https://idp.example.com".into(),
+                subject: subject.into(),
+                email: Some(email.into()),
+                email_verified,
+                name: Some("Member".into()),
+            },
+        }
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_login_request_is_taken_once(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = OidcRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            false,
+            true,
+            PasswordPolicy::default(),
+        );
+
+        repo.create_login_request(CreateOidcLoginRequest {
+            state: "state".into(),
+            nonce: "nonce".into(),
+            pkce_verifier: "verifier".into(),
+        })
+        .await?;
+        assert!(repo.take_login_request("other").await?.is_none());
+        let pending = repo.take_login_request("state").await?.unwrap();
+        assert_eq!(pending.nonce, "nonce");
+        assert_eq!(pending.pkce_verifier, "verifier");
+        assert!(repo.take_login_request("state").await?.is_none());
+
+        repo.create_login_request(CreateOidcLoginRequest {
+            state: "expired".into(),
+            nonce: "nonce".into(),
+            pkce_verifier: "verifier".into(),
+        })
+        .await?;
+        sqlx::query!(
+            "UPDATE oidc_login_requests SET expires_at = CURRENT_TIMESTAMP(3) - INTERVAL '1 second'"
+        )
+        .execute(&pool)
+        .await?;
+        assert!(repo.take_login_request("expired").await?.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_sign_in_links_existing_account(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = OidcRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            false,
+            true,
+            PasswordPolicy::default(),
+        );
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        let res = repo
+            .sign_in(identity("admin", "eleazar.fig@example.com", false))
+            .await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+
+        let user_id = repo
+            .sign_in(identity("admin", "eleazar.fig@example.com", true))
+            .await?;
+        assert_eq!(user_id, admin_id);
+        // Once linked, the identity no longer depends on the email address.
+        let user_id = repo
+            .sign_in(identity("admin", "changed@example.com", false))
+            .await?;
+        assert_eq!(user_id, admin_id);
+
+        let res = repo
+            .sign_in(identity("stranger", "stranger@example.com", true))
+            .await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+
+        sqlx::query!(
+            "UPDATE users SET deactivated_at = CURRENT_TIMESTAMP(3) WHERE user_id = $1",
+            admin_id.raw()
+        )
+        .execute(&pool)
+        .await?;
+        let res = repo
+            .sign_in(identity("admin", "eleazar.fig@example.com", true))
+            .await;
+        assert!(matches!(res, Err(AppError::ForbiddenOperation(_))));
+
+        let actions = sqlx::query_scalar!(
+            "SELECT action FROM audit_logs WHERE target_id = $1",
+            admin_id.raw()
+        )
+        .fetch_all(&pool)
+        .await?;
+        assert_eq!(actions, [AuditAction::UserIdentityLinked.as_ref()]);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_sign_in_creates_account(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = OidcRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            true,
+            false,
+            PasswordPolicy::default(),
+        );
+
+        let user_id = repo
+            .sign_in(identity("member", "member@example.com", true))
+            .await?;
+        let again = repo
+            .sign_in(identity("member", "member@example.com", true))
+            .await?;
+        assert_eq!(again, user_id);
+
+        let user = sqlx::query!(
+            r#"
+                SELECT u.name, r.name AS role
+                FROM users AS u
+                INNER JOIN roles AS r USING(role_id)
+                WHERE u.user_id = $1
+            "#,
+            user_id.raw()
+        )
+        .fetch_one(&pool)
+        .await?;
+        assert_eq!(user.name, "Member");
+        assert_eq!(user.role, Role::User.as_ref());
+
+        // Without linking, the address of an existing account cannot be taken over.
+        let res = repo
+            .sign_in(identity("admin", "eleazar.fig@example.com", true))
+            .await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use kernel::model::list::{Cursor, CursorDirection};
+
+/// Page fetched from a keyset query, with the cursors pointing at its edges.
+pub(crate) struct KeysetPage<R> {
+    pub rows: Vec<R>,
+    pub next_cursor: Option<Cursor>,
+    pub prev_cursor: Option<Cursor>,
+}
+
+/// Turns the rows of a keyset query into a page.
+///
+/// `rows` must be fetched in scan order with one row more than `limit`:
+/// ascending through the listing order for an `After` cursor (or no cursor),
+/// and in reverse for a `Before` cursor. The extra row only tells whether
+/// another page exists in the scan direction and is dropped.
+pub(crate) fn keyset_page<R>(
+    mut rows: Vec<R>,
+    limit: i64,
+    cursor: Option<&Cursor>,
+    has_rows_before: bool,
+    to_cursor: impl Fn(&R, CursorDirection) -> Cursor,
+) -> KeysetPage<R> {
+    let limit = usize::try_from(limit).unwrap_or_default();
+    let has_more = rows.len() > limit;
+    rows.truncate(limit);
+
+    let backwards = cursor.is_some_and(|c| c.direction == CursorDirection::Before);
+    let (has_prev, has_next) = if backwards {
+        rows.reverse();
+        (has_more, true)
+    } else {
+        (cursor.is_some() || has_rows_before, has_more)
+    };
+
+    let prev_cursor = rows
+        .first()
+        .filter(|_| has_prev)
+        .map(|row| to_cursor(row, CursorDirection::Before));
+    let next_cursor = rows
+        .last()
+        .filter(|_| has_next)
+        .map(|row| to_cursor(row, CursorDirection::After));
+
+    KeysetPage {
+        rows,
+        next_cursor,
+        prev_cursor,
+    }
+}
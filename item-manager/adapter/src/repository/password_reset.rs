@@ -0,0 +1,331 @@
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use derive_new::new;
+use kernel::model::{
+    audit::AuditAction,
+    id::UserId,
+    password_reset::{
+        PasswordResetToken,
+        event::{ConfirmPasswordReset, RequestPasswordReset},
+    },
+};
+use kernel::repository::password_reset::PasswordResetRepository;
+use shared::error::{AppError, AppResult};
+
+use crate::database::{
+    ConnectionPool,
+    model::{
+        auth::{generate_opaque_token, hash_opaque_token},
+        password::PasswordPolicy,
+    },
+};
+use crate::repository::audit::{AuditEntry, record_audit, user_snapshot};
+use crate::repository::auth::email_throttle_subject;
+use crate::repository::user::replace_password;
+
+/// Minimum time, in seconds, between two reset mails to the same account.
+const RESEND_INTERVAL: f64 = 60.0;
+
+#[derive(new)]
+pub struct PasswordResetRepositoryImpl {
+    db: ConnectionPool,
+    ttl: u64,
+    password_policy: PasswordPolicy,
+}
+
+#[async_trait]
+impl PasswordResetRepository for PasswordResetRepositoryImpl {
+    async fn create(&self, event: RequestPasswordReset) -> AppResult<Option<PasswordResetToken>> {
+        let mut tx = self.db.begin().await?;
+
+        let Some(user) = sqlx::query!(
+            r#"
+                SELECT user_id AS "user_id: UserId", name, email
+                FROM users
+                WHERE email = $1
+                  AND deactivated_at IS NULL
+                FOR UPDATE
+            "#,
+            event.email
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        else {
+            return Ok(None);
+        };
+
+        let recently_sent = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM password_reset_tokens
+                    WHERE user_id = $1
+                      AND created_at > CURRENT_TIMESTAMP(3) - make_interval(secs => $2)
+                ) AS "exists!"
+            "#,
+            user.user_id.raw(),
+            RESEND_INTERVAL
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if recently_sent {
+            return Ok(None);
+        }
+
+        // Only the latest link works, and expired ones of other users are cleared on the way.
+        sqlx::query!(
+            r#"
+                DELETE FROM password_reset_tokens
+                WHERE user_id = $1
+                   OR expires_at < CURRENT_TIMESTAMP(3)
+            "#,
+            user.user_id.raw()
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let token = generate_opaque_token();
+        let expires_at = Utc::now()
+            .checked_add_signed(Duration::seconds(self.ttl as i64))
+            .ok_or_else(|| {
+                AppError::ConversionEntityError(
+                    "Failed to calculate password reset token expiration".to_string(),
+                )
+            })?;
+        sqlx::query!(
+            r#"
+                INSERT INTO password_reset_tokens (user_id, token_hash, expires_at)
+                VALUES ($1, $2, $3)
+            "#,
+            user.user_id.raw(),
+            hash_opaque_token(&token),
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(Some(PasswordResetToken {
+            user_id: user.user_id,
+            name: user.name,
+            email: user.email,
+            token,
+            expires_at,
+        }))
+    }
+
+    async fn confirm(&self, event: ConfirmPasswordReset) -> AppResult<UserId> {
+        let mut tx = self.db.begin().await?;
+
+        let user = sqlx::query!(
+            r#"
+                SELECT u.user_id AS "user_id: UserId", u.email
+                FROM password_reset_tokens AS t
+                INNER JOIN users AS u USING(user_id)
+                WHERE t.token_hash = $1
+                  AND t.expires_at > CURRENT_TIMESTAMP(3)
+                  AND u.deactivated_at IS NULL
+                FOR UPDATE OF t
+            "#,
+            hash_opaque_token(&event.token)
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| {
+            AppError::UnprocessableEntity(
+                "The password reset link is invalid or has expired.".into(),
+            )
+        })?;
+
+        let before = user_snapshot(&mut tx, user.user_id).await?;
+        replace_password(
+            &mut tx,
+            &self.password_policy,
+            user.user_id,
+            &event.new_password,
+        )
+        .await?;
+        sqlx::query!(
+            r#"
+                UPDATE refresh_tokens
+                SET revoked_at = CURRENT_TIMESTAMP(3)
+                WHERE user_id = $1
+                  AND revoked_at IS NULL
+            "#,
+            user.user_id.raw(),
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        sqlx::query!(
+            r#"
+                DELETE FROM password_reset_tokens WHERE user_id = $1
+            "#,
+            user.user_id.raw(),
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        // Proving access to the mailbox is as good as an administrator unlocking the account.
+        sqlx::query!(
+            r#"
+                DELETE FROM login_throttles WHERE subject = $1
+            "#,
+            email_throttle_subject(&user.email),
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        let after = user_snapshot(&mut tx, user.user_id).await?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                user.user_id,
+                AuditAction::UserPasswordChanged,
+                user.user_id.raw(),
+                before,
+                after,
+            ),
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(user.user_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use kernel::{
+        model::{auth::event::CreateToken, user::event::CreateUser},
+        repository::{auth::AuthRepository, user::UserRepository},
+    };
+    use shared::config::LoginThrottleConfig;
+
+    use crate::database::model::auth::JwtSecret;
+    use crate::repository::{auth::AuthRepositoryImpl, user::UserRepositoryImpl};
+
+    use super::*;
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_password_reset(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let user_repo =
+            UserRepositoryImpl::new(ConnectionPool::new(pool.clone()), PasswordPolicy::default());
+        let auth_repo = AuthRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            JwtSecret::new("test_secret".to_string()),
+            3600,
+            3600,
+            LoginThrottleConfig::default(),
+            PasswordPolicy::default(),
+        );
+        let repo = PasswordResetRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            3600,
+            PasswordPolicy::default(),
+        );
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let user = user_repo
+            .create(CreateUser {
+                name: "Forgetful User".into(),
+                email: "forgetful@example.com".into(),
+                password: "old_password".into(),
+                requested_by: Some(admin_id),
+                invitation_code: None,
+            })
+            .await?;
+        let refresh_token = auth_repo
+            .create_refresh_token(CreateToken::new(user.id))
+            .await?;
+
+        let unknown = repo
+            .create(RequestPasswordReset {
+                email: "nobody@example.com".into(),
+            })
+            .await?;
+        assert!(unknown.is_none());
+
+        let reset = repo
+            .create(RequestPasswordReset {
+                email: "forgetful@example.com".into(),
+            })
+            .await?
+            .expect("a token should be issued");
+        assert_eq!(reset.user_id, user.id);
+        assert_eq!(reset.email, "forgetful@example.com");
+        // Asking again right away does not send another mail.
+        let again = repo
+            .create(RequestPasswordReset {
+                email: "forgetful@example.com".into(),
+            })
+            .await?;
+        assert!(again.is_none());
+
+        let result = repo
+            .confirm(ConfirmPasswordReset {
+                token: "not-a-token".into(),
+                new_password: "new_password".into(),
+            })
+            .await;
+        assert!(matches!(result, Err(AppError::UnprocessableEntity(_))));
+
+        let user_id = repo
+            .confirm(ConfirmPasswordReset {
+                token: reset.token.clone(),
+                new_password: "new_password".into(),
+            })
+            .await?;
+        assert_eq!(user_id, user.id);
+        let verified = auth_repo
+            .verify_user("forgetful@example.com", "new_password", None)
+            .await?;
+        assert_eq!(verified, user.id);
+        // Existing sessions are signed out.
+        let refreshed = auth_repo.rotate_refresh_token(&refresh_token).await;
+        assert!(matches!(refreshed, Err(AppError::UnauthenticatedError)));
+
+        // Tokens are single-use.
+        let result = repo
+            .confirm(ConfirmPasswordReset {
+                token: reset.token,
+                new_password: "another_password".into(),
+            })
+            .await;
+        assert!(matches!(result, Err(AppError::UnprocessableEntity(_))));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_password_reset_expired(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = PasswordResetRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            0,
+            PasswordPolicy::default(),
+        );
+
+        let reset = repo
+            .create(RequestPasswordReset {
+                email: "eleazar.fig@example.com".into(),
+            })
+            .await?
+            .expect("a token should be issued");
+        let result = repo
+            .confirm(ConfirmPasswordReset {
+                token: reset.token,
+                new_password: "new_password".into(),
+            })
+            .await;
+        assert!(matches!(result, Err(AppError::UnprocessableEntity(_))));
+
+        Ok(())
+    }
+}
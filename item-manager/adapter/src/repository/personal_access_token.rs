@@ -0,0 +1,237 @@
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::{
+    id::{PersonalAccessTokenId, UserId},
+    personal_access_token::{
+        CreatedPersonalAccessToken, PERSONAL_ACCESS_TOKEN_PREFIX, PersonalAccessToken,
+        event::{CreatePersonalAccessToken, DeletePersonalAccessToken},
+    },
+};
+use kernel::repository::personal_access_token::PersonalAccessTokenRepository;
+use shared::error::{AppError, AppResult};
+
+use crate::database::{
+    ConnectionPool,
+    model::{
+        auth::{generate_opaque_token, hash_opaque_token},
+        personal_access_token::PersonalAccessTokenRow,
+    },
+};
+
+#[derive(new)]
+pub struct PersonalAccessTokenRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl PersonalAccessTokenRepository for PersonalAccessTokenRepositoryImpl {
+    async fn create(
+        &self,
+        event: CreatePersonalAccessToken,
+    ) -> AppResult<CreatedPersonalAccessToken> {
+        let secret = format!("{PERSONAL_ACCESS_TOKEN_PREFIX}{}", generate_opaque_token());
+        let scopes = event
+            .scopes
+            .iter()
+            .map(|scope| scope.as_ref().to_string())
+            .collect::<Vec<_>>();
+
+        let row = sqlx::query_as!(
+            PersonalAccessTokenRow,
+            r#"
+                INSERT INTO personal_access_tokens
+                (user_id, name, token_hash, scopes, expires_at)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING
+                    personal_access_token_id AS "personal_access_token_id: PersonalAccessTokenId",
+                    user_id AS "user_id: UserId",
+                    name,
+                    scopes,
+                    expires_at,
+                    last_used_at,
+                    created_at
+            "#,
+            event.user_id.raw(),
+            event.name,
+            hash_opaque_token(&secret),
+            &scopes,
+            event.expires_at,
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(CreatedPersonalAccessToken {
+            token: row.try_into()?,
+            secret,
+        })
+    }
+
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Vec<PersonalAccessToken>> {
+        sqlx::query_as!(
+            PersonalAccessTokenRow,
+            r#"
+                SELECT
+                    personal_access_token_id AS "personal_access_token_id: PersonalAccessTokenId",
+                    user_id AS "user_id: UserId",
+                    name,
+                    scopes,
+                    expires_at,
+                    last_used_at,
+                    created_at
+                FROM personal_access_tokens
+                WHERE user_id = $1
+                ORDER BY created_at DESC, personal_access_token_id DESC
+            "#,
+            user_id.raw(),
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(PersonalAccessToken::try_from)
+        .collect()
+    }
+
+    async fn delete(&self, event: DeletePersonalAccessToken) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM personal_access_tokens
+                WHERE personal_access_token_id = $1
+                  AND user_id = $2
+            "#,
+            event.token_id.raw(),
+            event.user_id.raw(),
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(format!(
+                "Personal access token ({}) not found.",
+                event.token_id
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn authenticate(&self, secret: &str) -> AppResult<Option<PersonalAccessToken>> {
+        if !secret.starts_with(PERSONAL_ACCESS_TOKEN_PREFIX) {
+            return Ok(None);
+        }
+
+        let row = sqlx::query_as!(
+            PersonalAccessTokenRow,
+            r#"
+                UPDATE personal_access_tokens AS t
+                SET last_used_at = CURRENT_TIMESTAMP(3)
+                FROM users AS u
+                WHERE t.token_hash = $1
+                  AND t.expires_at > CURRENT_TIMESTAMP(3)
+                  AND u.user_id = t.user_id
+                  AND u.deactivated_at IS NULL
+                RETURNING
+                    t.personal_access_token_id AS "personal_access_token_id: PersonalAccessTokenId",
+                    t.user_id AS "user_id: UserId",
+                    t.name,
+                    t.scopes,
+                    t.expires_at,
+                    t.last_used_at,
+                    t.created_at
+            "#,
+            hash_opaque_token(secret),
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        row.map(PersonalAccessToken::try_from).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use chrono::{Duration, Utc};
+    use kernel::{
+        model::{personal_access_token::TokenScope, user::event::DeactivateUser},
+        repository::user::UserRepository,
+    };
+
+    use super::*;
+    use crate::database::model::password::PasswordPolicy;
+    use crate::repository::user::UserRepositoryImpl;
+
+    #[sqlx::test(fixtures("common", "item"))]
+    async fn test_personal_access_tokens(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let repo = PersonalAccessTokenRepositoryImpl::new(db.clone());
+        let user_repo = UserRepositoryImpl::new(db, PasswordPolicy::default());
+        let user_id = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let other_id = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5")?;
+        let create = |name: &str, expires_at| CreatePersonalAccessToken {
+            user_id,
+            name: name.into(),
+            scopes: vec![TokenScope::Read],
+            expires_at,
+        };
+
+        let created = repo
+            .create(create("inventory check", Utc::now() + Duration::days(30)))
+            .await?;
+        assert!(created.secret.starts_with(PERSONAL_ACCESS_TOKEN_PREFIX));
+        assert_eq!(created.token.scopes, [TokenScope::Read]);
+        assert!(created.token.last_used_at.is_none());
+        let expired = repo
+            .create(create("expired", Utc::now() - Duration::days(1)))
+            .await?;
+
+        // Only a hash of the secret is stored
+        let stored: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM personal_access_tokens WHERE token_hash = $1")
+                .bind(&created.secret)
+                .fetch_one(&pool)
+                .await?;
+        assert_eq!(stored, 0);
+
+        let token = repo.authenticate(&created.secret).await?.unwrap();
+        assert_eq!(token.id, created.token.id);
+        assert_eq!(token.user_id, user_id);
+        assert!(token.last_used_at.is_some());
+        assert!(repo.authenticate(&expired.secret).await?.is_none());
+        assert!(repo.authenticate("pat_unknown").await?.is_none());
+        assert!(repo.authenticate("not-a-token").await?.is_none());
+
+        let tokens = repo.find_by_user_id(user_id).await?;
+        assert_eq!(tokens.len(), 2);
+        assert!(repo.find_by_user_id(other_id).await?.is_empty());
+
+        // Tokens can only be revoked by their owner
+        let res = repo
+            .delete(DeletePersonalAccessToken {
+                token_id: expired.token.id,
+                user_id: other_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+        repo.delete(DeletePersonalAccessToken {
+            token_id: expired.token.id,
+            user_id,
+        })
+        .await?;
+        assert_eq!(repo.find_by_user_id(user_id).await?.len(), 1);
+
+        user_repo
+            .deactivate(DeactivateUser {
+                user_id,
+                requested_by: other_id,
+            })
+            .await?;
+        assert!(repo.authenticate(&created.secret).await?.is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,645 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use derive_new::new;
+use kernel::model::{
+    id::{CheckoutId, ItemId, ReservationId, UserId},
+    reservation::{
+        Reservation,
+        event::{CreateReservation, DeleteReservation},
+    },
+    role::Permission,
+};
+use kernel::repository::reservation::ReservationRepository;
+use shared::error::{AppError, AppResult};
+
+use crate::database::{
+    ConnectionPool,
+    model::{
+        checkout::CheckoutStateRow,
+        reservation::{ReservationHeadRow, ReservationHold, ReservationRow},
+    },
+    set_transaction_serializable,
+};
+use crate::repository::{item::ensure_not_archived, user::ensure_user_active};
+
+#[derive(new)]
+pub struct ReservationRepositoryImpl {
+    db: ConnectionPool,
+    hold_ttl: u64,
+}
+
+#[async_trait]
+impl ReservationRepository for ReservationRepositoryImpl {
+    async fn create(&self, event: CreateReservation) -> AppResult<ReservationId> {
+        let mut tx = self.db.begin().await?;
+
+        set_transaction_serializable(&mut tx).await?;
+
+        let state = sqlx::query_as!(
+            CheckoutStateRow,
+            r#"
+                SELECT
+                i.item_id,
+                c.checkout_id AS "checkout_id?: CheckoutId",
+                c.user_id AS "user_id?: UserId"
+                FROM items AS i
+                LEFT OUTER JOIN checkouts AS c USING(item_id)
+                WHERE item_id = $1;
+            "#,
+            event.item_id.raw()
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let checked_out = match state {
+            None => {
+                return Err(AppError::EntityNotFound(format!(
+                    "Item ({}) not found.",
+                    event.item_id
+                )));
+            }
+            Some(CheckoutStateRow {
+                user_id: Some(u), ..
+            }) if u == event.reserved_by => {
+                return Err(AppError::Conflict(format!(
+                    "The item ({}) is already checked out by the user.",
+                    event.item_id
+                )));
+            }
+            Some(CheckoutStateRow { checkout_id, .. }) => checkout_id.is_some(),
+        };
+
+        ensure_not_archived(&mut tx, event.item_id).await?;
+        ensure_user_active(&mut tx, event.reserved_by).await?;
+
+        let hold =
+            refresh_item_hold(&mut tx, event.item_id, event.reserved_at, self.hold_ttl).await?;
+        if !checked_out && hold.is_none() {
+            return Err(AppError::Conflict(format!(
+                "The item ({}) is available and can be checked out directly.",
+                event.item_id
+            )));
+        }
+
+        let reservation_id = sqlx::query_scalar!(
+            r#"
+                INSERT INTO reservations
+                (item_id, user_id, reserved_at)
+                VALUES ($1, $2, $3)
+                RETURNING reservation_id AS "reservation_id: ReservationId"
+                ;
+            "#,
+            event.item_id.raw(),
+            event.reserved_by.raw(),
+            event.reserved_at,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|err| map_sqlx_error_on_create(err, event.item_id))?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(reservation_id)
+    }
+
+    async fn delete(&self, event: DeleteReservation) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        set_transaction_serializable(&mut tx).await?;
+
+        let reserved_by = sqlx::query_scalar!(
+            r#"
+                SELECT user_id AS "user_id: UserId"
+                FROM reservations
+                WHERE reservation_id = $1
+                  AND item_id = $2
+                FOR UPDATE;
+            "#,
+            event.reservation_id.raw(),
+            event.item_id.raw(),
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        match reserved_by {
+            None => {
+                return Err(AppError::EntityNotFound(format!(
+                    "Reservation ({}) for item ({}) not found.",
+                    event.reservation_id, event.item_id
+                )));
+            }
+            Some(u)
+                if u != event.requested_by
+                    && !event
+                        .requested_by_role
+                        .has_permission(Permission::CheckoutsManageOthers) =>
+            {
+                return Err(AppError::ForbiddenOperation(format!(
+                    "Designated reservation (id({}), items({})) cannot be cancelled by another user",
+                    event.reservation_id, event.item_id
+                )));
+            }
+            Some(_) => {}
+        }
+
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM reservations WHERE reservation_id = $1;
+            "#,
+            event.reservation_id.raw(),
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::NoRowsAffectedError(
+                "No reservation record has been deleted".into(),
+            ));
+        }
+
+        // Hand the hold over to the next reservation if the cancelled one was holding the item
+        refresh_item_hold(&mut tx, event.item_id, Utc::now(), self.hold_ttl).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn find_by_item_id(&self, item_id: ItemId) -> AppResult<Vec<Reservation>> {
+        let mut tx = self.db.begin().await?;
+
+        refresh_item_hold(&mut tx, item_id, Utc::now(), self.hold_ttl).await?;
+
+        let reservations = sqlx::query_as!(
+            ReservationRow,
+            r#"
+                SELECT
+                r.reservation_id,
+                r.item_id,
+                r.user_id,
+                r.reserved_at,
+                ROW_NUMBER() OVER (ORDER BY r.reserved_at ASC, r.reservation_id ASC) AS "position!",
+                r.ready_at,
+                r.expires_at
+                FROM reservations AS r
+                WHERE r.item_id = $1
+                ORDER BY r.reserved_at ASC, r.reservation_id ASC
+                ;
+            "#,
+            item_id.raw()
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(reservations.into_iter().map(Reservation::from).collect())
+    }
+
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Reservation>> {
+        let mut tx = self.db.begin().await?;
+
+        let item_ids = sqlx::query_scalar!(
+            r#"
+                SELECT item_id AS "item_id: ItemId"
+                FROM reservations
+                WHERE user_id = $1;
+            "#,
+            user_id.raw()
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let now = Utc::now();
+        for item_id in item_ids {
+            refresh_item_hold(&mut tx, item_id, now, self.hold_ttl).await?;
+        }
+
+        let reservations = sqlx::query_as!(
+            ReservationRow,
+            r#"
+                SELECT
+                r.reservation_id AS "reservation_id!: ReservationId",
+                r.item_id AS "item_id!: ItemId",
+                r.user_id AS "user_id!: UserId",
+                r.reserved_at AS "reserved_at!",
+                r.position AS "position!",
+                r.ready_at,
+                r.expires_at
+                FROM (
+                    SELECT
+                    reservations.*,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY item_id
+                        ORDER BY reserved_at ASC, reservation_id ASC
+                    ) AS position
+                    FROM reservations
+                ) AS r
+                WHERE r.user_id = $1
+                ORDER BY r.reserved_at ASC
+                ;
+            "#,
+            user_id.raw()
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(reservations.into_iter().map(Reservation::from).collect())
+    }
+}
+
+/// Brings the hold on an item's reservation queue up to date and returns the active one.
+///
+/// Lapsed holds are dropped, and while the item is not checked out the head of the queue is
+/// held for `hold_ttl` seconds, starting where the previous hold ended.
+pub(crate) async fn refresh_item_hold(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    item_id: ItemId,
+    now: DateTime<Utc>,
+    hold_ttl: u64,
+) -> AppResult<Option<ReservationHold>> {
+    let hold_ttl = Duration::seconds(hold_ttl as i64);
+
+    let checked_out = sqlx::query_scalar!(
+        r#"
+            SELECT EXISTS(SELECT 1 FROM checkouts WHERE item_id = $1) AS "checked_out!";
+        "#,
+        item_id.raw()
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    let mut hold_start = now;
+    loop {
+        let head = sqlx::query_as!(
+            ReservationHeadRow,
+            r#"
+                SELECT
+                reservation_id,
+                user_id,
+                expires_at
+                FROM reservations
+                WHERE item_id = $1
+                ORDER BY reserved_at ASC, reservation_id ASC
+                LIMIT 1
+                FOR UPDATE;
+            "#,
+            item_id.raw()
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let Some(head) = head else {
+            return Ok(None);
+        };
+
+        let expires_at = match head.expires_at {
+            Some(expires_at) => expires_at,
+            None if checked_out => return Ok(None),
+            None => {
+                let expires_at = hold_start + hold_ttl;
+                if expires_at > now {
+                    sqlx::query!(
+                        r#"
+                            UPDATE reservations
+                            SET ready_at = $2, expires_at = $3
+                            WHERE reservation_id = $1;
+                        "#,
+                        head.reservation_id.raw(),
+                        hold_start,
+                        expires_at,
+                    )
+                    .execute(&mut **tx)
+                    .await
+                    .map_err(AppError::SpecificOperationError)?;
+                }
+                expires_at
+            }
+        };
+
+        if expires_at > now {
+            return Ok(Some(ReservationHold {
+                reservation_id: head.reservation_id,
+                user_id: head.user_id,
+                expires_at,
+            }));
+        }
+
+        sqlx::query!(
+            r#"
+                DELETE FROM reservations WHERE reservation_id = $1;
+            "#,
+            head.reservation_id.raw(),
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        hold_start = expires_at;
+    }
+}
+
+fn map_sqlx_error_on_create(err: sqlx::Error, item_id: ItemId) -> AppError {
+    match &err {
+        sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("23505") => {
+            let message = match db_err.constraint() {
+                Some("reservations_item_id_user_id_key") => {
+                    format!(
+                        "The item ({}) has already been reserved by the user.",
+                        item_id
+                    )
+                }
+                _ => "Unique constraint violation.".to_string(),
+            };
+            AppError::Conflict(message)
+        }
+        _ => AppError::SpecificOperationError(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use kernel::{
+        model::{
+            checkout::event::{CreateCheckout, UpdateReturned},
+            role::Role,
+        },
+        repository::checkout::CheckoutRepository,
+    };
+
+    use shared::config::LoanConfig;
+
+    use super::*;
+    use crate::repository::checkout::CheckoutRepositoryImpl;
+
+    const HOLD_TTL: u64 = 3600;
+
+    fn item_id() -> ItemId {
+        ItemId::from_str("9890736e-a4e4-461a-a77d-eac3517ef113").unwrap()
+    }
+
+    async fn checkout_and_return(
+        checkout_repo: &CheckoutRepositoryImpl,
+        user_id: UserId,
+        returned_at: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let checkout = checkout_repo
+            .find_history_by_item_id(item_id(), Default::default())
+            .await?
+            .items;
+        let checkout_id = match checkout.first() {
+            Some(c) if c.returned_at.is_none() => c.id,
+            _ => anyhow::bail!("item is not checked out"),
+        };
+        checkout_repo
+            .update_returned(UpdateReturned::new(
+                checkout_id,
+                item_id(),
+                user_id,
+                Role::User,
+                returned_at,
+            ))
+            .await?;
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "item"))]
+    async fn test_reservation_queue_flow(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let checkout_repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            HOLD_TTL,
+            LoanConfig::default(),
+        );
+        let repo = ReservationRepositoryImpl::new(ConnectionPool::new(pool), HOLD_TTL);
+        let borrower = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let waiter = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5")?;
+        let other = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        // An available item cannot be reserved
+        let res = repo
+            .create(CreateReservation::new(item_id(), waiter, Utc::now()))
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))), "{res:?}");
+
+        checkout_repo
+            .create(CreateCheckout::new(
+                item_id(),
+                borrower,
+                borrower,
+                Utc::now(),
+                None,
+            ))
+            .await?;
+
+        // The borrower cannot queue for their own checkout
+        let res = repo
+            .create(CreateReservation::new(item_id(), borrower, Utc::now()))
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))), "{res:?}");
+
+        let reservation_id = repo
+            .create(CreateReservation::new(item_id(), waiter, Utc::now()))
+            .await?;
+        repo.create(CreateReservation::new(item_id(), other, Utc::now()))
+            .await?;
+
+        // Reserving twice is rejected
+        let res = repo
+            .create(CreateReservation::new(item_id(), waiter, Utc::now()))
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))), "{res:?}");
+
+        let queue = repo.find_by_item_id(item_id()).await?;
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue[0].id, reservation_id);
+        assert_eq!(queue[0].position, 1);
+        assert_eq!(queue[1].reserved_by, other);
+        assert_eq!(queue[1].position, 2);
+        assert!(queue.iter().all(|r| r.ready_at.is_none()));
+
+        checkout_and_return(&checkout_repo, borrower, Utc::now()).await?;
+
+        // The head of the queue now holds the item
+        let queue = repo.find_by_item_id(item_id()).await?;
+        assert!(queue[0].ready_at.is_some());
+        assert!(queue[0].expires_at.is_some());
+        assert!(queue[1].ready_at.is_none());
+
+        // Nobody else can check the item out during the window
+        let res = checkout_repo
+            .create(CreateCheckout::new(
+                item_id(),
+                other,
+                other,
+                Utc::now(),
+                None,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))), "{res:?}");
+
+        // The holder can, which fulfils their reservation
+        checkout_repo
+            .create(CreateCheckout::new(
+                item_id(),
+                waiter,
+                waiter,
+                Utc::now(),
+                None,
+            ))
+            .await?;
+        let queue = repo.find_by_item_id(item_id()).await?;
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].reserved_by, other);
+        assert_eq!(queue[0].position, 1);
+
+        let mine = repo.find_by_user_id(other).await?;
+        assert_eq!(mine.len(), 1);
+        assert_eq!(mine[0].item_id, item_id());
+        assert_eq!(mine[0].position, 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "item"))]
+    async fn test_expired_hold_passes_to_next_reservation(
+        pool: sqlx::PgPool,
+    ) -> anyhow::Result<()> {
+        let checkout_repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            HOLD_TTL,
+            LoanConfig::default(),
+        );
+        let repo = ReservationRepositoryImpl::new(ConnectionPool::new(pool), HOLD_TTL);
+        let borrower = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let waiter = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5")?;
+        let other = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        let checked_out_at = Utc::now() - Duration::hours(3);
+        checkout_repo
+            .create(CreateCheckout::new(
+                item_id(),
+                borrower,
+                borrower,
+                checked_out_at,
+                None,
+            ))
+            .await?;
+        repo.create(CreateReservation::new(item_id(), waiter, checked_out_at))
+            .await?;
+        repo.create(CreateReservation::new(
+            item_id(),
+            other,
+            checked_out_at + Duration::seconds(1),
+        ))
+        .await?;
+
+        // Returned 90 minutes ago: the first hold lapsed 30 minutes ago
+        checkout_and_return(&checkout_repo, borrower, Utc::now() - Duration::minutes(90)).await?;
+
+        let queue = repo.find_by_item_id(item_id()).await?;
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].reserved_by, other);
+        let expires_at = queue[0]
+            .expires_at
+            .expect("next reservation should be held");
+        assert!(expires_at > Utc::now());
+        assert!(expires_at <= Utc::now() + Duration::minutes(31));
+
+        let res = checkout_repo
+            .create(CreateCheckout::new(
+                item_id(),
+                waiter,
+                waiter,
+                Utc::now(),
+                None,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))), "{res:?}");
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "item"))]
+    async fn test_cancel_reservation(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let checkout_repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            HOLD_TTL,
+            LoanConfig::default(),
+        );
+        let repo = ReservationRepositoryImpl::new(ConnectionPool::new(pool), HOLD_TTL);
+        let borrower = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let waiter = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5")?;
+
+        checkout_repo
+            .create(CreateCheckout::new(
+                item_id(),
+                borrower,
+                borrower,
+                Utc::now(),
+                None,
+            ))
+            .await?;
+        let reservation_id = repo
+            .create(CreateReservation::new(item_id(), waiter, Utc::now()))
+            .await?;
+        checkout_and_return(&checkout_repo, borrower, Utc::now()).await?;
+
+        // Only the owner or an admin can cancel
+        let res = repo
+            .delete(DeleteReservation::new(
+                reservation_id,
+                item_id(),
+                borrower,
+                Role::User,
+            ))
+            .await;
+        assert!(
+            matches!(res, Err(AppError::ForbiddenOperation(_))),
+            "{res:?}"
+        );
+
+        repo.delete(DeleteReservation::new(
+            reservation_id,
+            item_id(),
+            waiter,
+            Role::User,
+        ))
+        .await?;
+        assert!(repo.find_by_item_id(item_id()).await?.is_empty());
+
+        // With the hold released, anyone can check the item out again
+        checkout_repo
+            .create(CreateCheckout::new(
+                item_id(),
+                borrower,
+                borrower,
+                Utc::now(),
+                None,
+            ))
+            .await?;
+
+        let res = repo
+            .delete(DeleteReservation::new(
+                reservation_id,
+                item_id(),
+                waiter,
+                Role::User,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))), "{res:?}");
+
+        Ok(())
+    }
+}
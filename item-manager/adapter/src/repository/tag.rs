@@ -0,0 +1,432 @@
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::{
+    audit::AuditAction,
+    id::{ItemId, TagId},
+    tag::{
+        Tag,
+        event::{AttachTag, CreateTag, DeleteTag, DetachTag, UpdateTag},
+    },
+};
+use kernel::repository::tag::TagRepository;
+use shared::error::{AppError, AppResult};
+
+use crate::database::{ConnectionPool, model::tag::TagRow};
+use crate::repository::audit::{AuditEntry, record_audit};
+
+#[derive(new)]
+pub struct TagRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl TagRepository for TagRepositoryImpl {
+    async fn create(&self, event: CreateTag) -> AppResult<Tag> {
+        let mut tx = self.db.begin().await?;
+
+        let row = sqlx::query_as!(
+            TagRow,
+            r#"
+                INSERT INTO tags (name)
+                VALUES ($1)
+                RETURNING tag_id AS "tag_id: TagId", name
+            "#,
+            event.name.trim()
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(map_sqlx_error)?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.requested_by,
+                AuditAction::TagCreated,
+                row.tag_id.raw(),
+                None,
+                Some(tag_snapshot(&row)),
+            ),
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(row.into())
+    }
+
+    async fn find_all(&self) -> AppResult<Vec<Tag>> {
+        let rows = sqlx::query_as!(
+            TagRow,
+            r#"
+                SELECT tag_id AS "tag_id: TagId", name
+                FROM tags
+                ORDER BY LOWER(name), tag_id
+            "#
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(rows.into_iter().map(Tag::from).collect())
+    }
+
+    async fn update(&self, event: UpdateTag) -> AppResult<Tag> {
+        let mut tx = self.db.begin().await?;
+
+        let before = find_tag(&mut tx, event.tag_id).await?;
+        let row = sqlx::query_as!(
+            TagRow,
+            r#"
+                UPDATE tags
+                SET name = $2
+                WHERE tag_id = $1
+                RETURNING tag_id AS "tag_id: TagId", name
+            "#,
+            event.tag_id.raw(),
+            event.name.trim()
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(map_sqlx_error)?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.requested_by,
+                AuditAction::TagUpdated,
+                row.tag_id.raw(),
+                Some(tag_snapshot(&before)),
+                Some(tag_snapshot(&row)),
+            ),
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(row.into())
+    }
+
+    async fn delete(&self, event: DeleteTag) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        let before = find_tag(&mut tx, event.tag_id).await?;
+        sqlx::query!(
+            r#"
+                DELETE FROM tags WHERE tag_id = $1
+            "#,
+            event.tag_id.raw()
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.requested_by,
+                AuditAction::TagDeleted,
+                event.tag_id.raw(),
+                Some(tag_snapshot(&before)),
+                None,
+            ),
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn attach(&self, event: AttachTag) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        ensure_item_exists(&mut tx, event.item_id).await?;
+        let tag = find_tag(&mut tx, event.tag_id).await?;
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO item_tags (item_id, tag_id)
+                VALUES ($1, $2)
+                ON CONFLICT DO NOTHING
+            "#,
+            event.item_id.raw(),
+            event.tag_id.raw()
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() > 0 {
+            record_audit(
+                &mut tx,
+                AuditEntry::new(
+                    event.requested_by,
+                    AuditAction::ItemTagged,
+                    event.item_id.raw(),
+                    None,
+                    Some(tag_snapshot(&tag)),
+                ),
+            )
+            .await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn detach(&self, event: DetachTag) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        ensure_item_exists(&mut tx, event.item_id).await?;
+        let tag = find_tag(&mut tx, event.tag_id).await?;
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM item_tags
+                WHERE item_id = $1
+                  AND tag_id = $2
+            "#,
+            event.item_id.raw(),
+            event.tag_id.raw()
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() > 0 {
+            record_audit(
+                &mut tx,
+                AuditEntry::new(
+                    event.requested_by,
+                    AuditAction::ItemUntagged,
+                    event.item_id.raw(),
+                    Some(tag_snapshot(&tag)),
+                    None,
+                ),
+            )
+            .await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+}
+
+async fn find_tag(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    tag_id: TagId,
+) -> AppResult<TagRow> {
+    sqlx::query_as!(
+        TagRow,
+        r#"
+            SELECT tag_id AS "tag_id: TagId", name
+            FROM tags
+            WHERE tag_id = $1
+            FOR UPDATE
+        "#,
+        tag_id.raw()
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?
+    .ok_or_else(|| AppError::EntityNotFound("specified tag not found".into()))
+}
+
+async fn ensure_item_exists(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    item_id: ItemId,
+) -> AppResult<()> {
+    sqlx::query_scalar!(
+        r#"
+            SELECT item_id FROM items WHERE item_id = $1
+        "#,
+        item_id.raw()
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?
+    .ok_or_else(|| AppError::EntityNotFound("specified item not found".into()))?;
+
+    Ok(())
+}
+
+fn tag_snapshot(row: &TagRow) -> serde_json::Value {
+    serde_json::json!({
+        "tag_id": row.tag_id.raw(),
+        "name": row.name,
+    })
+}
+
+fn map_sqlx_error(err: sqlx::Error) -> AppError {
+    match &err {
+        sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("23505") => {
+            AppError::Conflict("A tag with the same name already exists.".into())
+        }
+        _ => AppError::SpecificOperationError(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use kernel::model::id::UserId;
+
+    use super::*;
+
+    fn admin_id() -> UserId {
+        UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c").unwrap()
+    }
+
+    fn item_id() -> ItemId {
+        ItemId::from_str("9890736e-a4e4-461a-a77d-eac3517ef113").unwrap()
+    }
+
+    fn create(name: &str) -> CreateTag {
+        CreateTag {
+            name: name.into(),
+            requested_by: admin_id(),
+        }
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_tags(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = TagRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+
+        let prep = repo.create(create("  JOI prep ")).await?;
+        assert_eq!(prep.name, "JOI prep");
+        let kit = repo.create(create("contest kit")).await?;
+
+        // Names are unique regardless of case
+        let res = repo.create(create("Contest Kit")).await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        let tags = repo.find_all().await?;
+        assert_eq!(tags, vec![kit.clone(), prep.clone()]);
+
+        let renamed = repo
+            .update(UpdateTag {
+                tag_id: kit.id,
+                name: "Contest kit".into(),
+                requested_by: admin_id(),
+            })
+            .await?;
+        assert_eq!(renamed.name, "Contest kit");
+        let res = repo
+            .update(UpdateTag {
+                tag_id: kit.id,
+                name: "joi PREP".into(),
+                requested_by: admin_id(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        repo.delete(DeleteTag {
+            tag_id: prep.id,
+            requested_by: admin_id(),
+        })
+        .await?;
+        assert_eq!(repo.find_all().await?, vec![renamed]);
+        let res = repo
+            .delete(DeleteTag {
+                tag_id: prep.id,
+                requested_by: admin_id(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        let actions = sqlx::query_scalar!(
+            r#"
+                SELECT action FROM audit_logs
+                WHERE target_id = $1
+                ORDER BY created_at
+            "#,
+            kit.id.raw()
+        )
+        .fetch_all(&pool)
+        .await?;
+        assert_eq!(
+            actions,
+            [
+                AuditAction::TagCreated.as_ref(),
+                AuditAction::TagUpdated.as_ref()
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "item"))]
+    async fn test_attach_and_detach(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = TagRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let tag = repo.create(create("contest kit")).await?;
+        let attach = || AttachTag {
+            item_id: item_id(),
+            tag_id: tag.id,
+            requested_by: admin_id(),
+        };
+        let detach = || DetachTag {
+            item_id: item_id(),
+            tag_id: tag.id,
+            requested_by: admin_id(),
+        };
+
+        // Repeating either operation changes nothing and is not audited again
+        repo.attach(attach()).await?;
+        repo.attach(attach()).await?;
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM item_tags WHERE item_id = $1"#,
+            item_id().raw()
+        )
+        .fetch_one(&pool)
+        .await?;
+        assert_eq!(count, 1);
+        repo.detach(detach()).await?;
+        repo.detach(detach()).await?;
+
+        let actions = sqlx::query_scalar!(
+            r#"
+                SELECT action FROM audit_logs
+                WHERE target_id = $1
+                ORDER BY created_at
+            "#,
+            item_id().raw()
+        )
+        .fetch_all(&pool)
+        .await?;
+        assert_eq!(
+            actions,
+            [
+                AuditAction::ItemTagged.as_ref(),
+                AuditAction::ItemUntagged.as_ref()
+            ]
+        );
+
+        let res = repo
+            .attach(AttachTag {
+                item_id: ItemId::new(),
+                ..attach()
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+        let res = repo
+            .attach(AttachTag {
+                tag_id: TagId::new(),
+                ..attach()
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        // Deleting a tag detaches it from its items
+        repo.attach(attach()).await?;
+        repo.delete(DeleteTag {
+            tag_id: tag.id,
+            requested_by: admin_id(),
+        })
+        .await?;
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM item_tags WHERE item_id = $1"#,
+            item_id().raw()
+        )
+        .fetch_one(&pool)
+        .await?;
+        assert_eq!(count, 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,714 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use derive_new::new;
+use kernel::model::{
+    audit::AuditAction,
+    id::UserId,
+    role::Role,
+    two_factor::{
+        CompletedLoginChallenge, LoginChallenge, TotpSetup, TwoFactorStatus,
+        event::{
+            CompleteLoginChallenge, ConfirmTotp, CreateLoginChallenge, DisableTotp, SetUpTotp,
+        },
+    },
+};
+use kernel::repository::two_factor::TwoFactorRepository;
+use shared::error::{AppError, AppResult};
+
+use crate::database::{
+    ConnectionPool,
+    model::{
+        auth::{generate_opaque_token, hash_opaque_token},
+        two_factor::{
+            LoginChallengeRow, TotpSecretRow, generate_recovery_code, generate_totp_setup,
+            is_totp_code, normalize_recovery_code, verify_totp,
+        },
+    },
+};
+use crate::repository::audit::{AuditEntry, record_audit, user_snapshot};
+
+/// How long, in seconds, the second login step may take.
+const CHALLENGE_TTL: i64 = 5 * 60;
+/// Wrong codes a challenge tolerates before the password has to be entered again.
+const MAX_CHALLENGE_ATTEMPTS: i32 = 5;
+const RECOVERY_CODE_COUNT: usize = 10;
+
+#[derive(new)]
+pub struct TwoFactorRepositoryImpl {
+    db: ConnectionPool,
+    /// Administrators without an authenticator have to set one up before their login completes.
+    required_for_admins: bool,
+}
+
+#[async_trait]
+impl TwoFactorRepository for TwoFactorRepositoryImpl {
+    async fn find_status(&self, user_id: UserId) -> AppResult<TwoFactorStatus> {
+        let status = sqlx::query!(
+            r#"
+                SELECT
+                    EXISTS (
+                        SELECT 1 FROM user_totp_secrets
+                        WHERE user_id = $1
+                          AND confirmed_at IS NOT NULL
+                    ) AS "enabled!",
+                    (
+                        SELECT COUNT(*) FROM totp_recovery_codes WHERE user_id = $1
+                    ) AS "recovery_codes_remaining!"
+            "#,
+            user_id.raw()
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(TwoFactorStatus {
+            enabled: status.enabled,
+            recovery_codes_remaining: status.recovery_codes_remaining as u32,
+        })
+    }
+
+    async fn set_up(&self, event: SetUpTotp) -> AppResult<TotpSetup> {
+        let mut tx = self.db.begin().await?;
+        let setup = set_up_secret(&mut tx, event.user_id).await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(setup)
+    }
+
+    async fn confirm(&self, event: ConfirmTotp) -> AppResult<Vec<String>> {
+        let mut tx = self.db.begin().await?;
+
+        let secret = pending_secret(&mut tx, event.user_id).await?;
+        let step = verify_totp(&secret.secret, event.code.trim(), secret.last_used_step)?
+            .ok_or_else(|| AppError::UnprocessableEntity("The code is incorrect.".into()))?;
+        let recovery_codes = enable(&mut tx, event.user_id, step).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(recovery_codes)
+    }
+
+    async fn disable(&self, event: DisableTotp) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        if self.required_for_admins && find_role(&mut tx, event.user_id).await? == Role::Admin {
+            return Err(AppError::ForbiddenOperation(
+                "Two-factor authentication is required for administrators.".into(),
+            ));
+        }
+
+        let secret = find_secret(&mut tx, event.user_id)
+            .await?
+            .filter(|secret| secret.confirmed)
+            .ok_or_else(|| {
+                AppError::UnprocessableEntity("Two-factor authentication is not enabled.".into())
+            })?;
+        if !verify_second_factor(&mut tx, event.user_id, &secret, &event.code).await? {
+            return Err(AppError::UnprocessableEntity(
+                "The code is incorrect.".into(),
+            ));
+        }
+
+        let before = user_snapshot(&mut tx, event.user_id).await?;
+        sqlx::query!(
+            r#"
+                DELETE FROM user_totp_secrets WHERE user_id = $1
+            "#,
+            event.user_id.raw()
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        sqlx::query!(
+            r#"
+                DELETE FROM totp_recovery_codes WHERE user_id = $1
+            "#,
+            event.user_id.raw()
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        let after = user_snapshot(&mut tx, event.user_id).await?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.user_id,
+                AuditAction::UserTwoFactorDisabled,
+                event.user_id.raw(),
+                before,
+                after,
+            ),
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn create_challenge(
+        &self,
+        event: CreateLoginChallenge,
+    ) -> AppResult<Option<LoginChallenge>> {
+        let mut tx = self.db.begin().await?;
+
+        let enabled = find_secret(&mut tx, event.user_id)
+            .await?
+            .is_some_and(|secret| secret.confirmed);
+        let setup_required = !enabled
+            && self.required_for_admins
+            && find_role(&mut tx, event.user_id).await? == Role::Admin;
+        if !enabled && !setup_required {
+            return Ok(None);
+        }
+
+        sqlx::query!(
+            r#"
+                DELETE FROM login_challenges WHERE expires_at < CURRENT_TIMESTAMP(3)
+            "#
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let token = generate_opaque_token();
+        let expires_at = Utc::now() + Duration::seconds(CHALLENGE_TTL);
+        sqlx::query!(
+            r#"
+                INSERT INTO login_challenges (user_id, token_hash, setup_required, expires_at)
+                VALUES ($1, $2, $3, $4)
+            "#,
+            event.user_id.raw(),
+            hash_opaque_token(&token),
+            setup_required,
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(Some(LoginChallenge {
+            token,
+            expires_at,
+            setup_required,
+        }))
+    }
+
+    async fn set_up_for_challenge(&self, token: &str) -> AppResult<TotpSetup> {
+        let mut tx = self.db.begin().await?;
+
+        let challenge = find_challenge(&mut tx, token)
+            .await?
+            .filter(|challenge| challenge.setup_required)
+            .ok_or(AppError::UnauthenticatedError)?;
+        let setup = set_up_secret(&mut tx, challenge.user_id).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(setup)
+    }
+
+    async fn complete_challenge(
+        &self,
+        event: CompleteLoginChallenge,
+    ) -> AppResult<CompletedLoginChallenge> {
+        let mut tx = self.db.begin().await?;
+
+        let challenge = find_challenge(&mut tx, &event.token)
+            .await?
+            .ok_or(AppError::UnauthenticatedError)?;
+        let secret = find_secret(&mut tx, challenge.user_id).await?;
+
+        let recovery_codes = match secret {
+            Some(secret) if secret.confirmed => {
+                verify_second_factor(&mut tx, challenge.user_id, &secret, &event.code)
+                    .await?
+                    .then_some(None)
+            }
+            // Only an authenticator proves the setup worked, so recovery codes are not accepted.
+            Some(secret) if challenge.setup_required => {
+                match verify_totp(&secret.secret, event.code.trim(), secret.last_used_step)? {
+                    Some(step) => Some(Some(enable(&mut tx, challenge.user_id, step).await?)),
+                    None => None,
+                }
+            }
+            _ => None,
+        };
+
+        let Some(recovery_codes) = recovery_codes else {
+            if challenge.failed_attempts + 1 >= MAX_CHALLENGE_ATTEMPTS {
+                delete_challenge(&mut tx, &challenge).await?;
+            } else {
+                sqlx::query!(
+                    r#"
+                        UPDATE login_challenges
+                        SET failed_attempts = failed_attempts + 1
+                        WHERE login_challenge_id = $1
+                    "#,
+                    challenge.login_challenge_id
+                )
+                .execute(&mut *tx)
+                .await
+                .map_err(AppError::SpecificOperationError)?;
+            }
+            tx.commit().await.map_err(AppError::TransactionError)?;
+            return Err(AppError::UnauthenticatedError);
+        };
+
+        delete_challenge(&mut tx, &challenge).await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(CompletedLoginChallenge {
+            user_id: challenge.user_id,
+            recovery_codes,
+        })
+    }
+}
+
+async fn find_role(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: UserId,
+) -> AppResult<Role> {
+    let role_name = sqlx::query_scalar!(
+        r#"
+            SELECT r.name
+            FROM users AS u
+            INNER JOIN roles AS r USING(role_id)
+            WHERE u.user_id = $1
+        "#,
+        user_id.raw()
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?
+    .ok_or_else(|| AppError::EntityNotFound(format!("User ({user_id}) not found.")))?;
+
+    Role::from_str(&role_name).map_err(|e| AppError::ConversionEntityError(e.to_string()))
+}
+
+async fn find_secret(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: UserId,
+) -> AppResult<Option<TotpSecretRow>> {
+    sqlx::query_as!(
+        TotpSecretRow,
+        r#"
+            SELECT
+                secret,
+                confirmed_at IS NOT NULL AS "confirmed!",
+                last_used_step
+            FROM user_totp_secrets
+            WHERE user_id = $1
+            FOR UPDATE
+        "#,
+        user_id.raw()
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)
+}
+
+/// The secret from an enrollment that has not been confirmed yet.
+async fn pending_secret(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: UserId,
+) -> AppResult<TotpSecretRow> {
+    match find_secret(tx, user_id).await? {
+        Some(secret) if secret.confirmed => Err(AppError::Conflict(
+            "Two-factor authentication is already enabled.".into(),
+        )),
+        Some(secret) => Ok(secret),
+        None => Err(AppError::UnprocessableEntity(
+            "Set up two-factor authentication first.".into(),
+        )),
+    }
+}
+
+async fn set_up_secret(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: UserId,
+) -> AppResult<TotpSetup> {
+    let email = sqlx::query_scalar!(
+        r#"
+            SELECT email FROM users WHERE user_id = $1
+        "#,
+        user_id.raw()
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?
+    .ok_or_else(|| AppError::EntityNotFound(format!("User ({user_id}) not found.")))?;
+    if find_secret(tx, user_id)
+        .await?
+        .is_some_and(|secret| secret.confirmed)
+    {
+        return Err(AppError::Conflict(
+            "Two-factor authentication is already enabled.".into(),
+        ));
+    }
+
+    let setup = generate_totp_setup(&email)?;
+    sqlx::query!(
+        r#"
+            INSERT INTO user_totp_secrets (user_id, secret)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE SET
+                secret = EXCLUDED.secret,
+                last_used_step = NULL,
+                created_at = CURRENT_TIMESTAMP(3)
+        "#,
+        user_id.raw(),
+        setup.secret
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    Ok(setup)
+}
+
+/// Confirms the pending secret and replaces the recovery codes, returning the new ones.
+async fn enable(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: UserId,
+    step: i64,
+) -> AppResult<Vec<String>> {
+    let before = user_snapshot(tx, user_id).await?;
+    sqlx::query!(
+        r#"
+            UPDATE user_totp_secrets
+            SET confirmed_at = CURRENT_TIMESTAMP(3),
+                last_used_step = $2
+            WHERE user_id = $1
+        "#,
+        user_id.raw(),
+        step
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    sqlx::query!(
+        r#"
+            DELETE FROM totp_recovery_codes WHERE user_id = $1
+        "#,
+        user_id.raw()
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+    let recovery_codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| generate_recovery_code())
+        .collect();
+    let code_hashes: Vec<String> = recovery_codes
+        .iter()
+        .map(|code| hash_opaque_token(&normalize_recovery_code(code)))
+        .collect();
+    sqlx::query!(
+        r#"
+            INSERT INTO totp_recovery_codes (user_id, code_hash)
+            SELECT $1, UNNEST($2::VARCHAR(255)[])
+        "#,
+        user_id.raw(),
+        &code_hashes
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    let after = user_snapshot(tx, user_id).await?;
+    record_audit(
+        tx,
+        AuditEntry::new(
+            user_id,
+            AuditAction::UserTwoFactorEnabled,
+            user_id.raw(),
+            before,
+            after,
+        ),
+    )
+    .await?;
+
+    Ok(recovery_codes)
+}
+
+/// Checks a TOTP code or consumes a recovery code of a user with two-factor authentication.
+async fn verify_second_factor(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: UserId,
+    secret: &TotpSecretRow,
+    code: &str,
+) -> AppResult<bool> {
+    let code = code.trim();
+    if is_totp_code(code) {
+        let Some(step) = verify_totp(&secret.secret, code, secret.last_used_step)? else {
+            return Ok(false);
+        };
+        sqlx::query!(
+            r#"
+                UPDATE user_totp_secrets SET last_used_step = $2 WHERE user_id = $1
+            "#,
+            user_id.raw(),
+            step
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        return Ok(true);
+    }
+
+    let res = sqlx::query!(
+        r#"
+            DELETE FROM totp_recovery_codes WHERE user_id = $1 AND code_hash = $2
+        "#,
+        user_id.raw(),
+        hash_opaque_token(&normalize_recovery_code(code))
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    Ok(res.rows_affected() > 0)
+}
+
+async fn find_challenge(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    token: &str,
+) -> AppResult<Option<LoginChallengeRow>> {
+    sqlx::query_as!(
+        LoginChallengeRow,
+        r#"
+            SELECT
+                c.login_challenge_id,
+                c.user_id AS "user_id: UserId",
+                c.setup_required,
+                c.failed_attempts
+            FROM login_challenges AS c
+            INNER JOIN users AS u USING(user_id)
+            WHERE c.token_hash = $1
+              AND c.expires_at > CURRENT_TIMESTAMP(3)
+              AND u.deactivated_at IS NULL
+            FOR UPDATE OF c
+        "#,
+        hash_opaque_token(token)
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)
+}
+
+async fn delete_challenge(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    challenge: &LoginChallengeRow,
+) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+            DELETE FROM login_challenges WHERE login_challenge_id = $1
+        "#,
+        challenge.login_challenge_id
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use totp_rs::{Algorithm, Secret, TOTP};
+
+    use super::*;
+
+    fn admin_id() -> UserId {
+        UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c").unwrap()
+    }
+
+    /// The code an authenticator app shows `offset` seconds from now.
+    fn code(setup: &TotpSetup, offset: i64) -> String {
+        let secret = Secret::Encoded(setup.secret.clone()).to_bytes().unwrap();
+        let totp = TOTP::new_unchecked(Algorithm::SHA1, 6, 0, 30, secret, None, String::new());
+        totp.generate((Utc::now().timestamp() + offset) as u64)
+    }
+
+    async fn challenge(repo: &TwoFactorRepositoryImpl) -> anyhow::Result<LoginChallenge> {
+        Ok(repo
+            .create_challenge(CreateLoginChallenge {
+                user_id: admin_id(),
+            })
+            .await?
+            .expect("two-factor authentication should be enabled"))
+    }
+
+    async fn complete(
+        repo: &TwoFactorRepositoryImpl,
+        token: &str,
+        code: &str,
+    ) -> AppResult<CompletedLoginChallenge> {
+        repo.complete_challenge(CompleteLoginChallenge {
+            token: token.into(),
+            code: code.into(),
+        })
+        .await
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_two_factor_login(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = TwoFactorRepositoryImpl::new(ConnectionPool::new(pool.clone()), false);
+
+        let none = repo
+            .create_challenge(CreateLoginChallenge {
+                user_id: admin_id(),
+            })
+            .await?;
+        assert!(none.is_none());
+
+        let res = repo
+            .confirm(ConfirmTotp {
+                user_id: admin_id(),
+                code: "123456".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        let setup = repo
+            .set_up(SetUpTotp {
+                user_id: admin_id(),
+            })
+            .await?;
+        assert!(
+            setup
+                .otpauth_url
+                .starts_with("otpauth://totp/procon-manager")
+        );
+        let res = repo
+            .confirm(ConfirmTotp {
+                user_id: admin_id(),
+                code: "wrong".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        let current = code(&setup, 0);
+        let recovery_codes = repo
+            .confirm(ConfirmTotp {
+                user_id: admin_id(),
+                code: current.clone(),
+            })
+            .await?;
+        assert_eq!(recovery_codes.len(), RECOVERY_CODE_COUNT);
+        let status = repo.find_status(admin_id()).await?;
+        assert!(status.enabled);
+        assert_eq!(status.recovery_codes_remaining, RECOVERY_CODE_COUNT as u32);
+        let res = repo
+            .set_up(SetUpTotp {
+                user_id: admin_id(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        // The code used for confirmation cannot be replayed.
+        let first = challenge(&repo).await?;
+        assert!(!first.setup_required);
+        let res = complete(&repo, &first.token, &current).await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+        let completed = complete(&repo, &first.token, &code(&setup, 30)).await?;
+        assert_eq!(completed.user_id, admin_id());
+        assert!(completed.recovery_codes.is_none());
+        let res = complete(&repo, &first.token, &code(&setup, 30)).await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+
+        // Recovery codes work once, regardless of case and separators.
+        let recovery_code = recovery_codes[0].replace('-', "").to_uppercase();
+        let second = challenge(&repo).await?;
+        complete(&repo, &second.token, &recovery_code).await?;
+        let third = challenge(&repo).await?;
+        let res = complete(&repo, &third.token, &recovery_code).await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+        let status = repo.find_status(admin_id()).await?;
+        assert_eq!(
+            status.recovery_codes_remaining,
+            RECOVERY_CODE_COUNT as u32 - 1
+        );
+
+        // The challenge is discarded after too many wrong codes.
+        for _ in 1..MAX_CHALLENGE_ATTEMPTS {
+            let res = complete(&repo, &third.token, "wrong").await;
+            assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+        }
+        let res = complete(&repo, &third.token, &recovery_codes[1]).await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+
+        let res = repo
+            .disable(DisableTotp {
+                user_id: admin_id(),
+                code: "wrong".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        repo.disable(DisableTotp {
+            user_id: admin_id(),
+            code: recovery_codes[1].clone(),
+        })
+        .await?;
+        let status = repo.find_status(admin_id()).await?;
+        assert!(!status.enabled);
+        assert_eq!(status.recovery_codes_remaining, 0);
+
+        let actions = sqlx::query_scalar!(
+            r#"
+                SELECT action FROM audit_logs
+                WHERE target_id = $1
+                ORDER BY created_at
+            "#,
+            admin_id().raw()
+        )
+        .fetch_all(&pool)
+        .await?;
+        assert_eq!(
+            actions,
+            [
+                AuditAction::UserTwoFactorEnabled.as_ref(),
+                AuditAction::UserTwoFactorDisabled.as_ref()
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_two_factor_required_for_admins(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = TwoFactorRepositoryImpl::new(ConnectionPool::new(pool), true);
+
+        let pending = challenge(&repo).await?;
+        assert!(pending.setup_required);
+        let res = complete(&repo, &pending.token, "123456").await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+
+        let setup = repo.set_up_for_challenge(&pending.token).await?;
+        let completed = complete(&repo, &pending.token, &code(&setup, 0)).await?;
+        assert_eq!(completed.user_id, admin_id());
+        assert_eq!(
+            completed.recovery_codes.map(|codes| codes.len()),
+            Some(RECOVERY_CODE_COUNT)
+        );
+
+        let enrolled = challenge(&repo).await?;
+        assert!(!enrolled.setup_required);
+        let res = repo.set_up_for_challenge(&enrolled.token).await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+
+        let res = repo
+            .disable(DisableTotp {
+                user_id: admin_id(),
+                code: code(&setup, 30),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::ForbiddenOperation(_))));
+
+        Ok(())
+    }
+}
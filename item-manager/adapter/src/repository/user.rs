@@ -1,21 +1,39 @@
 use async_trait::async_trait;
 use derive_new::new;
+use kernel::model::audit::AuditAction;
 use kernel::model::id::UserId;
+use kernel::model::list::{
+    Cursor, CursorDirection, CursorSort, PaginatedList, SortOrder, UserListOptions, UserSort,
+};
 use kernel::model::role::Role;
 use kernel::model::user::{
     User,
     event::{
-        CreateUser, DeleteUser, UpdateUserEmail, UpdateUserName, UpdateUserPassword, UpdateUserRole,
+        CreateUser, DeactivateUser, DeleteUser, ReactivateUser, UnlockUser, UpdateUserEmail,
+        UpdateUserName, UpdateUserPassword, UpdateUserRole,
     },
 };
 use kernel::repository::user::UserRepository;
 use shared::error::{AppError, AppResult};
 
-use crate::database::{ConnectionPool, model::user::UserRow};
+use crate::database::{
+    ConnectionPool,
+    model::{
+        password::PasswordPolicy,
+        user::{UserPageRow, UserRow},
+    },
+    set_transaction_serializable,
+};
+use crate::repository::audit::{AuditEntry, record_audit, user_snapshot};
+use crate::repository::auth::email_throttle_subject;
+use crate::repository::invitation::redeem_invitation;
+use crate::repository::like_pattern;
+use crate::repository::pagination::{KeysetPage, keyset_page};
 
 #[derive(new)]
 pub struct UserRepositoryImpl {
     db: ConnectionPool,
+    password_policy: PasswordPolicy,
 }
 
 #[async_trait]
@@ -29,6 +47,7 @@ impl UserRepository for UserRepositoryImpl {
                 u.name,
                 u.email,
                 r.name as role_name,
+                u.deactivated_at,
                 u.created_at,
                 u.updated_at
                 FROM users AS u
@@ -46,35 +65,169 @@ impl UserRepository for UserRepositoryImpl {
         }
     }
 
-    async fn find_all(&self) -> AppResult<Vec<User>> {
-        let users = sqlx::query_as!(
-            UserRow,
+    async fn find_all(&self, options: UserListOptions) -> AppResult<PaginatedList<User>> {
+        let UserListOptions {
+            limit,
+            offset,
+            cursor,
+            q,
+            role,
+            active,
+            sort,
+            order,
+        } = options;
+        let q_pattern = q.as_deref().map(like_pattern);
+        let role_param = role.map(|role| role.as_ref().to_string());
+
+        let backwards = cursor
+            .as_ref()
+            .is_some_and(|c| c.direction == CursorDirection::Before);
+        let scan_ascending = (order == SortOrder::Asc) != backwards;
+        let offset = if cursor.is_some() { 0 } else { offset };
+        let (cursor_text, cursor_time, cursor_id) = match &cursor {
+            Some(c) => (c.key_text.as_deref(), c.key_time, Some(c.id)),
+            None => (None, None, None),
+        };
+
+        // `filtered` holds every user matching the filter and is both counted
+        // and paged, so the two cannot disagree. Rows are ordered by
+        // (key, user_id); a `Before` cursor scans that order in reverse and the
+        // page is flipped back afterwards. An empty page still yields one row,
+        // which only carries the total.
+        let rows: Vec<UserPageRow> = sqlx::query_as!(
+            UserPageRow,
             r#"
+                WITH filtered AS (
+                    SELECT
+                        u.user_id,
+                        u.name,
+                        u.email,
+                        r.name AS role_name,
+                        u.deactivated_at,
+                        u.created_at,
+                        u.updated_at,
+                        CASE $5
+                            WHEN 'name' THEN u.name
+                            WHEN 'email' THEN u.email
+                            WHEN 'role' THEN r.name
+                        END AS key_text,
+                        CASE $5 WHEN 'created_at' THEN u.created_at END AS key_time
+                    FROM users AS u
+                    INNER JOIN roles AS r USING(role_id)
+                    WHERE ($3::text IS NULL OR u.name ILIKE $3 OR u.email ILIKE $3)
+                      AND ($4::text IS NULL OR r.name = $4)
+                      AND ($10::bool IS NULL OR (u.deactivated_at IS NULL) = $10)
+                ),
+                scanned AS (
+                    SELECT
+                        f.*,
+                        ROW_NUMBER() OVER (
+                            ORDER BY
+                                CASE WHEN $6 THEN f.key_text END ASC,
+                                CASE WHEN NOT $6 THEN f.key_text END DESC,
+                                CASE WHEN $6 THEN f.key_time END ASC,
+                                CASE WHEN NOT $6 THEN f.key_time END DESC,
+                                CASE WHEN $6 THEN f.user_id END ASC,
+                                CASE WHEN NOT $6 THEN f.user_id END DESC
+                        ) AS position
+                    FROM filtered AS f
+                    WHERE $9::uuid IS NULL
+                       OR CASE WHEN $6 THEN f.key_text > $7 ELSE f.key_text < $7 END
+                       OR (
+                            f.key_text IS NOT DISTINCT FROM $7
+                            AND (
+                                CASE WHEN $6 THEN f.key_time > $8 ELSE f.key_time < $8 END
+                                OR (
+                                    f.key_time IS NOT DISTINCT FROM $8
+                                    AND CASE WHEN $6 THEN f.user_id > $9 ELSE f.user_id < $9 END
+                                )
+                            )
+                       )
+                )
                 SELECT
-                    u.user_id,
-                    u.name,
-                    u.email,
-                    r.name as role_name,
-                    u.created_at,
-                    u.updated_at
-                FROM users AS u
-                INNER JOIN roles AS r USING(role_id)
-                ORDER BY u.created_at DESC;
-            "#
+                    total.count AS "total!",
+                    s.user_id AS "user_id?: UserId",
+                    s.name AS "name?",
+                    s.email AS "email?",
+                    s.role_name AS "role_name?",
+                    s.deactivated_at,
+                    s.created_at AS "created_at?",
+                    s.updated_at AS "updated_at?"
+                FROM (SELECT COUNT(*) FROM filtered) AS total
+                LEFT JOIN scanned AS s ON s.position > $2 AND s.position <= $2 + $1
+                ORDER BY s.position
+            "#,
+            limit + 1,
+            offset,
+            q_pattern.as_deref(),
+            role_param.as_deref(),
+            sort.as_ref(),
+            scan_ascending,
+            cursor_text,
+            cursor_time,
+            cursor_id,
+            active,
         )
         .fetch_all(self.db.inner_ref())
         .await
-        .map_err(AppError::SpecificOperationError)?
-        .into_iter()
-        .map(User::try_from)
-        .collect::<AppResult<Vec<_>>>()?;
-        Ok(users)
+        .map_err(AppError::SpecificOperationError)?;
+        let total = rows.first().map_or(0, |row| row.total);
+        let rows = rows
+            .into_iter()
+            .filter_map(UserPageRow::into_user_row)
+            .collect::<Vec<_>>();
+
+        let KeysetPage {
+            rows,
+            next_cursor,
+            prev_cursor,
+        } = keyset_page(
+            rows,
+            limit,
+            cursor.as_ref(),
+            offset > 0,
+            |row, direction| {
+                let key_text = match sort {
+                    UserSort::Name => Some(row.name.clone()),
+                    UserSort::Email => Some(row.email.clone()),
+                    UserSort::Role => Some(row.role_name.clone()),
+                    UserSort::CreatedAt => None,
+                };
+                Cursor {
+                    key_text,
+                    key_time: (sort == UserSort::CreatedAt).then_some(row.created_at),
+                    id: row.user_id.raw(),
+                    direction,
+                    sort: Some(CursorSort::new(sort, order)),
+                }
+            },
+        );
+
+        let items = rows
+            .into_iter()
+            .map(User::try_from)
+            .collect::<AppResult<Vec<_>>>()?;
+
+        Ok(PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+            next_cursor,
+            prev_cursor,
+        })
     }
 
     async fn create(&self, event: CreateUser) -> AppResult<User> {
         let user_id = UserId::new();
-        let hashed_password = hash_password(&event.password)?;
-        let role = Role::User;
+        self.password_policy.validate(&event.password)?;
+        let hashed_password = self.password_policy.hash(&event.password)?;
+        let mut tx = self.db.begin().await?;
+        // Redeemed in this transaction so a rejected signup does not use up the code.
+        let role = match &event.invitation_code {
+            Some(code) => redeem_invitation(&mut tx, code).await?,
+            None => Role::User,
+        };
         let res = sqlx::query!(
             r#"
                 INSERT INTO users(user_id, name, email, password_hash, role_id)
@@ -86,7 +239,7 @@ impl UserRepository for UserRepositoryImpl {
             hashed_password,
             role.as_ref()
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
         .map_err(map_sqlx_error)?;
         if res.rows_affected() < 1 {
@@ -94,11 +247,25 @@ impl UserRepository for UserRepositoryImpl {
                 "No user has been created".into(),
             ));
         }
+        let after = user_snapshot(&mut tx, user_id).await?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.requested_by.unwrap_or(user_id),
+                AuditAction::UserCreated,
+                user_id.raw(),
+                None,
+                after,
+            ),
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
         Ok(User {
             id: user_id,
             name: event.name,
             email: event.email,
             role,
+            deactivated_at: None,
         })
     }
 
@@ -117,23 +284,51 @@ impl UserRepository for UserRepositoryImpl {
             _ => AppError::SpecificOperationError(err),
         })?
         .password_hash;
-        verify_password(&event.current_password, &original_password_hash)?;
-        let new_password_hash = hash_password(&event.new_password)?;
+        if !self
+            .password_policy
+            .verify(&event.current_password, &original_password_hash)?
+        {
+            return Err(AppError::UnauthenticatedError);
+        }
+        let before = user_snapshot(&mut tx, event.user_id).await?;
+        replace_password(
+            &mut tx,
+            &self.password_policy,
+            event.user_id,
+            &event.new_password,
+        )
+        .await?;
         sqlx::query!(
             r#"
-                UPDATE users SET password_hash = $2 WHERE user_id = $1;
+                UPDATE refresh_tokens
+                SET revoked_at = CURRENT_TIMESTAMP(3)
+                WHERE user_id = $1
+                  AND revoked_at IS NULL;
             "#,
             event.user_id.raw(),
-            new_password_hash,
         )
         .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
+        let after = user_snapshot(&mut tx, event.user_id).await?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.user_id,
+                AuditAction::UserPasswordChanged,
+                event.user_id.raw(),
+                before,
+                after,
+            ),
+        )
+        .await?;
         tx.commit().await.map_err(AppError::TransactionError)?;
         Ok(())
     }
 
     async fn update_role(&self, event: UpdateUserRole) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        let before = user_snapshot(&mut tx, event.user_id).await?;
         let res = sqlx::query!(
             r#"
                 UPDATE users
@@ -145,16 +340,31 @@ impl UserRepository for UserRepositoryImpl {
             event.user_id.raw(),
             event.role.as_ref()
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
         if res.rows_affected() < 1 {
             return Err(AppError::EntityNotFound("Specified user not found".into()));
         }
+        let after = user_snapshot(&mut tx, event.user_id).await?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.requested_by,
+                AuditAction::UserRoleChanged,
+                event.user_id.raw(),
+                before,
+                after,
+            ),
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
         Ok(())
     }
 
     async fn update_name(&self, event: UpdateUserName) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        let before = user_snapshot(&mut tx, event.user_id).await?;
         let res = sqlx::query!(
             r#"
                 UPDATE users
@@ -164,16 +374,31 @@ impl UserRepository for UserRepositoryImpl {
             event.user_id.raw(),
             event.name
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
         if res.rows_affected() < 1 {
             return Err(AppError::EntityNotFound("Specified user not found".into()));
         }
+        let after = user_snapshot(&mut tx, event.user_id).await?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.user_id,
+                AuditAction::UserNameChanged,
+                event.user_id.raw(),
+                before,
+                after,
+            ),
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
         Ok(())
     }
 
     async fn update_email(&self, event: UpdateUserEmail) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        let before = user_snapshot(&mut tx, event.user_id).await?;
         let res = sqlx::query!(
             r#"
                 UPDATE users
@@ -183,16 +408,31 @@ impl UserRepository for UserRepositoryImpl {
             event.user_id.raw(),
             event.email
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
         .map_err(map_sqlx_error)?;
         if res.rows_affected() < 1 {
             return Err(AppError::EntityNotFound("Specified user not found".into()));
         }
+        let after = user_snapshot(&mut tx, event.user_id).await?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.user_id,
+                AuditAction::UserEmailChanged,
+                event.user_id.raw(),
+                before,
+                after,
+            ),
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
         Ok(())
     }
 
     async fn delete(&self, event: DeleteUser) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        let before = user_snapshot(&mut tx, event.user_id).await?;
         let res = sqlx::query!(
             r#"
                 DELETE FROM users
@@ -200,14 +440,221 @@ impl UserRepository for UserRepositoryImpl {
             "#,
             event.user_id.raw()
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
         .map_err(map_sqlx_error_on_delete)?;
         if res.rows_affected() < 1 {
             return Err(AppError::EntityNotFound("Specified user not found".into()));
         }
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.requested_by,
+                AuditAction::UserDeleted,
+                event.user_id.raw(),
+                before,
+                None,
+            ),
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        Ok(())
+    }
+
+    async fn deactivate(&self, event: DeactivateUser) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        set_transaction_serializable(&mut tx).await?;
+
+        let state = sqlx::query!(
+            r#"
+                SELECT
+                    u.deactivated_at IS NOT NULL AS "deactivated!",
+                    EXISTS (
+                        SELECT 1 FROM checkouts WHERE user_id = u.user_id
+                    ) AS "checked_out!"
+                FROM users AS u
+                WHERE u.user_id = $1
+                FOR UPDATE
+            "#,
+            event.user_id.raw()
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| AppError::EntityNotFound("Specified user not found".into()))?;
+        if state.deactivated {
+            return Err(AppError::Conflict(format!(
+                "The user ({}) has already been deactivated.",
+                event.user_id
+            )));
+        }
+        if state.checked_out {
+            return Err(AppError::Conflict(format!(
+                "The user ({}) still has items checked out.",
+                event.user_id
+            )));
+        }
+
+        let before = user_snapshot(&mut tx, event.user_id).await?;
+        sqlx::query!(
+            r#"
+                UPDATE users
+                SET deactivated_at = CURRENT_TIMESTAMP(3),
+                    token_generation = token_generation + 1
+                WHERE user_id = $1;
+            "#,
+            event.user_id.raw(),
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        sqlx::query!(
+            r#"
+                UPDATE refresh_tokens
+                SET revoked_at = CURRENT_TIMESTAMP(3)
+                WHERE user_id = $1
+                  AND revoked_at IS NULL;
+            "#,
+            event.user_id.raw(),
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        // Queues move on lazily: the next reservation is held once its item is looked at again.
+        sqlx::query!(
+            r#"
+                DELETE FROM reservations WHERE user_id = $1;
+            "#,
+            event.user_id.raw(),
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        let after = user_snapshot(&mut tx, event.user_id).await?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.requested_by,
+                AuditAction::UserDeactivated,
+                event.user_id.raw(),
+                before,
+                after,
+            ),
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        Ok(())
+    }
+
+    async fn reactivate(&self, event: ReactivateUser) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        let before = user_snapshot(&mut tx, event.user_id)
+            .await?
+            .ok_or_else(|| AppError::EntityNotFound("Specified user not found".into()))?;
+        let res = sqlx::query!(
+            r#"
+                UPDATE users
+                SET deactivated_at = NULL
+                WHERE user_id = $1
+                  AND deactivated_at IS NOT NULL
+            "#,
+            event.user_id.raw()
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::Conflict(format!(
+                "The user ({}) is not deactivated.",
+                event.user_id
+            )));
+        }
+        let after = user_snapshot(&mut tx, event.user_id).await?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.requested_by,
+                AuditAction::UserReactivated,
+                event.user_id.raw(),
+                Some(before),
+                after,
+            ),
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
         Ok(())
     }
+
+    async fn unlock(&self, event: UnlockUser) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        let email = sqlx::query_scalar!(
+            r#"
+                SELECT email FROM users WHERE user_id = $1
+            "#,
+            event.user_id.raw()
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| AppError::EntityNotFound("Specified user not found".into()))?;
+        let throttle = sqlx::query_scalar!(
+            r#"
+                DELETE FROM login_throttles AS t
+                WHERE t.subject = $1
+                RETURNING to_jsonb(t) AS "throttle!"
+            "#,
+            email_throttle_subject(&email)
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| {
+            AppError::Conflict(format!(
+                "The user ({}) has no failed login attempts.",
+                event.user_id
+            ))
+        })?;
+        record_audit(
+            &mut tx,
+            AuditEntry::new(
+                event.requested_by,
+                AuditAction::UserUnlocked,
+                event.user_id.raw(),
+                Some(throttle),
+                None,
+            ),
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        Ok(())
+    }
+}
+
+/// Fails with `Conflict` when the user has been deactivated and so cannot
+/// check out or reserve items.
+pub(crate) async fn ensure_user_active(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: UserId,
+) -> AppResult<()> {
+    let deactivated = sqlx::query_scalar!(
+        r#"
+            SELECT deactivated_at IS NOT NULL AS "deactivated!"
+            FROM users
+            WHERE user_id = $1
+        "#,
+        user_id.raw(),
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    if deactivated == Some(true) {
+        return Err(AppError::Conflict(format!(
+            "The user ({user_id}) has been deactivated."
+        )));
+    }
+
+    Ok(())
 }
 
 fn map_sqlx_error_on_delete(err: sqlx::Error) -> AppError {
@@ -216,22 +663,108 @@ fn map_sqlx_error_on_delete(err: sqlx::Error) -> AppError {
             if matches!(db_err.code().as_deref(), Some("23001" | "23503")) =>
         {
             AppError::Conflict(
-                "Cannot delete user because checkout history references them.".into(),
+                "Cannot delete user because checkout history references them; deactivate them instead."
+                    .into(),
             )
         }
         _ => AppError::SpecificOperationError(err),
     }
 }
 
-fn hash_password(password: &str) -> AppResult<String> {
-    bcrypt::hash(password, bcrypt::DEFAULT_COST).map_err(AppError::from)
-}
+/// Sets a new password after checking it against the policy, including the user's recent
+/// passwords, and revokes access tokens issued before the change.
+pub(crate) async fn replace_password(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    policy: &PasswordPolicy,
+    user_id: UserId,
+    new_password: &str,
+) -> AppResult<()> {
+    policy.validate(new_password)?;
+
+    let current_hash = sqlx::query_scalar!(
+        r#"
+            SELECT password_hash FROM users WHERE user_id = $1 FOR UPDATE
+        "#,
+        user_id.raw()
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?
+    .ok_or_else(|| AppError::EntityNotFound("Specified user not found".into()))?;
+
+    // The current password counts towards the history, so only the ones before it are kept.
+    let kept = i64::from(policy.history().saturating_sub(1));
+    if policy.history() > 0 {
+        let earlier_hashes = sqlx::query_scalar!(
+            r#"
+                SELECT password_hash
+                FROM password_history
+                WHERE user_id = $1
+                ORDER BY created_at DESC, password_history_id
+                LIMIT $2
+            "#,
+            user_id.raw(),
+            kept
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        for hash in std::iter::once(&current_hash).chain(&earlier_hashes) {
+            // Hashes that no longer parse cannot match, and should not block the change.
+            if policy.verify(new_password, hash).unwrap_or(false) {
+                return Err(AppError::UnprocessableEntity(format!(
+                    "The password must differ from the last {} passwords.",
+                    policy.history()
+                )));
+            }
+        }
+    }
 
-fn verify_password(password: &str, hash: &str) -> AppResult<()> {
-    let valid = bcrypt::verify(password, hash)?;
-    if !valid {
-        return Err(AppError::UnauthenticatedError);
+    if kept > 0 {
+        sqlx::query!(
+            r#"
+                INSERT INTO password_history (user_id, password_hash) VALUES ($1, $2)
+            "#,
+            user_id.raw(),
+            current_hash
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
     }
+    sqlx::query!(
+        r#"
+            DELETE FROM password_history
+            WHERE user_id = $1
+              AND password_history_id NOT IN (
+                SELECT password_history_id
+                FROM password_history
+                WHERE user_id = $1
+                ORDER BY created_at DESC, password_history_id
+                LIMIT $2
+              )
+        "#,
+        user_id.raw(),
+        kept
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    sqlx::query!(
+        r#"
+            UPDATE users
+            SET password_hash = $2,
+                token_generation = token_generation + 1
+            WHERE user_id = $1
+        "#,
+        user_id.raw(),
+        policy.hash(new_password)?,
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
     Ok(())
 }
 
@@ -250,11 +783,109 @@ fn map_sqlx_error(err: sqlx::Error) -> AppError {
 
 #[cfg(test)]
 mod tests {
+    use shared::config::PasswordConfig;
+
     use super::*;
 
+    fn all_users() -> UserListOptions {
+        UserListOptions {
+            limit: 100,
+            ..Default::default()
+        }
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_list_search_filter_and_pages(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = UserRepositoryImpl::new(ConnectionPool::new(pool), PasswordPolicy::default());
+        for i in 1..=12 {
+            repo.create(CreateUser {
+                name: format!("Member {i:02}"),
+                email: format!("member{i:02}@alumni.example.com"),
+                password: "password".into(),
+                requested_by: Some(UserId::new()),
+                invitation_code: None,
+            })
+            .await?;
+        }
+        let names = |page: &PaginatedList<User>| {
+            page.items
+                .iter()
+                .map(|u| u.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let res = repo.find_all(all_users()).await?;
+        assert_eq!(res.total, 13);
+        assert_eq!(res.items[0].name, "Member 12"); // newest first
+
+        // Search matches name or email, case-insensitively
+        let res = repo
+            .find_all(UserListOptions {
+                q: Some("ALUMNI".into()),
+                ..all_users()
+            })
+            .await?;
+        assert_eq!(res.total, 12);
+        let res = repo
+            .find_all(UserListOptions {
+                q: Some("member 0".into()),
+                ..all_users()
+            })
+            .await?;
+        assert_eq!(res.total, 9);
+        let res = repo
+            .find_all(UserListOptions {
+                q: Some("_".into()),
+                ..all_users()
+            })
+            .await?;
+        assert_eq!(res.total, 0);
+
+        // Role filter
+        let res = repo
+            .find_all(UserListOptions {
+                role: Some(Role::Admin),
+                ..all_users()
+            })
+            .await?;
+        assert_eq!(names(&res), ["Eleazar Fig"]);
+
+        // Offset and cursor pages agree
+        let options = |offset: i64, cursor: Option<Cursor>| UserListOptions {
+            limit: 5,
+            offset,
+            cursor,
+            sort: UserSort::Email,
+            order: SortOrder::Desc,
+            ..Default::default()
+        };
+        let first = repo.find_all(options(0, None)).await?;
+        assert_eq!(first.items[0].name, "Member 12");
+        assert!(first.prev_cursor.is_none());
+        let second = repo.find_all(options(0, first.next_cursor)).await?;
+        assert_eq!(
+            names(&second),
+            names(&repo.find_all(options(5, None)).await?)
+        );
+        let third = repo
+            .find_all(options(0, second.next_cursor.clone()))
+            .await?;
+        assert_eq!(names(&third), ["Member 02", "Member 01", "Eleazar Fig"]);
+        assert!(third.next_cursor.is_none());
+        let back = repo.find_all(options(0, third.prev_cursor)).await?;
+        assert_eq!(names(&back), names(&second));
+
+        // A page past the end is empty but still counts the matching users
+        let past_end = repo.find_all(options(20, None)).await?;
+        assert!(past_end.items.is_empty());
+        assert_eq!(past_end.total, 13);
+
+        Ok(())
+    }
+
     #[sqlx::test(fixtures("common"))]
     async fn test_user_crud(pool: sqlx::PgPool) -> anyhow::Result<()> {
-        let repo = UserRepositoryImpl::new(ConnectionPool::new(pool));
+        let repo = UserRepositoryImpl::new(ConnectionPool::new(pool), PasswordPolicy::default());
 
         // Test user creation
         let name = "Test User".to_string();
@@ -265,6 +896,8 @@ mod tests {
             name: name.clone(),
             email: email.clone(),
             password: password.clone(),
+            requested_by: Some(UserId::new()),
+            invitation_code: None,
         };
         let user = repo.create(create_event).await?;
 
@@ -297,12 +930,23 @@ mod tests {
         repo.update_role(UpdateUserRole {
             user_id: user.id,
             role: Role::Admin,
+            requested_by: UserId::new(),
         })
         .await?;
 
         let updated_user = repo.find_current_user(user.id).await?.unwrap();
         assert_eq!(updated_user.role, Role::Admin);
 
+        repo.update_role(UpdateUserRole {
+            user_id: user.id,
+            role: Role::Librarian,
+            requested_by: UserId::new(),
+        })
+        .await?;
+
+        let updated_user = repo.find_current_user(user.id).await?.unwrap();
+        assert_eq!(updated_user.role, Role::Librarian);
+
         // Test update name
         let new_name = "Updated Name".to_string();
         repo.update_name(UpdateUserName {
@@ -326,12 +970,16 @@ mod tests {
         assert_eq!(updated_user.email, new_email);
 
         // Test find all users
-        let users = repo.find_all().await?;
+        let users = repo.find_all(all_users()).await?.items;
         assert!(!users.is_empty());
         assert!(users.iter().any(|u| u.id == user.id));
 
         // Test delete user
-        repo.delete(DeleteUser { user_id: user.id }).await?;
+        repo.delete(DeleteUser {
+            user_id: user.id,
+            requested_by: UserId::new(),
+        })
+        .await?;
 
         // Verify user is deleted
         let deleted_user = repo.find_current_user(user.id).await?;
@@ -340,9 +988,92 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test(fixtures("common", "item"))]
+    async fn test_deactivate_and_reactivate(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        use chrono::Utc;
+        use kernel::{
+            model::{
+                checkout::event::{CreateCheckout, UpdateReturned},
+                id::ItemId,
+            },
+            repository::checkout::CheckoutRepository,
+        };
+        use shared::config::LoanConfig;
+        use std::str::FromStr;
+
+        use crate::repository::checkout::CheckoutRepositoryImpl;
+
+        let db = ConnectionPool::new(pool);
+        let repo = UserRepositoryImpl::new(db.clone(), PasswordPolicy::default());
+        let checkout_repo = CheckoutRepositoryImpl::new(db, 3600, LoanConfig::default());
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let user_id = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let item_id = ItemId::from_str("9890736e-a4e4-461a-a77d-eac3517ef113")?;
+        let deactivate = || DeactivateUser {
+            user_id,
+            requested_by: admin_id,
+        };
+        let checkout = || CreateCheckout::new(item_id, user_id, admin_id, Utc::now(), None);
+
+        // Items have to be returned first
+        checkout_repo.create(checkout()).await?;
+        let res = repo.deactivate(deactivate()).await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+        let checkout_id = checkout_repo.find_unreturned_by_user_id(user_id).await?[0].id;
+        checkout_repo
+            .update_returned(UpdateReturned::new(
+                checkout_id,
+                item_id,
+                admin_id,
+                Role::Admin,
+                Utc::now(),
+            ))
+            .await?;
+
+        repo.deactivate(deactivate()).await?;
+        let user = repo.find_current_user(user_id).await?.unwrap();
+        assert!(!user.is_active());
+        let res = repo.deactivate(deactivate()).await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        // History is kept, but nothing new can be checked out to them
+        let history = checkout_repo
+            .find_history_by_item_id(item_id, Default::default())
+            .await?;
+        assert_eq!(history.items.len(), 1);
+        let res = checkout_repo.create(checkout()).await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        let inactive = repo
+            .find_all(UserListOptions {
+                active: Some(false),
+                ..all_users()
+            })
+            .await?;
+        assert_eq!(inactive.total, 1);
+        assert_eq!(inactive.items[0].id, user_id);
+
+        repo.reactivate(ReactivateUser {
+            user_id,
+            requested_by: admin_id,
+        })
+        .await?;
+        assert!(repo.find_current_user(user_id).await?.unwrap().is_active());
+        let res = repo
+            .reactivate(ReactivateUser {
+                user_id,
+                requested_by: admin_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+        checkout_repo.create(checkout()).await?;
+
+        Ok(())
+    }
+
     #[sqlx::test(fixtures("common"))]
     async fn test_error_cases(pool: sqlx::PgPool) -> anyhow::Result<()> {
-        let repo = UserRepositoryImpl::new(ConnectionPool::new(pool));
+        let repo = UserRepositoryImpl::new(ConnectionPool::new(pool), PasswordPolicy::default());
 
         // Test non-existent user
         let non_existent_id = UserId::new();
@@ -353,6 +1084,7 @@ mod tests {
         let result = repo
             .delete(DeleteUser {
                 user_id: non_existent_id,
+                requested_by: UserId::new(),
             })
             .await;
         assert!(result.is_err());
@@ -362,6 +1094,7 @@ mod tests {
             .update_role(UpdateUserRole {
                 user_id: non_existent_id,
                 role: Role::Admin,
+                requested_by: UserId::new(),
             })
             .await;
         assert!(result.is_err());
@@ -399,7 +1132,8 @@ mod tests {
 
     #[sqlx::test(fixtures("common"))]
     async fn test_find_all_rejects_invalid_role(pool: sqlx::PgPool) -> anyhow::Result<()> {
-        let repo = UserRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let repo =
+            UserRepositoryImpl::new(ConnectionPool::new(pool.clone()), PasswordPolicy::default());
         let user_id = UserId::new();
 
         sqlx::query("INSERT INTO roles(name) VALUES ($1)")
@@ -420,9 +1154,67 @@ mod tests {
         .execute(&pool)
         .await?;
 
-        let result = repo.find_all().await;
+        let result = repo.find_all(all_users()).await;
         assert!(matches!(result, Err(AppError::ConversionEntityError(_))));
 
         Ok(())
     }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_password_policy(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let denylist = std::env::temp_dir().join(format!("denylist-{}.txt", UserId::new()));
+        std::fs::write(&denylist, "Password123\n\nletmein!!\n")?;
+        let policy = PasswordPolicy::new(PasswordConfig {
+            min_length: 10,
+            denylist_file: Some(denylist.to_string_lossy().into()),
+            history: 3,
+            // The lowest cost bcrypt accepts, to keep the test fast.
+            bcrypt_cost: 4,
+            ..Default::default()
+        })?;
+        std::fs::remove_file(&denylist)?;
+        let repo = UserRepositoryImpl::new(ConnectionPool::new(pool.clone()), policy);
+
+        let create = |password: &str| CreateUser {
+            name: "Policy User".into(),
+            email: "policy@example.com".into(),
+            password: password.into(),
+            requested_by: None,
+            invitation_code: None,
+        };
+        let res = repo.create(create("short")).await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        let res = repo.create(create("PASSWORD123")).await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        let user = repo.create(create("first password")).await?;
+
+        let change = |current: &str, new: &str| UpdateUserPassword {
+            user_id: user.id,
+            current_password: current.into(),
+            new_password: new.into(),
+        };
+        repo.update_password(change("first password", "second password"))
+            .await?;
+        repo.update_password(change("second password", "third password"))
+            .await?;
+        // The current password and the two before it are refused.
+        for reused in ["third password", "second password", "first password"] {
+            let res = repo.update_password(change("third password", reused)).await;
+            assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        }
+        repo.update_password(change("third password", "fourth password"))
+            .await?;
+        repo.update_password(change("fourth password", "first password"))
+            .await?;
+
+        let kept = sqlx::query_scalar!(
+            "SELECT COUNT(*) AS \"count!\" FROM password_history WHERE user_id = $1",
+            user.id.raw()
+        )
+        .fetch_one(&pool)
+        .await?;
+        assert_eq!(kept, 2);
+
+        Ok(())
+    }
 }
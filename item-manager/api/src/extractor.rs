@@ -1,23 +1,58 @@
-use axum::extract::FromRequestParts;
-use axum::http::{header::COOKIE, request::Parts};
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::{
+    HeaderMap,
+    header::{AUTHORIZATION, COOKIE},
+    request::Parts,
+};
 use kernel::model::auth::AccessToken;
 use kernel::model::id::UserId;
-use kernel::model::role::Role;
+use kernel::model::personal_access_token::{
+    PERSONAL_ACCESS_TOKEN_PREFIX, PersonalAccessToken, TokenScope,
+};
+use kernel::model::role::Permission;
 use kernel::model::user::User;
 use registry::AppRegistry;
 use shared::error::AppError;
 
 pub struct AuthorizedUser {
-    pub access_token: AccessToken,
     pub user: User,
+    /// The personal access token the request was made with, or `None` for a session.
+    pub personal_access_token: Option<PersonalAccessToken>,
 }
 
 impl AuthorizedUser {
     pub fn id(&self) -> UserId {
         self.user.id
     }
-    pub fn is_admin(&self) -> bool {
-        self.user.role == Role::Admin
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.user.role.has_permission(permission)
+    }
+
+    /// Guard for handlers: fails with `ForbiddenOperation` unless the user's role
+    /// grants `permission`.
+    pub fn require(&self, permission: Permission) -> Result<(), AppError> {
+        if self.has_permission(permission) {
+            Ok(())
+        } else {
+            Err(AppError::ForbiddenOperation(format!(
+                "The `{}` permission is required.",
+                permission.as_ref()
+            )))
+        }
+    }
+
+    /// Guard for operations that must not be reachable with a leaked script token.
+    pub fn require_session(&self) -> Result<(), AppError> {
+        match self.personal_access_token {
+            None => Ok(()),
+            Some(_) => Err(AppError::ForbiddenOperation(
+                "This operation is not available with a personal access token.".into(),
+            )),
+        }
     }
 }
 
@@ -28,20 +63,31 @@ impl FromRequestParts<AppRegistry> for AuthorizedUser {
         parts: &mut Parts,
         registry: &AppRegistry,
     ) -> Result<Self, Self::Rejection> {
+        if let Some(secret) = find_bearer_token(&parts.headers)
+            .filter(|token| token.starts_with(PERSONAL_ACCESS_TOKEN_PREFIX))
+        {
+            let token = registry
+                .personal_access_token_repository()
+                .authenticate(&secret)
+                .await?
+                .ok_or(AppError::UnauthenticatedError)?;
+            let scope = TokenScope::required_for(parts.method.as_str());
+            if !token.scopes.contains(&scope) {
+                return Err(AppError::ForbiddenOperation(format!(
+                    "The personal access token lacks the `{}` scope.",
+                    scope.as_ref()
+                )));
+            }
+            let user = find_active_user(registry, token.user_id).await?;
+            return Ok(Self {
+                user,
+                personal_access_token: Some(token),
+            });
+        }
+
         let web_config = registry.web_config();
-        let cookie_header = parts
-            .headers
-            .get(COOKIE)
-            .and_then(|value| value.to_str().ok())
+        let access_token = find_access_token(&parts.headers, &web_config.access_token_cookie_name)
             .ok_or(AppError::UnauthorizedError)?;
-        let token = cookie_header
-            .split(';')
-            .filter_map(|cookie| cookie.trim().split_once('='))
-            .find_map(|(name, value)| {
-                (name == web_config.access_token_cookie_name).then(|| value.to_string())
-            })
-            .ok_or(AppError::UnauthorizedError)?;
-        let access_token = AccessToken(token);
 
         let user_id = registry
             .auth_repository()
@@ -49,12 +95,80 @@ impl FromRequestParts<AppRegistry> for AuthorizedUser {
             .await?
             .ok_or(AppError::UnauthenticatedError)?;
 
-        let user = registry
-            .user_repository()
-            .find_current_user(user_id)
-            .await?
-            .ok_or(AppError::UnauthenticatedError)?;
+        let user = find_active_user(registry, user_id).await?;
+
+        Ok(Self {
+            user,
+            personal_access_token: None,
+        })
+    }
+}
+
+/// The client address failed logins are throttled by, if it is known.
+pub struct ClientIp(pub Option<IpAddr>);
 
-        Ok(Self { access_token, user })
+impl FromRequestParts<AppRegistry> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        registry: &AppRegistry,
+    ) -> Result<Self, Self::Rejection> {
+        // The proxy appends the address it saw, so only the last entry can be trusted.
+        let forwarded = registry
+            .web_config()
+            .trust_forwarded_for
+            .then(|| {
+                parts
+                    .headers
+                    .get("x-forwarded-for")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.rsplit(',').next())
+                    .and_then(|value| value.trim().parse().ok())
+            })
+            .flatten();
+        let connected = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+
+        Ok(Self(forwarded.or(connected)))
     }
 }
+
+async fn find_active_user(registry: &AppRegistry, user_id: UserId) -> Result<User, AppError> {
+    registry
+        .user_repository()
+        .find_current_user(user_id)
+        .await?
+        .filter(User::is_active)
+        .ok_or(AppError::UnauthenticatedError)
+}
+
+pub(crate) fn find_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(COOKIE)
+        .and_then(|value| value.to_str().ok())?
+        .split(';')
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find_map(|(cookie_name, value)| (cookie_name == name).then(|| value.to_string()))
+}
+
+/// Finds the session access token sent with the request.
+///
+/// A JWT in the `Authorization: Bearer` header takes precedence over the cookie, so CLI clients
+/// and Swagger UI keep working even when a stale cookie is present. Personal access tokens are
+/// never treated as session tokens.
+pub(crate) fn find_access_token(headers: &HeaderMap, cookie_name: &str) -> Option<AccessToken> {
+    find_bearer_token(headers)
+        .filter(|token| !token.starts_with(PERSONAL_ACCESS_TOKEN_PREFIX))
+        .or_else(|| find_cookie(headers, cookie_name))
+        .map(AccessToken)
+}
+
+fn find_bearer_token(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("Bearer") && !token.is_empty()).then(|| token.to_string())
+}
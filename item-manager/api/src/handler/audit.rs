@@ -0,0 +1,81 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use garde::Validate;
+use kernel::model::{
+    audit::{AuditAction, AuditTargetType},
+    role::Permission,
+};
+use registry::AppRegistry;
+use shared::error::AppResult;
+use utoipa::OpenApi;
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::{
+        audit::{AuditLogResponse, PaginatedAuditLogResponse},
+        error::ErrorResponse,
+        list::AuditLogListQuery,
+    },
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(list_audit_logs),
+    components(
+        schemas(
+            AuditLogResponse,
+            PaginatedAuditLogResponse,
+            AuditAction,
+            AuditTargetType,
+            ErrorResponse
+        )
+    ),
+    tags(
+        (name = "audit-logs", description = "Audit trail of changes to items, users and checkouts")
+    )
+)]
+pub struct ApiDoc;
+
+/// List audit log entries (requires `audit_logs:read`)
+///
+/// Retrieve recorded changes, newest first, optionally filtered by actor, action, target or time range
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit-logs",
+    params(
+        ("limit" = i64, Query, description = "Number of entries to return"),
+        ("offset" = i64, Query, description = "Number of entries to skip"),
+        ("actor_id" = Option<String>, Query, description = "Only changes made by this user"),
+        ("action" = Option<AuditAction>, Query, description = "Only this kind of change"),
+        ("target_type" = Option<AuditTargetType>, Query, description = "Only changes to this kind of target"),
+        ("target_id" = Option<String>, Query, description = "Only changes to this item, user or checkout"),
+        ("from" = Option<String>, Query, description = "Only changes at or after this RFC 3339 time"),
+        ("to" = Option<String>, Query, description = "Only changes before this RFC 3339 time"),
+    ),
+    responses(
+        (status = 200, description = "Success", body = PaginatedAuditLogResponse),
+        (status = 400, description = "Invalid query", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `audit_logs:read` permission required", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "audit-logs"
+)]
+pub async fn list_audit_logs(
+    user: AuthorizedUser,
+    Query(query): Query<AuditLogListQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<PaginatedAuditLogResponse>> {
+    user.require(Permission::AuditLogsRead)?;
+
+    query.validate()?;
+
+    registry
+        .audit_repository()
+        .find_all(query.into())
+        .await
+        .map(PaginatedAuditLogResponse::from)
+        .map(Json)
+}
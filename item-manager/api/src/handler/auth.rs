@@ -1,22 +1,61 @@
-use crate::model::{
-    auth::{LoginRequest, LoginResponse},
-    error::ErrorResponse,
+use crate::{
+    extractor::{ClientIp, find_access_token, find_cookie},
+    model::{
+        auth::{
+            CompleteLoginRequest, ConfirmPasswordResetRequest, LoginChallengeResponse,
+            LoginRequest, LoginResponse, LoginSetupRequest, PasswordResetRequest, RefreshQuery,
+            SignupRequest,
+        },
+        error::ErrorResponse,
+        two_factor::TotpSetupResponse,
+        user::UserResponse,
+    },
 };
 use axum::{
     Json,
-    extract::State,
+    extract::{Query, State},
     http::{HeaderMap, HeaderValue, StatusCode, header::SET_COOKIE},
+    response::{IntoResponse, Response},
+};
+use garde::Validate;
+use kernel::mailer::Mail;
+use kernel::model::{
+    auth::{AccessToken, RefreshToken, event::CreateToken},
+    id::UserId,
+    password_reset::PasswordResetToken,
+    two_factor::event::CreateLoginChallenge,
 };
-use kernel::model::auth::event::CreateToken;
 use registry::AppRegistry;
-use shared::error::AppResult;
+use shared::{
+    config::WebConfig,
+    error::{AppError, AppResult},
+};
 use utoipa::OpenApi;
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(login, logout),
+    paths(
+        login,
+        complete_login,
+        set_up_login_two_factor,
+        refresh,
+        logout,
+        signup,
+        request_password_reset,
+        confirm_password_reset
+    ),
     components(
-        schemas(LoginRequest, LoginResponse, ErrorResponse)
+        schemas(
+            LoginRequest,
+            LoginResponse,
+            LoginChallengeResponse,
+            CompleteLoginRequest,
+            LoginSetupRequest,
+            PasswordResetRequest,
+            ConfirmPasswordResetRequest,
+            SignupRequest,
+            ErrorResponse
+        )
     ),
     tags(
         (name = "auth", description = "Authentication endpoints")
@@ -24,65 +63,426 @@ use utoipa::OpenApi;
 )]
 pub struct ApiDoc;
 
+/// The refresh token is only ever needed by the endpoints under `/auth`.
+const REFRESH_TOKEN_COOKIE_PATH: &str = "/auth";
+
 /// Login to get access token
 ///
-/// Authenticate with email and password to get an access token
+/// Authenticate with email and password to get an access token and a refresh token. Both are
+/// set as cookies; set `includeToken` to also receive the access token in the body, for use in
+/// an `Authorization: Bearer` header. Accounts with two-factor authentication instead get a
+/// challenge to complete at `/auth/login/2fa`.
 #[utoipa::path(
     post,
     path = "/auth/login",
     request_body = LoginRequest,
     responses(
         (status = 200, description = "Login successful", body = LoginResponse),
+        (status = 202, description = "Password accepted; a second factor is required", body = LoginChallengeResponse),
         (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Account deactivated", body = ErrorResponse),
+        (status = 429, description = "Too many failed attempts; retry after the `Retry-After` seconds", body = ErrorResponse),
     ),
     tag = "auth"
 )]
 pub async fn login(
     State(registry): State<AppRegistry>,
+    ClientIp(client_ip): ClientIp,
     Json(req): Json<LoginRequest>,
-) -> AppResult<(HeaderMap, Json<LoginResponse>)> {
+) -> AppResult<Response> {
     let user_id = registry
         .auth_repository()
-        .verify_user(&req.email, &req.password)
+        .verify_user(&req.email, &req.password, client_ip)
+        .await?;
+
+    if let Some(challenge) = registry
+        .two_factor_repository()
+        .create_challenge(CreateLoginChallenge { user_id })
+        .await?
+    {
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(LoginChallengeResponse::from(challenge)),
+        )
+            .into_response());
+    }
+
+    let (headers, access_token) = issue_session(&registry, user_id).await?;
+
+    Ok((
+        headers,
+        Json(LoginResponse {
+            user_id,
+            access_token: req.include_token.then_some(access_token.0),
+            recovery_codes: None,
+        }),
+    )
+        .into_response())
+}
+
+/// Complete a login with a second factor
+///
+/// Finish a login that returned a challenge with a code from the authenticator app or a
+/// recovery code. A challenge expires after five minutes and allows five attempts, after which
+/// the password has to be entered again. Completing a challenge that required setup enables
+/// two-factor authentication and returns the recovery codes.
+#[utoipa::path(
+    post,
+    path = "/auth/login/2fa",
+    request_body = CompleteLoginRequest,
+    responses(
+        (status = 200, description = "Login successful", body = LoginResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 401, description = "Incorrect code, or unknown or expired challenge", body = ErrorResponse),
+    ),
+    tag = "auth"
+)]
+pub async fn complete_login(
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CompleteLoginRequest>,
+) -> AppResult<(HeaderMap, Json<LoginResponse>)> {
+    req.validate()?;
+    let include_token = req.include_token;
+
+    let completed = registry
+        .two_factor_repository()
+        .complete_challenge(req.into())
+        .await?;
+    let (headers, access_token) = issue_session(&registry, completed.user_id).await?;
+
+    Ok((
+        headers,
+        Json(LoginResponse {
+            user_id: completed.user_id,
+            access_token: include_token.then_some(access_token.0),
+            recovery_codes: completed.recovery_codes,
+        }),
+    ))
+}
+
+/// Set up two-factor authentication during login
+///
+/// Generate a TOTP secret for an account that must use two-factor authentication but has not
+/// set it up yet. The login is completed at `/auth/login/2fa` with a code from the new app.
+#[utoipa::path(
+    post,
+    path = "/auth/login/2fa/setup",
+    request_body = LoginSetupRequest,
+    responses(
+        (status = 200, description = "Secret generated", body = TotpSetupResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 401, description = "Unknown or expired challenge, or one that does not require setup", body = ErrorResponse),
+    ),
+    tag = "auth"
+)]
+pub async fn set_up_login_two_factor(
+    State(registry): State<AppRegistry>,
+    Json(req): Json<LoginSetupRequest>,
+) -> AppResult<Json<TotpSetupResponse>> {
+    req.validate()?;
+
+    registry
+        .two_factor_repository()
+        .set_up_for_challenge(&req.challenge_token)
+        .await
+        .map(TotpSetupResponse::from)
+        .map(Json)
+}
+
+/// Refresh the access token
+///
+/// Exchange the refresh token cookie for a new access token. The refresh token is rotated on
+/// every use, and presenting an already rotated one revokes the whole session. Set
+/// `includeToken` to also receive the new access token in the body, as on login.
+#[utoipa::path(
+    post,
+    path = "/auth/refresh",
+    params(
+        ("includeToken" = Option<bool>, Query, description = "Also return the new access token in the body"),
+    ),
+    responses(
+        (status = 200, description = "Access token refreshed", body = LoginResponse),
+        (status = 401, description = "Missing, expired or revoked refresh token", body = ErrorResponse),
+    ),
+    tag = "auth"
+)]
+pub async fn refresh(
+    State(registry): State<AppRegistry>,
+    Query(query): Query<RefreshQuery>,
+    request_headers: HeaderMap,
+) -> AppResult<(HeaderMap, Json<LoginResponse>)> {
+    let web_config = registry.web_config();
+    let refresh_token = find_cookie(&request_headers, &web_config.refresh_token_cookie_name)
+        .map(RefreshToken)
+        .ok_or(AppError::UnauthorizedError)?;
+
+    let rotated = registry
+        .auth_repository()
+        .rotate_refresh_token(&refresh_token)
         .await?;
     let access_token = registry
         .auth_repository()
-        .create_token(CreateToken::new(user_id))
+        .create_token(CreateToken::new(rotated.user_id))
         .await?;
 
-    let web_config = registry.web_config();
-    let cookie = format!(
-        "{}={}; HttpOnly; Secure; SameSite=Lax; Path=/; Max-Age={}",
-        web_config.access_token_cookie_name,
-        access_token.0,
-        web_config.access_token_cookie_max_age_seconds
-    );
-    let mut headers = HeaderMap::new();
-    headers.insert(
-        SET_COOKIE,
-        HeaderValue::from_str(&cookie).map_err(|_| shared::error::AppError::UnauthorizedError)?,
-    );
+    let headers = session_cookie_headers(&web_config, &access_token, &rotated.refresh_token)?;
 
-    Ok((headers, Json(LoginResponse { user_id })))
+    Ok((
+        headers,
+        Json(LoginResponse {
+            user_id: rotated.user_id,
+            access_token: query.include_token.then_some(access_token.0),
+            recovery_codes: None,
+        }),
+    ))
 }
 
+/// Logout and revoke the current session
+///
+/// Revoke the access and refresh tokens sent with the request, if any, and clear the cookies
 #[utoipa::path(
     post,
     path = "/auth/logout",
     responses((status = 204, description = "Logout successful")),
     tag = "auth"
 )]
-pub async fn logout(State(registry): State<AppRegistry>) -> AppResult<(HeaderMap, StatusCode)> {
+pub async fn logout(
+    State(registry): State<AppRegistry>,
+    request_headers: HeaderMap,
+) -> AppResult<(HeaderMap, StatusCode)> {
     let web_config = registry.web_config();
-    let cookie = format!(
-        "{}=; HttpOnly; Secure; SameSite=Lax; Path=/; Max-Age=0",
-        web_config.access_token_cookie_name,
+    if let Some(access_token) =
+        find_access_token(&request_headers, &web_config.access_token_cookie_name)
+    {
+        registry
+            .auth_repository()
+            .revoke_token(&access_token)
+            .await?;
+    }
+    if let Some(refresh_token) =
+        find_cookie(&request_headers, &web_config.refresh_token_cookie_name).map(RefreshToken)
+    {
+        registry
+            .auth_repository()
+            .revoke_refresh_token(&refresh_token)
+            .await?;
+    }
+
+    let mut headers = HeaderMap::new();
+    append_cookie(
+        &mut headers,
+        &web_config.access_token_cookie_name,
+        "",
+        "/",
+        0,
+    )?;
+    append_cookie(
+        &mut headers,
+        &web_config.refresh_token_cookie_name,
+        "",
+        REFRESH_TOKEN_COOKIE_PATH,
+        0,
+    )?;
+
+    Ok((headers, StatusCode::NO_CONTENT))
+}
+
+/// Sign up with an invitation code
+///
+/// Create an account with the role of the invitation and sign in to it. When the role requires
+/// two-factor authentication, the account is created but the sign-in continues with a challenge
+/// at `/auth/login/2fa/setup`, as for a login.
+#[utoipa::path(
+    post,
+    path = "/auth/signup",
+    request_body = SignupRequest,
+    responses(
+        (status = 201, description = "Account created and signed in", body = UserResponse),
+        (status = 202, description = "Account created; two-factor authentication must be set up to sign in", body = LoginChallengeResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 409, description = "Email already exists", body = ErrorResponse),
+        (status = 422, description = "Invalid, expired or used up invitation code, or a password that does not meet the password policy", body = ErrorResponse),
+    ),
+    tag = "auth"
+)]
+pub async fn signup(
+    State(registry): State<AppRegistry>,
+    Json(req): Json<SignupRequest>,
+) -> AppResult<Response> {
+    req.validate()?;
+
+    let user = registry.user_repository().create(req.into()).await?;
+
+    if let Some(challenge) = registry
+        .two_factor_repository()
+        .create_challenge(CreateLoginChallenge { user_id: user.id })
+        .await?
+    {
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(LoginChallengeResponse::from(challenge)),
+        )
+            .into_response());
+    }
+
+    let headers = issue_session_cookies(&registry, user.id).await?;
+
+    Ok((StatusCode::CREATED, headers, Json(UserResponse::from(user))).into_response())
+}
+
+/// Request a password reset
+///
+/// Email a single-use link for choosing a new password. The response is the same whether or not
+/// the address belongs to an account.
+#[utoipa::path(
+    post,
+    path = "/auth/password-reset/request",
+    request_body = PasswordResetRequest,
+    responses(
+        (status = 202, description = "Reset link sent if the account exists"),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+    ),
+    tag = "auth"
+)]
+pub async fn request_password_reset(
+    State(registry): State<AppRegistry>,
+    Json(req): Json<PasswordResetRequest>,
+) -> AppResult<StatusCode> {
+    req.validate()?;
+
+    let Some(reset) = registry
+        .password_reset_repository()
+        .create(req.into())
+        .await?
+    else {
+        return Ok(StatusCode::ACCEPTED);
+    };
+
+    // Reporting the failure would tell the caller that the address has an account.
+    let mail = password_reset_mail(&registry.web_config(), &reset);
+    if let Err(e) = registry.mailer().send(mail).await {
+        tracing::error!(
+            error.cause_chain = ?e,
+            error.message = %e,
+            "Failed to send password reset mail"
+        );
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Reset the password
+///
+/// Choose a new password with the token from a reset link. This signs the user out everywhere;
+/// they have to log in again afterwards.
+#[utoipa::path(
+    post,
+    path = "/auth/password-reset/confirm",
+    request_body = ConfirmPasswordResetRequest,
+    responses(
+        (status = 200, description = "Password reset successfully"),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 422, description = "Invalid, used or expired token, or a password that does not meet the password policy or was used recently", body = ErrorResponse),
+    ),
+    tag = "auth"
+)]
+pub async fn confirm_password_reset(
+    State(registry): State<AppRegistry>,
+    Json(req): Json<ConfirmPasswordResetRequest>,
+) -> AppResult<StatusCode> {
+    req.validate()?;
+
+    registry
+        .password_reset_repository()
+        .confirm(req.into())
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+fn password_reset_mail(web_config: &WebConfig, reset: &PasswordResetToken) -> Mail {
+    let link = format!(
+        "{}/password-reset?token={}",
+        web_config.frontend_origin.trim_end_matches('/'),
+        reset.token
     );
+    Mail {
+        to: reset.email.clone(),
+        subject: "Reset your password".into(),
+        body: format!(
+            "Hello {},\n\n\
+             Open the link below to choose a new password. It is valid until {} UTC and can be \
+             used once.\n\n{link}\n\n\
+             If you did not ask for this, you can ignore this mail.\n",
+            reset.name,
+            reset.expires_at.format("%Y-%m-%d %H:%M"),
+        ),
+    }
+}
+
+/// Starts a new session for the user and returns the cookies carrying its tokens.
+pub(crate) async fn issue_session_cookies(
+    registry: &AppRegistry,
+    user_id: UserId,
+) -> AppResult<HeaderMap> {
+    issue_session(registry, user_id)
+        .await
+        .map(|(headers, _)| headers)
+}
+
+/// Like [`issue_session_cookies`], but also hands back the access token for bearer clients.
+async fn issue_session(
+    registry: &AppRegistry,
+    user_id: UserId,
+) -> AppResult<(HeaderMap, AccessToken)> {
+    let access_token = registry
+        .auth_repository()
+        .create_token(CreateToken::new(user_id))
+        .await?;
+    let refresh_token = registry
+        .auth_repository()
+        .create_refresh_token(CreateToken::new(user_id))
+        .await?;
+
+    let headers = session_cookie_headers(&registry.web_config(), &access_token, &refresh_token)?;
+    Ok((headers, access_token))
+}
+
+fn session_cookie_headers(
+    web_config: &WebConfig,
+    access_token: &AccessToken,
+    refresh_token: &RefreshToken,
+) -> AppResult<HeaderMap> {
     let mut headers = HeaderMap::new();
-    headers.insert(
+    append_cookie(
+        &mut headers,
+        &web_config.access_token_cookie_name,
+        &access_token.0,
+        "/",
+        web_config.access_token_cookie_max_age_seconds,
+    )?;
+    append_cookie(
+        &mut headers,
+        &web_config.refresh_token_cookie_name,
+        &refresh_token.0,
+        REFRESH_TOKEN_COOKIE_PATH,
+        web_config.refresh_token_cookie_max_age_seconds,
+    )?;
+    Ok(headers)
+}
+
+pub(crate) fn append_cookie(
+    headers: &mut HeaderMap,
+    name: &str,
+    value: &str,
+    path: &str,
+    max_age: u64,
+) -> AppResult<()> {
+    let cookie =
+        format!("{name}={value}; HttpOnly; Secure; SameSite=Lax; Path={path}; Max-Age={max_age}");
+    headers.append(
         SET_COOKIE,
-        HeaderValue::from_str(&cookie).map_err(|_| shared::error::AppError::UnauthorizedError)?,
+        HeaderValue::from_str(&cookie).map_err(|_| AppError::UnauthorizedError)?,
     );
-
-    Ok((headers, StatusCode::NO_CONTENT))
+    Ok(())
 }
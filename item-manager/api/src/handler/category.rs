@@ -0,0 +1,195 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use garde::Validate;
+use kernel::model::{
+    category::{
+        AttributeDefinition, AttributeType,
+        event::{CreateCustomCategory, DeleteCustomCategory, UpdateCustomCategory},
+    },
+    id::CustomCategoryId,
+    role::Permission,
+};
+use registry::AppRegistry;
+use shared::error::AppResult;
+use utoipa::OpenApi;
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::{
+        category::{
+            CreateCustomCategoryRequest, CustomCategoriesResponse, CustomCategoryResponse,
+            UpdateCustomCategoryRequest,
+        },
+        error::ErrorResponse,
+    },
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_custom_categories,
+        create_custom_category,
+        update_custom_category,
+        delete_custom_category
+    ),
+    components(
+        schemas(
+            CreateCustomCategoryRequest,
+            UpdateCustomCategoryRequest,
+            CustomCategoryResponse,
+            CustomCategoriesResponse,
+            AttributeDefinition,
+            AttributeType,
+            ErrorResponse
+        )
+    ),
+    tags(
+        (name = "custom-categories", description = "Admin-defined item categories with typed attributes")
+    )
+)]
+pub struct ApiDoc;
+
+/// List custom categories
+///
+/// Retrieve every custom category with its attribute definitions, ordered by name
+#[utoipa::path(
+    get,
+    path = "/api/v1/custom-categories",
+    responses(
+        (status = 200, description = "Success", body = CustomCategoriesResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "custom-categories"
+)]
+pub async fn list_custom_categories(
+    _user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<CustomCategoriesResponse>> {
+    registry
+        .custom_category_repository()
+        .find_all()
+        .await
+        .map(CustomCategoriesResponse::from)
+        .map(Json)
+}
+
+/// Create a custom category (requires `categories:manage`)
+///
+/// Items of the new category are created with `"category": "custom"` and its key in `custom_category`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/custom-categories",
+    request_body = CreateCustomCategoryRequest,
+    responses(
+        (status = 201, description = "Custom category created", body = CustomCategoryResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `categories:manage` permission required", body = ErrorResponse),
+        (status = 409, description = "A custom category with the same key already exists", body = ErrorResponse),
+        (status = 422, description = "Invalid attribute definitions", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "custom-categories"
+)]
+pub async fn create_custom_category(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateCustomCategoryRequest>,
+) -> AppResult<(StatusCode, Json<CustomCategoryResponse>)> {
+    user.require(Permission::CategoriesManage)?;
+    req.validate()?;
+
+    registry
+        .custom_category_repository()
+        .create(CreateCustomCategory {
+            key: req.key,
+            name: req.name,
+            attributes: req.attributes,
+            requested_by: user.id(),
+        })
+        .await
+        .map(|category| (StatusCode::CREATED, Json(category.into())))
+}
+
+/// Update a custom category (requires `categories:manage`)
+///
+/// Rename the category and replace its attribute definitions. The change is refused if any of its items would no longer match.
+#[utoipa::path(
+    put,
+    path = "/api/v1/custom-categories/{custom_category_id}",
+    params(
+        ("custom_category_id" = String, Path, description = "Custom category ID"),
+    ),
+    request_body = UpdateCustomCategoryRequest,
+    responses(
+        (status = 200, description = "Custom category updated", body = CustomCategoryResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `categories:manage` permission required", body = ErrorResponse),
+        (status = 404, description = "Custom category not found", body = ErrorResponse),
+        (status = 409, description = "An item of the category does not match the new definitions", body = ErrorResponse),
+        (status = 422, description = "Invalid attribute definitions", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "custom-categories"
+)]
+pub async fn update_custom_category(
+    user: AuthorizedUser,
+    Path(custom_category_id): Path<CustomCategoryId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateCustomCategoryRequest>,
+) -> AppResult<Json<CustomCategoryResponse>> {
+    user.require(Permission::CategoriesManage)?;
+    req.validate()?;
+
+    registry
+        .custom_category_repository()
+        .update(UpdateCustomCategory {
+            custom_category_id,
+            name: req.name,
+            attributes: req.attributes,
+            requested_by: user.id(),
+        })
+        .await
+        .map(|category| Json(category.into()))
+}
+
+/// Delete a custom category (requires `categories:manage`)
+///
+/// Only a category without items, including archived ones, can be deleted.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/custom-categories/{custom_category_id}",
+    params(
+        ("custom_category_id" = String, Path, description = "Custom category ID"),
+    ),
+    responses(
+        (status = 200, description = "Custom category deleted"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `categories:manage` permission required", body = ErrorResponse),
+        (status = 404, description = "Custom category not found", body = ErrorResponse),
+        (status = 409, description = "The category still has items", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "custom-categories"
+)]
+pub async fn delete_custom_category(
+    user: AuthorizedUser,
+    Path(custom_category_id): Path<CustomCategoryId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    user.require(Permission::CategoriesManage)?;
+
+    registry
+        .custom_category_repository()
+        .delete(DeleteCustomCategory {
+            custom_category_id,
+            requested_by: user.id(),
+        })
+        .await
+        .map(|_| StatusCode::OK)
+}
@@ -1,11 +1,13 @@
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
 };
+use garde::Validate;
 use kernel::model::{
-    checkout::event::{CreateCheckout, UpdateReturned},
+    checkout::event::{CreateCheckout, RenewCheckout, UpdateReturned},
     id::{CheckoutId, ItemId},
+    role::Permission,
 };
 use registry::AppRegistry;
 use shared::error::AppResult;
@@ -14,8 +16,12 @@ use utoipa::OpenApi;
 use crate::{
     extractor::AuthorizedUser,
     model::{
-        checkout::{CheckoutsResponse, CreateCheckoutRequest},
+        checkout::{
+            CheckoutHistoryResponse, CheckoutRenewalResponse, CheckoutsResponse,
+            CreateCheckoutRequest, RenewCheckoutRequest,
+        },
         error::ErrorResponse,
+        list::CursorQuery,
     },
 };
 
@@ -24,11 +30,20 @@ use crate::{
     paths(
         checkout_item,
         return_item,
+        renew_checkout,
         show_checked_out_list,
+        show_overdue_list,
         checkout_history
     ),
     components(
-        schemas(CheckoutsResponse, CreateCheckoutRequest, ErrorResponse)
+        schemas(
+            CheckoutsResponse,
+            CheckoutHistoryResponse,
+            CheckoutRenewalResponse,
+            CreateCheckoutRequest,
+            RenewCheckoutRequest,
+            ErrorResponse
+        )
     ),
     tags(
         (name = "checkouts", description = "Item checkout management endpoints")
@@ -47,14 +62,15 @@ pub struct ApiDoc;
     ),
     request_body(
         content = Option<CreateCheckoutRequest>,
-        description = "Optional checkout user. Only administrators can specify another user."
+        description = "Optional checkout user and due date. Only users with `checkouts:manage_others` can specify another user or override the category's default loan period."
     ),
     responses(
         (status = 201, description = "Item checked out successfully"),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
-        (status = 403, description = "Forbidden - Admin access required to checkout for another user", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `checkouts:manage_others` permission required to checkout for another user or to set the due date", body = ErrorResponse),
         (status = 404, description = "Item not found", body = ErrorResponse),
         (status = 409, description = "Item already checked out", body = ErrorResponse),
+        (status = 422, description = "Due date is not in the future", body = ErrorResponse),
     ),
     security(("jwt" = [])),
     tag = "checkouts"
@@ -65,16 +81,33 @@ pub async fn checkout_item(
     State(registry): State<AppRegistry>,
     body: Option<Json<CreateCheckoutRequest>>,
 ) -> AppResult<StatusCode> {
-    let checked_out_by = body
-        .and_then(|Json(req)| req.checked_out_by)
-        .unwrap_or_else(|| user.id());
+    let (checked_out_by, due_at) = body
+        .map(|Json(req)| (req.checked_out_by, req.due_at))
+        .unwrap_or_default();
+    let checked_out_by = checked_out_by.unwrap_or_else(|| user.id());
+    let checked_out_at = chrono::Utc::now();
 
-    if checked_out_by != user.id() && !user.is_admin() {
+    if checked_out_by != user.id() && !user.has_permission(Permission::CheckoutsManageOthers) {
         return Err(shared::error::AppError::ForbiddenOperation(
-            "Admin access required to checkout for another user.".into(),
+            "The `checkouts:manage_others` permission is required to checkout for another user."
+                .into(),
         ));
     }
 
+    if let Some(due_at) = due_at {
+        if !user.has_permission(Permission::CheckoutsManageOthers) {
+            return Err(shared::error::AppError::ForbiddenOperation(
+                "The `checkouts:manage_others` permission is required to override the due date."
+                    .into(),
+            ));
+        }
+        if due_at <= checked_out_at {
+            return Err(shared::error::AppError::UnprocessableEntity(
+                "The due date must be in the future.".into(),
+            ));
+        }
+    }
+
     if registry
         .user_repository()
         .find_current_user(checked_out_by)
@@ -86,7 +119,8 @@ pub async fn checkout_item(
         )));
     }
 
-    let create_checkout_history = CreateCheckout::new(item_id, checked_out_by, chrono::Utc::now());
+    let create_checkout_history =
+        CreateCheckout::new(item_id, checked_out_by, user.id(), checked_out_at, due_at);
 
     registry
         .checkout_repository()
@@ -134,6 +168,72 @@ pub async fn return_item(
         .map(|_| StatusCode::OK)
 }
 
+/// Renew a checkout
+///
+/// Extend the loan of a checked out item by the default loan period of its category, without
+/// returning it. Users with `checkouts:manage_others` can set the new due date explicitly. Renewal is refused once the
+/// maximum number of renewals is reached or while other users are waiting for the item.
+#[utoipa::path(
+    put,
+    path = "/api/v1/items/{item_id}/checkouts/{checkout_id}/renewal",
+    params(
+        ("item_id" = String, Path, description = "Item ID to renew"),
+        ("checkout_id" = String, Path, description = "Checkout record ID"),
+    ),
+    request_body(
+        content = Option<RenewCheckoutRequest>,
+        description = "Optional new due date. Only users with `checkouts:manage_others` can specify it."
+    ),
+    responses(
+        (status = 200, description = "Checkout renewed successfully"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "Item or checkout record not found", body = ErrorResponse),
+        (status = 409, description = "Renewal limit reached or item reserved by another user", body = ErrorResponse),
+        (status = 422, description = "Due date is not in the future", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "checkouts"
+)]
+pub async fn renew_checkout(
+    user: AuthorizedUser,
+    Path((item_id, checkout_id)): Path<(ItemId, CheckoutId)>,
+    State(registry): State<AppRegistry>,
+    body: Option<Json<RenewCheckoutRequest>>,
+) -> AppResult<StatusCode> {
+    let due_at = body.and_then(|Json(req)| req.due_at);
+    let renewed_at = chrono::Utc::now();
+
+    if let Some(due_at) = due_at {
+        if !user.has_permission(Permission::CheckoutsManageOthers) {
+            return Err(shared::error::AppError::ForbiddenOperation(
+                "The `checkouts:manage_others` permission is required to override the due date."
+                    .into(),
+            ));
+        }
+        if due_at <= renewed_at {
+            return Err(shared::error::AppError::UnprocessableEntity(
+                "The due date must be in the future.".into(),
+            ));
+        }
+    }
+
+    let renew_checkout = RenewCheckout::new(
+        checkout_id,
+        item_id,
+        user.id(),
+        user.user.role,
+        renewed_at,
+        due_at,
+    );
+
+    registry
+        .checkout_repository()
+        .renew(renew_checkout)
+        .await
+        .map(|_| StatusCode::OK)
+}
+
 /// List all currently checked out items
 ///
 /// Get a list of all items that are currently checked out
@@ -159,17 +259,46 @@ pub async fn show_checked_out_list(
         .map(Json)
 }
 
+/// List all overdue checkouts
+///
+/// Get a list of all checked out items whose due date has passed, the most overdue first
+#[utoipa::path(
+    get,
+    path = "/api/v1/items/checkouts/overdue",
+    responses(
+        (status = 200, description = "Success", body = CheckoutsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "checkouts"
+)]
+pub async fn show_overdue_list(
+    _user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<CheckoutsResponse>> {
+    registry
+        .checkout_repository()
+        .find_overdue_all()
+        .await
+        .map(CheckoutsResponse::from)
+        .map(Json)
+}
+
 /// Get item checkout history
 ///
-/// Get the complete checkout history for a specific item
+/// Get the checkout history for a specific item, newest first. Without `limit`
+/// the complete history is returned; with it, the history is paged by cursor.
 #[utoipa::path(
     get,
     path = "/api/v1/items/{item_id}/checkout-history",
     params(
         ("item_id" = String, Path, description = "Item ID"),
+        ("limit" = Option<i64>, Query, description = "Number of checkouts to return"),
+        ("cursor" = Option<String>, Query, description = "Cursor from a previous page"),
     ),
     responses(
-        (status = 200, description = "Success", body = CheckoutsResponse),
+        (status = 200, description = "Success", body = CheckoutHistoryResponse),
+        (status = 400, description = "Invalid query", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 404, description = "Item not found", body = ErrorResponse),
     ),
@@ -179,8 +308,12 @@ pub async fn show_checked_out_list(
 pub async fn checkout_history(
     _user: AuthorizedUser,
     Path(item_id): Path<ItemId>,
+    Query(query): Query<CursorQuery>,
     State(registry): State<AppRegistry>,
-) -> AppResult<Json<CheckoutsResponse>> {
+) -> AppResult<Json<CheckoutHistoryResponse>> {
+    query.validate()?;
+    let options = query.try_into()?;
+
     let exists = registry.item_repository().find_by_id(item_id).await?;
     if exists.is_none() {
         return Err(shared::error::AppError::EntityNotFound(
@@ -190,8 +323,8 @@ pub async fn checkout_history(
 
     registry
         .checkout_repository()
-        .find_history_by_item_id(item_id)
+        .find_history_by_item_id(item_id, options)
         .await
-        .map(CheckoutsResponse::from)
+        .map(CheckoutHistoryResponse::from)
         .map(Json)
 }
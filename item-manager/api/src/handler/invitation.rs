@@ -0,0 +1,163 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::Duration;
+use garde::Validate;
+use kernel::model::{
+    id::InvitationId,
+    invitation::event::{CreateInvitation, DeleteInvitation},
+    role::Permission,
+};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+use utoipa::OpenApi;
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::{
+        error::ErrorResponse,
+        invitation::{
+            CreateInvitationRequest, CreatedInvitationResponse, InvitationResponse,
+            InvitationsResponse,
+        },
+    },
+};
+
+/// Longest lifetime an invitation can be created with.
+const MAX_INVITATION_LIFETIME_DAYS: i64 = 90;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        create_invitation,
+        list_invitations,
+        delete_invitation
+    ),
+    components(
+        schemas(
+            CreateInvitationRequest,
+            CreatedInvitationResponse,
+            InvitationResponse,
+            InvitationsResponse,
+            ErrorResponse
+        )
+    ),
+    tags(
+        (name = "invitations", description = "Invitation codes for self-service signup")
+    )
+)]
+pub struct ApiDoc;
+
+/// Create an invitation (requires `users:manage`)
+///
+/// Issue a code people can sign up with on their own, up to `maxUses` times until it expires.
+#[utoipa::path(
+    post,
+    path = "/api/v1/invitations",
+    request_body = CreateInvitationRequest,
+    responses(
+        (status = 201, description = "Invitation created; the code is only shown once", body = CreatedInvitationResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `users:manage` permission required, or requested with a personal access token", body = ErrorResponse),
+        (status = 422, description = "Expiry is in the past or more than 90 days away", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "invitations"
+)]
+pub async fn create_invitation(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateInvitationRequest>,
+) -> AppResult<(StatusCode, Json<CreatedInvitationResponse>)> {
+    user.require_session()?;
+    user.require(Permission::UsersManage)?;
+    req.validate()?;
+
+    let now = chrono::Utc::now();
+    if req.expires_at <= now {
+        return Err(AppError::UnprocessableEntity(
+            "The expiry must be in the future.".into(),
+        ));
+    }
+    if req.expires_at > now + Duration::days(MAX_INVITATION_LIFETIME_DAYS) {
+        return Err(AppError::UnprocessableEntity(format!(
+            "The expiry must be within {MAX_INVITATION_LIFETIME_DAYS} days."
+        )));
+    }
+
+    registry
+        .invitation_repository()
+        .create(CreateInvitation {
+            role: req.role.into(),
+            max_uses: req.max_uses,
+            expires_at: req.expires_at,
+            requested_by: user.id(),
+        })
+        .await
+        .map(|created| (StatusCode::CREATED, Json(created.into())))
+}
+
+/// List invitations (requires `users:manage`)
+///
+/// Retrieve every invitation, newest first, including expired and used up ones
+#[utoipa::path(
+    get,
+    path = "/api/v1/invitations",
+    responses(
+        (status = 200, description = "Success", body = InvitationsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `users:manage` permission required, or requested with a personal access token", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "invitations"
+)]
+pub async fn list_invitations(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<InvitationsResponse>> {
+    user.require_session()?;
+    user.require(Permission::UsersManage)?;
+
+    registry
+        .invitation_repository()
+        .find_all()
+        .await
+        .map(InvitationsResponse::from)
+        .map(Json)
+}
+
+/// Revoke an invitation (requires `users:manage`)
+///
+/// Delete an invitation so its code can no longer be used. Accounts created with it are kept.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/invitations/{invitation_id}",
+    params(
+        ("invitation_id" = String, Path, description = "Invitation ID"),
+    ),
+    responses(
+        (status = 200, description = "Invitation revoked successfully"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `users:manage` permission required, or requested with a personal access token", body = ErrorResponse),
+        (status = 404, description = "Invitation not found", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "invitations"
+)]
+pub async fn delete_invitation(
+    user: AuthorizedUser,
+    Path(invitation_id): Path<InvitationId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    user.require_session()?;
+    user.require(Permission::UsersManage)?;
+
+    registry
+        .invitation_repository()
+        .delete(DeleteInvitation { invitation_id })
+        .await
+        .map(|_| StatusCode::OK)
+}
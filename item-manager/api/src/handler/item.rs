@@ -1,19 +1,31 @@
 use axum::{
     Json,
+    body::Body,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{StatusCode, header},
+    response::IntoResponse,
 };
 use garde::Validate;
-use kernel::model::{id::ItemId, item::DeleteItem};
+use kernel::model::{
+    id::ItemId,
+    item::{DeleteItem, RestoreItem},
+    list::TagMatch,
+    role::Permission,
+};
 use registry::AppRegistry;
 use shared::error::{AppError, AppResult};
+use tokio_stream::StreamExt;
 use utoipa::OpenApi;
 
 use crate::{
     extractor::AuthorizedUser,
     model::{
         error::ErrorResponse,
-        item::{CreateItemRequest, ItemResponse, PaginatedItemResponse, UpdateItemRequest},
+        item::{
+            CreateItemRequest, DeleteItemQuery, ExportFormat, ExportQuery, ImportItemsResponse,
+            ItemExportRecord, ItemResponse, PaginatedItemResponse, UpdateItemRequest,
+            parse_item_csv,
+        },
         list::ListQuery,
     },
 };
@@ -22,10 +34,13 @@ use crate::{
 #[openapi(
     paths(
         create_item,
+        import_items,
+        export_items,
         list_items,
         get_item,
         update_item,
-        delete_item
+        delete_item,
+        restore_item
     ),
     components(
         schemas(
@@ -35,9 +50,17 @@ use crate::{
             crate::model::item::GeneralItemResponse,
             crate::model::item::BookResponse,
             crate::model::item::LaptopResponse,
+            crate::model::item::CustomItemResponse,
+            crate::model::category::CustomCategoryRefResponse,
             crate::model::item::PaginatedItemResponse,
             crate::model::item::ItemCheckoutResponse,
+            crate::model::item::ItemArchiveResponse,
             ListQuery,
+            TagMatch,
+            crate::model::tag::TagResponse,
+            crate::model::item::ImportItemsResponse,
+            crate::model::item::ImportRowError,
+            crate::model::item::ExportFormat,
             ErrorResponse
         )
     ),
@@ -47,19 +70,9 @@ use crate::{
 )]
 pub struct ApiDoc;
 
-fn ensure_admin(user: &AuthorizedUser) -> AppResult<()> {
-    if user.is_admin() {
-        Ok(())
-    } else {
-        Err(AppError::ForbiddenOperation(
-            "Admin access required.".into(),
-        ))
-    }
-}
-
 /// Create a new item
 ///
-/// Create a new item with the provided details. The item category (general, book, or laptop) determines the required fields.
+/// Create a new item with the provided details. The item category (general, book, laptop or custom) determines the required fields. Attributes of a custom item are checked against its category's definitions.
 #[utoipa::path(
     post,
     path = "/api/v1/items",
@@ -68,7 +81,8 @@ fn ensure_admin(user: &AuthorizedUser) -> AppResult<()> {
         (status = 201, description = "Item created successfully"),
         (status = 400, description = "Invalid request body", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
-        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `items:write` permission required", body = ErrorResponse),
+        (status = 422, description = "Unknown custom category or invalid attributes", body = ErrorResponse),
     ),
     security(("jwt" = [])),
     tag = "items"
@@ -78,16 +92,127 @@ pub async fn create_item(
     State(registry): State<AppRegistry>,
     Json(req): Json<CreateItemRequest>,
 ) -> Result<StatusCode, AppError> {
-    ensure_admin(&user)?;
+    user.require(Permission::ItemsWrite)?;
     req.validate()?;
 
     registry
         .item_repository()
-        .create(req.into())
+        .create(req.into_create_item(user.id()))
         .await
         .map(|_| StatusCode::CREATED)
 }
 
+/// Import items from CSV (requires `items:write`)
+///
+/// Create many items at once from a CSV with a header row. Columns are `category`, `name`, `description`, `location`, `author`, `isbn`, `mac_address`, `custom_category` and `attributes` (a JSON object); columns that do not apply to a row's category are ignored. Every row is validated like `POST /api/v1/items`, and the items are created only if all rows are valid.
+#[utoipa::path(
+    post,
+    path = "/api/v1/items/import",
+    request_body(
+        content = String,
+        content_type = "text/csv",
+        example = "category,name,description,location,author,isbn,mac_address\nbook,The Rust Programming Language,,Shelf A,Steve Klabnik,9781718503106,\nlaptop,Lab laptop 1,,,,,00:11:22:33:44:55"
+    ),
+    responses(
+        (status = 201, description = "All rows imported", body = ImportItemsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `items:write` permission required", body = ErrorResponse),
+        (status = 422, description = "Some rows are invalid; nothing was imported", body = ImportItemsResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "items"
+)]
+pub async fn import_items(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    body: String,
+) -> AppResult<(StatusCode, Json<ImportItemsResponse>)> {
+    user.require(Permission::ItemsWrite)?;
+
+    let requests = match parse_item_csv(&body) {
+        Ok(requests) => requests,
+        Err(errors) => {
+            return Ok((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(ImportItemsResponse {
+                    imported: 0,
+                    errors,
+                }),
+            ));
+        }
+    };
+    if requests.is_empty() {
+        return Err(AppError::UnprocessableEntity(
+            "The CSV contains no items.".into(),
+        ));
+    }
+
+    let imported = requests.len();
+    registry
+        .item_repository()
+        .create_many(
+            requests
+                .into_iter()
+                .map(|req| req.into_create_item(user.id()))
+                .collect(),
+        )
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ImportItemsResponse {
+            imported,
+            errors: Vec::new(),
+        }),
+    ))
+}
+
+/// Export all items (requires `items:write`)
+///
+/// Stream every item, archived ones included, with its location, current checkout holder and archive state as CSV or JSON Lines. The CSV uses the same columns as the import.
+#[utoipa::path(
+    get,
+    path = "/api/v1/items/export",
+    params(
+        ("format" = Option<ExportFormat>, Query, description = "`csv` (default) or `jsonl`"),
+    ),
+    responses(
+        (status = 200, description = "Success", content(
+            (String = "text/csv"),
+            (String = "application/jsonl"),
+        )),
+        (status = 400, description = "Invalid query", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `items:write` permission required", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "items"
+)]
+pub async fn export_items(
+    user: AuthorizedUser,
+    Query(query): Query<ExportQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<impl IntoResponse> {
+    user.require(Permission::ItemsWrite)?;
+
+    let format = query.format;
+    let header = tokio_stream::once(Ok(format.header()));
+    let records = registry
+        .item_repository()
+        .stream_all()
+        .map(move |item| format.encode(&ItemExportRecord::from(item?)));
+    let body = Body::from_stream(header.chain(records));
+
+    let headers = [
+        (header::CONTENT_TYPE, format.content_type().to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"items.{}\"", format.file_extension()),
+        ),
+    ];
+    Ok((headers, body))
+}
+
 /// List items
 ///
 /// Get a paginated list of all items
@@ -97,9 +222,14 @@ pub async fn create_item(
     params(
         ("limit" = i64, Query, description = "Number of items to return"),
         ("offset" = i64, Query, description = "Number of items to skip"),
+        ("cursor" = Option<String>, Query, description = "Cursor from a previous page; replaces offset"),
+        ("tags" = Option<String>, Query, description = "Comma-separated tag names, matched regardless of case"),
+        ("tag_match" = Option<TagMatch>, Query, description = "Whether items need `any` (default) or `all` of `tags`"),
+        ("custom_category" = Option<String>, Query, description = "Key of a custom category; only its items are listed"),
     ),
     responses(
         (status = 200, description = "Success", body = PaginatedItemResponse),
+        (status = 400, description = "Invalid query", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
     ),
     security(("jwt" = [])),
@@ -111,10 +241,11 @@ pub async fn list_items(
     State(registry): State<AppRegistry>,
 ) -> AppResult<Json<PaginatedItemResponse>> {
     query.validate()?;
+    let options = query.try_into()?;
 
     registry
         .item_repository()
-        .find_all(query.into())
+        .find_all(options)
         .await
         .and_then(PaginatedItemResponse::try_from)
         .map(Json)
@@ -167,8 +298,9 @@ pub async fn get_item(
         (status = 200, description = "Item updated successfully"),
         (status = 400, description = "Invalid request body", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
-        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `items:write` permission required", body = ErrorResponse),
         (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 422, description = "Unknown custom category or invalid attributes", body = ErrorResponse),
     ),
     security(("jwt" = [])),
     tag = "items"
@@ -179,30 +311,33 @@ pub async fn update_item(
     State(registry): State<AppRegistry>,
     Json(req): Json<UpdateItemRequest>,
 ) -> AppResult<StatusCode> {
-    ensure_admin(&user)?;
+    user.require(Permission::ItemsWrite)?;
     req.validate()?;
 
     registry
         .item_repository()
-        .update(req.into_update_item(item_id))
+        .update(req.into_update_item(item_id, user.id()))
         .await
         .map(|_| StatusCode::OK)
 }
 
-/// Delete item
+/// Archive item (requires `items:write`)
 ///
-/// Delete an existing item
+/// Retire an item from circulation. Archived items are hidden from listings unless `include_archived` is set, but stay resolvable by ID along with their checkout history. Items that are checked out cannot be archived.
 #[utoipa::path(
     delete,
     path = "/api/v1/items/{item_id}",
     params(
         ("item_id" = String, Path, description = "Item ID"),
+        ("reason" = Option<String>, Query, description = "Why the item is being archived"),
     ),
     responses(
-        (status = 200, description = "Item deleted successfully"),
+        (status = 200, description = "Item archived successfully"),
+        (status = 400, description = "Invalid query", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
-        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `items:write` permission required", body = ErrorResponse),
         (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 409, description = "Item is checked out or already archived", body = ErrorResponse),
     ),
     security(("jwt" = [])),
     tag = "items"
@@ -210,13 +345,56 @@ pub async fn update_item(
 pub async fn delete_item(
     user: AuthorizedUser,
     Path(item_id): Path<ItemId>,
+    Query(query): Query<DeleteItemQuery>,
     State(registry): State<AppRegistry>,
 ) -> AppResult<StatusCode> {
-    ensure_admin(&user)?;
-    let delete_item = DeleteItem { item_id };
+    user.require(Permission::ItemsWrite)?;
+    query.validate()?;
+
+    let delete_item = DeleteItem {
+        item_id,
+        requested_by: user.id(),
+        reason: query.reason.filter(|reason| !reason.trim().is_empty()),
+    };
     registry
         .item_repository()
         .delete(delete_item)
         .await
         .map(|_| StatusCode::OK)
 }
+
+/// Restore archived item (requires `items:write`)
+///
+/// Put an archived item back into circulation
+#[utoipa::path(
+    put,
+    path = "/api/v1/items/{item_id}/restore",
+    params(
+        ("item_id" = String, Path, description = "Item ID"),
+    ),
+    responses(
+        (status = 200, description = "Item restored successfully"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `items:write` permission required", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 409, description = "Item is not archived", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "items"
+)]
+pub async fn restore_item(
+    user: AuthorizedUser,
+    Path(item_id): Path<ItemId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    user.require(Permission::ItemsWrite)?;
+
+    registry
+        .item_repository()
+        .restore(RestoreItem {
+            item_id,
+            requested_by: user.id(),
+        })
+        .await
+        .map(|_| StatusCode::OK)
+}
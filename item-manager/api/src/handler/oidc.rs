@@ -0,0 +1,183 @@
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header::LOCATION},
+    response::{IntoResponse, Response},
+};
+use kernel::model::{
+    id::UserId,
+    oidc::event::{CreateOidcLoginRequest, SignInWithOidc},
+    two_factor::event::CreateLoginChallenge,
+};
+use registry::AppRegistry;
+use shared::{
+    config::WebConfig,
+    error::{AppError, AppResult},
+};
+use utoipa::OpenApi;
+
+use crate::{
+    extractor::find_cookie,
+    handler::auth::{append_cookie, issue_session_cookies},
+    model::{error::ErrorResponse, oidc::OidcCallbackQuery},
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(oidc_login, oidc_callback),
+    components(schemas(ErrorResponse)),
+    tags(
+        (name = "auth", description = "Authentication endpoints")
+    )
+)]
+pub struct ApiDoc;
+
+/// Binds the callback to the browser that started the sign-in.
+const STATE_COOKIE_NAME: &str = "oidc_state";
+const STATE_COOKIE_PATH: &str = "/auth/oidc";
+/// Matches how long the server keeps the pending sign-in.
+const STATE_COOKIE_MAX_AGE: u64 = 10 * 60;
+
+/// Sign in with the identity provider
+///
+/// Redirect the browser to the configured OpenID Connect provider. It is sent back to
+/// `/auth/oidc/callback` afterwards.
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/login",
+    responses(
+        (status = 303, description = "Redirect to the identity provider"),
+        (status = 404, description = "Single sign-on is not configured", body = ErrorResponse),
+    ),
+    tag = "auth"
+)]
+pub async fn oidc_login(State(registry): State<AppRegistry>) -> AppResult<(HeaderMap, StatusCode)> {
+    let provider = registry
+        .oidc_provider()
+        .ok_or_else(|| AppError::EntityNotFound("Single sign-on is not configured.".into()))?;
+
+    let authorization = provider.authorize().await?;
+    registry
+        .oidc_repository()
+        .create_login_request(CreateOidcLoginRequest {
+            state: authorization.state.clone(),
+            nonce: authorization.nonce,
+            pkce_verifier: authorization.pkce_verifier,
+        })
+        .await?;
+
+    let mut headers = HeaderMap::new();
+    append_cookie(
+        &mut headers,
+        STATE_COOKIE_NAME,
+        &authorization.state,
+        STATE_COOKIE_PATH,
+        STATE_COOKIE_MAX_AGE,
+    )?;
+    headers.insert(LOCATION, location(&authorization.url)?);
+
+    Ok((headers, StatusCode::SEE_OTHER))
+}
+
+/// Complete a sign-in with the identity provider
+///
+/// Verify the provider's response and sign in to the account linked to the identity. A first
+/// sign-in is linked to the account with the same verified email address, or creates a new one
+/// when signups are allowed. The browser is then redirected to the frontend; accounts with
+/// two-factor authentication land on `/login#challengeToken=...` to complete the login at
+/// `/auth/login/2fa`, and failures on `/login?error=...`.
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/callback",
+    params(
+        ("code" = Option<String>, Query, description = "Authorization code from the provider"),
+        ("state" = Option<String>, Query, description = "State of the sign-in being completed"),
+        ("error" = Option<String>, Query, description = "Set by the provider when the sign-in was denied"),
+    ),
+    responses(
+        (status = 303, description = "Redirect to the frontend"),
+    ),
+    tag = "auth"
+)]
+pub async fn oidc_callback(
+    State(registry): State<AppRegistry>,
+    Query(query): Query<OidcCallbackQuery>,
+    request_headers: HeaderMap,
+) -> AppResult<Response> {
+    let web_config = registry.web_config();
+    let (mut headers, path) = match sign_in(&registry, query, &request_headers).await {
+        Ok(SignIn::Completed(user_id)) => {
+            (issue_session_cookies(&registry, user_id).await?, "/".into())
+        }
+        Ok(SignIn::Challenged(path)) => (HeaderMap::new(), path),
+        Err(e) => {
+            tracing::warn!(error = %e, "Single sign-on failed");
+            let reason = match e {
+                AppError::ForbiddenOperation(_) => "account_deactivated",
+                _ => "sso_failed",
+            };
+            (HeaderMap::new(), format!("/login?error={reason}"))
+        }
+    };
+
+    append_cookie(&mut headers, STATE_COOKIE_NAME, "", STATE_COOKIE_PATH, 0)?;
+    headers.insert(LOCATION, location(&frontend_url(&web_config, &path))?);
+
+    Ok((StatusCode::SEE_OTHER, headers).into_response())
+}
+
+enum SignIn {
+    Completed(UserId),
+    /// A second factor is still required; holds where the frontend completes the login.
+    Challenged(String),
+}
+
+async fn sign_in(
+    registry: &AppRegistry,
+    query: OidcCallbackQuery,
+    request_headers: &HeaderMap,
+) -> AppResult<SignIn> {
+    let provider = registry
+        .oidc_provider()
+        .ok_or_else(|| AppError::EntityNotFound("Single sign-on is not configured.".into()))?;
+    if let Some(error) = query.error {
+        tracing::info!(error, "Identity provider declined the sign-in");
+        return Err(AppError::UnauthenticatedError);
+    }
+    let (Some(code), Some(state)) = (query.code, query.state) else {
+        return Err(AppError::UnauthenticatedError);
+    };
+    if find_cookie(request_headers, STATE_COOKIE_NAME).as_deref() != Some(state.as_str()) {
+        return Err(AppError::UnauthenticatedError);
+    }
+
+    let pending = registry
+        .oidc_repository()
+        .take_login_request(&state)
+        .await?
+        .ok_or(AppError::UnauthenticatedError)?;
+    let identity = provider.exchange_code(&code, pending).await?;
+    let user_id = registry
+        .oidc_repository()
+        .sign_in(SignInWithOidc { identity })
+        .await?;
+
+    match registry
+        .two_factor_repository()
+        .create_challenge(CreateLoginChallenge { user_id })
+        .await?
+    {
+        Some(challenge) => Ok(SignIn::Challenged(format!(
+            "/login#challengeToken={}&setupRequired={}",
+            challenge.token, challenge.setup_required
+        ))),
+        None => Ok(SignIn::Completed(user_id)),
+    }
+}
+
+fn frontend_url(web_config: &WebConfig, path: &str) -> String {
+    format!("{}{path}", web_config.frontend_origin.trim_end_matches('/'))
+}
+
+fn location(url: &str) -> AppResult<HeaderValue> {
+    HeaderValue::from_str(url).map_err(|e| AppError::ConversionEntityError(e.to_string()))
+}
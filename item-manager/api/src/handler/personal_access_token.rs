@@ -0,0 +1,171 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::Duration;
+use garde::Validate;
+use kernel::model::{
+    id::PersonalAccessTokenId,
+    personal_access_token::{
+        TokenScope,
+        event::{CreatePersonalAccessToken, DeletePersonalAccessToken},
+    },
+};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+use utoipa::OpenApi;
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::{
+        error::ErrorResponse,
+        personal_access_token::{
+            CreatePersonalAccessTokenRequest, CreatedPersonalAccessTokenResponse,
+            PersonalAccessTokenResponse, PersonalAccessTokensResponse,
+        },
+    },
+};
+
+/// Longest lifetime a personal access token can be created with.
+const MAX_TOKEN_LIFETIME_DAYS: i64 = 365;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        create_token,
+        list_tokens,
+        delete_token
+    ),
+    components(
+        schemas(
+            CreatePersonalAccessTokenRequest,
+            CreatedPersonalAccessTokenResponse,
+            PersonalAccessTokenResponse,
+            PersonalAccessTokensResponse,
+            TokenScope,
+            ErrorResponse
+        )
+    ),
+    tags(
+        (name = "tokens", description = "Personal access tokens for scripts")
+    )
+)]
+pub struct ApiDoc;
+
+/// Create a personal access token
+///
+/// Issue a token for scripts, sent as `Authorization: Bearer <secret>`. The token acts as the
+/// user within its scopes. Tokens can only be managed from a signed-in session.
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/tokens",
+    request_body = CreatePersonalAccessTokenRequest,
+    responses(
+        (status = 201, description = "Token created; the secret is only shown once", body = CreatedPersonalAccessTokenResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Requested with a personal access token", body = ErrorResponse),
+        (status = 422, description = "Expiry is in the past or more than a year away", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "tokens"
+)]
+pub async fn create_token(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreatePersonalAccessTokenRequest>,
+) -> AppResult<(StatusCode, Json<CreatedPersonalAccessTokenResponse>)> {
+    user.require_session()?;
+    req.validate()?;
+
+    let now = chrono::Utc::now();
+    if req.expires_at <= now {
+        return Err(AppError::UnprocessableEntity(
+            "The expiry must be in the future.".into(),
+        ));
+    }
+    if req.expires_at > now + Duration::days(MAX_TOKEN_LIFETIME_DAYS) {
+        return Err(AppError::UnprocessableEntity(format!(
+            "The expiry must be within {MAX_TOKEN_LIFETIME_DAYS} days."
+        )));
+    }
+
+    let mut scopes = req.scopes;
+    scopes.sort();
+    scopes.dedup();
+
+    registry
+        .personal_access_token_repository()
+        .create(CreatePersonalAccessToken {
+            user_id: user.id(),
+            name: req.name,
+            scopes,
+            expires_at: req.expires_at,
+        })
+        .await
+        .map(|created| (StatusCode::CREATED, Json(created.into())))
+}
+
+/// List personal access tokens
+///
+/// Retrieve the authenticated user's tokens, newest first, including expired ones
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/tokens",
+    responses(
+        (status = 200, description = "Success", body = PersonalAccessTokensResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Requested with a personal access token", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "tokens"
+)]
+pub async fn list_tokens(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<PersonalAccessTokensResponse>> {
+    user.require_session()?;
+
+    registry
+        .personal_access_token_repository()
+        .find_by_user_id(user.id())
+        .await
+        .map(PersonalAccessTokensResponse::from)
+        .map(Json)
+}
+
+/// Revoke a personal access token
+///
+/// Delete one of the authenticated user's tokens; requests made with it fail from then on
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me/tokens/{token_id}",
+    params(
+        ("token_id" = String, Path, description = "Token ID"),
+    ),
+    responses(
+        (status = 200, description = "Token revoked successfully"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Requested with a personal access token", body = ErrorResponse),
+        (status = 404, description = "Token not found", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "tokens"
+)]
+pub async fn delete_token(
+    user: AuthorizedUser,
+    Path(token_id): Path<PersonalAccessTokenId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    user.require_session()?;
+
+    registry
+        .personal_access_token_repository()
+        .delete(DeletePersonalAccessToken {
+            token_id,
+            user_id: user.id(),
+        })
+        .await
+        .map(|_| StatusCode::OK)
+}
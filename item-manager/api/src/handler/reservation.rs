@@ -0,0 +1,143 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use kernel::model::{
+    id::{ItemId, ReservationId},
+    reservation::event::{CreateReservation, DeleteReservation},
+};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+use utoipa::OpenApi;
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::{
+        error::ErrorResponse,
+        reservation::{CreatedReservationResponse, ReservationResponse, ReservationsResponse},
+    },
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        reserve_item,
+        cancel_reservation,
+        show_reservations
+    ),
+    components(
+        schemas(
+            CreatedReservationResponse,
+            ReservationResponse,
+            ReservationsResponse,
+            ErrorResponse
+        )
+    ),
+    tags(
+        (name = "reservations", description = "Item reservation queue endpoints")
+    )
+)]
+pub struct ApiDoc;
+
+/// Reserve an item
+///
+/// Join the reservation queue of an item that is currently checked out or held for someone else.
+/// When the item is returned, the head of the queue gets an exclusive window to check it out.
+#[utoipa::path(
+    post,
+    path = "/api/v1/items/{item_id}/reservations",
+    params(
+        ("item_id" = String, Path, description = "Item ID to reserve"),
+    ),
+    responses(
+        (status = 201, description = "Reservation created successfully", body = CreatedReservationResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 409, description = "Item is available, already checked out or already reserved by the user", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "reservations"
+)]
+pub async fn reserve_item(
+    user: AuthorizedUser,
+    Path(item_id): Path<ItemId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<(StatusCode, Json<CreatedReservationResponse>)> {
+    let create_reservation = CreateReservation::new(item_id, user.id(), chrono::Utc::now());
+
+    registry
+        .reservation_repository()
+        .create(create_reservation)
+        .await
+        .map(|id| (StatusCode::CREATED, Json(CreatedReservationResponse { id })))
+}
+
+/// Cancel a reservation
+///
+/// Leave the reservation queue of an item. Users with `checkouts:manage_others` can cancel any reservation.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/items/{item_id}/reservations/{reservation_id}",
+    params(
+        ("item_id" = String, Path, description = "Item ID"),
+        ("reservation_id" = String, Path, description = "Reservation ID"),
+    ),
+    responses(
+        (status = 204, description = "Reservation cancelled successfully"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "Reservation not found", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "reservations"
+)]
+pub async fn cancel_reservation(
+    user: AuthorizedUser,
+    Path((item_id, reservation_id)): Path<(ItemId, ReservationId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    let delete_reservation =
+        DeleteReservation::new(reservation_id, item_id, user.id(), user.user.role);
+
+    registry
+        .reservation_repository()
+        .delete(delete_reservation)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+/// Get the reservation queue of an item
+///
+/// List the reservations of an item in queue order
+#[utoipa::path(
+    get,
+    path = "/api/v1/items/{item_id}/reservations",
+    params(
+        ("item_id" = String, Path, description = "Item ID"),
+    ),
+    responses(
+        (status = 200, description = "Success", body = ReservationsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "reservations"
+)]
+pub async fn show_reservations(
+    _user: AuthorizedUser,
+    Path(item_id): Path<ItemId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<ReservationsResponse>> {
+    let exists = registry.item_repository().find_by_id(item_id).await?;
+    if exists.is_none() {
+        return Err(AppError::EntityNotFound("Item not found".into()));
+    }
+
+    registry
+        .reservation_repository()
+        .find_by_item_id(item_id)
+        .await
+        .map(ReservationsResponse::from)
+        .map(Json)
+}
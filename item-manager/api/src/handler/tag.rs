@@ -0,0 +1,255 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use garde::Validate;
+use kernel::model::{
+    id::{ItemId, TagId},
+    role::Permission,
+    tag::event::{AttachTag, CreateTag, DeleteTag, DetachTag, UpdateTag},
+};
+use registry::AppRegistry;
+use shared::error::AppResult;
+use utoipa::OpenApi;
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::{
+        error::ErrorResponse,
+        tag::{CreateTagRequest, TagResponse, TagsResponse, UpdateTagRequest},
+    },
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_tags,
+        create_tag,
+        update_tag,
+        delete_tag,
+        attach_tag,
+        detach_tag
+    ),
+    components(
+        schemas(
+            CreateTagRequest,
+            UpdateTagRequest,
+            TagResponse,
+            TagsResponse,
+            ErrorResponse
+        )
+    ),
+    tags(
+        (name = "tags", description = "Tags that group items across categories")
+    )
+)]
+pub struct ApiDoc;
+
+/// List tags
+///
+/// Retrieve every tag in alphabetical order
+#[utoipa::path(
+    get,
+    path = "/api/v1/tags",
+    responses(
+        (status = 200, description = "Success", body = TagsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "tags"
+)]
+pub async fn list_tags(
+    _user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<TagsResponse>> {
+    registry
+        .tag_repository()
+        .find_all()
+        .await
+        .map(TagsResponse::from)
+        .map(Json)
+}
+
+/// Create a tag (requires `items:write`)
+#[utoipa::path(
+    post,
+    path = "/api/v1/tags",
+    request_body = CreateTagRequest,
+    responses(
+        (status = 201, description = "Tag created", body = TagResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `items:write` permission required", body = ErrorResponse),
+        (status = 409, description = "A tag with the same name already exists", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "tags"
+)]
+pub async fn create_tag(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateTagRequest>,
+) -> AppResult<(StatusCode, Json<TagResponse>)> {
+    user.require(Permission::ItemsWrite)?;
+    req.validate()?;
+
+    registry
+        .tag_repository()
+        .create(CreateTag {
+            name: req.name,
+            requested_by: user.id(),
+        })
+        .await
+        .map(|tag| (StatusCode::CREATED, Json(tag.into())))
+}
+
+/// Rename a tag (requires `items:write`)
+///
+/// The tag stays attached to its items under the new name
+#[utoipa::path(
+    put,
+    path = "/api/v1/tags/{tag_id}",
+    params(
+        ("tag_id" = String, Path, description = "Tag ID"),
+    ),
+    request_body = UpdateTagRequest,
+    responses(
+        (status = 200, description = "Tag renamed", body = TagResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `items:write` permission required", body = ErrorResponse),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+        (status = 409, description = "A tag with the same name already exists", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "tags"
+)]
+pub async fn update_tag(
+    user: AuthorizedUser,
+    Path(tag_id): Path<TagId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateTagRequest>,
+) -> AppResult<Json<TagResponse>> {
+    user.require(Permission::ItemsWrite)?;
+    req.validate()?;
+
+    registry
+        .tag_repository()
+        .update(UpdateTag {
+            tag_id,
+            name: req.name,
+            requested_by: user.id(),
+        })
+        .await
+        .map(|tag| Json(tag.into()))
+}
+
+/// Delete a tag (requires `items:write`)
+///
+/// Remove the tag from every item it is attached to. The items themselves are kept.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tags/{tag_id}",
+    params(
+        ("tag_id" = String, Path, description = "Tag ID"),
+    ),
+    responses(
+        (status = 200, description = "Tag deleted"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `items:write` permission required", body = ErrorResponse),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "tags"
+)]
+pub async fn delete_tag(
+    user: AuthorizedUser,
+    Path(tag_id): Path<TagId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    user.require(Permission::ItemsWrite)?;
+
+    registry
+        .tag_repository()
+        .delete(DeleteTag {
+            tag_id,
+            requested_by: user.id(),
+        })
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+/// Tag an item (requires `items:write`)
+///
+/// Attach a tag to an item. Attaching a tag the item already has does nothing.
+#[utoipa::path(
+    put,
+    path = "/api/v1/items/{item_id}/tags/{tag_id}",
+    params(
+        ("item_id" = String, Path, description = "Item ID"),
+        ("tag_id" = String, Path, description = "Tag ID"),
+    ),
+    responses(
+        (status = 200, description = "Tag attached"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `items:write` permission required", body = ErrorResponse),
+        (status = 404, description = "Item or tag not found", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "tags"
+)]
+pub async fn attach_tag(
+    user: AuthorizedUser,
+    Path((item_id, tag_id)): Path<(ItemId, TagId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    user.require(Permission::ItemsWrite)?;
+
+    registry
+        .tag_repository()
+        .attach(AttachTag {
+            item_id,
+            tag_id,
+            requested_by: user.id(),
+        })
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+/// Untag an item (requires `items:write`)
+///
+/// Detach a tag from an item. Detaching a tag the item does not have does nothing.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/items/{item_id}/tags/{tag_id}",
+    params(
+        ("item_id" = String, Path, description = "Item ID"),
+        ("tag_id" = String, Path, description = "Tag ID"),
+    ),
+    responses(
+        (status = 200, description = "Tag detached"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `items:write` permission required", body = ErrorResponse),
+        (status = 404, description = "Item or tag not found", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "tags"
+)]
+pub async fn detach_tag(
+    user: AuthorizedUser,
+    Path((item_id, tag_id)): Path<(ItemId, TagId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    user.require(Permission::ItemsWrite)?;
+
+    registry
+        .tag_repository()
+        .detach(DetachTag {
+            item_id,
+            tag_id,
+            requested_by: user.id(),
+        })
+        .await
+        .map(|_| StatusCode::OK)
+}
@@ -0,0 +1,171 @@
+use axum::{Json, extract::State, http::StatusCode};
+use garde::Validate;
+use kernel::model::two_factor::event::{ConfirmTotp, DisableTotp, SetUpTotp};
+use registry::AppRegistry;
+use shared::error::AppResult;
+use utoipa::OpenApi;
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::{
+        error::ErrorResponse,
+        two_factor::{
+            RecoveryCodesResponse, TotpSetupResponse, TwoFactorCodeRequest, TwoFactorStatusResponse,
+        },
+    },
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_two_factor_status,
+        set_up_two_factor,
+        confirm_two_factor,
+        disable_two_factor
+    ),
+    components(
+        schemas(
+            TwoFactorStatusResponse,
+            TotpSetupResponse,
+            TwoFactorCodeRequest,
+            RecoveryCodesResponse,
+            ErrorResponse
+        )
+    ),
+    tags(
+        (name = "two-factor", description = "TOTP two-factor authentication")
+    )
+)]
+pub struct ApiDoc;
+
+/// Get the two-factor authentication status
+///
+/// Whether the authenticated user signs in with an authenticator app, and how many recovery
+/// codes they have left
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/2fa",
+    responses(
+        (status = 200, description = "Success", body = TwoFactorStatusResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Requested with a personal access token", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "two-factor"
+)]
+pub async fn get_two_factor_status(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<TwoFactorStatusResponse>> {
+    user.require_session()?;
+
+    registry
+        .two_factor_repository()
+        .find_status(user.id())
+        .await
+        .map(TwoFactorStatusResponse::from)
+        .map(Json)
+}
+
+/// Set up two-factor authentication
+///
+/// Generate a new TOTP secret for the authenticator app. Two-factor authentication is only
+/// enabled once a code from it is confirmed; setting up again replaces an unconfirmed secret.
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/2fa/setup",
+    responses(
+        (status = 200, description = "Secret generated", body = TotpSetupResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Requested with a personal access token", body = ErrorResponse),
+        (status = 409, description = "Two-factor authentication is already enabled", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "two-factor"
+)]
+pub async fn set_up_two_factor(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<TotpSetupResponse>> {
+    user.require_session()?;
+
+    registry
+        .two_factor_repository()
+        .set_up(SetUpTotp { user_id: user.id() })
+        .await
+        .map(TotpSetupResponse::from)
+        .map(Json)
+}
+
+/// Confirm two-factor authentication
+///
+/// Enable two-factor authentication with a code from the authenticator app that was just set
+/// up, and receive recovery codes for when the app is unavailable
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/2fa/confirm",
+    request_body = TwoFactorCodeRequest,
+    responses(
+        (status = 200, description = "Two-factor authentication enabled; the recovery codes are only shown once", body = RecoveryCodesResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Requested with a personal access token", body = ErrorResponse),
+        (status = 409, description = "Two-factor authentication is already enabled", body = ErrorResponse),
+        (status = 422, description = "Not set up or incorrect code", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "two-factor"
+)]
+pub async fn confirm_two_factor(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<TwoFactorCodeRequest>,
+) -> AppResult<Json<RecoveryCodesResponse>> {
+    user.require_session()?;
+    req.validate()?;
+
+    let recovery_codes = registry
+        .two_factor_repository()
+        .confirm(ConfirmTotp {
+            user_id: user.id(),
+            code: req.code,
+        })
+        .await?;
+
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+/// Disable two-factor authentication
+///
+/// Remove the authenticator and recovery codes after checking a current code or a recovery code
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/2fa/disable",
+    request_body = TwoFactorCodeRequest,
+    responses(
+        (status = 200, description = "Two-factor authentication disabled"),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Requested with a personal access token, or required for the user's role", body = ErrorResponse),
+        (status = 422, description = "Not enabled or incorrect code", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "two-factor"
+)]
+pub async fn disable_two_factor(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<TwoFactorCodeRequest>,
+) -> AppResult<StatusCode> {
+    user.require_session()?;
+    req.validate()?;
+
+    registry
+        .two_factor_repository()
+        .disable(DisableTotp {
+            user_id: user.id(),
+            code: req.code,
+        })
+        .await
+        .map(|_| StatusCode::OK)
+}
@@ -1,23 +1,32 @@
 use crate::{
     extractor::AuthorizedUser,
+    handler::auth::issue_session_cookies,
     model::{
         checkout::CheckoutsResponse,
         error::ErrorResponse,
+        list::UserListQuery,
+        reservation::ReservationsResponse,
         user::{
-            CreateUserRequest, RoleName, UpdateUserEmailRequest, UpdateUserEmailRequestWithUserId,
-            UpdateUserNameRequest, UpdateUserNameRequestWithUserId, UpdateUserPasswordRequest,
+            CreateUserRequest, CreateUserRequestWithUserId, PaginatedUserResponse, RoleName,
+            UpdateUserEmailRequest, UpdateUserEmailRequestWithUserId, UpdateUserNameRequest,
+            UpdateUserNameRequestWithUserId, UpdateUserPasswordRequest,
             UpdateUserPasswordRequestWithUserId, UpdateUserRoleRequest,
-            UpdateUserRoleRequestWithUserId, UserResponse, UsersResponse,
+            UpdateUserRoleRequestWithUserId, UserResponse,
         },
     },
 };
 use axum::{
     Json,
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
 };
 use garde::Validate;
-use kernel::model::{id::UserId, user::event::DeleteUser};
+use kernel::model::{
+    id::UserId,
+    list::{SortOrder, UserSort},
+    role::Permission,
+    user::event::{DeactivateUser, DeleteUser, ReactivateUser, UnlockUser},
+};
 use registry::AppRegistry;
 use shared::error::{AppError, AppResult};
 use utoipa::OpenApi;
@@ -28,24 +37,32 @@ use utoipa::OpenApi;
         register_user,
         list_users,
         delete_user,
+        deactivate_user,
+        reactivate_user,
+        unlock_user,
         change_role,
         get_current_user,
         change_password,
         change_name,
         change_email,
-        get_checkouts
+        get_checkouts,
+        get_reservations
     ),
     components(
         schemas(
             UserResponse,
-            UsersResponse,
+            PaginatedUserResponse,
             CreateUserRequest,
             UpdateUserPasswordRequest,
             UpdateUserRoleRequest,
             UpdateUserNameRequest,
             UpdateUserEmailRequest,
             CheckoutsResponse,
+            ReservationsResponse,
             RoleName,
+            Permission,
+            UserSort,
+            SortOrder,
             ErrorResponse
         )
     ),
@@ -55,9 +72,9 @@ use utoipa::OpenApi;
 )]
 pub struct ApiDoc;
 
-/// Register a new user (Admin only)
+/// Register a new user (requires `users:manage`)
 ///
-/// Create a new user account. Only administrators can perform this operation.
+/// Create a new user account.
 #[utoipa::path(
     post,
     path = "/api/v1/users",
@@ -66,8 +83,9 @@ pub struct ApiDoc;
         (status = 200, description = "User created successfully", body = UserResponse),
         (status = 400, description = "Invalid request body", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
-        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `users:manage` permission required, or requested with a personal access token", body = ErrorResponse),
         (status = 409, description = "Email already exists", body = ErrorResponse),
+        (status = 422, description = "The password does not meet the password policy", body = ErrorResponse),
     ),
     security(("jwt" = [])),
     tag = "users"
@@ -77,56 +95,66 @@ pub async fn register_user(
     State(registry): State<AppRegistry>,
     Json(req): Json<CreateUserRequest>,
 ) -> AppResult<Json<UserResponse>> {
-    if !user.is_admin() {
-        return Err(AppError::ForbiddenOperation(
-            "Admin access required.".into(),
-        ));
-    }
+    user.require_session()?;
+    user.require(Permission::UsersManage)?;
     req.validate()?;
 
-    let registered_user = registry.user_repository().create(req.into()).await?;
+    let registered_user = registry
+        .user_repository()
+        .create(CreateUserRequestWithUserId::new(user.id(), req).into())
+        .await?;
 
     Ok(Json(registered_user.into()))
 }
 
-/// Get a list of all users
+/// List users (requires `users:manage`)
 ///
-/// Retrieve a list of all registered users
+/// Retrieve a paginated list of registered users, optionally filtered by name, email or role
 #[utoipa::path(
     get,
     path = "/api/v1/users",
+    params(
+        ("limit" = i64, Query, description = "Number of users to return"),
+        ("offset" = i64, Query, description = "Number of users to skip"),
+        ("cursor" = Option<String>, Query, description = "Cursor from a previous page; replaces offset"),
+        ("q" = Option<String>, Query, description = "Search by name or email"),
+        ("role" = Option<RoleName>, Query, description = "Only users with this role"),
+        ("sort" = Option<UserSort>, Query, description = "Column to sort by"),
+        ("active" = Option<bool>, Query, description = "Only active (true) or deactivated (false) users"),
+        ("order" = Option<SortOrder>, Query, description = "Sort direction"),
+    ),
     responses(
-        (status = 200, description = "Success", body = UsersResponse),
+        (status = 200, description = "Success", body = PaginatedUserResponse),
+        (status = 400, description = "Invalid query", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
-        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `users:manage` permission required, or requested with a personal access token", body = ErrorResponse),
     ),
     security(("jwt" = [])),
     tag = "users"
 )]
 pub async fn list_users(
     user: AuthorizedUser,
+    Query(query): Query<UserListQuery>,
     State(registry): State<AppRegistry>,
-) -> AppResult<Json<UsersResponse>> {
-    if !user.is_admin() {
-        return Err(AppError::ForbiddenOperation(
-            "Admin access required.".into(),
-        ));
-    }
+) -> AppResult<Json<PaginatedUserResponse>> {
+    user.require_session()?;
+    user.require(Permission::UsersManage)?;
 
-    let items = registry
-        .user_repository()
-        .find_all()
-        .await?
-        .into_iter()
-        .map(UserResponse::from)
-        .collect();
+    query.validate()?;
+    let options = query.try_into()?;
 
-    Ok(Json(UsersResponse { items }))
+    registry
+        .user_repository()
+        .find_all(options)
+        .await
+        .map(PaginatedUserResponse::from)
+        .map(Json)
 }
 
-/// Delete a user account (Admin only)
+/// Delete a user account (requires `users:manage`)
 ///
-/// Delete an existing user account. Only administrators can perform this operation.
+/// Delete an existing user account.
+/// Users with checkout history cannot be deleted and should be deactivated instead.
 #[utoipa::path(
     delete,
     path = "/api/v1/users/{user_id}",
@@ -136,8 +164,9 @@ pub async fn list_users(
     responses(
         (status = 200, description = "User deleted successfully"),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
-        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `users:manage` permission required, or requested with a personal access token", body = ErrorResponse),
         (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 409, description = "User has checkout history", body = ErrorResponse),
     ),
     security(("jwt" = [])),
     tag = "users"
@@ -147,22 +176,143 @@ pub async fn delete_user(
     Path(user_id): Path<UserId>,
     State(registry): State<AppRegistry>,
 ) -> AppResult<StatusCode> {
-    if !user.is_admin() {
+    user.require_session()?;
+    user.require(Permission::UsersManage)?;
+
+    registry
+        .user_repository()
+        .delete(DeleteUser {
+            user_id,
+            requested_by: user.id(),
+        })
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+/// Deactivate a user account (requires `users:manage`)
+///
+/// Block the user from signing in and sign them out everywhere while keeping their checkout
+/// history. Their reservations are cancelled.
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/{user_id}/deactivate",
+    params(
+        ("user_id" = String, Path, description = "User ID"),
+    ),
+    responses(
+        (status = 200, description = "User deactivated successfully"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `users:manage` permission required, or requested with a personal access token", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 409, description = "User is already deactivated or still has items checked out", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "users"
+)]
+pub async fn deactivate_user(
+    user: AuthorizedUser,
+    Path(user_id): Path<UserId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    user.require_session()?;
+    user.require(Permission::UsersManage)?;
+    if user_id == user.id() {
         return Err(AppError::ForbiddenOperation(
-            "Admin access required.".into(),
+            "You cannot deactivate your own account.".into(),
         ));
     }
 
     registry
         .user_repository()
-        .delete(DeleteUser { user_id })
+        .deactivate(DeactivateUser {
+            user_id,
+            requested_by: user.id(),
+        })
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+/// Reactivate a user account (requires `users:manage`)
+///
+/// Allow a deactivated user to sign in again
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/{user_id}/reactivate",
+    params(
+        ("user_id" = String, Path, description = "User ID"),
+    ),
+    responses(
+        (status = 200, description = "User reactivated successfully"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `users:manage` permission required, or requested with a personal access token", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 409, description = "User is not deactivated", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "users"
+)]
+pub async fn reactivate_user(
+    user: AuthorizedUser,
+    Path(user_id): Path<UserId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    user.require_session()?;
+    user.require(Permission::UsersManage)?;
+
+    registry
+        .user_repository()
+        .reactivate(ReactivateUser {
+            user_id,
+            requested_by: user.id(),
+        })
         .await?;
 
     Ok(StatusCode::OK)
 }
-/// Change user role (Admin only)
+
+/// Unlock a user account (requires `users:manage`)
 ///
-/// Update the role of an existing user. Only administrators can perform this operation.
+/// Lift a lockout caused by repeated failed logins against the user's email address
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/{user_id}/unlock",
+    params(
+        ("user_id" = String, Path, description = "User ID"),
+    ),
+    responses(
+        (status = 200, description = "User unlocked successfully"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `users:manage` permission required, or requested with a personal access token", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 409, description = "User has no failed login attempts", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "users"
+)]
+pub async fn unlock_user(
+    user: AuthorizedUser,
+    Path(user_id): Path<UserId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    user.require_session()?;
+    user.require(Permission::UsersManage)?;
+
+    registry
+        .user_repository()
+        .unlock(UnlockUser {
+            user_id,
+            requested_by: user.id(),
+        })
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+/// Change user role (requires `users:manage`)
+///
+/// Update the role of an existing user.
 #[utoipa::path(
     put,
     path = "/api/v1/users/{user_id}/role",
@@ -174,7 +324,7 @@ pub async fn delete_user(
         (status = 200, description = "Role updated successfully"),
         (status = 400, description = "Invalid request body", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
-        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 403, description = "Forbidden - `users:manage` permission required, or requested with a personal access token", body = ErrorResponse),
         (status = 404, description = "User not found", body = ErrorResponse),
     ),
     security(("jwt" = [])),
@@ -186,15 +336,12 @@ pub async fn change_role(
     State(registry): State<AppRegistry>,
     Json(req): Json<UpdateUserRoleRequest>,
 ) -> AppResult<StatusCode> {
-    if !user.is_admin() {
-        return Err(AppError::ForbiddenOperation(
-            "Admin access required.".into(),
-        ));
-    }
+    user.require_session()?;
+    user.require(Permission::UsersManage)?;
 
     registry
         .user_repository()
-        .update_role(UpdateUserRoleRequestWithUserId::new(user_id, req).into())
+        .update_role(UpdateUserRoleRequestWithUserId::new(user_id, req, user.id()).into())
         .await?;
 
     Ok(StatusCode::OK)
@@ -219,7 +366,8 @@ pub async fn get_current_user(user: AuthorizedUser) -> Json<UserResponse> {
 
 /// Change user password
 ///
-/// Update the authenticated user's password
+/// Update the authenticated user's password. Every other session of the user is signed out and
+/// a fresh session is issued for the current one.
 #[utoipa::path(
     put,
     path = "/api/v1/users/me/password",
@@ -228,7 +376,8 @@ pub async fn get_current_user(user: AuthorizedUser) -> Json<UserResponse> {
         (status = 200, description = "Password updated successfully"),
         (status = 400, description = "Invalid request body", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
-        (status = 403, description = "Invalid current password", body = ErrorResponse),
+        (status = 403, description = "Invalid current password, or requested with a personal access token", body = ErrorResponse),
+        (status = 422, description = "The new password does not meet the password policy or was used recently", body = ErrorResponse),
     ),
     security(("jwt" = [])),
     tag = "users"
@@ -237,7 +386,8 @@ pub async fn change_password(
     user: AuthorizedUser,
     State(registry): State<AppRegistry>,
     Json(req): Json<UpdateUserPasswordRequest>,
-) -> AppResult<StatusCode> {
+) -> AppResult<(HeaderMap, StatusCode)> {
+    user.require_session()?;
     req.validate()?;
 
     registry
@@ -245,7 +395,9 @@ pub async fn change_password(
         .update_password(UpdateUserPasswordRequestWithUserId::new(user.id(), req).into())
         .await?;
 
-    Ok(StatusCode::OK)
+    let headers = issue_session_cookies(&registry, user.id()).await?;
+
+    Ok((headers, StatusCode::OK))
 }
 
 /// Change user name
@@ -259,6 +411,7 @@ pub async fn change_password(
         (status = 200, description = "Name updated successfully"),
         (status = 400, description = "Invalid request body", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Requested with a personal access token", body = ErrorResponse),
     ),
     security(("jwt" = [])),
     tag = "users"
@@ -268,6 +421,7 @@ pub async fn change_name(
     State(registry): State<AppRegistry>,
     Json(req): Json<UpdateUserNameRequest>,
 ) -> AppResult<StatusCode> {
+    user.require_session()?;
     req.validate()?;
 
     registry
@@ -289,6 +443,7 @@ pub async fn change_name(
         (status = 200, description = "Email updated successfully"),
         (status = 400, description = "Invalid request body", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Requested with a personal access token", body = ErrorResponse),
         (status = 409, description = "Email already exists", body = ErrorResponse),
     ),
     security(("jwt" = [])),
@@ -299,6 +454,7 @@ pub async fn change_email(
     State(registry): State<AppRegistry>,
     Json(req): Json<UpdateUserEmailRequest>,
 ) -> AppResult<StatusCode> {
+    user.require_session()?;
     req.validate()?;
 
     registry
@@ -333,3 +489,28 @@ pub async fn get_checkouts(
         .map(CheckoutsResponse::from)
         .map(Json)
 }
+
+/// Get user's reservations
+///
+/// Retrieve the reservations of the authenticated user with their position in each item's queue
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/reservations",
+    responses(
+        (status = 200, description = "Success", body = ReservationsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(("jwt" = [])),
+    tag = "users"
+)]
+pub async fn get_reservations(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<ReservationsResponse>> {
+    registry
+        .reservation_repository()
+        .find_by_user_id(user.id())
+        .await
+        .map(ReservationsResponse::from)
+        .map(Json)
+}
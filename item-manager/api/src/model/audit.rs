@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use kernel::model::{
+    audit::{AuditAction, AuditLog, AuditTargetType},
+    id::{AuditLogId, UserId},
+    list::PaginatedList,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedAuditLogResponse {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub items: Vec<AuditLogResponse>,
+}
+
+impl From<PaginatedList<AuditLog>> for PaginatedAuditLogResponse {
+    fn from(value: PaginatedList<AuditLog>) -> Self {
+        let PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+            ..
+        } = value;
+        Self {
+            total,
+            limit,
+            offset,
+            items: items.into_iter().map(AuditLogResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogResponse {
+    pub id: AuditLogId,
+    /// User who made the change; `null` if they no longer exist
+    pub actor_id: Option<UserId>,
+    pub action: AuditAction,
+    pub target_type: AuditTargetType,
+    #[schema(value_type = String, format = "uuid")]
+    pub target_id: Uuid,
+    /// State of the target before the change; `null` for creations
+    #[schema(value_type = Option<Object>)]
+    pub before: Option<serde_json::Value>,
+    /// State of the target after the change; `null` for deletions
+    #[schema(value_type = Option<Object>)]
+    pub after: Option<serde_json::Value>,
+    #[schema(value_type = String, format = "date-time", example = "2024-04-10T13:15:00Z")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AuditLog> for AuditLogResponse {
+    fn from(value: AuditLog) -> Self {
+        let AuditLog {
+            id,
+            actor_id,
+            action,
+            target_type,
+            target_id,
+            before,
+            after,
+            created_at,
+        } = value;
+        Self {
+            id,
+            actor_id,
+            action,
+            target_type,
+            target_id,
+            before,
+            after,
+            created_at,
+        }
+    }
+}
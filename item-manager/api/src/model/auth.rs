@@ -1,4 +1,11 @@
-use kernel::model::id::UserId;
+use chrono::{DateTime, Utc};
+use garde::Validate;
+use kernel::model::{
+    id::UserId,
+    password_reset::event::{ConfirmPasswordReset, RequestPasswordReset},
+    two_factor::{LoginChallenge, event::CompleteLoginChallenge},
+    user::event::CreateUser,
+};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -7,10 +14,142 @@ use utoipa::ToSchema;
 pub struct LoginRequest {
     pub email: String,
     pub password: String,
+    /// Also return the access token in the response body, for clients that cannot use cookies.
+    #[serde(default)]
+    pub include_token: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshQuery {
+    /// Also return the new access token in the response body, for clients that cannot use cookies.
+    #[serde(default)]
+    pub include_token: bool,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LoginResponse {
     pub user_id: UserId,
+    /// Only present when `includeToken` was set on login or refresh.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_token: Option<String>,
+    /// Only present when the login set up two-factor authentication; they are shown this once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recovery_codes: Option<Vec<String>>,
+}
+
+/// Returned instead of a session when the account needs a second factor.
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginChallengeResponse {
+    pub challenge_token: String,
+    #[schema(value_type = String, format = "date-time")]
+    pub expires_at: DateTime<Utc>,
+    /// The account must set up an authenticator with `/auth/login/2fa/setup` first.
+    pub setup_required: bool,
+}
+
+impl From<LoginChallenge> for LoginChallengeResponse {
+    fn from(value: LoginChallenge) -> Self {
+        Self {
+            challenge_token: value.token,
+            expires_at: value.expires_at,
+            setup_required: value.setup_required,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompleteLoginRequest {
+    #[garde(length(min = 1))]
+    pub challenge_token: String,
+    /// Code from the authenticator app, or a recovery code unless the login sets up the app
+    #[garde(length(min = 1, max = 64))]
+    #[schema(max_length = 64)]
+    pub code: String,
+    /// Also return the access token in the response body, for clients that cannot use cookies.
+    #[garde(skip)]
+    #[serde(default)]
+    pub include_token: bool,
+}
+
+impl From<CompleteLoginRequest> for CompleteLoginChallenge {
+    fn from(value: CompleteLoginRequest) -> Self {
+        Self {
+            token: value.challenge_token,
+            code: value.code,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginSetupRequest {
+    #[garde(length(min = 1))]
+    pub challenge_token: String,
+}
+
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordResetRequest {
+    #[garde(email)]
+    #[garde(length(max = 255))]
+    #[schema(max_length = 255)]
+    pub email: String,
+}
+
+impl From<PasswordResetRequest> for RequestPasswordReset {
+    fn from(value: PasswordResetRequest) -> Self {
+        Self { email: value.email }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmPasswordResetRequest {
+    /// The token from the reset link.
+    #[garde(length(min = 1))]
+    pub token: String,
+    #[garde(length(min = 1))]
+    pub new_password: String,
+}
+
+impl From<ConfirmPasswordResetRequest> for ConfirmPasswordReset {
+    fn from(value: ConfirmPasswordResetRequest) -> Self {
+        Self {
+            token: value.token,
+            new_password: value.new_password,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SignupRequest {
+    /// Code from an invitation created by an administrator
+    #[garde(length(min = 1))]
+    pub invitation_code: String,
+    #[garde(length(min = 1, max = 255))]
+    #[schema(max_length = 255)]
+    pub name: String,
+    #[garde(email)]
+    #[garde(length(max = 255))]
+    #[schema(max_length = 255)]
+    pub email: String,
+    #[garde(length(min = 1))]
+    pub password: String,
+}
+
+impl From<SignupRequest> for CreateUser {
+    fn from(value: SignupRequest) -> Self {
+        Self {
+            name: value.name,
+            email: value.email,
+            password: value.password,
+            requested_by: None,
+            invitation_code: Some(value.invitation_code),
+        }
+    }
 }
@@ -0,0 +1,103 @@
+use garde::Validate;
+use kernel::model::{
+    category::{AttributeDefinition, CustomCategory, CustomCategoryRef},
+    id::CustomCategoryId,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCustomCategoryRequest {
+    /// Identifies the category in item requests, imports and `custom_category=`; cannot be changed later
+    #[garde(length(min = 1, max = 64), custom(validate_category_key))]
+    #[schema(max_length = 64, example = "monitor")]
+    pub key: String,
+    #[garde(length(min = 1, max = 255))]
+    #[schema(max_length = 255, example = "Monitor")]
+    pub name: String,
+    #[garde(length(max = 64))]
+    #[schema(max_items = 64)]
+    pub attributes: Vec<AttributeDefinition>,
+}
+
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCustomCategoryRequest {
+    #[garde(length(min = 1, max = 255))]
+    #[schema(max_length = 255, example = "Monitor")]
+    pub name: String,
+    /// Replaces every definition; existing items must still match them
+    #[garde(length(max = 64))]
+    #[schema(max_items = 64)]
+    pub attributes: Vec<AttributeDefinition>,
+}
+
+fn validate_category_key(value: &str, _context: &()) -> garde::Result {
+    let well_formed = value.starts_with(|c: char| c.is_ascii_lowercase())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !well_formed {
+        return Err(garde::Error::new(
+            "must be lowercase letters, digits and underscores, starting with a letter",
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomCategoryResponse {
+    pub id: CustomCategoryId,
+    pub key: String,
+    pub name: String,
+    pub attributes: Vec<AttributeDefinition>,
+}
+
+impl From<CustomCategory> for CustomCategoryResponse {
+    fn from(value: CustomCategory) -> Self {
+        Self {
+            id: value.id,
+            key: value.key,
+            name: value.name,
+            attributes: value.attributes,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomCategoriesResponse {
+    pub items: Vec<CustomCategoryResponse>,
+}
+
+impl From<Vec<CustomCategory>> for CustomCategoriesResponse {
+    fn from(value: Vec<CustomCategory>) -> Self {
+        Self {
+            items: value
+                .into_iter()
+                .map(CustomCategoryResponse::from)
+                .collect(),
+        }
+    }
+}
+
+/// The category of a custom item
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomCategoryRefResponse {
+    pub id: CustomCategoryId,
+    pub key: String,
+    pub name: String,
+}
+
+impl From<CustomCategoryRef> for CustomCategoryRefResponse {
+    fn from(value: CustomCategoryRef) -> Self {
+        Self {
+            id: value.id,
+            key: value.key,
+            name: value.name,
+        }
+    }
+}
@@ -1,15 +1,27 @@
 use chrono::{DateTime, Utc};
 use kernel::model::{
-    checkout::Checkout,
+    checkout::{Checkout, CheckoutRenewal},
     id::{CheckoutId, ItemId, UserId},
+    list::PaginatedList,
 };
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use super::list::encode_cursor;
+
 #[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateCheckoutRequest {
     pub checked_out_by: Option<UserId>,
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RenewCheckoutRequest {
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub due_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -18,6 +30,32 @@ pub struct CheckoutsResponse {
     pub items: Vec<CheckoutResponse>,
 }
 
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutHistoryResponse {
+    pub total: i64,
+    pub items: Vec<CheckoutResponse>,
+    /// Cursor for older checkouts, if any
+    pub next_cursor: Option<String>,
+    /// Cursor for newer checkouts, if any
+    pub prev_cursor: Option<String>,
+}
+
+impl From<PaginatedList<Checkout>> for CheckoutHistoryResponse {
+    fn from(value: PaginatedList<Checkout>) -> Self {
+        Self {
+            total: value.total,
+            items: value
+                .items
+                .into_iter()
+                .map(CheckoutResponse::from)
+                .collect(),
+            next_cursor: value.next_cursor.as_ref().map(encode_cursor),
+            prev_cursor: value.prev_cursor.as_ref().map(encode_cursor),
+        }
+    }
+}
+
 impl From<Vec<Checkout>> for CheckoutsResponse {
     fn from(value: Vec<Checkout>) -> Self {
         Self {
@@ -33,19 +71,53 @@ pub struct CheckoutResponse {
     pub checked_out_by: UserId,
     #[schema(value_type = String, format = "date-time", example = "2024-04-10T13:15:00Z")]
     pub checked_out_at: DateTime<Utc>,
+    #[schema(value_type = String, format = "date-time", example = "2024-04-24T13:15:00Z")]
+    pub due_at: DateTime<Utc>,
+    pub is_overdue: bool,
     #[schema(value_type = String, format = "date-time")]
     pub returned_at: Option<DateTime<Utc>>,
     pub item_id: ItemId,
+    pub renewals: Vec<CheckoutRenewalResponse>,
 }
 
 impl From<Checkout> for CheckoutResponse {
     fn from(value: Checkout) -> Self {
         Self {
+            is_overdue: value.is_overdue(Utc::now()),
             id: value.id,
             checked_out_by: value.checked_out_by,
             checked_out_at: value.checked_out_at,
+            due_at: value.due_at,
             returned_at: value.returned_at,
             item_id: value.item_id,
+            renewals: value
+                .renewals
+                .into_iter()
+                .map(CheckoutRenewalResponse::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutRenewalResponse {
+    pub renewed_by: UserId,
+    #[schema(value_type = String, format = "date-time", example = "2024-04-20T13:15:00Z")]
+    pub renewed_at: DateTime<Utc>,
+    #[schema(value_type = String, format = "date-time", example = "2024-04-24T13:15:00Z")]
+    pub previous_due_at: DateTime<Utc>,
+    #[schema(value_type = String, format = "date-time", example = "2024-05-08T13:15:00Z")]
+    pub due_at: DateTime<Utc>,
+}
+
+impl From<CheckoutRenewal> for CheckoutRenewalResponse {
+    fn from(value: CheckoutRenewal) -> Self {
+        Self {
+            renewed_by: value.renewed_by,
+            renewed_at: value.renewed_at,
+            previous_due_at: value.previous_due_at,
+            due_at: value.due_at,
         }
     }
 }
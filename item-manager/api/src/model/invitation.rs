@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use garde::Validate;
+use kernel::model::{
+    id::{InvitationId, UserId},
+    invitation::{CreatedInvitation, Invitation},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::user::RoleName;
+
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateInvitationRequest {
+    /// Role given to users who sign up with the code
+    #[garde(skip)]
+    pub role: RoleName,
+    #[garde(range(min = 1, max = 1000))]
+    #[schema(minimum = 1, maximum = 1000)]
+    pub max_uses: u32,
+    /// At most 90 days from now
+    #[garde(skip)]
+    #[schema(value_type = String, format = "date-time")]
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InvitationsResponse {
+    pub items: Vec<InvitationResponse>,
+}
+
+impl From<Vec<Invitation>> for InvitationsResponse {
+    fn from(value: Vec<Invitation>) -> Self {
+        Self {
+            items: value.into_iter().map(InvitationResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InvitationResponse {
+    pub id: InvitationId,
+    pub role: RoleName,
+    pub max_uses: u32,
+    pub uses: u32,
+    #[schema(value_type = String, format = "date-time")]
+    pub expires_at: DateTime<Utc>,
+    pub created_by: Option<UserId>,
+    #[schema(value_type = String, format = "date-time")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Invitation> for InvitationResponse {
+    fn from(value: Invitation) -> Self {
+        Self {
+            id: value.id,
+            role: value.role.into(),
+            max_uses: value.max_uses,
+            uses: value.uses,
+            expires_at: value.expires_at,
+            created_by: value.created_by,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedInvitationResponse {
+    /// Code to hand out for `POST /auth/signup`; it is only shown once
+    pub code: String,
+    pub invitation: InvitationResponse,
+}
+
+impl From<CreatedInvitation> for CreatedInvitationResponse {
+    fn from(value: CreatedInvitation) -> Self {
+        Self {
+            code: value.code,
+            invitation: value.invitation.into(),
+        }
+    }
+}
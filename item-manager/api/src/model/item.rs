@@ -1,15 +1,19 @@
 use garde::Validate;
 use kernel::model::{
     checkout::SimpleCheckout,
-    id::{CheckoutId, ItemId},
-    item::{CreateItem, Item, UpdateItem},
+    id::{CheckoutId, ItemId, UserId},
+    item::{CreateItem, Item, ItemArchive, ItemCategory, UpdateItem},
     list::PaginatedList,
 };
 use mac_address::MacAddress;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use shared::error::{AppError, AppResult};
 use utoipa::ToSchema;
 
-use super::user::CheckoutUser;
+use super::{
+    category::CustomCategoryRefResponse, list::encode_cursor, tag::TagResponse, user::CheckoutUser,
+};
 
 // Create Request types
 
@@ -64,16 +68,39 @@ pub enum CreateItemRequest {
         #[schema(max_length = 255, nullable = true)]
         location: Option<String>,
     },
+    #[serde(rename = "custom")]
+    Custom {
+        #[garde(length(min = 1, max = 255))]
+        #[schema(max_length = 255)]
+        name: String,
+        /// Key of an admin-defined category
+        #[garde(length(min = 1, max = 64))]
+        #[schema(max_length = 64, example = "monitor")]
+        custom_category: String,
+        /// Values keyed by attribute key, checked against the category's definitions
+        #[serde(default)]
+        #[garde(skip)]
+        #[schema(value_type = Object)]
+        attributes: Map<String, Value>,
+        #[garde(length(max = 1024))]
+        #[schema(max_length = 1024)]
+        description: String,
+        #[serde(default)]
+        #[garde(length(max = 255))]
+        #[schema(max_length = 255, nullable = true)]
+        location: Option<String>,
+    },
 }
 
-impl From<CreateItemRequest> for CreateItem {
-    fn from(value: CreateItemRequest) -> Self {
-        match value {
+impl CreateItemRequest {
+    pub fn into_create_item(self, requested_by: UserId) -> CreateItem {
+        match self {
             CreateItemRequest::General {
                 name,
                 description,
                 location,
             } => CreateItem::General {
+                requested_by,
                 name,
                 description,
                 location: normalize_location(location),
@@ -85,6 +112,7 @@ impl From<CreateItemRequest> for CreateItem {
                 description,
                 location,
             } => CreateItem::Book {
+                requested_by,
                 name,
                 author,
                 isbn,
@@ -97,15 +125,355 @@ impl From<CreateItemRequest> for CreateItem {
                 description,
                 location,
             } => CreateItem::Laptop {
+                requested_by,
                 name,
                 mac_address,
                 description,
                 location: normalize_location(location),
             },
+            CreateItemRequest::Custom {
+                name,
+                custom_category,
+                attributes,
+                description,
+                location,
+            } => CreateItem::Custom {
+                requested_by,
+                name,
+                custom_category,
+                attributes,
+                description,
+                location: normalize_location(location),
+            },
+        }
+    }
+}
+
+// Import types
+
+/// One CSV row of a bulk import. Columns that do not apply to the row's
+/// category are ignored.
+#[derive(Debug, Deserialize)]
+struct ItemCsvRecord {
+    category: ItemCategory,
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    location: Option<String>,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    isbn: Option<String>,
+    #[serde(default)]
+    mac_address: Option<String>,
+    #[serde(default)]
+    custom_category: Option<String>,
+    /// A JSON object of attribute values
+    #[serde(default)]
+    attributes: Option<String>,
+}
+
+impl TryFrom<ItemCsvRecord> for CreateItemRequest {
+    type Error = String;
+
+    fn try_from(value: ItemCsvRecord) -> Result<Self, Self::Error> {
+        let ItemCsvRecord {
+            category,
+            name,
+            description,
+            location,
+            author,
+            isbn,
+            mac_address,
+            custom_category,
+            attributes,
+        } = value;
+        let description = description.unwrap_or_default();
+
+        Ok(match category {
+            ItemCategory::General => CreateItemRequest::General {
+                name,
+                description,
+                location,
+            },
+            ItemCategory::Book => CreateItemRequest::Book {
+                name,
+                author: author.unwrap_or_default(),
+                isbn: isbn.unwrap_or_default(),
+                description,
+                location,
+            },
+            ItemCategory::Laptop => {
+                let mac_address = mac_address
+                    .ok_or("mac_address: required for laptops")?
+                    .parse::<MacAddress>()
+                    .map_err(|e| format!("mac_address: {e}"))?;
+                CreateItemRequest::Laptop {
+                    name,
+                    mac_address,
+                    description,
+                    location,
+                }
+            }
+            ItemCategory::Custom => {
+                let custom_category =
+                    custom_category.ok_or("custom_category: required for custom items")?;
+                let attributes = match attributes.as_deref() {
+                    None | Some("") => Map::new(),
+                    Some(json) => {
+                        serde_json::from_str(json).map_err(|e| format!("attributes: {e}"))?
+                    }
+                };
+                CreateItemRequest::Custom {
+                    name,
+                    custom_category,
+                    attributes,
+                    description,
+                    location,
+                }
+            }
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportItemsResponse {
+    /// Number of items created
+    pub imported: usize,
+    /// Rows that failed validation; nothing is imported when this is not empty
+    pub errors: Vec<ImportRowError>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRowError {
+    /// Line number in the CSV, counting the header as line 1
+    pub line: u64,
+    pub message: String,
+}
+
+/// Parses and validates every row of an item CSV. Returns the requests when all
+/// rows are valid and the errors of every invalid row otherwise.
+pub fn parse_item_csv(csv: &str) -> Result<Vec<CreateItemRequest>, Vec<ImportRowError>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(csv.as_bytes());
+    let headers = reader.headers().cloned().map_err(|e| {
+        vec![ImportRowError {
+            line: 1,
+            message: e.to_string(),
+        }]
+    })?;
+
+    let mut requests = Vec::new();
+    let mut errors = Vec::new();
+    for record in reader.records() {
+        let parsed = record.map_err(|e| (e.position().map(|p| p.line()), e.to_string()));
+        let result = parsed.and_then(|record| {
+            let line = record.position().map(|p| p.line());
+            record
+                .deserialize::<ItemCsvRecord>(Some(&headers))
+                .map_err(|e| e.to_string())
+                .and_then(CreateItemRequest::try_from)
+                .and_then(|req| req.validate().map(|_| req).map_err(|e| e.to_string()))
+                .map_err(|message| (line, message))
+        });
+        match result {
+            Ok(req) => requests.push(req),
+            Err((line, message)) => errors.push(ImportRowError {
+                line: line.unwrap_or_default(),
+                message,
+            }),
         }
     }
+
+    if errors.is_empty() {
+        Ok(requests)
+    } else {
+        Err(errors)
+    }
 }
 
+// Export types
+
+#[derive(Debug, Default, Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Jsonl,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// One item of an inventory export with every variant's fields side by side.
+/// The column names match the import CSV, so an export can be imported again.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemExportRecord {
+    pub id: ItemId,
+    pub category: ItemCategory,
+    pub name: String,
+    pub description: String,
+    pub location: Option<String>,
+    pub author: Option<String>,
+    pub isbn: Option<String>,
+    pub mac_address: Option<String>,
+    pub custom_category: Option<String>,
+    /// The attribute values as a JSON object
+    pub attributes: Option<String>,
+    pub checked_out_by_id: Option<UserId>,
+    pub checked_out_by_name: Option<String>,
+    pub checked_out_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub due_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub archive_reason: Option<String>,
+}
+
+impl From<Item> for ItemExportRecord {
+    fn from(value: Item) -> Self {
+        let (id, category, name, description, location, checkout, archive) = match &value {
+            Item::General(i) => (
+                i.id,
+                ItemCategory::General,
+                &i.name,
+                &i.description,
+                &i.location,
+                &i.checkout,
+                &i.archive,
+            ),
+            Item::Book(b) => (
+                b.id,
+                ItemCategory::Book,
+                &b.name,
+                &b.description,
+                &b.location,
+                &b.checkout,
+                &b.archive,
+            ),
+            Item::Laptop(l) => (
+                l.id,
+                ItemCategory::Laptop,
+                &l.name,
+                &l.description,
+                &l.location,
+                &l.checkout,
+                &l.archive,
+            ),
+            Item::Custom(c) => (
+                c.id,
+                ItemCategory::Custom,
+                &c.name,
+                &c.description,
+                &c.location,
+                &c.checkout,
+                &c.archive,
+            ),
+        };
+        let (author, isbn, mac_address) = match &value {
+            Item::General(_) | Item::Custom(_) => (None, None, None),
+            Item::Book(b) => (Some(b.author.clone()), Some(b.isbn.clone()), None),
+            Item::Laptop(l) => (None, None, Some(l.mac_address.to_string())),
+        };
+        let (custom_category, attributes) = match &value {
+            Item::Custom(c) => (
+                Some(c.category.key.clone()),
+                Some(Value::Object(c.attributes.clone()).to_string()),
+            ),
+            _ => (None, None),
+        };
+        Self {
+            id,
+            category,
+            name: name.clone(),
+            description: description.clone(),
+            location: location.clone(),
+            author,
+            isbn,
+            mac_address,
+            custom_category,
+            attributes,
+            checked_out_by_id: checkout.as_ref().map(|c| c.checked_out_by.id),
+            checked_out_by_name: checkout.as_ref().map(|c| c.checked_out_by.name.clone()),
+            checked_out_at: checkout.as_ref().map(|c| c.checked_out_at),
+            due_at: checkout.as_ref().map(|c| c.due_at),
+            archived_at: archive.as_ref().map(|a| a.archived_at),
+            archive_reason: archive.as_ref().and_then(|a| a.reason.clone()),
+        }
+    }
+}
+
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Jsonl => "application/jsonl",
+        }
+    }
+
+    pub fn file_extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Jsonl => "jsonl",
+        }
+    }
+
+    /// Lines written before the first record.
+    pub fn header(self) -> Vec<u8> {
+        match self {
+            Self::Csv => format!("{}\n", CSV_EXPORT_COLUMNS.join(",")).into_bytes(),
+            Self::Jsonl => Vec::new(),
+        }
+    }
+
+    /// Encodes one record as a complete line.
+    pub fn encode(self, record: &ItemExportRecord) -> AppResult<Vec<u8>> {
+        match self {
+            Self::Csv => {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(false)
+                    .from_writer(Vec::new());
+                writer
+                    .serialize(record)
+                    .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+                writer
+                    .into_inner()
+                    .map_err(|e| AppError::ConversionEntityError(e.to_string()))
+            }
+            Self::Jsonl => {
+                let mut line = serde_json::to_vec(record)
+                    .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+                line.push(b'\n');
+                Ok(line)
+            }
+        }
+    }
+}
+
+const CSV_EXPORT_COLUMNS: [&str; 16] = [
+    "id",
+    "category",
+    "name",
+    "description",
+    "location",
+    "author",
+    "isbn",
+    "mac_address",
+    "custom_category",
+    "attributes",
+    "checked_out_by_id",
+    "checked_out_by_name",
+    "checked_out_at",
+    "due_at",
+    "archived_at",
+    "archive_reason",
+];
+
 // Update Request types
 
 #[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
@@ -159,10 +527,32 @@ pub enum UpdateItemRequest {
         #[schema(max_length = 255, nullable = true)]
         location: Option<String>,
     },
+    #[serde(rename = "custom")]
+    Custom {
+        #[garde(length(min = 1, max = 255))]
+        #[schema(max_length = 255)]
+        name: String,
+        /// Key of an admin-defined category
+        #[garde(length(min = 1, max = 64))]
+        #[schema(max_length = 64, example = "monitor")]
+        custom_category: String,
+        /// Values keyed by attribute key, checked against the category's definitions
+        #[serde(default)]
+        #[garde(skip)]
+        #[schema(value_type = Object)]
+        attributes: Map<String, Value>,
+        #[garde(length(max = 1024))]
+        #[schema(max_length = 1024)]
+        description: String,
+        #[serde(default)]
+        #[garde(length(max = 255))]
+        #[schema(max_length = 255, nullable = true)]
+        location: Option<String>,
+    },
 }
 
 impl UpdateItemRequest {
-    pub fn into_update_item(self, item_id: ItemId) -> UpdateItem {
+    pub fn into_update_item(self, item_id: ItemId, requested_by: UserId) -> UpdateItem {
         match self {
             UpdateItemRequest::General {
                 name,
@@ -170,6 +560,7 @@ impl UpdateItemRequest {
                 location,
             } => UpdateItem::General {
                 item_id,
+                requested_by,
                 name,
                 description,
                 location: normalize_location(location),
@@ -182,6 +573,7 @@ impl UpdateItemRequest {
                 location,
             } => UpdateItem::Book {
                 item_id,
+                requested_by,
                 name,
                 author,
                 isbn,
@@ -195,15 +587,42 @@ impl UpdateItemRequest {
                 location,
             } => UpdateItem::Laptop {
                 item_id,
+                requested_by,
                 name,
                 mac_address,
                 description,
                 location: normalize_location(location),
             },
+            UpdateItemRequest::Custom {
+                name,
+                custom_category,
+                attributes,
+                description,
+                location,
+            } => UpdateItem::Custom {
+                item_id,
+                requested_by,
+                name,
+                custom_category,
+                attributes,
+                description,
+                location: normalize_location(location),
+            },
         }
     }
 }
 
+// Archive Request types
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct DeleteItemQuery {
+    /// Why the item is being retired, e.g. lost or broken
+    #[serde(default)]
+    #[garde(length(max = 1024))]
+    #[schema(max_length = 1024)]
+    pub reason: Option<String>,
+}
+
 fn normalize_location(location: Option<String>) -> Option<String> {
     location.and_then(|value| {
         let trimmed = value.trim().to_string();
@@ -221,6 +640,10 @@ pub struct GeneralItemResponse {
     pub description: String,
     pub location: Option<String>,
     pub checkout: Option<ItemCheckoutResponse>,
+    /// Set when the item has been archived
+    pub archive: Option<ItemArchiveResponse>,
+    /// In alphabetical order
+    pub tags: Vec<TagResponse>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -233,6 +656,10 @@ pub struct BookResponse {
     pub description: String,
     pub location: Option<String>,
     pub checkout: Option<ItemCheckoutResponse>,
+    /// Set when the item has been archived
+    pub archive: Option<ItemArchiveResponse>,
+    /// In alphabetical order
+    pub tags: Vec<TagResponse>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -245,6 +672,28 @@ pub struct LaptopResponse {
     pub description: String,
     pub location: Option<String>,
     pub checkout: Option<ItemCheckoutResponse>,
+    /// Set when the item has been archived
+    pub archive: Option<ItemArchiveResponse>,
+    /// In alphabetical order
+    pub tags: Vec<TagResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomItemResponse {
+    pub id: ItemId,
+    pub name: String,
+    pub custom_category: CustomCategoryRefResponse,
+    /// Values keyed by attribute key; attributes without a value are left out
+    #[schema(value_type = Object)]
+    pub attributes: Map<String, Value>,
+    pub description: String,
+    pub location: Option<String>,
+    pub checkout: Option<ItemCheckoutResponse>,
+    /// Set when the item has been archived
+    pub archive: Option<ItemArchiveResponse>,
+    /// In alphabetical order
+    pub tags: Vec<TagResponse>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -256,6 +705,8 @@ pub enum ItemResponse {
     Book(BookResponse),
     #[serde(rename = "laptop")]
     Laptop(LaptopResponse),
+    #[serde(rename = "custom")]
+    Custom(CustomItemResponse),
 }
 
 impl TryFrom<Item> for ItemResponse {
@@ -269,6 +720,8 @@ impl TryFrom<Item> for ItemResponse {
                 description: item.description,
                 location: item.location,
                 checkout: item.checkout.map(ItemCheckoutResponse::from),
+                archive: item.archive.map(ItemArchiveResponse::from),
+                tags: item.tags.into_iter().map(TagResponse::from).collect(),
             }),
             Item::Book(book) => ItemResponse::Book(BookResponse {
                 id: book.id,
@@ -278,6 +731,8 @@ impl TryFrom<Item> for ItemResponse {
                 description: book.description,
                 location: book.location,
                 checkout: book.checkout.map(ItemCheckoutResponse::from),
+                archive: book.archive.map(ItemArchiveResponse::from),
+                tags: book.tags.into_iter().map(TagResponse::from).collect(),
             }),
             Item::Laptop(laptop) => ItemResponse::Laptop(LaptopResponse {
                 id: laptop.id,
@@ -286,6 +741,19 @@ impl TryFrom<Item> for ItemResponse {
                 description: laptop.description,
                 location: laptop.location,
                 checkout: laptop.checkout.map(ItemCheckoutResponse::from),
+                archive: laptop.archive.map(ItemArchiveResponse::from),
+                tags: laptop.tags.into_iter().map(TagResponse::from).collect(),
+            }),
+            Item::Custom(item) => ItemResponse::Custom(CustomItemResponse {
+                id: item.id,
+                name: item.name,
+                custom_category: item.category.into(),
+                attributes: item.attributes,
+                description: item.description,
+                location: item.location,
+                checkout: item.checkout.map(ItemCheckoutResponse::from),
+                archive: item.archive.map(ItemArchiveResponse::from),
+                tags: item.tags.into_iter().map(TagResponse::from).collect(),
             }),
         })
     }
@@ -298,6 +766,10 @@ pub struct PaginatedItemResponse {
     pub limit: i64,
     pub offset: i64,
     pub items: Vec<ItemResponse>,
+    /// Cursor for the following page, if any
+    pub next_cursor: Option<String>,
+    /// Cursor for the preceding page, if any
+    pub prev_cursor: Option<String>,
 }
 
 impl TryFrom<PaginatedList<Item>> for PaginatedItemResponse {
@@ -309,6 +781,8 @@ impl TryFrom<PaginatedList<Item>> for PaginatedItemResponse {
             limit,
             offset,
             items,
+            next_cursor,
+            prev_cursor,
         } = value;
 
         let converted_items: Result<Vec<_>, _> =
@@ -319,6 +793,8 @@ impl TryFrom<PaginatedList<Item>> for PaginatedItemResponse {
             limit,
             offset,
             items: converted_items?,
+            next_cursor: next_cursor.as_ref().map(encode_cursor),
+            prev_cursor: prev_cursor.as_ref().map(encode_cursor),
         })
     }
 }
@@ -330,14 +806,36 @@ pub struct ItemCheckoutResponse {
     pub checked_out_by: CheckoutUser,
     #[schema(value_type = String, format = "date-time", example = "2024-04-10T13:15:00Z")]
     pub checked_out_at: chrono::DateTime<chrono::Utc>,
+    #[schema(value_type = String, format = "date-time", example = "2024-04-24T13:15:00Z")]
+    pub due_at: chrono::DateTime<chrono::Utc>,
+    pub is_overdue: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemArchiveResponse {
+    #[schema(value_type = String, format = "date-time", example = "2024-04-10T13:15:00Z")]
+    pub archived_at: chrono::DateTime<chrono::Utc>,
+    pub reason: Option<String>,
+}
+
+impl From<ItemArchive> for ItemArchiveResponse {
+    fn from(value: ItemArchive) -> Self {
+        Self {
+            archived_at: value.archived_at,
+            reason: value.reason,
+        }
+    }
 }
 
 impl From<SimpleCheckout> for ItemCheckoutResponse {
     fn from(value: SimpleCheckout) -> Self {
         Self {
+            is_overdue: value.is_overdue(chrono::Utc::now()),
             id: value.checkout_id,
             checked_out_by: value.checked_out_by.into(),
             checked_out_at: value.checked_out_at,
+            due_at: value.due_at,
         }
     }
 }
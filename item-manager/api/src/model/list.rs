@@ -1,11 +1,26 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
 use garde::Validate;
-use kernel::model::{item::ItemCategory, list::ListOptions};
+use kernel::model::{
+    audit::{AuditAction, AuditLogListOptions, AuditTargetType},
+    id::UserId,
+    item::ItemCategory,
+    list::{
+        Cursor, CursorListOptions, CursorSort, ItemFilter, ItemSort, ListOptions, SortOrder,
+        TagMatch, UserListOptions, UserSort,
+    },
+};
+use mac_address::MacAddress;
 use serde::Deserialize;
+use shared::error::{AppError, AppResult};
 use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::user::RoleName;
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct ListQuery {
-    #[garde(range(min = 0, max = 100))]
+    #[garde(range(min = 1, max = 100))]
     #[serde(default = "default_limit")]
     pub limit: i64,
 
@@ -13,9 +28,82 @@ pub struct ListQuery {
     #[serde(default)]
     pub offset: i64,
 
+    /// Opaque cursor from `nextCursor` or `prevCursor` of a previous page listed with the same `sort` and `order`; `offset` is ignored when set
+    #[serde(default)]
+    #[garde(length(max = 1024))]
+    #[schema(max_length = 1024)]
+    pub cursor: Option<String>,
+
     #[serde(default)]
     #[garde(skip)]
     pub category: Option<ItemCategory>,
+
+    /// Key of a custom category; only its items are listed
+    #[serde(default)]
+    #[garde(length(max = 64))]
+    #[schema(max_length = 64, example = "monitor")]
+    pub custom_category: Option<String>,
+
+    /// Free-text search over name, description, location, book author and ISBN
+    #[serde(default)]
+    #[garde(length(max = 255))]
+    #[schema(max_length = 255)]
+    pub q: Option<String>,
+
+    /// Exact book author
+    #[serde(default)]
+    #[garde(length(max = 255))]
+    #[schema(max_length = 255)]
+    pub author: Option<String>,
+
+    /// Exact book ISBN
+    #[serde(default)]
+    #[garde(length(max = 255))]
+    #[schema(max_length = 255)]
+    pub isbn: Option<String>,
+
+    /// Exact laptop MAC address
+    #[serde(default)]
+    #[garde(skip)]
+    #[schema(value_type = Option<String>, example = "00:00:00:00:00:00")]
+    pub mac_address: Option<MacAddress>,
+
+    /// Exact location
+    #[serde(default)]
+    #[garde(length(max = 255))]
+    #[schema(max_length = 255)]
+    pub location: Option<String>,
+
+    /// Only items that are (`true`) or are not (`false`) available for checkout
+    #[serde(default)]
+    #[garde(skip)]
+    pub available: Option<bool>,
+
+    /// Also list archived items
+    #[serde(default)]
+    #[garde(skip)]
+    pub include_archived: bool,
+
+    /// Comma-separated tag names, matched regardless of case
+    #[serde(default)]
+    #[garde(length(max = 1024))]
+    #[schema(max_length = 1024, example = "contest kit,JOI prep")]
+    pub tags: Option<String>,
+
+    /// Whether items need `any` or `all` of `tags`
+    #[serde(default)]
+    #[garde(skip)]
+    pub tag_match: TagMatch,
+
+    /// Column to sort by
+    #[serde(default)]
+    #[garde(skip)]
+    pub sort: ItemSort,
+
+    /// Sort direction; defaults to `desc` for timestamps and `asc` otherwise
+    #[serde(default)]
+    #[garde(skip)]
+    pub order: Option<SortOrder>,
 }
 
 const DEFAULT_LIMIT: i64 = 20;
@@ -23,12 +111,223 @@ const fn default_limit() -> i64 {
     DEFAULT_LIMIT
 }
 
-impl From<ListQuery> for ListOptions {
-    fn from(value: ListQuery) -> Self {
-        Self {
+impl TryFrom<ListQuery> for ListOptions {
+    type Error = AppError;
+
+    fn try_from(value: ListQuery) -> Result<Self, Self::Error> {
+        let order = value.order.unwrap_or(value.sort.default_order());
+        Ok(Self {
             limit: value.limit,
             offset: value.offset,
+            cursor: value
+                .cursor
+                .as_deref()
+                .map(|cursor| decode_sorted_cursor(cursor, CursorSort::new(value.sort, order)))
+                .transpose()?,
             category: value.category,
+            filter: ItemFilter {
+                q: non_blank(value.q),
+                author: non_blank(value.author),
+                isbn: non_blank(value.isbn),
+                mac_address: value.mac_address,
+                location: non_blank(value.location),
+                available: value.available,
+                include_archived: value.include_archived,
+                tags: value
+                    .tags
+                    .iter()
+                    .flat_map(|tags| tags.split(','))
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(String::from)
+                    .collect(),
+                tag_match: value.tag_match,
+                custom_category: non_blank(value.custom_category),
+            },
+            sort: value.sort,
+            order,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UserListQuery {
+    #[garde(range(min = 1, max = 100))]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+
+    #[garde(range(min = 0))]
+    #[serde(default)]
+    pub offset: i64,
+
+    /// Opaque cursor from `nextCursor` or `prevCursor` of a previous page listed with the same `sort` and `order`; `offset` is ignored when set
+    #[serde(default)]
+    #[garde(length(max = 1024))]
+    #[schema(max_length = 1024)]
+    pub cursor: Option<String>,
+
+    /// Case-insensitive search over name and email
+    #[serde(default)]
+    #[garde(length(max = 255))]
+    #[schema(max_length = 255)]
+    pub q: Option<String>,
+
+    #[serde(default)]
+    #[garde(skip)]
+    pub role: Option<RoleName>,
+
+    /// Only active (`true`) or deactivated (`false`) users
+    #[serde(default)]
+    #[garde(skip)]
+    pub active: Option<bool>,
+
+    /// Column to sort by
+    #[serde(default)]
+    #[garde(skip)]
+    pub sort: UserSort,
+
+    /// Sort direction; defaults to `desc` for timestamps and `asc` otherwise
+    #[serde(default)]
+    #[garde(skip)]
+    pub order: Option<SortOrder>,
+}
+
+impl TryFrom<UserListQuery> for UserListOptions {
+    type Error = AppError;
+
+    fn try_from(value: UserListQuery) -> Result<Self, Self::Error> {
+        let order = value.order.unwrap_or(value.sort.default_order());
+        Ok(Self {
+            limit: value.limit,
+            offset: value.offset,
+            cursor: value
+                .cursor
+                .as_deref()
+                .map(|cursor| decode_sorted_cursor(cursor, CursorSort::new(value.sort, order)))
+                .transpose()?,
+            q: non_blank(value.q),
+            role: value.role.map(Into::into),
+            active: value.active,
+            sort: value.sort,
+            order,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CursorQuery {
+    /// Page size; the whole list is returned when omitted
+    #[garde(inner(range(min = 1, max = 100)))]
+    #[serde(default)]
+    pub limit: Option<i64>,
+
+    /// Opaque cursor from `nextCursor` or `prevCursor` of a previous page
+    #[serde(default)]
+    #[garde(length(max = 1024))]
+    #[schema(max_length = 1024)]
+    pub cursor: Option<String>,
+}
+
+impl TryFrom<CursorQuery> for CursorListOptions {
+    type Error = AppError;
+
+    fn try_from(value: CursorQuery) -> Result<Self, Self::Error> {
+        Ok(Self {
+            limit: value.limit,
+            cursor: value.cursor.as_deref().map(decode_cursor).transpose()?,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct AuditLogListQuery {
+    #[garde(range(min = 0, max = 100))]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+
+    #[garde(range(min = 0))]
+    #[serde(default)]
+    pub offset: i64,
+
+    /// Only entries recorded for this user's actions
+    #[serde(default)]
+    #[garde(skip)]
+    #[schema(value_type = Option<String>)]
+    pub actor_id: Option<UserId>,
+
+    #[serde(default)]
+    #[garde(skip)]
+    pub action: Option<AuditAction>,
+
+    #[serde(default)]
+    #[garde(skip)]
+    pub target_type: Option<AuditTargetType>,
+
+    /// ID of the item, user, checkout, tag or custom category that was changed
+    #[serde(default)]
+    #[garde(skip)]
+    #[schema(value_type = Option<String>, format = "uuid")]
+    pub target_id: Option<Uuid>,
+
+    /// Only entries recorded at or after this time
+    #[serde(default)]
+    #[garde(skip)]
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub from: Option<DateTime<Utc>>,
+
+    /// Only entries recorded before this time
+    #[serde(default)]
+    #[garde(skip)]
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl From<AuditLogListQuery> for AuditLogListOptions {
+    fn from(value: AuditLogListQuery) -> Self {
+        Self {
+            limit: value.limit,
+            offset: value.offset,
+            actor_id: value.actor_id,
+            action: value.action,
+            target_type: value.target_type,
+            target_id: value.target_id,
+            from: value.from,
+            to: value.to,
         }
     }
 }
+
+pub fn encode_cursor(cursor: &Cursor) -> String {
+    let json = serde_json::to_vec(cursor).expect("cursor is always serializable");
+    URL_SAFE_NO_PAD.encode(json)
+}
+
+pub fn decode_cursor(value: &str) -> AppResult<Cursor> {
+    URL_SAFE_NO_PAD
+        .decode(value)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .ok_or_else(|| cursor_error("invalid cursor"))
+}
+
+/// Decodes the cursor of a sortable listing, which must have been made under
+/// the same sort and order as the page being requested.
+fn decode_sorted_cursor(value: &str, sort: CursorSort) -> AppResult<Cursor> {
+    let cursor = decode_cursor(value)?;
+    if cursor.sort.as_ref() != Some(&sort) {
+        return Err(cursor_error(
+            "cursor belongs to a listing with a different sort or order",
+        ));
+    }
+    Ok(cursor)
+}
+
+fn cursor_error(message: &'static str) -> AppError {
+    let mut report = garde::Report::new();
+    report.append(garde::Path::new("cursor"), garde::Error::new(message));
+    AppError::ValidationError(report)
+}
+
+fn non_blank(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.trim().is_empty())
+}
@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+/// Parameters the identity provider sends the browser back with.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    /// Set instead of `code` when the sign-in was denied or cancelled at the provider
+    pub error: Option<String>,
+}
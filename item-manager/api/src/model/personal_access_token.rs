@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use garde::Validate;
+use kernel::model::{
+    id::PersonalAccessTokenId,
+    personal_access_token::{CreatedPersonalAccessToken, PersonalAccessToken, TokenScope},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePersonalAccessTokenRequest {
+    /// What the token is used for
+    #[garde(length(min = 1, max = 255))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: String,
+    #[garde(length(min = 1))]
+    #[schema(min_items = 1)]
+    pub scopes: Vec<TokenScope>,
+    /// At most one year from now
+    #[garde(skip)]
+    #[schema(value_type = String, format = "date-time")]
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonalAccessTokensResponse {
+    pub items: Vec<PersonalAccessTokenResponse>,
+}
+
+impl From<Vec<PersonalAccessToken>> for PersonalAccessTokensResponse {
+    fn from(value: Vec<PersonalAccessToken>) -> Self {
+        Self {
+            items: value
+                .into_iter()
+                .map(PersonalAccessTokenResponse::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonalAccessTokenResponse {
+    pub id: PersonalAccessTokenId,
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    #[schema(value_type = String, format = "date-time")]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = String, format = "date-time")]
+    pub expires_at: DateTime<Utc>,
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl From<PersonalAccessToken> for PersonalAccessTokenResponse {
+    fn from(value: PersonalAccessToken) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            scopes: value.scopes,
+            created_at: value.created_at,
+            expires_at: value.expires_at,
+            last_used_at: value.last_used_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedPersonalAccessTokenResponse {
+    /// Send as `Authorization: Bearer <secret>`; it is only shown once
+    pub secret: String,
+    pub token: PersonalAccessTokenResponse,
+}
+
+impl From<CreatedPersonalAccessToken> for CreatedPersonalAccessTokenResponse {
+    fn from(value: CreatedPersonalAccessToken) -> Self {
+        Self {
+            secret: value.secret,
+            token: value.token.into(),
+        }
+    }
+}
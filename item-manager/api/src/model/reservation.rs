@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use kernel::model::{
+    id::{ItemId, ReservationId, UserId},
+    reservation::Reservation,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedReservationResponse {
+    pub id: ReservationId,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReservationsResponse {
+    pub items: Vec<ReservationResponse>,
+}
+
+impl From<Vec<Reservation>> for ReservationsResponse {
+    fn from(value: Vec<Reservation>) -> Self {
+        Self {
+            items: value.into_iter().map(ReservationResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReservationResponse {
+    pub id: ReservationId,
+    pub item_id: ItemId,
+    pub reserved_by: UserId,
+    #[schema(value_type = String, format = "date-time", example = "2024-04-10T13:15:00Z")]
+    pub reserved_at: DateTime<Utc>,
+    /// 1-based position in the item's reservation queue
+    pub position: i64,
+    /// When the item started being held for this reservation
+    #[schema(value_type = String, format = "date-time")]
+    pub ready_at: Option<DateTime<Utc>>,
+    /// Until when only this reservation's user can check the item out
+    #[schema(value_type = String, format = "date-time")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<Reservation> for ReservationResponse {
+    fn from(value: Reservation) -> Self {
+        Self {
+            id: value.id,
+            item_id: value.item_id,
+            reserved_by: value.reserved_by,
+            reserved_at: value.reserved_at,
+            position: value.position,
+            ready_at: value.ready_at,
+            expires_at: value.expires_at,
+        }
+    }
+}
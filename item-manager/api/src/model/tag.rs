@@ -0,0 +1,62 @@
+use garde::Validate;
+use kernel::model::{id::TagId, tag::Tag};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTagRequest {
+    /// Unique regardless of case; cannot contain commas, which separate tags in `tags=`
+    #[garde(length(min = 1, max = 64), custom(validate_tag_name))]
+    #[schema(max_length = 64)]
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTagRequest {
+    /// Unique regardless of case; cannot contain commas, which separate tags in `tags=`
+    #[garde(length(min = 1, max = 64), custom(validate_tag_name))]
+    #[schema(max_length = 64)]
+    pub name: String,
+}
+
+fn validate_tag_name(value: &str, _context: &()) -> garde::Result {
+    if value.trim().is_empty() {
+        return Err(garde::Error::new("must not be blank"));
+    }
+    if value.contains(',') {
+        return Err(garde::Error::new("must not contain commas"));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TagResponse {
+    pub id: TagId,
+    pub name: String,
+}
+
+impl From<Tag> for TagResponse {
+    fn from(value: Tag) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TagsResponse {
+    pub items: Vec<TagResponse>,
+}
+
+impl From<Vec<Tag>> for TagsResponse {
+    fn from(value: Vec<Tag>) -> Self {
+        Self {
+            items: value.into_iter().map(TagResponse::from).collect(),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use garde::Validate;
+use kernel::model::two_factor::{TotpSetup, TwoFactorStatus};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorStatusResponse {
+    pub enabled: bool,
+    pub recovery_codes_remaining: u32,
+}
+
+impl From<TwoFactorStatus> for TwoFactorStatusResponse {
+    fn from(value: TwoFactorStatus) -> Self {
+        Self {
+            enabled: value.enabled,
+            recovery_codes_remaining: value.recovery_codes_remaining,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpSetupResponse {
+    /// Base32 key for authenticator apps that cannot scan the URL
+    pub secret: String,
+    /// `otpauth://` URL to show as a QR code
+    pub otpauth_url: String,
+}
+
+impl From<TotpSetup> for TotpSetupResponse {
+    fn from(value: TotpSetup) -> Self {
+        Self {
+            secret: value.secret,
+            otpauth_url: value.otpauth_url,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorCodeRequest {
+    /// Six-digit code from the authenticator app; disabling also accepts a recovery code
+    #[garde(length(min = 1, max = 64))]
+    #[schema(max_length = 64)]
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryCodesResponse {
+    /// Single-use codes for signing in without the authenticator; they are only shown once
+    pub recovery_codes: Vec<String>,
+}
@@ -1,8 +1,10 @@
+use chrono::{DateTime, Utc};
 use derive_new::new;
 use garde::Validate;
 use kernel::model::{
     id::UserId,
-    role::Role,
+    list::PaginatedList,
+    role::{Permission, Role},
     user::{
         User,
         event::{CreateUser, UpdateUserEmail, UpdateUserName, UpdateUserPassword, UpdateUserRole},
@@ -12,10 +14,13 @@ use serde::{Deserialize, Serialize};
 use strum::VariantNames;
 use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, VariantNames, ToSchema)]
+use super::list::encode_cursor;
+
+#[derive(Debug, Serialize, Deserialize, VariantNames, ToSchema)]
 #[strum(serialize_all = "kebab-case")]
 pub enum RoleName {
     Admin,
+    Librarian,
     User,
 }
 
@@ -23,6 +28,7 @@ impl From<Role> for RoleName {
     fn from(value: Role) -> Self {
         match value {
             Role::Admin => Self::Admin,
+            Role::Librarian => Self::Librarian,
             Role::User => Self::User,
         }
     }
@@ -32,6 +38,7 @@ impl From<RoleName> for Role {
     fn from(value: RoleName) -> Self {
         match value {
             RoleName::Admin => Self::Admin,
+            RoleName::Librarian => Self::Librarian,
             RoleName::User => Self::User,
         }
     }
@@ -39,8 +46,36 @@ impl From<RoleName> for Role {
 
 #[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct UsersResponse {
+pub struct PaginatedUserResponse {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
     pub items: Vec<UserResponse>,
+    /// Cursor for the following page, if any
+    pub next_cursor: Option<String>,
+    /// Cursor for the preceding page, if any
+    pub prev_cursor: Option<String>,
+}
+
+impl From<PaginatedList<User>> for PaginatedUserResponse {
+    fn from(value: PaginatedList<User>) -> Self {
+        let PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+            next_cursor,
+            prev_cursor,
+        } = value;
+        Self {
+            total,
+            limit,
+            offset,
+            items: items.into_iter().map(UserResponse::from).collect(),
+            next_cursor: next_cursor.as_ref().map(encode_cursor),
+            prev_cursor: prev_cursor.as_ref().map(encode_cursor),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -50,6 +85,11 @@ pub struct UserResponse {
     pub name: String,
     pub email: String,
     pub role: RoleName,
+    /// What the user's role allows beyond their own checkouts and profile
+    pub permissions: Vec<Permission>,
+    /// When the account was deactivated; absent for active users
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub deactivated_at: Option<DateTime<Utc>>,
 }
 
 impl From<User> for UserResponse {
@@ -59,6 +99,8 @@ impl From<User> for UserResponse {
             name: value.name,
             email: value.email,
             role: value.role.into(),
+            permissions: value.role.permissions().to_vec(),
+            deactivated_at: value.deactivated_at,
         }
     }
 }
@@ -98,12 +140,17 @@ pub struct CreateUserRequest {
     pub password: String,
 }
 
-impl From<CreateUserRequest> for CreateUser {
-    fn from(value: CreateUserRequest) -> Self {
+/// A registration request together with the administrator who submitted it.
+#[derive(new)]
+pub struct CreateUserRequestWithUserId(UserId, CreateUserRequest);
+impl From<CreateUserRequestWithUserId> for CreateUser {
+    fn from(value: CreateUserRequestWithUserId) -> Self {
         Self {
-            name: value.name,
-            email: value.email,
-            password: value.password,
+            name: value.1.name,
+            email: value.1.email,
+            password: value.1.password,
+            requested_by: Some(value.0),
+            invitation_code: None,
         }
     }
 }
@@ -114,13 +161,15 @@ pub struct UpdateUserRoleRequest {
     pub role: RoleName,
 }
 
+/// A role change for the first user, requested by the second.
 #[derive(new)]
-pub struct UpdateUserRoleRequestWithUserId(UserId, UpdateUserRoleRequest);
+pub struct UpdateUserRoleRequestWithUserId(UserId, UpdateUserRoleRequest, UserId);
 impl From<UpdateUserRoleRequestWithUserId> for UpdateUserRole {
     fn from(value: UpdateUserRoleRequestWithUserId) -> Self {
         Self {
             user_id: value.0,
             role: value.1.role.into(),
+            requested_by: value.2,
         }
     }
 }
@@ -1,15 +1,46 @@
-use utoipa::OpenApi;
+use utoipa::{
+    OpenApi,
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+};
 
 use crate::handler::{
-    auth::ApiDoc as AuthApiDoc, checkout::ApiDoc as CheckoutApiDoc, health::ApiDoc as HealthApiDoc,
-    item::ApiDoc as ItemApiDoc, user::ApiDoc as UserApiDoc,
+    audit::ApiDoc as AuditApiDoc, auth::ApiDoc as AuthApiDoc,
+    category::ApiDoc as CustomCategoryApiDoc, checkout::ApiDoc as CheckoutApiDoc,
+    health::ApiDoc as HealthApiDoc, invitation::ApiDoc as InvitationApiDoc,
+    item::ApiDoc as ItemApiDoc, oidc::ApiDoc as OidcApiDoc,
+    personal_access_token::ApiDoc as PersonalAccessTokenApiDoc,
+    reservation::ApiDoc as ReservationApiDoc, tag::ApiDoc as TagApiDoc,
+    two_factor::ApiDoc as TwoFactorApiDoc, user::ApiDoc as UserApiDoc,
 };
 
 pub fn build_openapi() -> utoipa::openapi::OpenApi {
     let mut api_doc = HealthApiDoc::openapi();
     api_doc.merge(AuthApiDoc::openapi());
+    api_doc.merge(OidcApiDoc::openapi());
     api_doc.merge(CheckoutApiDoc::openapi());
     api_doc.merge(ItemApiDoc::openapi());
+    api_doc.merge(TagApiDoc::openapi());
+    api_doc.merge(CustomCategoryApiDoc::openapi());
+    api_doc.merge(ReservationApiDoc::openapi());
     api_doc.merge(UserApiDoc::openapi());
+    api_doc.merge(PersonalAccessTokenApiDoc::openapi());
+    api_doc.merge(TwoFactorApiDoc::openapi());
+    api_doc.merge(InvitationApiDoc::openapi());
+    api_doc.merge(AuditApiDoc::openapi());
+
+    // Handlers declare `security(("jwt" = []))`; the scheme itself is defined once here. It
+    // accepts both session JWTs and personal access tokens.
+    api_doc
+        .components
+        .get_or_insert_with(Default::default)
+        .add_security_scheme(
+            "jwt",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
     api_doc
 }
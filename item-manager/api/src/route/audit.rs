@@ -0,0 +1,8 @@
+use axum::{Router, routing::get};
+use registry::AppRegistry;
+
+use crate::handler::audit::list_audit_logs;
+
+pub fn routes() -> Router<AppRegistry> {
+    Router::new().route("/audit-logs", get(list_audit_logs))
+}
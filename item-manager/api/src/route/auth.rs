@@ -1,12 +1,27 @@
-use axum::{Router, routing::post};
+use axum::{
+    Router,
+    routing::{get, post},
+};
 use registry::AppRegistry;
 
-use crate::handler::auth::{login, logout};
+use crate::handler::auth::{
+    complete_login, confirm_password_reset, login, logout, refresh, request_password_reset,
+    set_up_login_two_factor, signup,
+};
+use crate::handler::oidc::{oidc_callback, oidc_login};
 
 pub fn routes() -> Router<AppRegistry> {
     let auth_router = Router::new()
         .route("/login", post(login))
-        .route("/logout", post(logout));
+        .route("/login/2fa", post(complete_login))
+        .route("/login/2fa/setup", post(set_up_login_two_factor))
+        .route("/refresh", post(refresh))
+        .route("/oidc/login", get(oidc_login))
+        .route("/oidc/callback", get(oidc_callback))
+        .route("/logout", post(logout))
+        .route("/signup", post(signup))
+        .route("/password-reset/request", post(request_password_reset))
+        .route("/password-reset/confirm", post(confirm_password_reset));
 
     Router::new().nest("/auth", auth_router)
 }
@@ -0,0 +1,21 @@
+use axum::{
+    Router,
+    routing::{get, put},
+};
+use registry::AppRegistry;
+
+use crate::handler::category::{
+    create_custom_category, delete_custom_category, list_custom_categories, update_custom_category,
+};
+
+pub fn routes() -> Router<AppRegistry> {
+    Router::new()
+        .route(
+            "/custom-categories",
+            get(list_custom_categories).post(create_custom_category),
+        )
+        .route(
+            "/custom-categories/{custom_category_id}",
+            put(update_custom_category).delete(delete_custom_category),
+        )
+}
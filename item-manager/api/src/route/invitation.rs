@@ -0,0 +1,16 @@
+use axum::{
+    Router,
+    routing::{delete, get},
+};
+use registry::AppRegistry;
+
+use crate::handler::invitation::{create_invitation, delete_invitation, list_invitations};
+
+pub fn routes() -> Router<AppRegistry> {
+    Router::new()
+        .route(
+            "/invitations",
+            get(list_invitations).post(create_invitation),
+        )
+        .route("/invitations/{invitation_id}", delete(delete_invitation))
+}
@@ -5,26 +5,56 @@ use axum::{
 use registry::AppRegistry;
 
 use crate::handler::{
-    checkout::{checkout_history, checkout_item, return_item, show_checked_out_list},
-    item::{create_item, delete_item, get_item, list_items, update_item},
+    checkout::{
+        checkout_history, checkout_item, renew_checkout, return_item, show_checked_out_list,
+        show_overdue_list,
+    },
+    item::{
+        create_item, delete_item, export_items, get_item, import_items, list_items, restore_item,
+        update_item,
+    },
+    reservation::{cancel_reservation, reserve_item, show_reservations},
 };
 
 pub fn routes() -> Router<AppRegistry> {
     let items_router = Router::new()
         .route("/", get(list_items))
         .route("/", post(create_item))
+        .route("/import", post(import_items))
+        .route("/export", get(export_items))
         .route("/{item_id}", get(get_item))
         .route("/{item_id}", put(update_item))
-        .route("/{item_id}", delete(delete_item));
+        .route("/{item_id}", delete(delete_item))
+        .route("/{item_id}/restore", put(restore_item));
 
     let checkout_router = Router::new()
         .route("/checkouts", get(show_checked_out_list))
+        .route("/checkouts/overdue", get(show_overdue_list))
         .route("/{item_id}/checkouts", post(checkout_item))
         .route(
             "/{item_id}/checkouts/{checkout_id}/returned",
             put(return_item),
         )
+        .route(
+            "/{item_id}/checkouts/{checkout_id}/renewal",
+            put(renew_checkout),
+        )
         .route("/{item_id}/checkout-history", get(checkout_history));
 
-    Router::new().nest("/items", items_router.merge(checkout_router))
+    let reservation_router = Router::new()
+        .route(
+            "/{item_id}/reservations",
+            get(show_reservations).post(reserve_item),
+        )
+        .route(
+            "/{item_id}/reservations/{reservation_id}",
+            delete(cancel_reservation),
+        );
+
+    Router::new().nest(
+        "/items",
+        items_router
+            .merge(checkout_router)
+            .merge(reservation_router),
+    )
 }
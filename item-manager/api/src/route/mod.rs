@@ -1,5 +1,9 @@
+pub mod audit;
 pub mod auth;
+pub mod category;
 pub mod health;
+pub mod invitation;
 pub mod item;
+pub mod tag;
 pub mod user;
 pub mod v1;
@@ -0,0 +1,17 @@
+use axum::{
+    Router,
+    routing::{get, put},
+};
+use registry::AppRegistry;
+
+use crate::handler::tag::{attach_tag, create_tag, delete_tag, detach_tag, list_tags, update_tag};
+
+pub fn routes() -> Router<AppRegistry> {
+    Router::new()
+        .route("/tags", get(list_tags).post(create_tag))
+        .route("/tags/{tag_id}", put(update_tag).delete(delete_tag))
+        .route(
+            "/items/{item_id}/tags/{tag_id}",
+            put(attach_tag).delete(detach_tag),
+        )
+}
@@ -1,12 +1,17 @@
 use axum::{
     Router,
-    routing::{delete, get, put},
+    routing::{delete, get, post, put},
 };
 use registry::AppRegistry;
 
+use crate::handler::personal_access_token::{create_token, delete_token, list_tokens};
+use crate::handler::two_factor::{
+    confirm_two_factor, disable_two_factor, get_two_factor_status, set_up_two_factor,
+};
 use crate::handler::user::{
-    change_email, change_name, change_password, change_role, delete_user, get_checkouts,
-    get_current_user, list_users, register_user,
+    change_email, change_name, change_password, change_role, deactivate_user, delete_user,
+    get_checkouts, get_current_user, get_reservations, list_users, reactivate_user, register_user,
+    unlock_user,
 };
 
 pub fn routes() -> Router<AppRegistry> {
@@ -16,7 +21,17 @@ pub fn routes() -> Router<AppRegistry> {
         .route("/users/me/name", put(change_name))
         .route("/users/me/email", put(change_email))
         .route("/users/me/checkouts", get(get_checkouts))
+        .route("/users/me/reservations", get(get_reservations))
+        .route("/users/me/tokens", get(list_tokens).post(create_token))
+        .route("/users/me/tokens/{token_id}", delete(delete_token))
+        .route("/users/me/2fa", get(get_two_factor_status))
+        .route("/users/me/2fa/setup", post(set_up_two_factor))
+        .route("/users/me/2fa/confirm", post(confirm_two_factor))
+        .route("/users/me/2fa/disable", post(disable_two_factor))
         .route("/users", get(list_users).post(register_user))
         .route("/users/{user_id}", delete(delete_user))
         .route("/users/{user_id}/role", put(change_role))
+        .route("/users/{user_id}/deactivate", put(deactivate_user))
+        .route("/users/{user_id}/reactivate", put(reactivate_user))
+        .route("/users/{user_id}/unlock", put(unlock_user))
 }
@@ -1,13 +1,17 @@
 use axum::Router;
 use registry::AppRegistry;
 
-use super::{health, item, user};
+use super::{audit, category, health, invitation, item, tag, user};
 
 pub fn routes() -> Router<AppRegistry> {
     let router = Router::new()
         .merge(health::routes())
         .merge(item::routes())
-        .merge(user::routes());
+        .merge(tag::routes())
+        .merge(category::routes())
+        .merge(user::routes())
+        .merge(invitation::routes())
+        .merge(audit::routes());
 
     Router::new().nest("/api/v1", router)
 }
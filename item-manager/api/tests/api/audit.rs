@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use api::model::audit::PaginatedAuditLogResponse;
+use axum::{body::Body, http::Request};
+use chrono::{DateTime, Utc};
+use kernel::{
+    model::{
+        audit::{AuditAction, AuditLog, AuditTargetType},
+        id::{AuditLogId, ItemId, UserId},
+        list::PaginatedList,
+    },
+    repository::audit::MockAuditRepository,
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{TestRequestExt, fixture, fixture_admin, make_router, v1},
+};
+
+#[rstest]
+#[case("/audit-logs", None, None, None, (20, 0))]
+#[case(
+    "/audit-logs?action=item_updated&limit=50&offset=50",
+    Some(AuditAction::ItemUpdated),
+    None,
+    None,
+    (50, 50))]
+#[case(
+    "/audit-logs?target_type=checkout&from=2024-04-01T00:00:00Z",
+    None,
+    Some(AuditTargetType::Checkout),
+    Some("2024-04-01T00:00:00Z"),
+    (20, 0))]
+#[tokio::test]
+async fn list_audit_logs_200(
+    mut fixture_admin: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] action: Option<AuditAction>,
+    #[case] target_type: Option<AuditTargetType>,
+    #[case] from: Option<&'static str>,
+    #[case] (expected_limit, expected_offset): (i64, i64),
+) -> anyhow::Result<()> {
+    let item_id = ItemId::new();
+    fixture_admin.expect_audit_repository().returning(move || {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_find_all().returning(move |opt| {
+            assert_eq!(opt.action, action);
+            assert_eq!(opt.target_type, target_type);
+            assert_eq!(opt.from, from.map(|v| v.parse::<DateTime<Utc>>().unwrap()));
+            assert!(opt.actor_id.is_none());
+            Ok(PaginatedList {
+                total: 1,
+                limit: opt.limit,
+                offset: opt.offset,
+                items: vec![AuditLog {
+                    id: AuditLogId::new(),
+                    actor_id: Some(UserId::new()),
+                    action: AuditAction::ItemUpdated,
+                    target_type: AuditTargetType::Item,
+                    target_id: item_id.raw(),
+                    before: Some(serde_json::json!({ "name": "old" })),
+                    after: Some(serde_json::json!({ "name": "new" })),
+                    created_at: Utc::now(),
+                }],
+                next_cursor: None,
+                prev_cursor: None,
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_admin);
+
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, PaginatedAuditLogResponse);
+    assert_eq!(result.total, 1);
+    assert_eq!(result.limit, expected_limit);
+    assert_eq!(result.offset, expected_offset);
+    assert_eq!(result.items[0].action, AuditAction::ItemUpdated);
+    assert_eq!(result.items[0].target_id, item_id.raw());
+    assert_eq!(result.items[0].after.as_ref().unwrap()["name"], "new");
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/audit-logs?limit=101")]
+#[case("/audit-logs?action=item_viewed")]
+#[case("/audit-logs?target_id=not-a-uuid")]
+#[case("/audit-logs?from=yesterday")]
+#[tokio::test]
+async fn list_audit_logs_400(
+    fixture_admin: registry::MockAppRegistryExt,
+    #[case] path: &str,
+) -> anyhow::Result<()> {
+    let app = make_router(fixture_admin);
+
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn list_audit_logs_403(fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let app = make_router(fixture);
+
+    let req = Request::get(v1("/audit-logs"))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::FORBIDDEN);
+
+    Ok(())
+}
@@ -1,13 +1,37 @@
 use std::sync::Arc;
 
-use api::model::auth::{LoginRequest, LoginResponse};
+use api::model::auth::{
+    CompleteLoginRequest, ConfirmPasswordResetRequest, LoginChallengeResponse, LoginRequest,
+    LoginResponse, PasswordResetRequest, SignupRequest,
+};
+use api::model::{two_factor::TotpSetupResponse, user::UserResponse};
 use axum::{
     body::Body,
-    http::{Request, header::SET_COOKIE},
+    http::{
+        Request,
+        header::{COOKIE, LOCATION, RETRY_AFTER, SET_COOKIE},
+    },
 };
+use chrono::{Duration, Utc};
 use kernel::{
-    model::{auth::AccessToken, id::UserId},
-    repository::auth::MockAuthRepository,
+    mailer::MockMailer,
+    model::{
+        auth::{AccessToken, RefreshToken, RotatedRefreshToken},
+        id::UserId,
+        oidc::{OidcAuthorization, OidcIdentity, PendingOidcLogin},
+        password_reset::PasswordResetToken,
+        role::Role,
+        two_factor::{CompletedLoginChallenge, LoginChallenge, TotpSetup},
+        user::User,
+    },
+    oidc::MockOidcProvider,
+    repository::{
+        auth::{AuthRepository, MockAuthRepository},
+        oidc::MockOidcRepository,
+        password_reset::MockPasswordResetRepository,
+        two_factor::MockTwoFactorRepository,
+        user::MockUserRepository,
+    },
 };
 use rstest::rstest;
 use shared::config::WebConfig;
@@ -19,11 +43,22 @@ use crate::{
     helper::{TestRequestExt, fixture_registry, make_router, v1},
 };
 
+fn expect_no_two_factor(registry: &mut registry::MockAppRegistryExt) {
+    registry.expect_two_factor_repository().returning(|| {
+        let mut mock = MockTwoFactorRepository::new();
+        mock.expect_create_challenge().returning(|_event| Ok(None));
+        Arc::new(mock)
+    });
+}
+
 fn expect_web_config(registry: &mut registry::MockAppRegistryExt) {
     registry.expect_web_config().returning(|| WebConfig {
         frontend_origin: "http://localhost:5173".to_string(),
         access_token_cookie_name: "access_token".to_string(),
         access_token_cookie_max_age_seconds: 86_400,
+        refresh_token_cookie_name: "refresh_token".to_string(),
+        refresh_token_cookie_max_age_seconds: 2_592_000,
+        trust_forwarded_for: false,
     });
 }
 
@@ -36,6 +71,7 @@ async fn login_success_200(
     let test_token = Arc::new("test_token".to_string());
 
     expect_web_config(&mut fixture_registry);
+    expect_no_two_factor(&mut fixture_registry);
 
     fixture_registry.expect_auth_repository().returning({
         let test_token = Arc::clone(&test_token);
@@ -45,11 +81,14 @@ async fn login_success_200(
             let test_token = Arc::clone(&test_token);
 
             mock.expect_verify_user()
-                .returning(move |_email, _password| Ok(user_id));
+                .returning(move |_email, _password, _client_ip| Ok(user_id));
 
             mock.expect_create_token()
                 .returning(move |_event| Ok(AccessToken((*test_token).clone())));
 
+            mock.expect_create_refresh_token()
+                .returning(|_event| Ok(RefreshToken("test_refresh_token".into())));
+
             Arc::new(mock)
         }
     });
@@ -59,6 +98,7 @@ async fn login_success_200(
     let req = LoginRequest {
         email: "test@example.com".to_string(),
         password: "password123".to_string(),
+        include_token: false,
     };
 
     let req = Request::post("/auth/login")
@@ -68,17 +108,72 @@ async fn login_success_200(
     let resp = app.oneshot(req).await?;
     assert_eq!(resp.status(), axum::http::StatusCode::OK);
 
-    let cookie = resp
-        .headers()
-        .get(SET_COOKIE)
-        .and_then(|value| value.to_str().ok())
+    let cookies = set_cookies(&resp);
+    let access_cookie = cookies
+        .iter()
+        .find(|cookie| cookie.starts_with("access_token="))
         .expect("login response should set access token cookie");
-    assert!(cookie.contains("access_token=test_token"));
-    assert!(cookie.contains("HttpOnly"));
-    assert!(cookie.contains("Secure"));
+    assert!(access_cookie.contains("access_token=test_token"));
+    assert!(access_cookie.contains("HttpOnly"));
+    assert!(access_cookie.contains("Secure"));
+    let refresh_cookie = cookies
+        .iter()
+        .find(|cookie| cookie.starts_with("refresh_token="))
+        .expect("login response should set refresh token cookie");
+    assert!(refresh_cookie.contains("refresh_token=test_refresh_token"));
+    assert!(refresh_cookie.contains("HttpOnly"));
+    assert!(refresh_cookie.contains("Path=/auth"));
 
     let result = deserialize_json!(resp, LoginResponse);
     assert_eq!(result.user_id, user_id);
+    assert!(result.access_token.is_none());
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn login_include_token_200(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    expect_web_config(&mut fixture_registry);
+    expect_no_two_factor(&mut fixture_registry);
+
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || {
+            let mut mock = MockAuthRepository::new();
+            mock.expect_verify_user()
+                .returning(|_email, _password, _client_ip| Ok(UserId::new()));
+            mock.expect_create_token()
+                .returning(|_event| Ok(AccessToken("test_token".into())));
+            mock.expect_create_refresh_token()
+                .returning(|_event| Ok(RefreshToken("test_refresh_token".into())));
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_registry);
+
+    let req = LoginRequest {
+        email: "test@example.com".to_string(),
+        password: "password123".to_string(),
+        include_token: true,
+    };
+
+    let req = Request::post("/auth/login")
+        .application_json()
+        .body(Body::from(serde_json::to_string(&req)?))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+    assert!(
+        set_cookies(&resp)
+            .iter()
+            .any(|cookie| cookie.starts_with("access_token=test_token"))
+    );
+
+    let result = deserialize_json!(resp, LoginResponse);
+    assert_eq!(result.access_token.as_deref(), Some("test_token"));
 
     Ok(())
 }
@@ -96,8 +191,98 @@ async fn login_failed_401(
             let mut mock = MockAuthRepository::new();
 
             mock.expect_verify_user()
-                .returning(move |_email, _password| Err(AppError::UnauthenticatedError));
+                .returning(move |_email, _password, _client_ip| {
+                    Err(AppError::UnauthenticatedError)
+                });
+
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_registry);
+
+    let req = LoginRequest {
+        email: "test@example.com".to_string(),
+        password: "wrong_password".to_string(),
+        include_token: false,
+    };
+
+    let req = Request::post("/auth/login")
+        .application_json()
+        .body(Body::from(serde_json::to_string(&req)?))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::UNAUTHORIZED);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn login_locked_out_429(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    expect_web_config(&mut fixture_registry);
+
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || {
+            let mut mock = MockAuthRepository::new();
+            mock.expect_verify_user()
+                .returning(|_email, _password, _client_ip| {
+                    Err(AppError::TooManyRequests { retry_after: 42 })
+                });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_registry);
+
+    let req = LoginRequest {
+        email: "test@example.com".to_string(),
+        password: "password123".to_string(),
+        include_token: false,
+    };
+
+    let req = Request::post("/auth/login")
+        .application_json()
+        .body(Body::from(serde_json::to_string(&req)?))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(resp.headers()[RETRY_AFTER], "42");
+    assert!(set_cookies(&resp).is_empty());
+
+    Ok(())
+}
+
+#[rstest]
+#[case::untrusted(false, None)]
+#[case::trusted(true, Some("198.51.100.7"))]
+#[tokio::test]
+async fn login_passes_client_ip(
+    mut fixture_registry: registry::MockAppRegistryExt,
+    #[case] trust_forwarded_for: bool,
+    #[case] expected: Option<&'static str>,
+) -> anyhow::Result<()> {
+    fixture_registry
+        .expect_web_config()
+        .returning(move || WebConfig {
+            frontend_origin: "http://localhost:5173".to_string(),
+            access_token_cookie_name: "access_token".to_string(),
+            access_token_cookie_max_age_seconds: 86_400,
+            refresh_token_cookie_name: "refresh_token".to_string(),
+            refresh_token_cookie_max_age_seconds: 2_592_000,
+            trust_forwarded_for,
+        });
 
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || {
+            let mut mock = MockAuthRepository::new();
+            mock.expect_verify_user()
+                .withf(move |_email, _password, client_ip| {
+                    *client_ip == expected.map(|ip| ip.parse().unwrap())
+                })
+                .returning(|_email, _password, _client_ip| Err(AppError::UnauthenticatedError));
             Arc::new(mock)
         });
 
@@ -106,14 +291,214 @@ async fn login_failed_401(
     let req = LoginRequest {
         email: "test@example.com".to_string(),
         password: "wrong_password".to_string(),
+        include_token: false,
+    };
+
+    // Only the last entry was added by the trusted proxy.
+    let req = Request::post("/auth/login")
+        .application_json()
+        .header("X-Forwarded-For", "203.0.113.9, 198.51.100.7")
+        .body(Body::from(serde_json::to_string(&req)?))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::UNAUTHORIZED);
+
+    Ok(())
+}
+
+#[rstest]
+#[case::enrolled(false)]
+#[case::setup_required(true)]
+#[tokio::test]
+async fn login_two_factor_challenge_202(
+    mut fixture_registry: registry::MockAppRegistryExt,
+    #[case] setup_required: bool,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+
+    expect_web_config(&mut fixture_registry);
+
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || {
+            let mut mock = MockAuthRepository::new();
+            mock.expect_verify_user()
+                .returning(move |_email, _password, _client_ip| Ok(user_id));
+            Arc::new(mock)
+        });
+    fixture_registry
+        .expect_two_factor_repository()
+        .returning(move || {
+            let mut mock = MockTwoFactorRepository::new();
+            mock.expect_create_challenge()
+                .withf(move |event| event.user_id == user_id)
+                .returning(move |_event| {
+                    Ok(Some(LoginChallenge {
+                        token: "challenge".into(),
+                        expires_at: Utc::now() + Duration::minutes(5),
+                        setup_required,
+                    }))
+                });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_registry);
+
+    let req = LoginRequest {
+        email: "admin@example.com".to_string(),
+        password: "password123".to_string(),
+        include_token: true,
     };
 
     let req = Request::post("/auth/login")
         .application_json()
         .body(Body::from(serde_json::to_string(&req)?))?;
 
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::ACCEPTED);
+    assert!(set_cookies(&resp).is_empty());
+
+    let result = deserialize_json!(resp, LoginChallengeResponse);
+    assert_eq!(result.challenge_token, "challenge");
+    assert_eq!(result.setup_required, setup_required);
+
+    Ok(())
+}
+
+#[rstest]
+#[case::enrolled(None)]
+#[case::setup(Some(vec!["aaaa-bbbb-cccc-dddd".to_string()]))]
+#[tokio::test]
+async fn complete_login_200(
+    mut fixture_registry: registry::MockAppRegistryExt,
+    #[case] recovery_codes: Option<Vec<String>>,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+
+    expect_web_config(&mut fixture_registry);
+
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || {
+            let mut mock = MockAuthRepository::new();
+            mock.expect_create_token()
+                .withf(move |event| event.user_id == user_id)
+                .returning(|_event| Ok(AccessToken("test_token".into())));
+            mock.expect_create_refresh_token()
+                .returning(|_event| Ok(RefreshToken("test_refresh_token".into())));
+            Arc::new(mock)
+        });
+    fixture_registry.expect_two_factor_repository().returning({
+        let recovery_codes = recovery_codes.clone();
+        move || {
+            let mut mock = MockTwoFactorRepository::new();
+            let recovery_codes = recovery_codes.clone();
+            mock.expect_complete_challenge()
+                .withf(|event| event.token == "challenge" && event.code == "123456")
+                .returning(move |_event| {
+                    Ok(CompletedLoginChallenge {
+                        user_id,
+                        recovery_codes: recovery_codes.clone(),
+                    })
+                });
+            Arc::new(mock)
+        }
+    });
+
+    let app = make_router(fixture_registry);
+
+    let req = CompleteLoginRequest {
+        challenge_token: "challenge".to_string(),
+        code: "123456".to_string(),
+        include_token: true,
+    };
+
+    let req = Request::post("/auth/login/2fa")
+        .application_json()
+        .body(Body::from(serde_json::to_string(&req)?))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+    assert!(
+        set_cookies(&resp)
+            .iter()
+            .any(|cookie| cookie.starts_with("access_token=test_token"))
+    );
+
+    let result = deserialize_json!(resp, LoginResponse);
+    assert_eq!(result.user_id, user_id);
+    assert_eq!(result.access_token.as_deref(), Some("test_token"));
+    assert_eq!(result.recovery_codes, recovery_codes);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn complete_login_wrong_code_401(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_registry
+        .expect_two_factor_repository()
+        .returning(|| {
+            let mut mock = MockTwoFactorRepository::new();
+            mock.expect_complete_challenge()
+                .returning(|_event| Err(AppError::UnauthenticatedError));
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_registry);
+
+    let req = CompleteLoginRequest {
+        challenge_token: "challenge".to_string(),
+        code: "000000".to_string(),
+        include_token: false,
+    };
+
+    let req = Request::post("/auth/login/2fa")
+        .application_json()
+        .body(Body::from(serde_json::to_string(&req)?))?;
+
     let resp = app.oneshot(req).await?;
     assert_eq!(resp.status(), axum::http::StatusCode::UNAUTHORIZED);
+    assert!(set_cookies(&resp).is_empty());
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn set_up_login_two_factor_200(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_registry
+        .expect_two_factor_repository()
+        .returning(|| {
+            let mut mock = MockTwoFactorRepository::new();
+            mock.expect_set_up_for_challenge()
+                .withf(|token| token == "challenge")
+                .returning(|_token| {
+                    Ok(TotpSetup {
+                        secret: "SECRET".into(),
+                        otpauth_url: "otpauth://totp/procon-manager:admin%40example.com".into(),
+                    })
+                });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_registry);
+
+    let req = Request::post("/auth/login/2fa/setup")
+        .application_json()
+        .body(Body::from(
+            serde_json::json!({ "challengeToken": "challenge" }).to_string(),
+        ))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, TotpSetupResponse);
+    assert_eq!(result.secret, "SECRET");
 
     Ok(())
 }
@@ -175,3 +560,780 @@ async fn verify_token_invalid(
 
     Ok(())
 }
+
+#[rstest]
+#[case::header_only(Some("Bearer header-token"), None, axum::http::StatusCode::OK)]
+#[case::lowercase_scheme(Some("bearer header-token"), None, axum::http::StatusCode::OK)]
+#[case::header_wins_over_cookie(
+    Some("Bearer header-token"),
+    Some("access_token=cookie-token"),
+    axum::http::StatusCode::OK
+)]
+#[case::non_bearer_header_falls_back_to_cookie(
+    Some("Basic dXNlcjpwYXNz"),
+    Some("access_token=header-token"),
+    axum::http::StatusCode::OK
+)]
+#[case::empty_bearer(Some("Bearer "), None, axum::http::StatusCode::UNAUTHORIZED)]
+#[case::unknown_token(
+    Some("Bearer cookie-token"),
+    None,
+    axum::http::StatusCode::UNAUTHORIZED
+)]
+#[tokio::test]
+async fn verify_token_from_authorization_header(
+    mut fixture_registry: registry::MockAppRegistryExt,
+    #[case] authorization: Option<&str>,
+    #[case] cookie: Option<&str>,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    expect_web_config(&mut fixture_registry);
+
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || {
+            let mut mock = MockAuthRepository::new();
+            // Only `header-token` is a valid session
+            mock.expect_fetch_user_id_from_token()
+                .returning(|token| Ok((token.0 == "header-token").then(UserId::new)));
+            Arc::new(mock)
+        });
+    fixture_registry.expect_user_repository().returning(|| {
+        let mut mock = MockUserRepository::new();
+        mock.expect_find_current_user().returning(|id| {
+            Ok(Some(User {
+                id,
+                name: "dummy-user".into(),
+                email: "dummy@example.com".into(),
+                role: Role::User,
+                deactivated_at: None,
+            }))
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_registry);
+
+    let mut req = Request::get(v1("/users/me"));
+    if let Some(authorization) = authorization {
+        req = req.header("Authorization", authorization);
+    }
+    if let Some(cookie) = cookie {
+        req = req.header("Cookie", cookie);
+    }
+
+    let resp = app.oneshot(req.body(Body::empty())?).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn logout_revokes_bearer_token_204(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    expect_web_config(&mut fixture_registry);
+
+    let mut mock = MockAuthRepository::new();
+    mock.expect_revoke_token()
+        .withf(|token| token.0 == "header-token")
+        .times(1)
+        .returning(|_token| Ok(()));
+    let mock: Arc<dyn AuthRepository> = Arc::new(mock);
+
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || mock.clone());
+
+    let app = make_router(fixture_registry);
+
+    let req = Request::post("/auth/logout")
+        .header("Authorization", "Bearer header-token")
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::NO_CONTENT);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn logout_revokes_token_204(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    expect_web_config(&mut fixture_registry);
+
+    // Both tokens must be revoked through the same repository instance
+    let mut mock = MockAuthRepository::new();
+    mock.expect_revoke_token()
+        .withf(|token| token.0 == "dummy")
+        .times(1)
+        .returning(|_token| Ok(()));
+    mock.expect_revoke_refresh_token()
+        .withf(|token| token.0 == "dummy-refresh")
+        .times(1)
+        .returning(|_token| Ok(()));
+    let mock: Arc<dyn AuthRepository> = Arc::new(mock);
+
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || mock.clone());
+
+    let app = make_router(fixture_registry);
+
+    let req = Request::post("/auth/logout")
+        .header("Cookie", "access_token=dummy; refresh_token=dummy-refresh")
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::NO_CONTENT);
+
+    let cookies = set_cookies(&resp);
+    assert!(
+        cookies
+            .iter()
+            .any(|cookie| cookie.starts_with("access_token=;") && cookie.contains("Max-Age=0"))
+    );
+    assert!(
+        cookies
+            .iter()
+            .any(|cookie| cookie.starts_with("refresh_token=;") && cookie.contains("Max-Age=0"))
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/auth/refresh", None)]
+#[case("/auth/refresh?includeToken=true", Some("new_access_token"))]
+#[tokio::test]
+async fn refresh_success_200(
+    mut fixture_registry: registry::MockAppRegistryExt,
+    #[case] uri: &str,
+    #[case] expected_token: Option<&str>,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+
+    expect_web_config(&mut fixture_registry);
+
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || {
+            let mut mock = MockAuthRepository::new();
+
+            mock.expect_rotate_refresh_token()
+                .withf(|token| token.0 == "old_refresh_token")
+                .returning(move |_token| {
+                    Ok(RotatedRefreshToken {
+                        user_id,
+                        refresh_token: RefreshToken("new_refresh_token".into()),
+                    })
+                });
+
+            mock.expect_create_token()
+                .withf(move |event| event.user_id == user_id)
+                .returning(|_event| Ok(AccessToken("new_access_token".into())));
+
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_registry);
+
+    let req = Request::post(uri)
+        .header("Cookie", "refresh_token=old_refresh_token")
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let cookies = set_cookies(&resp);
+    assert!(
+        cookies
+            .iter()
+            .any(|cookie| cookie.starts_with("access_token=new_access_token;"))
+    );
+    assert!(
+        cookies
+            .iter()
+            .any(|cookie| cookie.starts_with("refresh_token=new_refresh_token;"))
+    );
+
+    let result = deserialize_json!(resp, LoginResponse);
+    assert_eq!(result.user_id, user_id);
+    assert_eq!(result.access_token.as_deref(), expected_token);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn refresh_reused_token_401(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    expect_web_config(&mut fixture_registry);
+
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || {
+            let mut mock = MockAuthRepository::new();
+
+            // Simulate a refresh token that has already been rotated
+            mock.expect_rotate_refresh_token()
+                .returning(|_token| Err(AppError::UnauthenticatedError));
+
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_registry);
+
+    let req = Request::post("/auth/refresh")
+        .header("Cookie", "refresh_token=rotated_refresh_token")
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::UNAUTHORIZED);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn refresh_without_cookie_401(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    expect_web_config(&mut fixture_registry);
+
+    let app = make_router(fixture_registry);
+
+    let req = Request::post("/auth/refresh").body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::UNAUTHORIZED);
+
+    Ok(())
+}
+
+#[rstest]
+#[case::sent(true, || Ok(()))]
+#[case::delivery_failed(true, || Err(AppError::MailError("connection refused".into())))]
+#[case::unknown_email(false, || Ok(()))]
+#[tokio::test]
+async fn request_password_reset_202(
+    mut fixture_registry: registry::MockAppRegistryExt,
+    #[case] registered: bool,
+    #[case] delivery: fn() -> Result<(), AppError>,
+) -> anyhow::Result<()> {
+    expect_web_config(&mut fixture_registry);
+
+    fixture_registry
+        .expect_password_reset_repository()
+        .returning(move || {
+            let mut mock = MockPasswordResetRepository::new();
+            mock.expect_create().returning(move |event| {
+                Ok(registered.then(|| PasswordResetToken {
+                    user_id: UserId::new(),
+                    name: "Forgetful User".into(),
+                    email: event.email,
+                    token: "reset-token".into(),
+                    expires_at: chrono::Utc::now(),
+                }))
+            });
+            Arc::new(mock)
+        });
+    fixture_registry.expect_mailer().returning(move || {
+        let mut mock = MockMailer::new();
+        mock.expect_send()
+            .withf(|mail| {
+                mail.to == "user@example.com"
+                    && mail
+                        .body
+                        .contains("http://localhost:5173/password-reset?token=reset-token")
+            })
+            .times(usize::from(registered))
+            .returning(move |_| delivery());
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_registry);
+
+    let req = PasswordResetRequest {
+        email: "user@example.com".to_string(),
+    };
+    let req = Request::post("/auth/password-reset/request")
+        .application_json()
+        .body(Body::from(serde_json::to_string(&req)?))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::ACCEPTED);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn request_password_reset_400(
+    fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let app = make_router(fixture_registry);
+
+    let req = PasswordResetRequest {
+        email: "not-an-email".to_string(),
+    };
+    let req = Request::post("/auth/password-reset/request")
+        .application_json()
+        .body(Body::from(serde_json::to_string(&req)?))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("new_password", || Ok(UserId::new()), axum::http::StatusCode::OK)]
+#[case(
+    "new_password",
+    || Err(AppError::UnprocessableEntity("expired".into())),
+    axum::http::StatusCode::UNPROCESSABLE_ENTITY
+)]
+#[case("", || Ok(UserId::new()), axum::http::StatusCode::BAD_REQUEST)]
+#[tokio::test]
+async fn confirm_password_reset(
+    mut fixture_registry: registry::MockAppRegistryExt,
+    #[case] new_password: &str,
+    #[case] result: fn() -> Result<UserId, AppError>,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    fixture_registry
+        .expect_password_reset_repository()
+        .returning(move || {
+            let mut mock = MockPasswordResetRepository::new();
+            mock.expect_confirm()
+                .withf(|event| event.token == "reset-token")
+                .returning(move |_| result());
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_registry);
+
+    let req = ConfirmPasswordResetRequest {
+        token: "reset-token".to_string(),
+        new_password: new_password.to_string(),
+    };
+    let req = Request::post("/auth/password-reset/confirm")
+        .application_json()
+        .body(Body::from(serde_json::to_string(&req)?))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn signup_201(mut fixture_registry: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    expect_web_config(&mut fixture_registry);
+    expect_no_two_factor(&mut fixture_registry);
+
+    fixture_registry.expect_user_repository().returning(|| {
+        let mut mock = MockUserRepository::new();
+        mock.expect_create()
+            .withf(|event| {
+                event.requested_by.is_none()
+                    && event.invitation_code.as_deref() == Some("invite-code")
+            })
+            .returning(|event| {
+                Ok(User {
+                    id: UserId::new(),
+                    name: event.name,
+                    email: event.email,
+                    role: Role::Librarian,
+                    deactivated_at: None,
+                })
+            });
+        Arc::new(mock)
+    });
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || {
+            let mut mock = MockAuthRepository::new();
+            mock.expect_create_token()
+                .returning(|_event| Ok(AccessToken("test_token".into())));
+            mock.expect_create_refresh_token()
+                .returning(|_event| Ok(RefreshToken("test_refresh_token".into())));
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_registry);
+
+    let req = SignupRequest {
+        invitation_code: "invite-code".to_string(),
+        name: "New Member".to_string(),
+        email: "new@example.com".to_string(),
+        password: "password123".to_string(),
+    };
+    let req = Request::post("/auth/signup")
+        .application_json()
+        .body(Body::from(serde_json::to_string(&req)?))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::CREATED);
+    assert!(
+        set_cookies(&resp)
+            .iter()
+            .any(|cookie| cookie.starts_with("access_token=test_token"))
+    );
+
+    let result = deserialize_json!(resp, UserResponse);
+    assert_eq!(result.email, "new@example.com");
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn signup_202_admin_must_set_up_two_factor(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    fixture_registry
+        .expect_user_repository()
+        .returning(move || {
+            let mut mock = MockUserRepository::new();
+            mock.expect_create().returning(move |event| {
+                Ok(User {
+                    id: user_id,
+                    name: event.name,
+                    email: event.email,
+                    role: Role::Admin,
+                    deactivated_at: None,
+                })
+            });
+            Arc::new(mock)
+        });
+    // Two-factor authentication is mandatory for admins, who have not set it up at signup
+    fixture_registry
+        .expect_two_factor_repository()
+        .returning(move || {
+            let mut mock = MockTwoFactorRepository::new();
+            mock.expect_create_challenge()
+                .withf(move |event| event.user_id == user_id)
+                .returning(|_event| {
+                    Ok(Some(LoginChallenge {
+                        token: "challenge".into(),
+                        expires_at: Utc::now() + Duration::minutes(5),
+                        setup_required: true,
+                    }))
+                });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_registry);
+
+    let req = SignupRequest {
+        invitation_code: "admin-invite".to_string(),
+        name: "New Admin".to_string(),
+        email: "admin2@example.com".to_string(),
+        password: "password123".to_string(),
+    };
+    let req = Request::post("/auth/signup")
+        .application_json()
+        .body(Body::from(serde_json::to_string(&req)?))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::ACCEPTED);
+    assert!(set_cookies(&resp).is_empty());
+
+    let result = deserialize_json!(resp, LoginChallengeResponse);
+    assert_eq!(result.challenge_token, "challenge");
+    assert!(result.setup_required);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(
+    "new@example.com",
+    || Err(AppError::UnprocessableEntity("used up".into())),
+    axum::http::StatusCode::UNPROCESSABLE_ENTITY
+)]
+#[case(
+    "new@example.com",
+    || Err(AppError::Conflict("Email already exists.".into())),
+    axum::http::StatusCode::CONFLICT
+)]
+#[case(
+    "not-an-email",
+    || Err(AppError::Conflict("unreachable".into())),
+    axum::http::StatusCode::BAD_REQUEST
+)]
+#[tokio::test]
+async fn signup_rejected(
+    mut fixture_registry: registry::MockAppRegistryExt,
+    #[case] email: &str,
+    #[case] result: fn() -> Result<User, AppError>,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    fixture_registry
+        .expect_user_repository()
+        .returning(move || {
+            let mut mock = MockUserRepository::new();
+            mock.expect_create().returning(move |_| result());
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_registry);
+
+    let req = SignupRequest {
+        invitation_code: "invite-code".to_string(),
+        name: "New Member".to_string(),
+        email: email.to_string(),
+        password: "password123".to_string(),
+    };
+    let req = Request::post("/auth/signup")
+        .application_json()
+        .body(Body::from(serde_json::to_string(&req)?))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+    assert!(set_cookies(&resp).is_empty());
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn oidc_login_303(mut fixture_registry: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture_registry.expect_oidc_provider().returning(|| {
+        let mut mock = MockOidcProvider::new();
+        mock.expect_authorize().returning(|| {
+            Ok(OidcAuthorization {
+                url: "https://idp.example.com/authorize?state=state".into(),
+                state: "state".into(),
+                nonce: "nonce".into(),
+                pkce_verifier: "verifier".into(),
+            })
+        });
+        Some(Arc::new(mock))
+    });
+    fixture_registry.expect_oidc_repository().returning(|| {
+        let mut mock = MockOidcRepository::new();
+        mock.expect_create_login_request()
+            .withf(|event| {
+                event.state == "state"
+                    && event.nonce == "nonce"
+                    && event.pkce_verifier == "verifier"
+            })
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_registry);
+
+    let req = Request::get("/auth/oidc/login").body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::SEE_OTHER);
+    assert_eq!(
+        resp.headers()[LOCATION],
+        "https://idp.example.com/authorize?state=state"
+    );
+    assert!(
+        set_cookies(&resp)
+            .iter()
+            .any(|cookie| cookie.starts_with("oidc_state=state;"))
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn oidc_login_not_configured_404(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_registry.expect_oidc_provider().returning(|| None);
+
+    let app = make_router(fixture_registry);
+
+    let req = Request::get("/auth/oidc/login").body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+/// Expects a callback for `state` that the provider resolves to an identity signing in as the
+/// result of `sign_in`.
+fn expect_oidc_sign_in(
+    registry: &mut registry::MockAppRegistryExt,
+    sign_in: fn() -> Result<UserId, AppError>,
+) {
+    registry.expect_oidc_provider().returning(|| {
+        let mut mock = MockOidcProvider::new();
+        mock.expect_exchange_code()
+            .withf(|code, pending| code == "code" && pending.nonce == "nonce")
+            .returning(|_, _| {
+                Ok(OidcIdentity {
+                    issuer: "https://idp.example.com".into(),
+                    subject: "member-1".into(),
+                    email: Some("member@example.com".into()),
+                    email_verified: true,
+                    name: None,
+                })
+            });
+        Some(Arc::new(mock))
+    });
+    registry.expect_oidc_repository().returning(move || {
+        let mut mock = MockOidcRepository::new();
+        mock.expect_take_login_request().returning(|state| {
+            Ok((state == "state").then(|| PendingOidcLogin {
+                nonce: "nonce".into(),
+                pkce_verifier: "verifier".into(),
+            }))
+        });
+        mock.expect_sign_in()
+            .withf(|event| event.identity.subject == "member-1")
+            .returning(move |_| sign_in());
+        Arc::new(mock)
+    });
+}
+
+#[rstest]
+#[tokio::test]
+async fn oidc_callback_303(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    expect_web_config(&mut fixture_registry);
+    expect_no_two_factor(&mut fixture_registry);
+    expect_oidc_sign_in(&mut fixture_registry, || Ok(UserId::new()));
+    fixture_registry.expect_auth_repository().returning(|| {
+        let mut mock = MockAuthRepository::new();
+        mock.expect_create_token()
+            .returning(|_event| Ok(AccessToken("test_token".into())));
+        mock.expect_create_refresh_token()
+            .returning(|_event| Ok(RefreshToken("test_refresh_token".into())));
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_registry);
+
+    let req = Request::get("/auth/oidc/callback?code=code&state=state")
+        .header(COOKIE, "oidc_state=state")
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::SEE_OTHER);
+    assert_eq!(resp.headers()[LOCATION], "http://localhost:5173/");
+    let cookies = set_cookies(&resp);
+    assert!(
+        cookies
+            .iter()
+            .any(|cookie| cookie.starts_with("access_token=test_token"))
+    );
+    assert!(
+        cookies
+            .iter()
+            .any(|cookie| cookie.starts_with("oidc_state=;") && cookie.contains("Max-Age=0"))
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn oidc_callback_two_factor_303(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    expect_web_config(&mut fixture_registry);
+    expect_oidc_sign_in(&mut fixture_registry, || Ok(UserId::new()));
+    fixture_registry
+        .expect_two_factor_repository()
+        .returning(|| {
+            let mut mock = MockTwoFactorRepository::new();
+            mock.expect_create_challenge().returning(|_event| {
+                Ok(Some(LoginChallenge {
+                    token: "challenge".into(),
+                    expires_at: Utc::now() + Duration::minutes(5),
+                    setup_required: false,
+                }))
+            });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_registry);
+
+    let req = Request::get("/auth/oidc/callback?code=code&state=state")
+        .header(COOKIE, "oidc_state=state")
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::SEE_OTHER);
+    assert_eq!(
+        resp.headers()[LOCATION],
+        "http://localhost:5173/login#challengeToken=challenge&setupRequired=false"
+    );
+    assert!(
+        !set_cookies(&resp)
+            .iter()
+            .any(|cookie| cookie.starts_with("access_token="))
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[case::state_mismatch("/auth/oidc/callback?code=code&state=state", "oidc_state=other", || Ok(UserId::new()), "sso_failed")]
+#[case::unknown_state("/auth/oidc/callback?code=code&state=unknown", "oidc_state=unknown", || Ok(UserId::new()), "sso_failed")]
+#[case::denied("/auth/oidc/callback?error=access_denied&state=state", "oidc_state=state", || Ok(UserId::new()), "sso_failed")]
+#[case::no_account("/auth/oidc/callback?code=code&state=state", "oidc_state=state", || Err(AppError::UnauthenticatedError), "sso_failed")]
+#[case::deactivated(
+    "/auth/oidc/callback?code=code&state=state",
+    "oidc_state=state",
+    || Err(AppError::ForbiddenOperation("This account has been deactivated.".into())),
+    "account_deactivated"
+)]
+#[tokio::test]
+async fn oidc_callback_failed_303(
+    mut fixture_registry: registry::MockAppRegistryExt,
+    #[case] uri: &str,
+    #[case] cookie: &str,
+    #[case] sign_in: fn() -> Result<UserId, AppError>,
+    #[case] reason: &str,
+) -> anyhow::Result<()> {
+    expect_web_config(&mut fixture_registry);
+    expect_oidc_sign_in(&mut fixture_registry, sign_in);
+
+    let app = make_router(fixture_registry);
+
+    let req = Request::get(uri)
+        .header(COOKIE, cookie)
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::SEE_OTHER);
+    assert_eq!(
+        resp.headers()[LOCATION],
+        format!("http://localhost:5173/login?error={reason}").as_str()
+    );
+    assert!(
+        !set_cookies(&resp)
+            .iter()
+            .any(|cookie| cookie.starts_with("access_token="))
+    );
+
+    Ok(())
+}
+
+fn set_cookies(resp: &axum::response::Response) -> Vec<String> {
+    resp.headers()
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .map(str::to_string)
+        .collect()
+}
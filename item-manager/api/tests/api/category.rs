@@ -0,0 +1,256 @@
+use std::sync::Arc;
+
+use api::model::category::{CustomCategoriesResponse, CustomCategoryResponse};
+use axum::{body::Body, http::Request};
+use kernel::{
+    model::{
+        category::{AttributeDefinition, AttributeType, CustomCategory},
+        id::CustomCategoryId,
+    },
+    repository::category::MockCustomCategoryRepository,
+};
+use rstest::rstest;
+use shared::error::AppError;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{TestRequestExt, fixture, fixture_admin, fixture_librarian, make_router, v1},
+};
+
+fn monitor(id: CustomCategoryId) -> CustomCategory {
+    CustomCategory {
+        id,
+        key: "monitor".into(),
+        name: "Monitor".into(),
+        attributes: vec![AttributeDefinition {
+            key: "screen_size".into(),
+            label: "Screen size".into(),
+            attribute_type: AttributeType::Number,
+            required: true,
+            options: vec![],
+        }],
+    }
+}
+
+fn monitor_body() -> serde_json::Value {
+    serde_json::json!({
+        "key": "monitor",
+        "name": "Monitor",
+        "attributes": [
+            { "key": "screen_size", "label": "Screen size", "type": "number", "required": true }
+        ]
+    })
+}
+
+#[rstest]
+#[tokio::test]
+async fn list_custom_categories_200(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture.expect_custom_category_repository().returning(|| {
+        let mut mock = MockCustomCategoryRepository::new();
+        mock.expect_find_all()
+            .returning(|| Ok(vec![monitor(CustomCategoryId::new())]));
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+
+    let req = Request::get(v1("/custom-categories"))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, CustomCategoriesResponse);
+    assert_eq!(result.items.len(), 1);
+    assert_eq!(result.items[0].key, "monitor");
+    assert_eq!(
+        result.items[0].attributes[0].attribute_type,
+        AttributeType::Number
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[case(|| Ok(monitor(CustomCategoryId::new())), axum::http::StatusCode::CREATED)]
+#[case(
+    || Err(AppError::Conflict("duplicate".into())),
+    axum::http::StatusCode::CONFLICT
+)]
+#[case(
+    || Err(AppError::UnprocessableEntity("invalid definitions".into())),
+    axum::http::StatusCode::UNPROCESSABLE_ENTITY
+)]
+#[tokio::test]
+async fn create_custom_category(
+    mut fixture_admin: registry::MockAppRegistryExt,
+    #[case] result: fn() -> Result<CustomCategory, AppError>,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    fixture_admin
+        .expect_custom_category_repository()
+        .returning(move || {
+            let mut mock = MockCustomCategoryRepository::new();
+            mock.expect_create().returning(move |event| {
+                assert_eq!(event.key, "monitor");
+                assert_eq!(
+                    event.attributes,
+                    monitor(CustomCategoryId::new()).attributes
+                );
+                result()
+            });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_admin);
+
+    let req = Request::post(v1("/custom-categories"))
+        .bearer()
+        .application_json()
+        .body(Body::from(monitor_body().to_string()))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+    if expected == axum::http::StatusCode::CREATED {
+        let result = deserialize_json!(resp, CustomCategoryResponse);
+        assert_eq!(result.key, "monitor");
+    }
+
+    Ok(())
+}
+
+#[rstest]
+#[case(serde_json::json!({ "key": "", "name": "Monitor", "attributes": [] }))]
+#[case(serde_json::json!({ "key": "Monitor", "name": "Monitor", "attributes": [] }))]
+#[case(serde_json::json!({ "key": "1monitor", "name": "Monitor", "attributes": [] }))]
+#[case(serde_json::json!({ "key": "monitor", "name": "", "attributes": [] }))]
+#[tokio::test]
+async fn create_custom_category_400(
+    fixture_admin: registry::MockAppRegistryExt,
+    #[case] body: serde_json::Value,
+) -> anyhow::Result<()> {
+    let app = make_router(fixture_admin);
+
+    let req = Request::post(v1("/custom-categories"))
+        .bearer()
+        .application_json()
+        .body(Body::from(body.to_string()))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(|id| Ok(monitor(id)), axum::http::StatusCode::OK)]
+#[case(
+    |_| Err(AppError::Conflict("an item does not match".into())),
+    axum::http::StatusCode::CONFLICT
+)]
+#[case(
+    |_| Err(AppError::EntityNotFound("not found".into())),
+    axum::http::StatusCode::NOT_FOUND
+)]
+#[tokio::test]
+async fn update_custom_category(
+    mut fixture_admin: registry::MockAppRegistryExt,
+    #[case] result: fn(CustomCategoryId) -> Result<CustomCategory, AppError>,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let custom_category_id = CustomCategoryId::new();
+    fixture_admin
+        .expect_custom_category_repository()
+        .returning(move || {
+            let mut mock = MockCustomCategoryRepository::new();
+            mock.expect_update().returning(move |event| {
+                assert_eq!(event.custom_category_id, custom_category_id);
+                assert_eq!(event.name, "Monitor");
+                result(event.custom_category_id)
+            });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_admin);
+
+    let mut body = monitor_body();
+    body.as_object_mut().unwrap().remove("key");
+    let req = Request::put(v1(&format!("/custom-categories/{custom_category_id}")))
+        .bearer()
+        .application_json()
+        .body(Body::from(body.to_string()))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+    if expected == axum::http::StatusCode::OK {
+        let result = deserialize_json!(resp, CustomCategoryResponse);
+        assert_eq!(result.id, custom_category_id);
+    }
+
+    Ok(())
+}
+
+#[rstest]
+#[case(|| Ok(()), axum::http::StatusCode::OK)]
+#[case(
+    || Err(AppError::Conflict("still has items".into())),
+    axum::http::StatusCode::CONFLICT
+)]
+#[tokio::test]
+async fn delete_custom_category(
+    mut fixture_admin: registry::MockAppRegistryExt,
+    #[case] result: fn() -> Result<(), AppError>,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let custom_category_id = CustomCategoryId::new();
+    fixture_admin
+        .expect_custom_category_repository()
+        .returning(move || {
+            let mut mock = MockCustomCategoryRepository::new();
+            mock.expect_delete().returning(move |event| {
+                assert_eq!(event.custom_category_id, custom_category_id);
+                result()
+            });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_admin);
+
+    let req = Request::delete(v1(&format!("/custom-categories/{custom_category_id}")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("POST", "/custom-categories".to_string())]
+#[case("PUT", format!("/custom-categories/{}", CustomCategoryId::new()))]
+#[case("DELETE", format!("/custom-categories/{}", CustomCategoryId::new()))]
+#[tokio::test]
+async fn custom_categories_403_without_categories_manage(
+    fixture_librarian: registry::MockAppRegistryExt,
+    #[case] method: &str,
+    #[case] path: String,
+) -> anyhow::Result<()> {
+    let app = make_router(fixture_librarian);
+
+    let req = Request::builder()
+        .method(method)
+        .uri(v1(&path))
+        .bearer()
+        .application_json()
+        .body(Body::from(monitor_body().to_string()))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::FORBIDDEN);
+
+    Ok(())
+}
@@ -3,7 +3,12 @@ use std::sync::Arc;
 use api::route::{auth, v1};
 use axum::{Router, http::request::Builder};
 use kernel::{
-    model::{auth::AccessToken, id::UserId, role::Role, user::User},
+    model::{
+        auth::{AccessToken, RefreshToken},
+        id::UserId,
+        role::Role,
+        user::User,
+    },
     repository::{auth::MockAuthRepository, user::MockUserRepository},
 };
 use registry::MockAppRegistryExt;
@@ -34,6 +39,9 @@ pub fn fixture_auth(mut fixture_registry: MockAppRegistryExt) -> MockAppRegistry
             frontend_origin: "http://localhost:5173".to_string(),
             access_token_cookie_name: "access_token".to_string(),
             access_token_cookie_max_age_seconds: 86_400,
+            refresh_token_cookie_name: "refresh_token".to_string(),
+            refresh_token_cookie_max_age_seconds: 2_592_000,
+            trust_forwarded_for: false,
         });
     fixture_registry.expect_auth_repository().returning(|| {
         let mut mock_auth_repository = MockAuthRepository::new();
@@ -42,10 +50,13 @@ pub fn fixture_auth(mut fixture_registry: MockAppRegistryExt) -> MockAppRegistry
             .returning(|_| Ok(Some(UserId::new())));
         mock_auth_repository
             .expect_verify_user()
-            .returning(|_, _| Ok(UserId::new()));
+            .returning(|_, _, _| Ok(UserId::new()));
         mock_auth_repository
             .expect_create_token()
             .returning(|_| Ok(AccessToken("dummy".into())));
+        mock_auth_repository
+            .expect_create_refresh_token()
+            .returning(|_| Ok(RefreshToken("dummy-refresh".into())));
         Arc::new(mock_auth_repository)
     });
     fixture_registry
@@ -63,6 +74,7 @@ pub fn fixture(mut fixture_auth: MockAppRegistryExt) -> MockAppRegistryExt {
                     name: "dummy-user".into(),
                     email: "dummy@example.com".into(),
                     role: Role::User,
+                    deactivated_at: None,
                 }))
             });
         Arc::new(mock_user_repository)
@@ -82,6 +94,27 @@ pub fn fixture_admin(mut fixture_auth: MockAppRegistryExt) -> MockAppRegistryExt
                     name: "admin-user".into(),
                     email: "admin@example.com".into(),
                     role: Role::Admin,
+                    deactivated_at: None,
+                }))
+            });
+        Arc::new(mock_user_repository)
+    });
+    fixture_auth
+}
+
+#[fixture]
+pub fn fixture_librarian(mut fixture_auth: MockAppRegistryExt) -> MockAppRegistryExt {
+    fixture_auth.expect_user_repository().returning(|| {
+        let mut mock_user_repository = MockUserRepository::new();
+        mock_user_repository
+            .expect_find_current_user()
+            .returning(|id| {
+                Ok(Some(User {
+                    id,
+                    name: "librarian-user".into(),
+                    email: "librarian@example.com".into(),
+                    role: Role::Librarian,
+                    deactivated_at: None,
                 }))
             });
         Arc::new(mock_user_repository)
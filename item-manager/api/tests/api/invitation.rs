@@ -0,0 +1,193 @@
+use std::sync::Arc;
+
+use api::model::invitation::{CreatedInvitationResponse, InvitationsResponse};
+use axum::{body::Body, http::Request};
+use chrono::{Duration, Utc};
+use kernel::{
+    model::{
+        id::{InvitationId, UserId},
+        invitation::{CreatedInvitation, Invitation},
+        role::Role,
+    },
+    repository::invitation::MockInvitationRepository,
+};
+use rstest::rstest;
+use shared::error::AppError;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{TestRequestExt, fixture, fixture_admin, make_router, v1},
+};
+
+fn invitation(role: Role, max_uses: u32) -> Invitation {
+    Invitation {
+        id: InvitationId::new(),
+        role,
+        max_uses,
+        uses: 0,
+        expires_at: Utc::now() + Duration::days(7),
+        created_by: Some(UserId::new()),
+        created_at: Utc::now(),
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn create_invitation_201(
+    mut fixture_admin: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_admin.expect_invitation_repository().returning(|| {
+        let mut mock = MockInvitationRepository::new();
+        mock.expect_create().returning(|event| {
+            assert_eq!(event.role, Role::Librarian);
+            assert_eq!(event.max_uses, 10);
+            Ok(CreatedInvitation {
+                invitation: invitation(event.role, event.max_uses),
+                code: "invite-code".into(),
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_admin);
+
+    let req = Request::post(v1("/invitations"))
+        .bearer()
+        .application_json()
+        .body(Body::from(
+            serde_json::json!({
+                "role": "Librarian",
+                "maxUses": 10,
+                "expiresAt": Utc::now() + Duration::days(7),
+            })
+            .to_string(),
+        ))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::CREATED);
+
+    let result = deserialize_json!(resp, CreatedInvitationResponse);
+    assert_eq!(result.code, "invite-code");
+    assert_eq!(result.invitation.max_uses, 10);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(serde_json::json!({ "role": "User", "maxUses": 0, "expiresAt": Utc::now() + Duration::days(1) }), axum::http::StatusCode::BAD_REQUEST)]
+#[case(serde_json::json!({ "role": "User", "maxUses": 1, "expiresAt": Utc::now() - Duration::days(1) }), axum::http::StatusCode::UNPROCESSABLE_ENTITY)]
+#[case(serde_json::json!({ "role": "User", "maxUses": 1, "expiresAt": Utc::now() + Duration::days(91) }), axum::http::StatusCode::UNPROCESSABLE_ENTITY)]
+#[tokio::test]
+async fn create_invitation_rejected(
+    fixture_admin: registry::MockAppRegistryExt,
+    #[case] body: serde_json::Value,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let app = make_router(fixture_admin);
+
+    let req = Request::post(v1("/invitations"))
+        .bearer()
+        .application_json()
+        .body(Body::from(body.to_string()))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn list_invitations_200(
+    mut fixture_admin: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_admin.expect_invitation_repository().returning(|| {
+        let mut mock = MockInvitationRepository::new();
+        mock.expect_find_all()
+            .returning(|| Ok(vec![invitation(Role::User, 5)]));
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_admin);
+
+    let req = Request::get(v1("/invitations"))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, InvitationsResponse);
+    assert_eq!(result.items.len(), 1);
+    assert_eq!(result.items[0].max_uses, 5);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(|| Ok(()), axum::http::StatusCode::OK)]
+#[case(
+    || Err(AppError::EntityNotFound("not found".into())),
+    axum::http::StatusCode::NOT_FOUND
+)]
+#[tokio::test]
+async fn delete_invitation(
+    mut fixture_admin: registry::MockAppRegistryExt,
+    #[case] result: fn() -> Result<(), AppError>,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let invitation_id = InvitationId::new();
+    fixture_admin
+        .expect_invitation_repository()
+        .returning(move || {
+            let mut mock = MockInvitationRepository::new();
+            mock.expect_delete().returning(move |event| {
+                assert_eq!(event.invitation_id, invitation_id);
+                result()
+            });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_admin);
+
+    let req = Request::delete(v1(&format!("/invitations/{invitation_id}")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("GET", "/invitations")]
+#[case("POST", "/invitations")]
+#[tokio::test]
+async fn invitations_403_not_admin(
+    fixture: registry::MockAppRegistryExt,
+    #[case] method: &str,
+    #[case] path: &str,
+) -> anyhow::Result<()> {
+    let app = make_router(fixture);
+
+    let req = Request::builder()
+        .method(method)
+        .uri(v1(path))
+        .bearer()
+        .application_json()
+        .body(Body::from(
+            serde_json::json!({
+                "role": "Admin",
+                "maxUses": 1,
+                "expiresAt": Utc::now() + Duration::days(1),
+            })
+            .to_string(),
+        ))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::FORBIDDEN);
+
+    Ok(())
+}
@@ -1,25 +1,41 @@
 use std::sync::Arc;
 
 use api::model::{
-    checkout::CheckoutsResponse,
-    item::{CreateItemRequest, ItemResponse, PaginatedItemResponse, UpdateItemRequest},
+    checkout::{CheckoutHistoryResponse, CheckoutsResponse},
+    item::{
+        CreateItemRequest, ImportItemsResponse, ItemExportRecord, ItemResponse,
+        PaginatedItemResponse, UpdateItemRequest,
+    },
+    list::{decode_cursor, encode_cursor},
+    reservation::{CreatedReservationResponse, ReservationsResponse},
 };
 use axum::{body::Body, http::Request};
 use kernel::{
     model::{
-        checkout::Checkout,
-        id::{CheckoutId, ItemId, UserId},
-        item::{Item, ItemCategory, book::Book},
-        list::PaginatedList,
+        category::CustomCategoryRef,
+        checkout::{Checkout, CheckoutRenewal, SimpleCheckout},
+        id::{CheckoutId, CustomCategoryId, ItemId, ReservationId, TagId, UserId},
+        item::{
+            CreateItem, Item, ItemArchive, ItemCategory, book::Book, custom::CustomItem,
+            general::GeneralItem, laptop::Laptop,
+        },
+        list::{Cursor, CursorDirection, CursorSort, ItemSort, PaginatedList, SortOrder, TagMatch},
+        reservation::Reservation,
+        tag::Tag,
+        user::CheckoutUser,
+    },
+    repository::{
+        checkout::MockCheckoutRepository, item::MockItemRepository,
+        reservation::MockReservationRepository,
     },
-    repository::{checkout::MockCheckoutRepository, item::MockItemRepository},
 };
 use rstest::rstest;
+use shared::error::AppError;
 use tower::ServiceExt;
 
 use crate::{
     deserialize_json,
-    helper::{TestRequestExt, fixture, fixture_admin, make_router, v1},
+    helper::{TestRequestExt, fixture, fixture_admin, fixture_librarian, make_router, v1},
 };
 
 #[rstest]
@@ -49,12 +65,16 @@ async fn list_items_200(
                 description: "RustによるWebアプリケーション開発".into(),
                 location: Some("Library".into()),
                 checkout: None,
+                archive: None,
+                tags: vec![],
             })];
             Ok(PaginatedList {
                 total: 1,
                 limit: opt.limit,
                 offset: opt.offset,
                 items,
+                next_cursor: None,
+                prev_cursor: None,
             })
         });
         Arc::new(mock)
@@ -73,10 +93,237 @@ async fn list_items_200(
     Ok(())
 }
 
+#[rstest]
+#[tokio::test]
+async fn list_items_with_search_filters_200(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture.expect_item_repository().returning(move || {
+        let mut mock = MockItemRepository::new();
+        mock.expect_find_all().returning(move |opt| {
+            assert_eq!(opt.filter.q.as_deref(), Some("rust web"));
+            assert_eq!(opt.filter.author.as_deref(), Some("Yuki Toyoda"));
+            assert_eq!(opt.filter.isbn, None);
+            assert_eq!(
+                opt.filter.mac_address.map(|m| m.to_string()),
+                Some("00:00:00:00:00:01".into())
+            );
+            assert_eq!(opt.filter.location, None);
+            assert_eq!(opt.filter.available, Some(true));
+            assert!(opt.filter.include_archived);
+            assert_eq!(opt.sort, ItemSort::default());
+            assert_eq!(opt.order, SortOrder::Desc);
+            Ok(PaginatedList {
+                total: 0,
+                limit: opt.limit,
+                offset: opt.offset,
+                items: vec![],
+                next_cursor: None,
+                prev_cursor: None,
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(v1(
+        "/items?q=rust%20web&author=Yuki%20Toyoda&isbn=&location=%20&mac_address=00:00:00:00:00:01&available=true&include_archived=true",
+    ))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/items?tags=contest%20kit", &["contest kit"], TagMatch::Any)]
+#[case("/items?tags=contest%20kit,%20JOI%20prep,&tag_match=all", &["contest kit", "JOI prep"], TagMatch::All)]
+#[case("/items?tags=&tag_match=any", &[], TagMatch::Any)]
+#[tokio::test]
+async fn list_items_with_tags_200(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] expected_tags: &'static [&'static str],
+    #[case] expected_match: TagMatch,
+) -> anyhow::Result<()> {
+    fixture.expect_item_repository().returning(move || {
+        let mut mock = MockItemRepository::new();
+        mock.expect_find_all().returning(move |opt| {
+            assert_eq!(opt.filter.tags, expected_tags);
+            assert_eq!(opt.filter.tag_match, expected_match);
+            Ok(PaginatedList {
+                total: 0,
+                limit: opt.limit,
+                offset: opt.offset,
+                items: vec![],
+                next_cursor: None,
+                prev_cursor: None,
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/items?sort=name", ItemSort::Name, SortOrder::Asc)]
+#[case("/items?sort=name&order=desc", ItemSort::Name, SortOrder::Desc)]
+#[case("/items?sort=updated_at", ItemSort::UpdatedAt, SortOrder::Desc)]
+#[case(
+    "/items?sort=checked_out_at&order=asc",
+    ItemSort::CheckedOutAt,
+    SortOrder::Asc
+)]
+#[case("/items?order=asc", ItemSort::CreatedAt, SortOrder::Asc)]
+#[tokio::test]
+async fn list_items_sorted_200(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] sort: ItemSort,
+    #[case] order: SortOrder,
+) -> anyhow::Result<()> {
+    fixture.expect_item_repository().returning(move || {
+        let mut mock = MockItemRepository::new();
+        mock.expect_find_all().returning(move |opt| {
+            assert_eq!(opt.sort, sort);
+            assert_eq!(opt.order, order);
+            Ok(PaginatedList {
+                total: 0,
+                limit: opt.limit,
+                offset: opt.offset,
+                items: vec![],
+                next_cursor: None,
+                prev_cursor: None,
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn list_items_with_cursor_200(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let cursor = Cursor {
+        key_text: Some("book005".into()),
+        key_time: None,
+        id: ItemId::new().raw(),
+        direction: CursorDirection::Before,
+        sort: Some(CursorSort::new(ItemSort::Name, SortOrder::Asc)),
+    };
+    let prev_cursor = Cursor {
+        key_text: Some("book001".into()),
+        ..cursor.clone()
+    };
+
+    let (expected, returned) = (cursor.clone(), prev_cursor.clone());
+    fixture.expect_item_repository().returning(move || {
+        let mut mock = MockItemRepository::new();
+        let (expected, returned) = (expected.clone(), returned.clone());
+        mock.expect_find_all().returning(move |opt| {
+            assert_eq!(opt.cursor.as_ref(), Some(&expected));
+            assert_eq!(opt.sort, ItemSort::Name);
+            Ok(PaginatedList {
+                total: 50,
+                limit: opt.limit,
+                offset: 0,
+                items: vec![],
+                next_cursor: None,
+                prev_cursor: Some(returned.clone()),
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(v1(&format!(
+        "/items?sort=name&limit=4&cursor={}",
+        encode_cursor(&cursor)
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, PaginatedItemResponse);
+    assert!(result.next_cursor.is_none());
+    assert_eq!(
+        result
+            .prev_cursor
+            .as_deref()
+            .map(decode_cursor)
+            .transpose()?,
+        Some(prev_cursor)
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[case(
+    "/items?sort=created_at",
+    Some(CursorSort::new(ItemSort::Name, SortOrder::Asc))
+)]
+#[case(
+    "/items?sort=name&order=desc",
+    Some(CursorSort::new(ItemSort::Name, SortOrder::Asc))
+)]
+#[case("/items?sort=name", None)]
+#[tokio::test]
+async fn list_items_400_cursor_from_another_sort(
+    fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] sort: Option<CursorSort>,
+) -> anyhow::Result<()> {
+    let app: axum::Router = make_router(fixture);
+
+    let cursor = Cursor {
+        key_text: Some("book005".into()),
+        key_time: None,
+        id: ItemId::new().raw(),
+        direction: CursorDirection::After,
+        sort,
+    };
+    let req = Request::get(v1(&format!("{path}&cursor={}", encode_cursor(&cursor))))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
 #[rstest]
 #[case("/items?limit=-1")]
+#[case("/items?limit=0")]
 #[case("/items?limit=101")]
 #[case("/items?offset=aaa")]
+#[case("/items?available=maybe")]
+#[case("/items?mac_address=invalid")]
+#[case("/items?sort=author")]
+#[case("/items?order=up")]
+#[case("/items?cursor=invalid")]
+#[case("/items?tag_match=some")]
 #[tokio::test]
 async fn list_items_400(
     fixture: registry::MockAppRegistryExt,
@@ -121,6 +368,339 @@ async fn create_item_201(mut fixture_admin: registry::MockAppRegistryExt) -> any
     Ok(())
 }
 
+#[rstest]
+#[tokio::test]
+async fn create_custom_item_201(
+    mut fixture_admin: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_admin.expect_item_repository().returning(move || {
+        let mut mock = MockItemRepository::new();
+        mock.expect_create().returning(|event| {
+            let CreateItem::Custom {
+                custom_category,
+                attributes,
+                ..
+            } = event
+            else {
+                panic!("Expected a custom item");
+            };
+            assert_eq!(custom_category, "monitor");
+            assert_eq!(attributes["screen_size"], 27);
+            Ok(())
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_admin);
+
+    let req = Request::post(v1("/items"))
+        .bearer()
+        .application_json()
+        .body(Body::from(
+            serde_json::json!({
+                "category": "custom",
+                "name": "Monitor 1",
+                "custom_category": "monitor",
+                "attributes": { "screen_size": 27 },
+                "description": "",
+            })
+            .to_string(),
+        ))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::CREATED);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn import_items_201(mut fixture_admin: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture_admin.expect_item_repository().returning(move || {
+        let mut mock = MockItemRepository::new();
+        mock.expect_create_many().times(1).returning(|events| {
+            assert_eq!(events.len(), 4);
+            assert!(matches!(
+                &events[0],
+                CreateItem::Book { name, author, location, .. }
+                    if name == "Rust in Action" && author == "Tim McNamara" && location.as_deref() == Some("Shelf A")
+            ));
+            assert!(matches!(
+                &events[1],
+                CreateItem::Laptop { mac_address, location: None, .. }
+                    if mac_address.to_string() == "00:11:22:33:44:55"
+            ));
+            assert!(matches!(
+                &events[2],
+                CreateItem::General { description, .. } if description.is_empty()
+            ));
+            assert!(matches!(
+                &events[3],
+                CreateItem::Custom { custom_category, attributes, .. }
+                    if custom_category == "monitor" && attributes["screen_size"] == 27
+            ));
+            Ok(())
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_admin);
+
+    let csv = "category,name,description,location,author,isbn,mac_address,custom_category,attributes\n\
+        book,Rust in Action,Systems programming,Shelf A,Tim McNamara,9781617294556,,,\n\
+        laptop,Lab laptop 1,ThinkPad,,,,00:11:22:33:44:55,,\n\
+        general,HDMI cable,,,,,,,\n\
+        custom,Monitor 1,,,,,,monitor,\"{\"\"screen_size\"\": 27}\"\n";
+    let req = Request::post(v1("/items/import"))
+        .bearer()
+        .header("Content-Type", "text/csv")
+        .body(Body::from(csv))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::CREATED);
+
+    let result = deserialize_json!(resp, ImportItemsResponse);
+    assert_eq!(result.imported, 4);
+    assert!(result.errors.is_empty());
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn import_items_422_reports_every_invalid_row(
+    fixture_admin: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let app = make_router(fixture_admin);
+
+    let csv = "category,name,author,isbn,mac_address\n\
+        book,Valid Book,Author,1234567890123,\n\
+        book,Missing ISBN,Author,,\n\
+        laptop,No MAC,,,\n\
+        laptop,Bad MAC,,,not-a-mac\n\
+        dvd,Unknown Category,,,\n\
+        general,,,,\n";
+    let req = Request::post(v1("/items/import"))
+        .bearer()
+        .header("Content-Type", "text/csv")
+        .body(Body::from(csv))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+
+    let result = deserialize_json!(resp, ImportItemsResponse);
+    assert_eq!(result.imported, 0);
+    assert_eq!(
+        result.errors.iter().map(|e| e.line).collect::<Vec<_>>(),
+        [3, 4, 5, 6, 7]
+    );
+    assert!(result.errors[0].message.contains("isbn"));
+    assert!(result.errors[1].message.contains("mac_address"));
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn import_items_422_empty(fixture_admin: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let app = make_router(fixture_admin);
+
+    let req = Request::post(v1("/items/import"))
+        .bearer()
+        .header("Content-Type", "text/csv")
+        .body(Body::from("category,name\n"))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn import_items_403_not_admin(fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let app = make_router(fixture);
+
+    let req = Request::post(v1("/items/import"))
+        .bearer()
+        .header("Content-Type", "text/csv")
+        .body(Body::from("category,name\ngeneral,Cable\n"))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+fn export_fixture() -> Vec<Item> {
+    let user_id = UserId::new();
+    vec![
+        Item::Book(Book {
+            id: ItemId::new(),
+            name: "Rust, in Action".into(),
+            isbn: "9781617294556".into(),
+            author: "Tim McNamara".into(),
+            description: "Systems programming".into(),
+            location: Some("Shelf A".into()),
+            checkout: Some(SimpleCheckout {
+                checkout_id: CheckoutId::new(),
+                checked_out_by: CheckoutUser {
+                    id: user_id,
+                    name: "Eleazar Fig".into(),
+                },
+                checked_out_at: chrono::Utc::now(),
+                due_at: chrono::Utc::now() + chrono::Duration::days(14),
+            }),
+            archive: None,
+            tags: vec![],
+        }),
+        Item::Laptop(Laptop {
+            id: ItemId::new(),
+            name: "Lab laptop 1".into(),
+            mac_address: mac_address::MacAddress::new([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
+            description: "".into(),
+            location: None,
+            checkout: None,
+            archive: None,
+            tags: vec![],
+        }),
+        Item::General(GeneralItem {
+            id: ItemId::new(),
+            name: "Broken projector".into(),
+            description: "".into(),
+            location: None,
+            checkout: None,
+            archive: Some(ItemArchive {
+                archived_at: chrono::Utc::now(),
+                reason: Some("broken".into()),
+            }),
+            tags: vec![],
+        }),
+    ]
+}
+
+#[rstest]
+#[case("/items/export", "text/csv; charset=utf-8")]
+#[case("/items/export?format=jsonl", "application/jsonl")]
+#[tokio::test]
+async fn export_items_200(
+    mut fixture_admin: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] content_type: &str,
+) -> anyhow::Result<()> {
+    fixture_admin.expect_item_repository().returning(move || {
+        let mut mock = MockItemRepository::new();
+        mock.expect_stream_all()
+            .returning(|| Box::pin(tokio_stream::iter(export_fixture().into_iter().map(Ok))));
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_admin);
+
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], content_type);
+    assert!(
+        resp.headers()["content-disposition"]
+            .to_str()?
+            .starts_with("attachment")
+    );
+
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await?;
+    let records: Vec<ItemExportRecord> = if content_type.starts_with("text/csv") {
+        csv::Reader::from_reader(&body[..])
+            .deserialize()
+            .collect::<Result<_, _>>()?
+    } else {
+        body.split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(serde_json::from_slice)
+            .collect::<Result<_, _>>()?
+    };
+
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].name, "Rust, in Action");
+    assert_eq!(records[0].author.as_deref(), Some("Tim McNamara"));
+    assert_eq!(records[0].location.as_deref(), Some("Shelf A"));
+    assert_eq!(
+        records[0].checked_out_by_name.as_deref(),
+        Some("Eleazar Fig")
+    );
+    assert_eq!(records[1].mac_address.as_deref(), Some("00:11:22:33:44:55"));
+    assert_eq!(records[1].location, None);
+    assert_eq!(records[1].checked_out_by_id, None);
+    assert_eq!(records[1].archived_at, None);
+    assert!(records[2].archived_at.is_some());
+    assert_eq!(records[2].archive_reason.as_deref(), Some("broken"));
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn export_items_403_not_admin(fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let app = make_router(fixture);
+
+    let req = Request::get(v1("/items/export"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn export_items_400_unknown_format(
+    fixture_admin: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let app = make_router(fixture_admin);
+
+    let req = Request::get(v1("/items/export?format=xml"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn create_item_201_librarian(
+    mut fixture_librarian: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_librarian
+        .expect_item_repository()
+        .returning(move || {
+            let mut mock = MockItemRepository::new();
+            mock.expect_create().returning(|_| Ok(()));
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_librarian);
+
+    let req = CreateItemRequest::Book {
+        name: "Test Book".into(),
+        author: "Test Author".into(),
+        isbn: "1234567890123".into(),
+        description: "Test Description".into(),
+        location: None,
+    };
+
+    let req = Request::post(v1("/items"))
+        .bearer()
+        .application_json()
+        .body(Body::from(serde_json::to_string(&req)?))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::CREATED);
+
+    Ok(())
+}
+
 #[rstest]
 #[tokio::test]
 async fn create_item_403_not_admin(fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
@@ -193,6 +773,11 @@ async fn get_item_200(mut fixture: registry::MockAppRegistryExt) -> anyhow::Resu
                 description: "Test Description".into(),
                 location: Some("Shelf A".into()),
                 checkout: None,
+                archive: None,
+                tags: vec![Tag {
+                    id: TagId::new(),
+                    name: "contest kit".into(),
+                }],
             })))
         });
         Arc::new(mock)
@@ -215,6 +800,8 @@ async fn get_item_200(mut fixture: registry::MockAppRegistryExt) -> anyhow::Resu
             assert_eq!(book.isbn, "1234567890123");
             assert_eq!(book.description, "Test Description");
             assert_eq!(book.location, Some("Shelf A".into()));
+            assert_eq!(book.tags.len(), 1);
+            assert_eq!(book.tags[0].name, "contest kit");
         }
         _ => panic!("Expected BookResponse"),
     }
@@ -222,6 +809,57 @@ async fn get_item_200(mut fixture: registry::MockAppRegistryExt) -> anyhow::Resu
     Ok(())
 }
 
+#[rstest]
+#[tokio::test]
+async fn get_custom_item_200(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let item_id = ItemId::new();
+    fixture.expect_item_repository().returning(move || {
+        let mut mock = MockItemRepository::new();
+        mock.expect_find_by_id().returning(move |_id| {
+            Ok(Some(Item::Custom(CustomItem {
+                id: item_id,
+                name: "Monitor 1".into(),
+                category: CustomCategoryRef {
+                    id: CustomCategoryId::new(),
+                    key: "monitor".into(),
+                    name: "Monitor".into(),
+                },
+                attributes: serde_json::json!({ "screen_size": 27 })
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+                description: "".into(),
+                location: None,
+                checkout: None,
+                archive: None,
+                tags: vec![],
+            })))
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+
+    let req = Request::get(v1(&format!("/items/{item_id}")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, ItemResponse);
+    match result {
+        ItemResponse::Custom(item) => {
+            assert_eq!(item.name, "Monitor 1");
+            assert_eq!(item.custom_category.key, "monitor");
+            assert_eq!(item.attributes["screen_size"], 27);
+        }
+        _ => panic!("Expected CustomItemResponse"),
+    }
+
+    Ok(())
+}
+
 #[rstest]
 #[tokio::test]
 async fn get_item_404(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
@@ -339,13 +977,17 @@ async fn delete_item_200(mut fixture_admin: registry::MockAppRegistryExt) -> any
     let item_id = ItemId::new();
     fixture_admin.expect_item_repository().returning(move || {
         let mut mock = MockItemRepository::new();
-        mock.expect_delete().returning(|_| Ok(()));
+        mock.expect_delete().returning(move |event| {
+            assert_eq!(event.item_id, item_id);
+            assert_eq!(event.reason.as_deref(), Some("lost"));
+            Ok(())
+        });
         Arc::new(mock)
     });
 
     let app = make_router(fixture_admin);
 
-    let req = Request::delete(v1(&format!("/items/{item_id}")))
+    let req = Request::delete(v1(&format!("/items/{item_id}?reason=lost")))
         .bearer()
         .body(Body::empty())?;
 
@@ -355,6 +997,31 @@ async fn delete_item_200(mut fixture_admin: registry::MockAppRegistryExt) -> any
     Ok(())
 }
 
+#[rstest]
+#[tokio::test]
+async fn delete_item_409_checked_out(
+    mut fixture_admin: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let item_id = ItemId::new();
+    fixture_admin.expect_item_repository().returning(move || {
+        let mut mock = MockItemRepository::new();
+        mock.expect_delete()
+            .returning(|_| Err(AppError::Conflict("checked out".into())));
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_admin);
+
+    let req = Request::delete(v1(&format!("/items/{item_id}")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::CONFLICT);
+
+    Ok(())
+}
+
 #[rstest]
 #[tokio::test]
 async fn delete_item_403_not_admin(fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
@@ -371,6 +1038,60 @@ async fn delete_item_403_not_admin(fixture: registry::MockAppRegistryExt) -> any
     Ok(())
 }
 
+#[rstest]
+#[case(Ok(()), axum::http::StatusCode::OK)]
+#[case(
+    Err(AppError::Conflict("not archived".into())),
+    axum::http::StatusCode::CONFLICT
+)]
+#[case(
+    Err(AppError::EntityNotFound("not found".into())),
+    axum::http::StatusCode::NOT_FOUND
+)]
+#[tokio::test]
+async fn restore_item(
+    mut fixture_admin: registry::MockAppRegistryExt,
+    #[case] result: Result<(), AppError>,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let item_id = ItemId::new();
+    fixture_admin.expect_item_repository().return_once(move || {
+        let mut mock = MockItemRepository::new();
+        mock.expect_restore().return_once(move |event| {
+            assert_eq!(event.item_id, item_id);
+            result
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_admin);
+
+    let req = Request::put(v1(&format!("/items/{item_id}/restore")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn restore_item_403_not_admin(fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let item_id = ItemId::new();
+    let app = make_router(fixture);
+
+    let req = Request::put(v1(&format!("/items/{item_id}/restore")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
 #[rstest]
 #[tokio::test]
 async fn show_checked_out_list_200(
@@ -388,8 +1109,10 @@ async fn show_checked_out_list_200(
                 id: checkout_id,
                 checked_out_by: user_id,
                 checked_out_at: now,
+                due_at: now + chrono::Duration::days(14),
                 returned_at: None,
                 item_id,
+                renewals: vec![],
             }])
         });
         Arc::new(mock)
@@ -410,12 +1133,54 @@ async fn show_checked_out_list_200(
     assert_eq!(checkout.id, checkout_id);
     assert_eq!(checkout.checked_out_by, user_id);
     assert_eq!(checkout.checked_out_at, now);
+    assert_eq!(checkout.due_at, now + chrono::Duration::days(14));
+    assert!(!checkout.is_overdue);
     assert_eq!(checkout.returned_at, None);
     assert_eq!(checkout.item_id, item_id);
 
     Ok(())
 }
 
+#[rstest]
+#[tokio::test]
+async fn show_overdue_list_200(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let item_id = ItemId::new();
+    let checkout_id = CheckoutId::new();
+    let checked_out_at = chrono::Utc::now() - chrono::Duration::days(10);
+
+    fixture.expect_checkout_repository().returning(move || {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_find_overdue_all().returning(move || {
+            Ok(vec![Checkout {
+                id: checkout_id,
+                checked_out_by: UserId::new(),
+                checked_out_at,
+                due_at: checked_out_at + chrono::Duration::days(7),
+                returned_at: None,
+                item_id,
+                renewals: vec![],
+            }])
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+
+    let req = Request::get(v1("/items/checkouts/overdue"))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, CheckoutsResponse);
+    assert_eq!(result.items.len(), 1);
+    assert_eq!(result.items[0].id, checkout_id);
+    assert!(result.items[0].is_overdue);
+
+    Ok(())
+}
+
 #[rstest]
 #[tokio::test]
 async fn checkout_item_201(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
@@ -426,11 +1191,79 @@ async fn checkout_item_201(mut fixture: registry::MockAppRegistryExt) -> anyhow:
         Arc::new(mock)
     });
 
-    let app = make_router(fixture);
+    let app = make_router(fixture);
+
+    let req = Request::post(v1(&format!("/items/{item_id}/checkouts")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::CREATED);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn checkout_item_as_admin_for_user_201(
+    mut fixture_admin: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let item_id = ItemId::new();
+    let target_user_id = UserId::new();
+
+    fixture_admin
+        .expect_checkout_repository()
+        .returning(move || {
+            let mut mock = MockCheckoutRepository::new();
+            mock.expect_create().returning(move |event| {
+                assert_eq!(event.checked_out_by, target_user_id);
+                Ok(())
+            });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_admin);
+
+    let req = Request::post(v1(&format!("/items/{item_id}/checkouts")))
+        .bearer()
+        .application_json()
+        .body(Body::from(
+            serde_json::json!({ "checkedOutBy": target_user_id }).to_string(),
+        ))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::CREATED);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn checkout_item_as_librarian_for_user_201(
+    mut fixture_librarian: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let item_id = ItemId::new();
+    let target_user_id = UserId::new();
+
+    fixture_librarian
+        .expect_checkout_repository()
+        .returning(move || {
+            let mut mock = MockCheckoutRepository::new();
+            mock.expect_create().returning(move |event| {
+                assert_eq!(event.checked_out_by, target_user_id);
+                Ok(())
+            });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_librarian);
 
     let req = Request::post(v1(&format!("/items/{item_id}/checkouts")))
         .bearer()
-        .body(Body::empty())?;
+        .application_json()
+        .body(Body::from(
+            serde_json::json!({ "checkedOutBy": target_user_id }).to_string(),
+        ))?;
 
     let resp = app.oneshot(req).await?;
     assert_eq!(resp.status(), axum::http::StatusCode::CREATED);
@@ -440,20 +1273,19 @@ async fn checkout_item_201(mut fixture: registry::MockAppRegistryExt) -> anyhow:
 
 #[rstest]
 #[tokio::test]
-async fn checkout_item_as_admin_for_user_201(
+async fn checkout_item_with_due_date_as_admin_201(
     mut fixture_admin: registry::MockAppRegistryExt,
 ) -> anyhow::Result<()> {
     let item_id = ItemId::new();
-    let target_user_id = UserId::new();
+    let due_at = chrono::Utc::now() + chrono::Duration::days(30);
 
     fixture_admin
         .expect_checkout_repository()
         .returning(move || {
             let mut mock = MockCheckoutRepository::new();
-            mock.expect_create().returning(move |event| {
-                assert_eq!(event.checked_out_by, target_user_id);
-                Ok(())
-            });
+            mock.expect_create()
+                .withf(move |event| event.due_at == Some(due_at))
+                .returning(|_event| Ok(()));
             Arc::new(mock)
         });
 
@@ -463,7 +1295,7 @@ async fn checkout_item_as_admin_for_user_201(
         .bearer()
         .application_json()
         .body(Body::from(
-            serde_json::json!({ "checkedOutBy": target_user_id }).to_string(),
+            serde_json::json!({ "dueAt": due_at }).to_string(),
         ))?;
 
     let resp = app.oneshot(req).await?;
@@ -472,6 +1304,35 @@ async fn checkout_item_as_admin_for_user_201(
     Ok(())
 }
 
+#[rstest]
+#[case(chrono::Duration::days(30), false, axum::http::StatusCode::FORBIDDEN)]
+#[case(-chrono::Duration::days(1), true, axum::http::StatusCode::UNPROCESSABLE_ENTITY)]
+#[tokio::test]
+async fn checkout_item_with_due_date_rejected(
+    fixture: registry::MockAppRegistryExt,
+    fixture_admin: registry::MockAppRegistryExt,
+    #[case] offset: chrono::Duration,
+    #[case] as_admin: bool,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let item_id = ItemId::new();
+    let due_at = chrono::Utc::now() + offset;
+
+    let app = make_router(if as_admin { fixture_admin } else { fixture });
+
+    let req = Request::post(v1(&format!("/items/{item_id}/checkouts")))
+        .bearer()
+        .application_json()
+        .body(Body::from(
+            serde_json::json!({ "dueAt": due_at }).to_string(),
+        ))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
 #[rstest]
 #[tokio::test]
 async fn checkout_item_for_other_user_fails_403(
@@ -525,6 +1386,91 @@ async fn return_item_200(mut fixture: registry::MockAppRegistryExt) -> anyhow::R
     Ok(())
 }
 
+#[rstest]
+#[tokio::test]
+async fn renew_checkout_200(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let item_id = ItemId::new();
+    let checkout_id = CheckoutId::new();
+    fixture.expect_checkout_repository().returning(move || {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_renew()
+            .withf(move |event| {
+                event.item_id == item_id
+                    && event.checkout_id == checkout_id
+                    && event.due_at.is_none()
+            })
+            .returning(|_event| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+
+    let req = Request::put(v1(&format!(
+        "/items/{item_id}/checkouts/{checkout_id}/renewal"
+    )))
+    .bearer()
+    .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn renew_checkout_with_due_date_non_admin_fails_403(
+    fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let item_id = ItemId::new();
+    let checkout_id = CheckoutId::new();
+    let due_at = chrono::Utc::now() + chrono::Duration::days(30);
+
+    let app = make_router(fixture);
+
+    let req = Request::put(v1(&format!(
+        "/items/{item_id}/checkouts/{checkout_id}/renewal"
+    )))
+    .bearer()
+    .application_json()
+    .body(Body::from(
+        serde_json::json!({ "dueAt": due_at }).to_string(),
+    ))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn renew_checkout_limit_reached_409(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture.expect_checkout_repository().returning(|| {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_renew()
+            .returning(|_event| Err(AppError::Conflict("limit reached".into())));
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+
+    let req = Request::put(v1(&format!(
+        "/items/{}/checkouts/{}/renewal",
+        ItemId::new(),
+        CheckoutId::new()
+    )))
+    .bearer()
+    .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::CONFLICT);
+
+    Ok(())
+}
+
 #[rstest]
 #[tokio::test]
 async fn checkout_history_200(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
@@ -544,6 +1490,8 @@ async fn checkout_history_200(mut fixture: registry::MockAppRegistryExt) -> anyh
                 description: "Test Description".into(),
                 location: None,
                 checkout: None,
+                archive: None,
+                tags: vec![],
             })))
         });
         Arc::new(mock)
@@ -551,15 +1499,32 @@ async fn checkout_history_200(mut fixture: registry::MockAppRegistryExt) -> anyh
 
     fixture.expect_checkout_repository().returning(move || {
         let mut mock = MockCheckoutRepository::new();
-        mock.expect_find_history_by_item_id().returning(move |_id| {
-            Ok(vec![Checkout {
-                id: checkout_id,
-                checked_out_by: user_id,
-                checked_out_at: now,
-                returned_at: Some(now),
-                item_id,
-            }])
-        });
+        mock.expect_find_history_by_item_id()
+            .returning(move |_id, opt| {
+                assert_eq!(opt.limit, None);
+                assert_eq!(opt.cursor, None);
+                Ok(PaginatedList {
+                    total: 1,
+                    limit: 1,
+                    offset: 0,
+                    items: vec![Checkout {
+                        id: checkout_id,
+                        checked_out_by: user_id,
+                        checked_out_at: now,
+                        due_at: now + chrono::Duration::days(14),
+                        returned_at: Some(now),
+                        item_id,
+                        renewals: vec![CheckoutRenewal {
+                            renewed_by: user_id,
+                            renewed_at: now,
+                            previous_due_at: now + chrono::Duration::days(7),
+                            due_at: now + chrono::Duration::days(14),
+                        }],
+                    }],
+                    next_cursor: None,
+                    prev_cursor: None,
+                })
+            });
         Arc::new(mock)
     });
 
@@ -572,14 +1537,124 @@ async fn checkout_history_200(mut fixture: registry::MockAppRegistryExt) -> anyh
     let resp = app.oneshot(req).await?;
     assert_eq!(resp.status(), axum::http::StatusCode::OK);
 
-    let result = deserialize_json!(resp, CheckoutsResponse);
+    let result = deserialize_json!(resp, CheckoutHistoryResponse);
+    assert_eq!(result.total, 1);
     assert_eq!(result.items.len(), 1);
+    assert!(result.next_cursor.is_none());
     let checkout = &result.items[0];
     assert_eq!(checkout.id, checkout_id);
     assert_eq!(checkout.checked_out_by, user_id);
     assert_eq!(checkout.checked_out_at, now);
     assert_eq!(checkout.returned_at, Some(now));
     assert_eq!(checkout.item_id, item_id);
+    assert_eq!(checkout.renewals.len(), 1);
+    assert_eq!(checkout.renewals[0].renewed_by, user_id);
+    assert_eq!(checkout.renewals[0].due_at, checkout.due_at);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn checkout_history_paged_200(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let item_id = ItemId::new();
+    let cursor = Cursor {
+        key_text: None,
+        key_time: Some(chrono::Utc::now()),
+        id: CheckoutId::new().raw(),
+        direction: CursorDirection::After,
+        sort: None,
+    };
+    let next_cursor = Cursor {
+        id: CheckoutId::new().raw(),
+        ..cursor.clone()
+    };
+
+    fixture.expect_item_repository().returning(move || {
+        let mut mock = MockItemRepository::new();
+        mock.expect_find_by_id().returning(move |_id| {
+            Ok(Some(Item::Book(Book {
+                id: item_id,
+                name: "Test Book".into(),
+                isbn: "1234567890123".into(),
+                author: "Test Author".into(),
+                description: "Test Description".into(),
+                location: None,
+                checkout: None,
+                archive: None,
+                tags: vec![],
+            })))
+        });
+        Arc::new(mock)
+    });
+
+    let (expected, returned) = (cursor.clone(), next_cursor.clone());
+    fixture.expect_checkout_repository().returning(move || {
+        let mut mock = MockCheckoutRepository::new();
+        let (expected, returned) = (expected.clone(), returned.clone());
+        mock.expect_find_history_by_item_id()
+            .returning(move |_id, opt| {
+                assert_eq!(opt.limit, Some(10));
+                assert_eq!(opt.cursor.as_ref(), Some(&expected));
+                Ok(PaginatedList {
+                    total: 30,
+                    limit: 10,
+                    offset: 0,
+                    items: vec![],
+                    next_cursor: Some(returned.clone()),
+                    prev_cursor: None,
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+
+    let req = Request::get(v1(&format!(
+        "/items/{item_id}/checkout-history?limit=10&cursor={}",
+        encode_cursor(&cursor)
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, CheckoutHistoryResponse);
+    assert_eq!(result.total, 30);
+    assert_eq!(
+        result
+            .next_cursor
+            .as_deref()
+            .map(decode_cursor)
+            .transpose()?,
+        Some(next_cursor)
+    );
+    assert!(result.prev_cursor.is_none());
+
+    Ok(())
+}
+
+#[rstest]
+#[case("limit=0")]
+#[case("limit=101")]
+#[case("cursor=invalid")]
+#[tokio::test]
+async fn checkout_history_400(
+    fixture: registry::MockAppRegistryExt,
+    #[case] query: &str,
+) -> anyhow::Result<()> {
+    let app = make_router(fixture);
+
+    let req = Request::get(v1(&format!(
+        "/items/{}/checkout-history?{query}",
+        ItemId::new()
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::BAD_REQUEST);
 
     Ok(())
 }
@@ -673,3 +1748,144 @@ async fn return_item_non_admin_fails_403(
 
     Ok(())
 }
+
+#[rstest]
+#[tokio::test]
+async fn reserve_item_201(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let item_id = ItemId::new();
+    let reservation_id = ReservationId::new();
+    fixture.expect_reservation_repository().returning(move || {
+        let mut mock = MockReservationRepository::new();
+        mock.expect_create()
+            .withf(move |event| event.item_id == item_id)
+            .returning(move |_event| Ok(reservation_id));
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+
+    let req = Request::post(v1(&format!("/items/{item_id}/reservations")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::CREATED);
+
+    let result = deserialize_json!(resp, CreatedReservationResponse);
+    assert_eq!(result.id, reservation_id);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn reserve_available_item_409(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture.expect_reservation_repository().returning(|| {
+        let mut mock = MockReservationRepository::new();
+        mock.expect_create()
+            .returning(|_event| Err(AppError::Conflict("available".into())));
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+
+    let req = Request::post(v1(&format!("/items/{}/reservations", ItemId::new())))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::CONFLICT);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn cancel_reservation_204(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let item_id = ItemId::new();
+    let reservation_id = ReservationId::new();
+    fixture.expect_reservation_repository().returning(move || {
+        let mut mock = MockReservationRepository::new();
+        mock.expect_delete()
+            .withf(move |event| event.item_id == item_id && event.reservation_id == reservation_id)
+            .returning(|_event| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+
+    let req = Request::delete(v1(&format!(
+        "/items/{item_id}/reservations/{reservation_id}"
+    )))
+    .bearer()
+    .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::NO_CONTENT);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_reservations_200(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let item_id = ItemId::new();
+    let reservation_id = ReservationId::new();
+    let user_id = UserId::new();
+    let now = chrono::Utc::now();
+
+    fixture.expect_item_repository().returning(move || {
+        let mut mock = MockItemRepository::new();
+        mock.expect_find_by_id().returning(move |_id| {
+            Ok(Some(Item::Book(Book {
+                id: item_id,
+                name: "Test Book".into(),
+                isbn: "1234567890123".into(),
+                author: "Test Author".into(),
+                description: "Test Description".into(),
+                location: None,
+                checkout: None,
+                archive: None,
+                tags: vec![],
+            })))
+        });
+        Arc::new(mock)
+    });
+
+    fixture.expect_reservation_repository().returning(move || {
+        let mut mock = MockReservationRepository::new();
+        mock.expect_find_by_item_id().returning(move |_id| {
+            Ok(vec![Reservation {
+                id: reservation_id,
+                item_id,
+                reserved_by: user_id,
+                reserved_at: now,
+                position: 1,
+                ready_at: Some(now),
+                expires_at: Some(now + chrono::Duration::hours(48)),
+            }])
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+
+    let req = Request::get(v1(&format!("/items/{item_id}/reservations")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, ReservationsResponse);
+    assert_eq!(result.items.len(), 1);
+    let reservation = &result.items[0];
+    assert_eq!(reservation.id, reservation_id);
+    assert_eq!(reservation.reserved_by, user_id);
+    assert_eq!(reservation.position, 1);
+    assert_eq!(reservation.ready_at, Some(now));
+
+    Ok(())
+}
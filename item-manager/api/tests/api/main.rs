@@ -1,4 +1,10 @@
+mod audit;
 mod auth;
+mod category;
 mod helper;
+mod invitation;
 mod item;
+mod personal_access_token;
+mod tag;
+mod two_factor;
 mod user;
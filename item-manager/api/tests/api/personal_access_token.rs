@@ -0,0 +1,333 @@
+use std::sync::Arc;
+
+use api::model::{
+    personal_access_token::{CreatedPersonalAccessTokenResponse, PersonalAccessTokensResponse},
+    user::UserResponse,
+};
+use axum::{body::Body, http::Request};
+use chrono::{Duration, Utc};
+use kernel::{
+    model::{
+        id::{PersonalAccessTokenId, UserId},
+        personal_access_token::{CreatedPersonalAccessToken, PersonalAccessToken, TokenScope},
+    },
+    repository::personal_access_token::MockPersonalAccessTokenRepository,
+};
+use rstest::rstest;
+use shared::error::AppError;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{TestRequestExt, fixture, fixture_admin, make_router, v1},
+};
+
+fn token(scopes: Vec<TokenScope>) -> PersonalAccessToken {
+    PersonalAccessToken {
+        id: PersonalAccessTokenId::new(),
+        user_id: UserId::new(),
+        name: "inventory check".into(),
+        scopes,
+        created_at: Utc::now(),
+        expires_at: Utc::now() + Duration::days(30),
+        last_used_at: None,
+    }
+}
+
+/// Authenticates `pat_valid` as a token with the given scopes.
+fn expect_token(registry: &mut registry::MockAppRegistryExt, scopes: Vec<TokenScope>) {
+    registry
+        .expect_personal_access_token_repository()
+        .returning(move || {
+            let mut mock = MockPersonalAccessTokenRepository::new();
+            let scopes = scopes.clone();
+            mock.expect_authenticate().returning(move |secret| {
+                Ok((secret == "pat_valid").then(|| token(scopes.clone())))
+            });
+            Arc::new(mock)
+        });
+}
+
+#[rstest]
+#[tokio::test]
+async fn create_token_201(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture
+        .expect_personal_access_token_repository()
+        .returning(|| {
+            let mut mock = MockPersonalAccessTokenRepository::new();
+            mock.expect_create().returning(|event| {
+                assert_eq!(event.name, "inventory check");
+                assert_eq!(event.scopes, [TokenScope::Read, TokenScope::Write]);
+                Ok(CreatedPersonalAccessToken {
+                    token: token(event.scopes),
+                    secret: "pat_secret".into(),
+                })
+            });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture);
+
+    let req = Request::post(v1("/users/me/tokens"))
+        .bearer()
+        .application_json()
+        .body(Body::from(
+            serde_json::json!({
+                "name": "inventory check",
+                "scopes": ["write", "read", "write"],
+                "expiresAt": Utc::now() + Duration::days(30),
+            })
+            .to_string(),
+        ))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::CREATED);
+
+    let result = deserialize_json!(resp, CreatedPersonalAccessTokenResponse);
+    assert_eq!(result.secret, "pat_secret");
+    assert_eq!(result.token.name, "inventory check");
+
+    Ok(())
+}
+
+#[rstest]
+#[case(serde_json::json!({ "name": "", "scopes": ["read"], "expiresAt": Utc::now() + Duration::days(1) }), axum::http::StatusCode::BAD_REQUEST)]
+#[case(serde_json::json!({ "name": "ci", "scopes": [], "expiresAt": Utc::now() + Duration::days(1) }), axum::http::StatusCode::BAD_REQUEST)]
+#[case(serde_json::json!({ "name": "ci", "scopes": ["read"], "expiresAt": Utc::now() - Duration::days(1) }), axum::http::StatusCode::UNPROCESSABLE_ENTITY)]
+#[case(serde_json::json!({ "name": "ci", "scopes": ["read"], "expiresAt": Utc::now() + Duration::days(400) }), axum::http::StatusCode::UNPROCESSABLE_ENTITY)]
+#[tokio::test]
+async fn create_token_rejected(
+    fixture: registry::MockAppRegistryExt,
+    #[case] body: serde_json::Value,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let app = make_router(fixture);
+
+    let req = Request::post(v1("/users/me/tokens"))
+        .bearer()
+        .application_json()
+        .body(Body::from(body.to_string()))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn list_tokens_200(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture
+        .expect_personal_access_token_repository()
+        .returning(|| {
+            let mut mock = MockPersonalAccessTokenRepository::new();
+            mock.expect_find_by_user_id()
+                .returning(|_| Ok(vec![token(vec![TokenScope::Read])]));
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture);
+
+    let req = Request::get(v1("/users/me/tokens"))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, PersonalAccessTokensResponse);
+    assert_eq!(result.items.len(), 1);
+    assert_eq!(result.items[0].scopes, [TokenScope::Read]);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(|| Ok(()), axum::http::StatusCode::OK)]
+#[case(
+    || Err(AppError::EntityNotFound("not found".into())),
+    axum::http::StatusCode::NOT_FOUND
+)]
+#[tokio::test]
+async fn delete_token(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] result: fn() -> Result<(), AppError>,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let token_id = PersonalAccessTokenId::new();
+    fixture
+        .expect_personal_access_token_repository()
+        .returning(move || {
+            let mut mock = MockPersonalAccessTokenRepository::new();
+            mock.expect_delete().returning(move |event| {
+                assert_eq!(event.token_id, token_id);
+                result()
+            });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture);
+
+    let req = Request::delete(v1(&format!("/users/me/tokens/{token_id}")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("GET", "/users/me", "pat_valid", axum::http::StatusCode::OK)]
+#[case(
+    "GET",
+    "/users/me",
+    "pat_unknown",
+    axum::http::StatusCode::UNAUTHORIZED
+)]
+#[case(
+    "PUT",
+    "/users/me/name",
+    "pat_valid",
+    axum::http::StatusCode::FORBIDDEN
+)]
+#[case(
+    "GET",
+    "/users/me/tokens",
+    "pat_valid",
+    axum::http::StatusCode::FORBIDDEN
+)]
+#[case("GET", "/users/me/2fa", "pat_valid", axum::http::StatusCode::FORBIDDEN)]
+#[tokio::test]
+async fn authenticate_with_read_only_token(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] method: &str,
+    #[case] path: &str,
+    #[case] secret: &str,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    expect_token(&mut fixture, vec![TokenScope::Read]);
+
+    let app = make_router(fixture);
+
+    let req = Request::builder()
+        .method(method)
+        .uri(v1(path))
+        .header("Authorization", format!("Bearer {secret}"))
+        .application_json()
+        .body(Body::from(
+            serde_json::json!({ "name": "Renamed" }).to_string(),
+        ))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+    if expected == axum::http::StatusCode::OK {
+        let result = deserialize_json!(resp, UserResponse);
+        assert_eq!(result.name, "dummy-user");
+    }
+
+    Ok(())
+}
+
+#[rstest]
+#[case(
+    "/users/me/password",
+    serde_json::json!({ "currentPassword": "old password", "newPassword": "new password" })
+)]
+#[case("/users/me/name", serde_json::json!({ "name": "Renamed" }))]
+#[case("/users/me/email", serde_json::json!({ "email": "attacker@example.com" }))]
+#[tokio::test]
+async fn account_changes_403_with_write_token(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] body: serde_json::Value,
+) -> anyhow::Result<()> {
+    expect_token(&mut fixture, vec![TokenScope::Read, TokenScope::Write]);
+
+    let app = make_router(fixture);
+
+    let req = Request::put(v1(path))
+        .header("Authorization", "Bearer pat_valid")
+        .application_json()
+        .body(Body::from(body.to_string()))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::FORBIDDEN);
+    assert!(resp.headers().get("set-cookie").is_none());
+
+    Ok(())
+}
+
+#[rstest]
+#[case("GET", "/users", serde_json::Value::Null)]
+#[case(
+    "POST",
+    "/users",
+    serde_json::json!({ "name": "Intruder", "email": "intruder@example.com", "password": "password" })
+)]
+#[case(
+    "DELETE",
+    "/users/9582f9de-0fd1-4892-b20c-70139a7eb95b",
+    serde_json::Value::Null
+)]
+#[case(
+    "PUT",
+    "/users/9582f9de-0fd1-4892-b20c-70139a7eb95b/role",
+    serde_json::json!({ "role": "Admin" })
+)]
+#[case(
+    "PUT",
+    "/users/9582f9de-0fd1-4892-b20c-70139a7eb95b/deactivate",
+    serde_json::Value::Null
+)]
+#[case(
+    "PUT",
+    "/users/9582f9de-0fd1-4892-b20c-70139a7eb95b/reactivate",
+    serde_json::Value::Null
+)]
+#[case(
+    "PUT",
+    "/users/9582f9de-0fd1-4892-b20c-70139a7eb95b/unlock",
+    serde_json::Value::Null
+)]
+#[case("GET", "/invitations", serde_json::Value::Null)]
+#[case(
+    "POST",
+    "/invitations",
+    serde_json::json!({ "role": "Admin", "maxUses": 1, "expiresAt": Utc::now() + Duration::days(1) })
+)]
+#[case(
+    "DELETE",
+    "/invitations/9582f9de-0fd1-4892-b20c-70139a7eb95b",
+    serde_json::Value::Null
+)]
+#[tokio::test]
+async fn user_management_403_with_admin_write_token(
+    mut fixture_admin: registry::MockAppRegistryExt,
+    #[case] method: &str,
+    #[case] path: &str,
+    #[case] body: serde_json::Value,
+) -> anyhow::Result<()> {
+    expect_token(
+        &mut fixture_admin,
+        vec![TokenScope::Read, TokenScope::Write],
+    );
+
+    let app = make_router(fixture_admin);
+
+    let req = Request::builder()
+        .method(method)
+        .uri(v1(path))
+        .header("Authorization", "Bearer pat_valid");
+    let req = if body.is_null() {
+        req.body(Body::empty())?
+    } else {
+        req.application_json().body(Body::from(body.to_string()))?
+    };
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::FORBIDDEN);
+
+    Ok(())
+}
@@ -0,0 +1,251 @@
+use std::sync::Arc;
+
+use api::model::tag::{TagResponse, TagsResponse};
+use axum::{body::Body, http::Request};
+use kernel::{
+    model::{
+        id::{ItemId, TagId},
+        tag::Tag,
+    },
+    repository::tag::MockTagRepository,
+};
+use rstest::rstest;
+use shared::error::AppError;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{TestRequestExt, fixture, fixture_librarian, make_router, v1},
+};
+
+#[rstest]
+#[tokio::test]
+async fn list_tags_200(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture.expect_tag_repository().returning(|| {
+        let mut mock = MockTagRepository::new();
+        mock.expect_find_all().returning(|| {
+            Ok(vec![Tag {
+                id: TagId::new(),
+                name: "contest kit".into(),
+            }])
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+
+    let req = Request::get(v1("/tags")).bearer().body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, TagsResponse);
+    assert_eq!(result.items.len(), 1);
+    assert_eq!(result.items[0].name, "contest kit");
+
+    Ok(())
+}
+
+#[rstest]
+#[case(|name| Ok(Tag { id: TagId::new(), name }), axum::http::StatusCode::CREATED)]
+#[case(
+    |_| Err(AppError::Conflict("duplicate".into())),
+    axum::http::StatusCode::CONFLICT
+)]
+#[tokio::test]
+async fn create_tag(
+    mut fixture_librarian: registry::MockAppRegistryExt,
+    #[case] result: fn(String) -> Result<Tag, AppError>,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    fixture_librarian
+        .expect_tag_repository()
+        .returning(move || {
+            let mut mock = MockTagRepository::new();
+            mock.expect_create()
+                .returning(move |event| result(event.name));
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_librarian);
+
+    let req = Request::post(v1("/tags"))
+        .bearer()
+        .application_json()
+        .body(Body::from(
+            serde_json::json!({ "name": "JOI prep" }).to_string(),
+        ))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+    if expected == axum::http::StatusCode::CREATED {
+        let result = deserialize_json!(resp, TagResponse);
+        assert_eq!(result.name, "JOI prep");
+    }
+
+    Ok(())
+}
+
+#[rstest]
+#[case(serde_json::json!({ "name": "" }))]
+#[case(serde_json::json!({ "name": "   " }))]
+#[case(serde_json::json!({ "name": "kit,prep" }))]
+#[case(serde_json::json!({ "name": "x".repeat(65) }))]
+#[tokio::test]
+async fn create_tag_400(
+    fixture_librarian: registry::MockAppRegistryExt,
+    #[case] body: serde_json::Value,
+) -> anyhow::Result<()> {
+    let app = make_router(fixture_librarian);
+
+    let req = Request::post(v1("/tags"))
+        .bearer()
+        .application_json()
+        .body(Body::from(body.to_string()))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn update_tag_200(mut fixture_librarian: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let tag_id = TagId::new();
+    fixture_librarian
+        .expect_tag_repository()
+        .returning(move || {
+            let mut mock = MockTagRepository::new();
+            mock.expect_update().returning(move |event| {
+                assert_eq!(event.tag_id, tag_id);
+                Ok(Tag {
+                    id: event.tag_id,
+                    name: event.name,
+                })
+            });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_librarian);
+
+    let req = Request::put(v1(&format!("/tags/{tag_id}")))
+        .bearer()
+        .application_json()
+        .body(Body::from(
+            serde_json::json!({ "name": "Contest kit" }).to_string(),
+        ))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, TagResponse);
+    assert_eq!(result.id, tag_id);
+    assert_eq!(result.name, "Contest kit");
+
+    Ok(())
+}
+
+#[rstest]
+#[case(|| Ok(()), axum::http::StatusCode::OK)]
+#[case(
+    || Err(AppError::EntityNotFound("not found".into())),
+    axum::http::StatusCode::NOT_FOUND
+)]
+#[tokio::test]
+async fn delete_tag(
+    mut fixture_librarian: registry::MockAppRegistryExt,
+    #[case] result: fn() -> Result<(), AppError>,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let tag_id = TagId::new();
+    fixture_librarian
+        .expect_tag_repository()
+        .returning(move || {
+            let mut mock = MockTagRepository::new();
+            mock.expect_delete().returning(move |event| {
+                assert_eq!(event.tag_id, tag_id);
+                result()
+            });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_librarian);
+
+    let req = Request::delete(v1(&format!("/tags/{tag_id}")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("PUT")]
+#[case("DELETE")]
+#[tokio::test]
+async fn item_tags_200(
+    mut fixture_librarian: registry::MockAppRegistryExt,
+    #[case] method: &str,
+) -> anyhow::Result<()> {
+    let item_id = ItemId::new();
+    let tag_id = TagId::new();
+    fixture_librarian
+        .expect_tag_repository()
+        .returning(move || {
+            let mut mock = MockTagRepository::new();
+            mock.expect_attach().returning(move |event| {
+                assert_eq!((event.item_id, event.tag_id), (item_id, tag_id));
+                Ok(())
+            });
+            mock.expect_detach().returning(move |event| {
+                assert_eq!((event.item_id, event.tag_id), (item_id, tag_id));
+                Ok(())
+            });
+            Arc::new(mock)
+        });
+
+    let app = make_router(fixture_librarian);
+
+    let req = Request::builder()
+        .method(method)
+        .uri(v1(&format!("/items/{item_id}/tags/{tag_id}")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("POST", "/tags".to_string())]
+#[case("PUT", format!("/tags/{}", TagId::new()))]
+#[case("DELETE", format!("/tags/{}", TagId::new()))]
+#[case("PUT", format!("/items/{}/tags/{}", ItemId::new(), TagId::new()))]
+#[case("DELETE", format!("/items/{}/tags/{}", ItemId::new(), TagId::new()))]
+#[tokio::test]
+async fn tags_403_without_items_write(
+    fixture: registry::MockAppRegistryExt,
+    #[case] method: &str,
+    #[case] path: String,
+) -> anyhow::Result<()> {
+    let app = make_router(fixture);
+
+    let req = Request::builder()
+        .method(method)
+        .uri(v1(&path))
+        .bearer()
+        .application_json()
+        .body(Body::from(
+            serde_json::json!({ "name": "contest kit" }).to_string(),
+        ))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::FORBIDDEN);
+
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use api::model::two_factor::{RecoveryCodesResponse, TotpSetupResponse, TwoFactorStatusResponse};
+use axum::{body::Body, http::Request};
+use kernel::{
+    model::two_factor::{TotpSetup, TwoFactorStatus},
+    repository::two_factor::MockTwoFactorRepository,
+};
+use rstest::rstest;
+use shared::error::AppError;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{TestRequestExt, fixture, make_router, v1},
+};
+
+#[rstest]
+#[tokio::test]
+async fn get_two_factor_status_200(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture.expect_two_factor_repository().returning(|| {
+        let mut mock = MockTwoFactorRepository::new();
+        mock.expect_find_status().returning(|_| {
+            Ok(TwoFactorStatus {
+                enabled: true,
+                recovery_codes_remaining: 7,
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+
+    let req = Request::get(v1("/users/me/2fa"))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, TwoFactorStatusResponse);
+    assert!(result.enabled);
+    assert_eq!(result.recovery_codes_remaining, 7);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(|| Ok(()), axum::http::StatusCode::OK)]
+#[case(
+    || Err(AppError::Conflict("Two-factor authentication is already enabled.".into())),
+    axum::http::StatusCode::CONFLICT
+)]
+#[tokio::test]
+async fn set_up_two_factor(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] result: fn() -> Result<(), AppError>,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    fixture.expect_two_factor_repository().returning(move || {
+        let mut mock = MockTwoFactorRepository::new();
+        mock.expect_set_up().returning(move |_| {
+            result().map(|_| TotpSetup {
+                secret: "SECRET".into(),
+                otpauth_url: "otpauth://totp/procon-manager:dummy%40example.com".into(),
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+
+    let req = Request::post(v1("/users/me/2fa/setup"))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+    if expected == axum::http::StatusCode::OK {
+        let result = deserialize_json!(resp, TotpSetupResponse);
+        assert_eq!(result.secret, "SECRET");
+    }
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn confirm_two_factor_200(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture.expect_two_factor_repository().returning(|| {
+        let mut mock = MockTwoFactorRepository::new();
+        mock.expect_confirm()
+            .withf(|event| event.code == "123456")
+            .returning(|_| Ok(vec!["aaaa-bbbb-cccc-dddd".into()]));
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+
+    let req = Request::post(v1("/users/me/2fa/confirm"))
+        .bearer()
+        .application_json()
+        .body(Body::from(
+            serde_json::json!({ "code": "123456" }).to_string(),
+        ))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, RecoveryCodesResponse);
+    assert_eq!(result.recovery_codes, ["aaaa-bbbb-cccc-dddd"]);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn confirm_two_factor_empty_code_400(
+    fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let app = make_router(fixture);
+
+    let req = Request::post(v1("/users/me/2fa/confirm"))
+        .bearer()
+        .application_json()
+        .body(Body::from(serde_json::json!({ "code": "" }).to_string()))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(|| Ok(()), axum::http::StatusCode::OK)]
+#[case(
+    || Err(AppError::UnprocessableEntity("Incorrect code.".into())),
+    axum::http::StatusCode::UNPROCESSABLE_ENTITY
+)]
+#[case(
+    || Err(AppError::ForbiddenOperation("Two-factor authentication is required.".into())),
+    axum::http::StatusCode::FORBIDDEN
+)]
+#[tokio::test]
+async fn disable_two_factor(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] result: fn() -> Result<(), AppError>,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    fixture.expect_two_factor_repository().returning(move || {
+        let mut mock = MockTwoFactorRepository::new();
+        mock.expect_disable()
+            .withf(|event| event.code == "aaaa-bbbb-cccc-dddd")
+            .returning(move |_| result());
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture);
+
+    let req = Request::post(v1("/users/me/2fa/disable"))
+        .bearer()
+        .application_json()
+        .body(Body::from(
+            serde_json::json!({ "code": "aaaa-bbbb-cccc-dddd" }).to_string(),
+        ))?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
@@ -1,18 +1,26 @@
 use std::sync::Arc;
 
 use api::model::user::{
-    CreateUserRequest, RoleName, UpdateUserEmailRequest, UpdateUserNameRequest,
-    UpdateUserPasswordRequest, UpdateUserRoleRequest,
+    CreateUserRequest, PaginatedUserResponse, RoleName, UpdateUserEmailRequest,
+    UpdateUserNameRequest, UpdateUserPasswordRequest, UpdateUserRoleRequest, UserResponse,
 };
 use axum::{body::Body, http::Request};
 use kernel::{
-    model::{id::UserId, role::Role, user::User},
+    model::{
+        id::UserId,
+        list::{PaginatedList, SortOrder, UserSort},
+        role::{Permission, Role},
+        user::User,
+    },
     repository::{checkout::MockCheckoutRepository, user::MockUserRepository},
 };
 use rstest::rstest;
 use tower::ServiceExt;
 
-use crate::helper::{TestRequestExt, fixture, fixture_auth, make_router, v1};
+use crate::{
+    deserialize_json,
+    helper::{TestRequestExt, fixture, fixture_auth, fixture_librarian, make_router, v1},
+};
 
 #[rstest]
 #[tokio::test]
@@ -28,6 +36,7 @@ async fn update_user_name_200(
                 name: "dummy-user".into(),
                 email: "dummy@example.com".into(),
                 role: kernel::model::role::Role::User,
+                deactivated_at: None,
             }))
         });
 
@@ -67,6 +76,7 @@ async fn update_user_email_200(
                 name: "dummy-user".into(),
                 email: "dummy@example.com".into(),
                 role: kernel::model::role::Role::User,
+                deactivated_at: None,
             }))
         });
 
@@ -106,6 +116,7 @@ async fn update_user_email_409_duplicate(
                 name: "dummy-user".into(),
                 email: "dummy@example.com".into(),
                 role: kernel::model::role::Role::User,
+                deactivated_at: None,
             }))
         });
 
@@ -191,6 +202,7 @@ async fn register_user_200(mut fixture_auth: registry::MockAppRegistryExt) -> an
                 name: "admin-user".into(),
                 email: "admin@example.com".into(),
                 role: Role::Admin,
+                deactivated_at: None,
             }))
         });
 
@@ -200,6 +212,7 @@ async fn register_user_200(mut fixture_auth: registry::MockAppRegistryExt) -> an
                 name: event.name,
                 email: event.email,
                 role: Role::User,
+                deactivated_at: None,
             })
         });
 
@@ -239,6 +252,7 @@ async fn register_user_409_duplicate_email(
                 name: "admin-user".into(),
                 email: "admin@example.com".into(),
                 role: Role::Admin,
+                deactivated_at: None,
             }))
         });
 
@@ -284,6 +298,7 @@ async fn register_user_403_not_admin(
                 name: "regular-user".into(),
                 email: "user@example.com".into(),
                 role: Role::User,
+                deactivated_at: None,
             }))
         });
 
@@ -323,6 +338,7 @@ async fn register_user_400_invalid_email(
                 name: "admin-user".into(),
                 email: "admin@example.com".into(),
                 role: Role::Admin,
+                deactivated_at: None,
             }))
         });
 
@@ -349,8 +365,41 @@ async fn register_user_400_invalid_email(
 }
 
 #[rstest]
+#[case("/users", None, None, UserSort::CreatedAt, SortOrder::Desc, (20, 0))]
+#[case(
+    "/users?q=%20",
+    None,
+    None,
+    UserSort::CreatedAt,
+    SortOrder::Desc, (20, 0))]
+#[case(
+    "/users?q=alumni&role=Admin&limit=50&offset=50",
+    Some("alumni"),
+    Some(Role::Admin),
+    UserSort::CreatedAt,
+    SortOrder::Desc, (50, 50))]
+#[case(
+    "/users?sort=email",
+    None,
+    None,
+    UserSort::Email,
+    SortOrder::Asc, (20, 0))]
+#[case(
+    "/users?sort=name&order=desc&role=User",
+    None,
+    Some(Role::User),
+    UserSort::Name,
+    SortOrder::Desc, (20, 0))]
 #[tokio::test]
-async fn list_users_200(mut fixture_auth: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+async fn list_users_200(
+    mut fixture_auth: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] q: Option<&'static str>,
+    #[case] role: Option<Role>,
+    #[case] sort: UserSort,
+    #[case] order: SortOrder,
+    #[case] (expected_limit, expected_offset): (i64, i64),
+) -> anyhow::Result<()> {
     fixture_auth.expect_user_repository().returning(move || {
         let mut mock = MockUserRepository::new();
 
@@ -360,24 +409,39 @@ async fn list_users_200(mut fixture_auth: registry::MockAppRegistryExt) -> anyho
                 name: "test-user".into(),
                 email: "test@example.com".into(),
                 role: Role::Admin,
+                deactivated_at: None,
             }))
         });
 
-        mock.expect_find_all().returning(|| {
-            Ok(vec![
-                User {
-                    id: UserId::new(),
-                    name: "User 1".into(),
-                    email: "user1@example.com".into(),
-                    role: Role::User,
-                },
-                User {
-                    id: UserId::new(),
-                    name: "User 2".into(),
-                    email: "user2@example.com".into(),
-                    role: Role::Admin,
-                },
-            ])
+        mock.expect_find_all().returning(move |opt| {
+            assert_eq!(opt.q.as_deref(), q);
+            assert_eq!(opt.role, role);
+            assert_eq!(opt.sort, sort);
+            assert_eq!(opt.order, order);
+            assert!(opt.cursor.is_none());
+            Ok(PaginatedList {
+                total: 2,
+                limit: opt.limit,
+                offset: opt.offset,
+                items: vec![
+                    User {
+                        id: UserId::new(),
+                        name: "User 1".into(),
+                        email: "user1@example.com".into(),
+                        role: Role::User,
+                        deactivated_at: None,
+                    },
+                    User {
+                        id: UserId::new(),
+                        name: "User 2".into(),
+                        email: "user2@example.com".into(),
+                        role: Role::Admin,
+                        deactivated_at: None,
+                    },
+                ],
+                next_cursor: None,
+                prev_cursor: None,
+            })
         });
 
         Arc::new(mock)
@@ -385,11 +449,55 @@ async fn list_users_200(mut fixture_auth: registry::MockAppRegistryExt) -> anyho
 
     let app = make_router(fixture_auth);
 
-    let req = Request::get(v1("/users")).bearer().body(Body::empty())?;
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
 
     let resp = app.oneshot(req).await?;
     assert_eq!(resp.status(), axum::http::StatusCode::OK);
 
+    let result = deserialize_json!(resp, PaginatedUserResponse);
+    assert_eq!(result.total, 2);
+    assert_eq!(result.items.len(), 2);
+    assert_eq!(result.limit, expected_limit);
+    assert_eq!(result.offset, expected_offset);
+    assert!(result.next_cursor.is_none());
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/users?limit=0")]
+#[case("/users?limit=101")]
+#[case("/users?role=Owner")]
+#[case("/users?sort=password")]
+#[case("/users?cursor=invalid")]
+#[tokio::test]
+async fn list_users_400(
+    mut fixture_auth: registry::MockAppRegistryExt,
+    #[case] path: &str,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_user_repository().returning(move || {
+        let mut mock = MockUserRepository::new();
+
+        mock.expect_find_current_user().returning(|id| {
+            Ok(Some(User {
+                id,
+                name: "test-user".into(),
+                email: "test@example.com".into(),
+                role: Role::Admin,
+                deactivated_at: None,
+            }))
+        });
+
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_auth);
+
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::BAD_REQUEST);
+
     Ok(())
 }
 
@@ -405,6 +513,7 @@ async fn list_users_403(mut fixture_auth: registry::MockAppRegistryExt) -> anyho
                 name: "test-user".into(),
                 email: "test@example.com".into(),
                 role: Role::User,
+                deactivated_at: None,
             }))
         });
 
@@ -433,6 +542,7 @@ async fn delete_user_200(mut fixture_auth: registry::MockAppRegistryExt) -> anyh
                 name: "admin-user".into(),
                 email: "admin@example.com".into(),
                 role: Role::Admin,
+                deactivated_at: None,
             }))
         });
 
@@ -468,6 +578,7 @@ async fn delete_user_403_not_admin(
                 name: "regular-user".into(),
                 email: "user@example.com".into(),
                 role: Role::User,
+                deactivated_at: None,
             }))
         });
 
@@ -487,6 +598,205 @@ async fn delete_user_403_not_admin(
     Ok(())
 }
 
+#[rstest]
+#[case(|| Ok(()), axum::http::StatusCode::OK)]
+#[case(
+    || Err(shared::error::AppError::Conflict("still has items checked out".into())),
+    axum::http::StatusCode::CONFLICT
+)]
+#[case(
+    || Err(shared::error::AppError::EntityNotFound("not found".into())),
+    axum::http::StatusCode::NOT_FOUND
+)]
+#[tokio::test]
+async fn deactivate_user(
+    mut fixture_auth: registry::MockAppRegistryExt,
+    #[case] result: fn() -> Result<(), shared::error::AppError>,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    fixture_auth.expect_user_repository().returning(move || {
+        let mut mock = MockUserRepository::new();
+
+        mock.expect_find_current_user().returning(|id| {
+            Ok(Some(User {
+                id,
+                name: "admin-user".into(),
+                email: "admin@example.com".into(),
+                role: Role::Admin,
+                deactivated_at: None,
+            }))
+        });
+
+        mock.expect_deactivate().returning(move |event| {
+            assert_eq!(event.user_id, user_id);
+            result()
+        });
+
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_auth);
+
+    let req = Request::put(v1(&format!("/users/{user_id}/deactivate")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn reactivate_user_200(mut fixture_auth: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture_auth.expect_user_repository().returning(move || {
+        let mut mock = MockUserRepository::new();
+
+        mock.expect_find_current_user().returning(|id| {
+            Ok(Some(User {
+                id,
+                name: "admin-user".into(),
+                email: "admin@example.com".into(),
+                role: Role::Admin,
+                deactivated_at: None,
+            }))
+        });
+
+        mock.expect_reactivate().returning(|_| Ok(()));
+
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_auth);
+
+    let user_id = UserId::new();
+    let req = Request::put(v1(&format!("/users/{user_id}/reactivate")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(|| Ok(()), axum::http::StatusCode::OK)]
+#[case(
+    || Err(shared::error::AppError::EntityNotFound("not found".into())),
+    axum::http::StatusCode::NOT_FOUND
+)]
+#[case(
+    || Err(shared::error::AppError::Conflict("not locked".into())),
+    axum::http::StatusCode::CONFLICT
+)]
+#[tokio::test]
+async fn unlock_user(
+    mut fixture_auth: registry::MockAppRegistryExt,
+    #[case] result: fn() -> Result<(), shared::error::AppError>,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    fixture_auth.expect_user_repository().returning(move || {
+        let mut mock = MockUserRepository::new();
+
+        mock.expect_find_current_user().returning(|id| {
+            Ok(Some(User {
+                id,
+                name: "admin-user".into(),
+                email: "admin@example.com".into(),
+                role: Role::Admin,
+                deactivated_at: None,
+            }))
+        });
+
+        mock.expect_unlock().returning(move |event| {
+            assert_eq!(event.user_id, user_id);
+            result()
+        });
+
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_auth);
+
+    let req = Request::put(v1(&format!("/users/{user_id}/unlock")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn unlock_user_403_not_admin(fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let app = make_router(fixture);
+
+    let user_id = UserId::new();
+    let req = Request::put(v1(&format!("/users/{user_id}/unlock")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn deactivate_user_403_not_admin(
+    fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let app = make_router(fixture);
+
+    let user_id = UserId::new();
+    let req = Request::put(v1(&format!("/users/{user_id}/deactivate")))
+        .bearer()
+        .body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn deactivated_user_401(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_user_repository().returning(move || {
+        let mut mock = MockUserRepository::new();
+
+        mock.expect_find_current_user().returning(|id| {
+            Ok(Some(User {
+                id,
+                name: "graduated-user".into(),
+                email: "graduated@example.com".into(),
+                role: Role::Admin,
+                deactivated_at: Some(chrono::Utc::now()),
+            }))
+        });
+
+        Arc::new(mock)
+    });
+
+    let app = make_router(fixture_auth);
+
+    let req = Request::get(v1("/users/me")).bearer().body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::UNAUTHORIZED);
+
+    Ok(())
+}
+
 #[rstest]
 #[tokio::test]
 async fn change_role_200(mut fixture_auth: registry::MockAppRegistryExt) -> anyhow::Result<()> {
@@ -499,6 +809,7 @@ async fn change_role_200(mut fixture_auth: registry::MockAppRegistryExt) -> anyh
                 name: "admin-user".into(),
                 email: "admin@example.com".into(),
                 role: Role::Admin,
+                deactivated_at: None,
             }))
         });
 
@@ -539,6 +850,7 @@ async fn change_role_403_not_admin(
                 name: "regular-user".into(),
                 email: "user@example.com".into(),
                 role: Role::User,
+                deactivated_at: None,
             }))
         });
 
@@ -577,6 +889,7 @@ async fn get_current_user_200(
                 name: "test-user".into(),
                 email: "test@example.com".into(),
                 role: Role::User,
+                deactivated_at: None,
             }))
         });
 
@@ -590,6 +903,49 @@ async fn get_current_user_200(
     let resp = app.oneshot(req).await?;
     assert_eq!(resp.status(), axum::http::StatusCode::OK);
 
+    let result = deserialize_json!(resp, UserResponse);
+    assert!(result.permissions.is_empty());
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn get_current_user_200_librarian(
+    fixture_librarian: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let app = make_router(fixture_librarian);
+
+    let req = Request::get(v1("/users/me")).bearer().body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, UserResponse);
+    assert!(matches!(result.role, RoleName::Librarian));
+    assert_eq!(
+        result.permissions,
+        [Permission::ItemsWrite, Permission::CheckoutsManageOthers]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/users")]
+#[case("/audit-logs")]
+#[tokio::test]
+async fn librarian_403(
+    fixture_librarian: registry::MockAppRegistryExt,
+    #[case] path: &str,
+) -> anyhow::Result<()> {
+    let app = make_router(fixture_librarian);
+
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
+
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::FORBIDDEN);
+
     Ok(())
 }
 
@@ -605,6 +961,7 @@ async fn change_password_200(mut fixture_auth: registry::MockAppRegistryExt) ->
                 name: "test-user".into(),
                 email: "test@example.com".into(),
                 role: Role::User,
+                deactivated_at: None,
             }))
         });
 
@@ -628,6 +985,23 @@ async fn change_password_200(mut fixture_auth: registry::MockAppRegistryExt) ->
     let resp = app.oneshot(req).await?;
     assert_eq!(resp.status(), axum::http::StatusCode::OK);
 
+    let cookies = resp
+        .headers()
+        .get_all(axum::http::header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>();
+    assert!(
+        cookies
+            .iter()
+            .any(|cookie| cookie.starts_with("access_token=dummy;"))
+    );
+    assert!(
+        cookies
+            .iter()
+            .any(|cookie| cookie.starts_with("refresh_token=dummy-refresh;"))
+    );
+
     Ok(())
 }
 
@@ -645,6 +1019,7 @@ async fn change_password_404_user_not_found(
                 name: "test-user".into(),
                 email: "test@example.com".into(),
                 role: Role::User,
+                deactivated_at: None,
             }))
         });
 
@@ -710,6 +1085,7 @@ async fn get_checkouts_200(mut fixture_auth: registry::MockAppRegistryExt) -> an
                 name: "test-user".into(),
                 email: "test@example.com".into(),
                 role: Role::User,
+                deactivated_at: None,
             }))
         });
 
@@ -2,6 +2,7 @@ INSERT INTO
     roles (name)
 VALUES
     ('Admin'),
+    ('Librarian'),
     ('User')
 ON CONFLICT DO NOTHING;
 
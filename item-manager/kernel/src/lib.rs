@@ -1,2 +1,4 @@
+pub mod mailer;
 pub mod model;
+pub mod oidc;
 pub mod repository;
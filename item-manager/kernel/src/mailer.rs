@@ -0,0 +1,17 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+/// A plain text email.
+#[derive(Debug, Clone)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[mockall::automock]
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    /// Fails with `MailError` when the mail could not be handed over for delivery.
+    async fn send(&self, mail: Mail) -> AppResult<()>;
+}
@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumString};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::id::{AuditLogId, UserId};
+
+/// A recorded mutation, written in the same transaction as the change itself.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    pub id: AuditLogId,
+    /// `None` once the acting user has been deleted.
+    pub actor_id: Option<UserId>,
+    pub action: AuditAction,
+    pub target_type: AuditTargetType,
+    pub target_id: Uuid,
+    /// State of the target before the change; `None` for creations.
+    pub before: Option<serde_json::Value>,
+    /// State of the target after the change; `None` for deletions.
+    pub after: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(
+    Debug, Clone, Copy, EnumString, AsRefStr, PartialEq, Eq, Serialize, Deserialize, ToSchema,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    ItemCreated,
+    ItemUpdated,
+    ItemDeleted,
+    ItemRestored,
+    ItemTagged,
+    ItemUntagged,
+    TagCreated,
+    TagUpdated,
+    TagDeleted,
+    CustomCategoryCreated,
+    CustomCategoryUpdated,
+    CustomCategoryDeleted,
+    UserCreated,
+    UserRoleChanged,
+    UserNameChanged,
+    UserEmailChanged,
+    UserPasswordChanged,
+    UserDeleted,
+    UserDeactivated,
+    UserReactivated,
+    UserUnlocked,
+    UserTwoFactorEnabled,
+    UserTwoFactorDisabled,
+    UserIdentityLinked,
+    ItemCheckedOut,
+    ItemReturned,
+    CheckoutRenewed,
+}
+
+impl AuditAction {
+    pub fn target_type(self) -> AuditTargetType {
+        match self {
+            Self::ItemCreated
+            | Self::ItemUpdated
+            | Self::ItemDeleted
+            | Self::ItemRestored
+            | Self::ItemTagged
+            | Self::ItemUntagged => AuditTargetType::Item,
+            Self::TagCreated | Self::TagUpdated | Self::TagDeleted => AuditTargetType::Tag,
+            Self::CustomCategoryCreated
+            | Self::CustomCategoryUpdated
+            | Self::CustomCategoryDeleted => AuditTargetType::CustomCategory,
+            Self::UserCreated
+            | Self::UserRoleChanged
+            | Self::UserNameChanged
+            | Self::UserEmailChanged
+            | Self::UserPasswordChanged
+            | Self::UserDeleted
+            | Self::UserDeactivated
+            | Self::UserReactivated
+            | Self::UserUnlocked
+            | Self::UserTwoFactorEnabled
+            | Self::UserTwoFactorDisabled
+            | Self::UserIdentityLinked => AuditTargetType::User,
+            Self::ItemCheckedOut | Self::ItemReturned | Self::CheckoutRenewed => {
+                AuditTargetType::Checkout
+            }
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, EnumString, AsRefStr, PartialEq, Eq, Serialize, Deserialize, ToSchema,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditTargetType {
+    Item,
+    User,
+    Checkout,
+    Tag,
+    CustomCategory,
+}
+
+/// Filters for the audit log listing, which is always ordered newest first.
+#[derive(Debug, Default)]
+pub struct AuditLogListOptions {
+    pub limit: i64,
+    pub offset: i64,
+    pub actor_id: Option<UserId>,
+    pub action: Option<AuditAction>,
+    pub target_type: Option<AuditTargetType>,
+    pub target_id: Option<Uuid>,
+    /// Inclusive lower bound on `created_at`.
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on `created_at`.
+    pub to: Option<DateTime<Utc>>,
+}
@@ -1,3 +1,12 @@
+use super::id::UserId;
+
 pub mod event;
 
 pub struct AccessToken(pub String);
+
+pub struct RefreshToken(pub String);
+
+pub struct RotatedRefreshToken {
+    pub user_id: UserId,
+    pub refresh_token: RefreshToken,
+}
@@ -0,0 +1,28 @@
+use crate::model::id::{CustomCategoryId, UserId};
+
+use super::AttributeDefinition;
+
+#[derive(Debug)]
+pub struct CreateCustomCategory {
+    pub key: String,
+    pub name: String,
+    pub attributes: Vec<AttributeDefinition>,
+    pub requested_by: UserId,
+}
+
+/// Renames the category and replaces its attribute definitions. The key stays
+/// the same, and every existing item must still match the new definitions.
+#[derive(Debug)]
+pub struct UpdateCustomCategory {
+    pub custom_category_id: CustomCategoryId,
+    pub name: String,
+    pub attributes: Vec<AttributeDefinition>,
+    pub requested_by: UserId,
+}
+
+/// Deletes a category that no item, archived or not, belongs to.
+#[derive(Debug)]
+pub struct DeleteCustomCategory {
+    pub custom_category_id: CustomCategoryId,
+    pub requested_by: UserId,
+}
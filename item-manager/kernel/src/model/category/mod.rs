@@ -0,0 +1,217 @@
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use shared::error::{AppError, AppResult};
+use strum::AsRefStr;
+use utoipa::ToSchema;
+
+use super::id::CustomCategoryId;
+
+pub mod event;
+
+/// Longest string an attribute of type `string` may hold.
+const MAX_STRING_LENGTH: usize = 1024;
+const MAX_KEY_LENGTH: usize = 64;
+
+/// An item category defined by an administrator. Its items are stored as
+/// custom items whose attributes are checked against `attributes`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomCategory {
+    pub id: CustomCategoryId,
+    /// Identifies the category in item requests, imports and list filters.
+    pub key: String,
+    pub name: String,
+    pub attributes: Vec<AttributeDefinition>,
+}
+
+/// The category of a custom item, without its attribute definitions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomCategoryRef {
+    pub id: CustomCategoryId,
+    pub key: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeDefinition {
+    /// Lowercase letters, digits and underscores, starting with a letter
+    #[schema(example = "screen_size")]
+    pub key: String,
+    #[schema(example = "Screen size")]
+    pub label: String,
+    #[serde(rename = "type")]
+    pub attribute_type: AttributeType,
+    #[serde(default)]
+    pub required: bool,
+    /// Allowed values of an `enum` attribute; empty for every other type
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, AsRefStr, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeType {
+    String,
+    Number,
+    /// A calendar date such as `2024-04-10`
+    Date,
+    /// One of the definition's `options`
+    Enum,
+    MacAddress,
+    /// An absolute `http` or `https` URL
+    Url,
+}
+
+/// Fails with `UnprocessableEntity` unless the keys are well-formed and
+/// unique, every label is set and only `enum` attributes list options.
+pub fn validate_definitions(definitions: &[AttributeDefinition]) -> AppResult<()> {
+    let mut keys = HashSet::new();
+    for definition in definitions {
+        let key = definition.key.as_str();
+        let well_formed = key.len() <= MAX_KEY_LENGTH
+            && key.starts_with(|c: char| c.is_ascii_lowercase())
+            && key
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !well_formed {
+            return Err(invalid(format!(
+                "The attribute key `{key}` must be up to {MAX_KEY_LENGTH} lowercase letters, digits and underscores, starting with a letter."
+            )));
+        }
+        if !keys.insert(key) {
+            return Err(invalid(format!("The attribute key `{key}` is used twice.")));
+        }
+        if definition.label.trim().is_empty() {
+            return Err(invalid(format!("The attribute `{key}` needs a label.")));
+        }
+        match definition.attribute_type {
+            AttributeType::Enum => {
+                let options = definition.options.iter().collect::<HashSet<_>>();
+                if options.is_empty()
+                    || options.len() != definition.options.len()
+                    || options.iter().any(|option| option.trim().is_empty())
+                {
+                    return Err(invalid(format!(
+                        "The enum attribute `{key}` needs distinct, non-blank options."
+                    )));
+                }
+            }
+            _ if !definition.options.is_empty() => {
+                return Err(invalid(format!(
+                    "Only enum attributes have options, but `{key}` is a {} attribute.",
+                    definition.attribute_type.as_ref()
+                )));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+impl CustomCategory {
+    /// Checks attribute values against the category's definitions and returns
+    /// them normalized: strings are trimmed, MAC addresses and URLs are
+    /// rewritten in canonical form, and nulls and blank strings are dropped.
+    pub fn validate_attributes(&self, values: Map<String, Value>) -> AppResult<Map<String, Value>> {
+        if let Some(key) = values
+            .keys()
+            .find(|key| !self.attributes.iter().any(|d| &d.key == *key))
+        {
+            return Err(invalid(format!(
+                "The {} category has no attribute `{key}`.",
+                self.name
+            )));
+        }
+
+        let mut normalized = Map::new();
+        for definition in &self.attributes {
+            let value = match values.get(&definition.key) {
+                None | Some(Value::Null) => None,
+                Some(value) => normalize_value(definition, value)?,
+            };
+            match value {
+                Some(value) => {
+                    normalized.insert(definition.key.clone(), value);
+                }
+                None if definition.required => {
+                    return Err(invalid(format!(
+                        "The attribute `{}` is required.",
+                        definition.key
+                    )));
+                }
+                None => {}
+            }
+        }
+        Ok(normalized)
+    }
+
+    pub fn to_ref(&self) -> CustomCategoryRef {
+        CustomCategoryRef {
+            id: self.id,
+            key: self.key.clone(),
+            name: self.name.clone(),
+        }
+    }
+}
+
+/// `None` for a blank string, which counts as no value.
+fn normalize_value(definition: &AttributeDefinition, value: &Value) -> AppResult<Option<Value>> {
+    let key = &definition.key;
+    let expected = |what: &str| invalid(format!("The attribute `{key}` must be {what}."));
+
+    if definition.attribute_type == AttributeType::Number {
+        return match value {
+            Value::Number(_) => Ok(Some(value.clone())),
+            _ => Err(expected("a number")),
+        };
+    }
+    let Value::String(text) = value else {
+        return Err(expected("a string"));
+    };
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+
+    let normalized = match definition.attribute_type {
+        AttributeType::String if text.chars().count() > MAX_STRING_LENGTH => {
+            return Err(expected(&format!(
+                "at most {MAX_STRING_LENGTH} characters long"
+            )));
+        }
+        AttributeType::String => text.to_string(),
+        AttributeType::Date => NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .map_err(|_| expected("a date in YYYY-MM-DD format"))?
+            .format("%Y-%m-%d")
+            .to_string(),
+        AttributeType::Enum if definition.options.iter().any(|option| option == text) => {
+            text.to_string()
+        }
+        AttributeType::Enum => {
+            return Err(expected(&format!(
+                "one of {}",
+                definition.options.join(", ")
+            )));
+        }
+        AttributeType::MacAddress => text
+            .parse::<mac_address::MacAddress>()
+            .map_err(|_| expected("a MAC address"))?
+            .to_string(),
+        AttributeType::Url => match url::Url::parse(text) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") && url.has_host() => {
+                url.to_string()
+            }
+            _ => return Err(expected("an http or https URL")),
+        },
+        AttributeType::Number => unreachable!("numbers are handled above"),
+    };
+    Ok(Some(Value::String(normalized)))
+}
+
+fn invalid(message: String) -> AppError {
+    AppError::UnprocessableEntity(message)
+}
@@ -8,7 +8,11 @@ use crate::model::role::Role;
 pub struct CreateCheckout {
     pub item_id: ItemId,
     pub checked_out_by: UserId,
+    /// Differs from `checked_out_by` when an administrator checks out on someone's behalf.
+    pub requested_by: UserId,
     pub checked_out_at: DateTime<Utc>,
+    /// Overrides the default loan period of the item's category.
+    pub due_at: Option<DateTime<Utc>>,
 }
 
 #[derive(new)]
@@ -19,3 +23,14 @@ pub struct UpdateReturned {
     pub returned_by_role: Role,
     pub returned_at: DateTime<Utc>,
 }
+
+#[derive(new)]
+pub struct RenewCheckout {
+    pub checkout_id: CheckoutId,
+    pub item_id: ItemId,
+    pub renewed_by: UserId,
+    pub renewed_by_role: Role,
+    pub renewed_at: DateTime<Utc>,
+    /// Overrides the loan period added to the current due date.
+    pub due_at: Option<DateTime<Utc>>,
+}
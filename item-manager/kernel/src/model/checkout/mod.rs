@@ -10,8 +10,25 @@ pub struct Checkout {
     pub id: CheckoutId,
     pub checked_out_by: UserId,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
     pub returned_at: Option<DateTime<Utc>>,
     pub item_id: ItemId,
+    /// Renewals of the loan, oldest first.
+    pub renewals: Vec<CheckoutRenewal>,
+}
+
+impl Checkout {
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.returned_at.is_none() && self.due_at < now
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckoutRenewal {
+    pub renewed_by: UserId,
+    pub renewed_at: DateTime<Utc>,
+    pub previous_due_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone)]
@@ -19,4 +36,11 @@ pub struct SimpleCheckout {
     pub checkout_id: CheckoutId,
     pub checked_out_by: CheckoutUser,
     pub checked_out_at: chrono::DateTime<chrono::Utc>,
+    pub due_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl SimpleCheckout {
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.due_at < now
+    }
 }
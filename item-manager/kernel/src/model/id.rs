@@ -58,3 +58,9 @@ macro_rules! define_id {
 define_id!(UserId);
 define_id!(ItemId);
 define_id!(CheckoutId);
+define_id!(ReservationId);
+define_id!(AuditLogId);
+define_id!(PersonalAccessTokenId);
+define_id!(InvitationId);
+define_id!(TagId);
+define_id!(CustomCategoryId);
@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+
+use crate::model::{
+    id::{InvitationId, UserId},
+    role::Role,
+};
+
+#[derive(Debug)]
+pub struct CreateInvitation {
+    pub role: Role,
+    pub max_uses: u32,
+    pub expires_at: DateTime<Utc>,
+    pub requested_by: UserId,
+}
+
+#[derive(Debug)]
+pub struct DeleteInvitation {
+    pub invitation_id: InvitationId,
+}
@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+
+use crate::model::{
+    id::{InvitationId, UserId},
+    role::Role,
+};
+
+pub mod event;
+
+/// A code that lets people sign up on their own. Only its hash is stored.
+#[derive(Debug)]
+pub struct Invitation {
+    pub id: InvitationId,
+    /// Role given to users who sign up with it.
+    pub role: Role,
+    pub max_uses: u32,
+    pub uses: u32,
+    pub expires_at: DateTime<Utc>,
+    /// `None` once the administrator who created it has been deleted.
+    pub created_by: Option<UserId>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A newly created invitation together with its code, which is only available at creation.
+#[derive(Debug)]
+pub struct CreatedInvitation {
+    pub invitation: Invitation,
+    pub code: String,
+}
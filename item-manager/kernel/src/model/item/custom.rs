@@ -0,0 +1,20 @@
+use serde_json::{Map, Value};
+
+use crate::model::{category::CustomCategoryRef, checkout::SimpleCheckout, id::ItemId, tag::Tag};
+
+use super::ItemArchive;
+
+/// An item of an admin-defined category.
+#[derive(Debug, Clone)]
+pub struct CustomItem {
+    pub id: ItemId,
+    pub name: String,
+    pub category: CustomCategoryRef,
+    /// Values keyed by attribute key, already validated against the category.
+    pub attributes: Map<String, Value>,
+    pub description: String,
+    pub location: Option<String>,
+    pub checkout: Option<SimpleCheckout>,
+    pub archive: Option<ItemArchive>,
+    pub tags: Vec<Tag>,
+}
@@ -1,4 +1,6 @@
-use crate::model::{checkout::SimpleCheckout, id::ItemId};
+use crate::model::{checkout::SimpleCheckout, id::ItemId, tag::Tag};
+
+use super::ItemArchive;
 
 #[derive(Debug, Clone)]
 pub struct Laptop {
@@ -8,4 +10,6 @@ pub struct Laptop {
     pub description: String,
     pub location: Option<String>,
     pub checkout: Option<SimpleCheckout>,
+    pub archive: Option<ItemArchive>,
+    pub tags: Vec<Tag>,
 }
@@ -1,10 +1,13 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use strum::{AsRefStr, EnumString};
 use utoipa::ToSchema;
 
-use super::id::ItemId;
+use super::id::{ItemId, UserId};
 
 pub mod book;
+pub mod custom;
 pub mod general;
 pub mod laptop;
 
@@ -15,6 +18,8 @@ pub enum ItemCategory {
     General,
     Book,
     Laptop,
+    /// Any admin-defined category
+    Custom,
 }
 
 #[derive(Debug, Clone, AsRefStr)]
@@ -23,17 +28,28 @@ pub enum Item {
     General(general::GeneralItem),
     Book(book::Book),
     Laptop(laptop::Laptop),
+    Custom(custom::CustomItem),
+}
+
+/// Set while an item is retired from circulation. Archived items keep their
+/// checkout history and can be restored.
+#[derive(Debug, Clone)]
+pub struct ItemArchive {
+    pub archived_at: DateTime<Utc>,
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Clone, AsRefStr)]
 #[strum(serialize_all = "snake_case")]
 pub enum CreateItem {
     General {
+        requested_by: UserId,
         name: String,
         description: String,
         location: Option<String>,
     },
     Book {
+        requested_by: UserId,
         name: String,
         author: String,
         isbn: String,
@@ -41,11 +57,33 @@ pub enum CreateItem {
         location: Option<String>,
     },
     Laptop {
+        requested_by: UserId,
         name: String,
         mac_address: mac_address::MacAddress,
         description: String,
         location: Option<String>,
     },
+    Custom {
+        requested_by: UserId,
+        name: String,
+        /// Key of the custom category.
+        custom_category: String,
+        /// Checked against the category's attribute definitions when stored.
+        attributes: Map<String, Value>,
+        description: String,
+        location: Option<String>,
+    },
+}
+
+impl CreateItem {
+    pub fn requested_by(&self) -> UserId {
+        match self {
+            Self::General { requested_by, .. }
+            | Self::Book { requested_by, .. }
+            | Self::Laptop { requested_by, .. }
+            | Self::Custom { requested_by, .. } => *requested_by,
+        }
+    }
 }
 
 #[derive(Debug, Clone, AsRefStr)]
@@ -53,12 +91,14 @@ pub enum CreateItem {
 pub enum UpdateItem {
     General {
         item_id: ItemId,
+        requested_by: UserId,
         name: String,
         description: String,
         location: Option<String>,
     },
     Book {
         item_id: ItemId,
+        requested_by: UserId,
         name: String,
         author: String,
         isbn: String,
@@ -67,14 +107,46 @@ pub enum UpdateItem {
     },
     Laptop {
         item_id: ItemId,
+        requested_by: UserId,
         name: String,
         mac_address: mac_address::MacAddress,
         description: String,
         location: Option<String>,
     },
+    Custom {
+        item_id: ItemId,
+        requested_by: UserId,
+        name: String,
+        /// Key of the custom category.
+        custom_category: String,
+        /// Checked against the category's attribute definitions when stored.
+        attributes: Map<String, Value>,
+        description: String,
+        location: Option<String>,
+    },
+}
+
+impl UpdateItem {
+    pub fn requested_by(&self) -> UserId {
+        match self {
+            Self::General { requested_by, .. }
+            | Self::Book { requested_by, .. }
+            | Self::Laptop { requested_by, .. }
+            | Self::Custom { requested_by, .. } => *requested_by,
+        }
+    }
 }
 
+/// Archives an item rather than removing it, so its history stays resolvable.
 #[derive(Debug, Clone)]
 pub struct DeleteItem {
     pub item_id: ItemId,
+    pub requested_by: UserId,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RestoreItem {
+    pub item_id: ItemId,
+    pub requested_by: UserId,
 }
@@ -1,4 +1,10 @@
-use super::item::ItemCategory;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::AsRefStr;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{item::ItemCategory, role::Role};
 
 #[derive(Debug)]
 pub struct PaginatedList<T> {
@@ -6,6 +12,8 @@ pub struct PaginatedList<T> {
     pub limit: i64,
     pub offset: i64,
     pub items: Vec<T>,
+    pub next_cursor: Option<Cursor>,
+    pub prev_cursor: Option<Cursor>,
 }
 
 impl<T> PaginatedList<T> {
@@ -14,9 +22,174 @@ impl<T> PaginatedList<T> {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct ListOptions {
     pub limit: i64,
+    /// Ignored when `cursor` is set.
     pub offset: i64,
+    pub cursor: Option<Cursor>,
     pub category: Option<ItemCategory>,
+    pub filter: ItemFilter,
+    pub sort: ItemSort,
+    pub order: SortOrder,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ItemFilter {
+    /// Free-text search over name, description, location, book author and ISBN.
+    pub q: Option<String>,
+    pub author: Option<String>,
+    pub isbn: Option<String>,
+    pub mac_address: Option<mac_address::MacAddress>,
+    pub location: Option<String>,
+    /// Whether the item is currently not checked out.
+    pub available: Option<bool>,
+    /// Also list items that have been archived.
+    pub include_archived: bool,
+    /// Tag names, matched regardless of case.
+    pub tags: Vec<String>,
+    pub tag_match: TagMatch,
+    /// Key of a custom category; only its items are listed.
+    pub custom_category: Option<String>,
+}
+
+/// Whether an item needs one or all of the requested tags to be listed.
+#[derive(
+    Debug, Default, Clone, Copy, AsRefStr, PartialEq, Eq, Serialize, Deserialize, ToSchema,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TagMatch {
+    #[default]
+    Any,
+    All,
+}
+
+#[derive(
+    Debug, Default, Clone, Copy, AsRefStr, PartialEq, Eq, Serialize, Deserialize, ToSchema,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ItemSort {
+    Name,
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    Category,
+    Location,
+    /// When the current checkout started; items that are not checked out come last.
+    CheckedOutAt,
+}
+
+impl ItemSort {
+    /// Order used when the client does not specify one: newest first for
+    /// timestamps, alphabetical for text columns.
+    pub fn default_order(self) -> SortOrder {
+        match self {
+            Self::Name | Self::Category | Self::Location => SortOrder::Asc,
+            Self::CreatedAt | Self::UpdatedAt | Self::CheckedOutAt => SortOrder::Desc,
+        }
+    }
+}
+
+#[derive(
+    Debug, Default, Clone, Copy, AsRefStr, PartialEq, Eq, Serialize, Deserialize, ToSchema,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+#[derive(Debug, Default)]
+pub struct UserListOptions {
+    pub limit: i64,
+    /// Ignored when `cursor` is set.
+    pub offset: i64,
+    pub cursor: Option<Cursor>,
+    /// Case-insensitive substring match on name or email.
+    pub q: Option<String>,
+    pub role: Option<Role>,
+    /// Only active (`true`) or deactivated (`false`) users.
+    pub active: Option<bool>,
+    pub sort: UserSort,
+    pub order: SortOrder,
+}
+
+#[derive(
+    Debug, Default, Clone, Copy, AsRefStr, PartialEq, Eq, Serialize, Deserialize, ToSchema,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum UserSort {
+    Name,
+    Email,
+    Role,
+    #[default]
+    CreatedAt,
+}
+
+impl UserSort {
+    /// Order used when the client does not specify one: newest first for
+    /// timestamps, alphabetical for text columns.
+    pub fn default_order(self) -> SortOrder {
+        match self {
+            Self::Name | Self::Email | Self::Role => SortOrder::Asc,
+            Self::CreatedAt => SortOrder::Desc,
+        }
+    }
+}
+
+/// Options for listings that are paged by cursor only.
+#[derive(Debug, Default, Clone)]
+pub struct CursorListOptions {
+    /// Page size; `None` returns every remaining row.
+    pub limit: Option<i64>,
+    pub cursor: Option<Cursor>,
+}
+
+/// Position of a boundary row in a keyset-paginated listing.
+///
+/// The sort key is stored as text or as a timestamp depending on the column
+/// being sorted, and `id` breaks ties between equal keys. Listings that can be
+/// sorted also record the sort the cursor was made under, so that it is not
+/// compared against a different column.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cursor {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_time: Option<DateTime<Utc>>,
+    pub id: Uuid,
+    pub direction: CursorDirection,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<CursorSort>,
+}
+
+/// Sort column and direction of the listing a cursor belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CursorSort {
+    /// Name of the sort column as given in the list query
+    pub column: String,
+    pub order: SortOrder,
+}
+
+impl CursorSort {
+    pub fn new(column: impl AsRef<str>, order: SortOrder) -> Self {
+        Self {
+            column: column.as_ref().to_string(),
+            order,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CursorDirection {
+    /// Rows following the boundary row
+    After,
+    /// Rows preceding the boundary row
+    Before,
 }
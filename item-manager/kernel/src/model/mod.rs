@@ -1,7 +1,16 @@
+pub mod audit;
 pub mod auth;
+pub mod category;
 pub mod checkout;
 pub mod id;
+pub mod invitation;
 pub mod item;
 pub mod list;
+pub mod oidc;
+pub mod password_reset;
+pub mod personal_access_token;
+pub mod reservation;
 pub mod role;
+pub mod tag;
+pub mod two_factor;
 pub mod user;
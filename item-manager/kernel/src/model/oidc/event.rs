@@ -0,0 +1,13 @@
+use crate::model::oidc::OidcIdentity;
+
+#[derive(Debug)]
+pub struct CreateOidcLoginRequest {
+    pub state: String,
+    pub nonce: String,
+    pub pkce_verifier: String,
+}
+
+#[derive(Debug)]
+pub struct SignInWithOidc {
+    pub identity: OidcIdentity,
+}
@@ -0,0 +1,28 @@
+pub mod event;
+
+/// Where to send the browser to sign in at the identity provider, with the values the callback
+/// has to be checked against.
+#[derive(Debug)]
+pub struct OidcAuthorization {
+    pub url: String,
+    pub state: String,
+    pub nonce: String,
+    pub pkce_verifier: String,
+}
+
+/// A sign-in started by this server that is waiting for the provider's callback.
+#[derive(Debug)]
+pub struct PendingOidcLogin {
+    pub nonce: String,
+    pub pkce_verifier: String,
+}
+
+/// The claims of a verified ID token.
+#[derive(Debug, Clone)]
+pub struct OidcIdentity {
+    pub issuer: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+}
@@ -0,0 +1,10 @@
+#[derive(Debug)]
+pub struct RequestPasswordReset {
+    pub email: String,
+}
+
+#[derive(Debug)]
+pub struct ConfirmPasswordReset {
+    pub token: String,
+    pub new_password: String,
+}
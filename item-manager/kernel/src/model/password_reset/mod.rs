@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+
+use crate::model::id::UserId;
+
+pub mod event;
+
+/// A freshly issued reset token. Only its hash is stored, so `token` is available here alone.
+#[derive(Debug)]
+pub struct PasswordResetToken {
+    pub user_id: UserId,
+    pub name: String,
+    pub email: String,
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
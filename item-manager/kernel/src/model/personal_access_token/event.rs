@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+
+use crate::model::id::{PersonalAccessTokenId, UserId};
+
+use super::TokenScope;
+
+#[derive(Debug)]
+pub struct CreatePersonalAccessToken {
+    pub user_id: UserId,
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Revokes one of the requesting user's own tokens.
+#[derive(Debug)]
+pub struct DeletePersonalAccessToken {
+    pub token_id: PersonalAccessTokenId,
+    pub user_id: UserId,
+}
@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumString};
+use utoipa::ToSchema;
+
+use super::id::{PersonalAccessTokenId, UserId};
+
+pub mod event;
+
+/// Prefix of every personal access token, which tells them apart from session JWTs.
+pub const PERSONAL_ACCESS_TOKEN_PREFIX: &str = "pat_";
+
+/// A long-lived credential for scripts. Only a hash of the secret is stored.
+#[derive(Debug, Clone)]
+pub struct PersonalAccessToken {
+    pub id: PersonalAccessTokenId,
+    pub user_id: UserId,
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// What a personal access token may do on top of the owner's role permissions.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    EnumString,
+    AsRefStr,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TokenScope {
+    /// Read-only requests (`GET`, `HEAD` and `OPTIONS`).
+    Read,
+    /// Every other request.
+    Write,
+}
+
+impl TokenScope {
+    /// The scope a token needs for a request with the given HTTP method.
+    pub fn required_for(method: &str) -> Self {
+        match method {
+            "GET" | "HEAD" | "OPTIONS" => Self::Read,
+            _ => Self::Write,
+        }
+    }
+}
+
+/// A freshly created token together with its secret, which cannot be retrieved again.
+#[derive(Debug)]
+pub struct CreatedPersonalAccessToken {
+    pub token: PersonalAccessToken,
+    pub secret: String,
+}
@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+use derive_new::new;
+
+use crate::model::id::{ItemId, ReservationId, UserId};
+use crate::model::role::Role;
+
+#[derive(new)]
+pub struct CreateReservation {
+    pub item_id: ItemId,
+    pub reserved_by: UserId,
+    pub reserved_at: DateTime<Utc>,
+}
+
+#[derive(new)]
+pub struct DeleteReservation {
+    pub reservation_id: ReservationId,
+    pub item_id: ItemId,
+    pub requested_by: UserId,
+    pub requested_by_role: Role,
+}
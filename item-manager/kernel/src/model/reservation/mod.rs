@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+
+use super::id::{ItemId, ReservationId, UserId};
+
+pub mod event;
+
+#[derive(Debug, Clone)]
+pub struct Reservation {
+    pub id: ReservationId,
+    pub item_id: ItemId,
+    pub reserved_by: UserId,
+    pub reserved_at: DateTime<Utc>,
+    /// 1-based position in the item's queue.
+    pub position: i64,
+    /// Set once the item has been returned and is being held for this reservation.
+    pub ready_at: Option<DateTime<Utc>>,
+    /// End of the exclusive checkout window that started at `ready_at`.
+    pub expires_at: Option<DateTime<Utc>>,
+}
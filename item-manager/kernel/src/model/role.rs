@@ -18,6 +18,55 @@ use utoipa::ToSchema;
 )]
 pub enum Role {
     Admin,
+    /// Manages the inventory and everyone's checkouts, but not user accounts.
+    Librarian,
     #[default]
     User,
 }
+
+impl Role {
+    pub fn permissions(self) -> &'static [Permission] {
+        match self {
+            Self::Admin => &[
+                Permission::ItemsWrite,
+                Permission::CheckoutsManageOthers,
+                Permission::UsersManage,
+                Permission::AuditLogsRead,
+                Permission::CategoriesManage,
+            ],
+            Self::Librarian => &[Permission::ItemsWrite, Permission::CheckoutsManageOthers],
+            Self::User => &[],
+        }
+    }
+
+    pub fn has_permission(self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+/// An operation beyond managing one's own checkouts, reservations and profile.
+#[derive(
+    Debug, AsRefStr, EnumIter, PartialEq, Eq, Copy, Clone, Serialize, Deserialize, ToSchema,
+)]
+pub enum Permission {
+    /// Create, update, archive, import and export items.
+    #[strum(serialize = "items:write")]
+    #[serde(rename = "items:write")]
+    ItemsWrite,
+    /// Check out, return and renew items on behalf of other users, override due
+    /// dates and cancel other users' reservations.
+    #[strum(serialize = "checkouts:manage_others")]
+    #[serde(rename = "checkouts:manage_others")]
+    CheckoutsManageOthers,
+    /// Register, list, change the role of, deactivate and delete users.
+    #[strum(serialize = "users:manage")]
+    #[serde(rename = "users:manage")]
+    UsersManage,
+    #[strum(serialize = "audit_logs:read")]
+    #[serde(rename = "audit_logs:read")]
+    AuditLogsRead,
+    /// Define, change and delete custom item categories.
+    #[strum(serialize = "categories:manage")]
+    #[serde(rename = "categories:manage")]
+    CategoriesManage,
+}
@@ -0,0 +1,35 @@
+use crate::model::id::{ItemId, TagId, UserId};
+
+#[derive(Debug)]
+pub struct CreateTag {
+    pub name: String,
+    pub requested_by: UserId,
+}
+
+#[derive(Debug)]
+pub struct UpdateTag {
+    pub tag_id: TagId,
+    pub name: String,
+    pub requested_by: UserId,
+}
+
+/// Deletes the tag and removes it from every item.
+#[derive(Debug)]
+pub struct DeleteTag {
+    pub tag_id: TagId,
+    pub requested_by: UserId,
+}
+
+#[derive(Debug)]
+pub struct AttachTag {
+    pub item_id: ItemId,
+    pub tag_id: TagId,
+    pub requested_by: UserId,
+}
+
+#[derive(Debug)]
+pub struct DetachTag {
+    pub item_id: ItemId,
+    pub tag_id: TagId,
+    pub requested_by: UserId,
+}
@@ -0,0 +1,11 @@
+use crate::model::id::TagId;
+
+pub mod event;
+
+/// A label that groups items across categories, such as a contest kit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub id: TagId,
+    /// Unique regardless of case.
+    pub name: String,
+}
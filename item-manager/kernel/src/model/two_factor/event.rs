@@ -0,0 +1,31 @@
+use crate::model::id::UserId;
+
+#[derive(Debug)]
+pub struct SetUpTotp {
+    pub user_id: UserId,
+}
+
+#[derive(Debug)]
+pub struct ConfirmTotp {
+    pub user_id: UserId,
+    pub code: String,
+}
+
+#[derive(Debug)]
+pub struct DisableTotp {
+    pub user_id: UserId,
+    /// A current TOTP code or an unused recovery code.
+    pub code: String,
+}
+
+#[derive(Debug)]
+pub struct CreateLoginChallenge {
+    pub user_id: UserId,
+}
+
+#[derive(Debug)]
+pub struct CompleteLoginChallenge {
+    pub token: String,
+    /// A TOTP code, or an unused recovery code unless the challenge requires setup.
+    pub code: String,
+}
@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+
+use crate::model::id::UserId;
+
+pub mod event;
+
+#[derive(Debug)]
+pub struct TwoFactorStatus {
+    /// Whether a confirmed authenticator is required on login.
+    pub enabled: bool,
+    pub recovery_codes_remaining: u32,
+}
+
+/// A new TOTP secret awaiting confirmation with a code from the authenticator app.
+#[derive(Debug)]
+pub struct TotpSetup {
+    /// Base32 secret for entering the key by hand.
+    pub secret: String,
+    /// `otpauth://` URL, usually shown as a QR code.
+    pub otpauth_url: String,
+}
+
+/// A login whose password matched but which still needs a second factor. Only the hash of
+/// `token` is stored.
+#[derive(Debug)]
+pub struct LoginChallenge {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+    /// The user has no authenticator yet but their role requires one, so it has to be set up
+    /// before the login can complete.
+    pub setup_required: bool,
+}
+
+#[derive(Debug)]
+pub struct CompletedLoginChallenge {
+    pub user_id: UserId,
+    /// Set when completing the challenge enrolled the user; they are only shown this once.
+    pub recovery_codes: Option<Vec<String>>,
+}
@@ -5,12 +5,17 @@ pub struct CreateUser {
     pub name: String,
     pub email: String,
     pub password: String,
+    /// The administrator creating the account, or `None` when the user signs up themselves.
+    pub requested_by: Option<UserId>,
+    /// Redeemed in the same transaction; the invitation's role replaces the default `User`.
+    pub invitation_code: Option<String>,
 }
 
 #[derive(Debug)]
 pub struct UpdateUserRole {
     pub user_id: UserId,
     pub role: Role,
+    pub requested_by: UserId,
 }
 
 #[derive(Debug)]
@@ -35,4 +40,25 @@ pub struct UpdateUserEmail {
 #[derive(Debug)]
 pub struct DeleteUser {
     pub user_id: UserId,
+    pub requested_by: UserId,
+}
+
+/// Deactivates an account while keeping its checkout history.
+#[derive(Debug)]
+pub struct DeactivateUser {
+    pub user_id: UserId,
+    pub requested_by: UserId,
+}
+
+#[derive(Debug)]
+pub struct ReactivateUser {
+    pub user_id: UserId,
+    pub requested_by: UserId,
+}
+
+/// Clears the failed login attempts recorded against a user's email address.
+#[derive(Debug)]
+pub struct UnlockUser {
+    pub user_id: UserId,
+    pub requested_by: UserId,
 }
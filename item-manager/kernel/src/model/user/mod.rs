@@ -1,3 +1,5 @@
+use chrono::{DateTime, Utc};
+
 use crate::model::{id::UserId, role::Role};
 pub mod event;
 
@@ -7,6 +9,15 @@ pub struct User {
     pub name: String,
     pub email: String,
     pub role: Role,
+    /// Set once the account has been deactivated; deactivated users can neither
+    /// sign in nor use tokens issued before.
+    pub deactivated_at: Option<DateTime<Utc>>,
+}
+
+impl User {
+    pub fn is_active(&self) -> bool {
+        self.deactivated_at.is_none()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,16 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::oidc::{OidcAuthorization, OidcIdentity, PendingOidcLogin};
+
+#[mockall::automock]
+#[async_trait]
+pub trait OidcProvider: Send + Sync {
+    /// Builds the authorization request with a fresh state, nonce and PKCE verifier.
+    async fn authorize(&self) -> AppResult<OidcAuthorization>;
+    /// Redeems the code from the callback and verifies the returned ID token. Fails with
+    /// `UnauthenticatedError` when the provider rejects the code or the token is invalid, and
+    /// with `IdentityProviderError` when the provider cannot be reached.
+    async fn exchange_code(&self, code: &str, pending: PendingOidcLogin)
+    -> AppResult<OidcIdentity>;
+}
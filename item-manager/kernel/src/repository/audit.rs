@@ -0,0 +1,15 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{
+    audit::{AuditLog, AuditLogListOptions},
+    list::PaginatedList,
+};
+
+/// Read side of the audit log. Entries are written by the other repositories
+/// inside the transactions of the mutations they describe.
+#[mockall::automock]
+#[async_trait]
+pub trait AuditRepository: Send + Sync {
+    async fn find_all(&self, options: AuditLogListOptions) -> AppResult<PaginatedList<AuditLog>>;
+}
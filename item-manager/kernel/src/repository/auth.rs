@@ -1,8 +1,10 @@
+use std::net::IpAddr;
+
 use async_trait::async_trait;
 use shared::error::AppResult;
 
 use crate::model::{
-    auth::{AccessToken, event::CreateToken},
+    auth::{AccessToken, RefreshToken, RotatedRefreshToken, event::CreateToken},
     id::UserId,
 };
 
@@ -13,6 +15,20 @@ pub trait AuthRepository: Send + Sync {
         &self,
         access_token: &AccessToken,
     ) -> AppResult<Option<UserId>>;
-    async fn verify_user(&self, email: &str, password: &str) -> AppResult<UserId>;
+    /// Fails with `ForbiddenOperation` for deactivated accounts, and with `TooManyRequests` while
+    /// the email address or client IP is locked out after repeated failures.
+    async fn verify_user(
+        &self,
+        email: &str,
+        password: &str,
+        client_ip: Option<IpAddr>,
+    ) -> AppResult<UserId>;
     async fn create_token(&self, event: CreateToken) -> AppResult<AccessToken>;
+    async fn revoke_token(&self, access_token: &AccessToken) -> AppResult<()>;
+    async fn create_refresh_token(&self, event: CreateToken) -> AppResult<RefreshToken>;
+    async fn rotate_refresh_token(
+        &self,
+        refresh_token: &RefreshToken,
+    ) -> AppResult<RotatedRefreshToken>;
+    async fn revoke_refresh_token(&self, refresh_token: &RefreshToken) -> AppResult<()>;
 }
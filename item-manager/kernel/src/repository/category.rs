@@ -0,0 +1,17 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::category::{
+    CustomCategory,
+    event::{CreateCustomCategory, DeleteCustomCategory, UpdateCustomCategory},
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait CustomCategoryRepository: Send + Sync {
+    async fn create(&self, event: CreateCustomCategory) -> AppResult<CustomCategory>;
+    /// Lists every custom category ordered by name.
+    async fn find_all(&self) -> AppResult<Vec<CustomCategory>>;
+    async fn update(&self, event: UpdateCustomCategory) -> AppResult<CustomCategory>;
+    async fn delete(&self, event: DeleteCustomCategory) -> AppResult<()>;
+}
@@ -4,9 +4,10 @@ use shared::error::AppResult;
 use crate::model::{
     checkout::{
         Checkout,
-        event::{CreateCheckout, UpdateReturned},
+        event::{CreateCheckout, RenewCheckout, UpdateReturned},
     },
     id::{ItemId, UserId},
+    list::{CursorListOptions, PaginatedList},
 };
 
 #[mockall::automock]
@@ -14,7 +15,13 @@ use crate::model::{
 pub trait CheckoutRepository: Send + Sync {
     async fn create(&self, event: CreateCheckout) -> AppResult<()>;
     async fn update_returned(&self, event: UpdateReturned) -> AppResult<()>;
+    async fn renew(&self, event: RenewCheckout) -> AppResult<()>;
     async fn find_unreturned_all(&self) -> AppResult<Vec<Checkout>>;
+    async fn find_overdue_all(&self) -> AppResult<Vec<Checkout>>;
     async fn find_unreturned_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Checkout>>;
-    async fn find_history_by_item_id(&self, item_id: ItemId) -> AppResult<Vec<Checkout>>;
+    async fn find_history_by_item_id(
+        &self,
+        item_id: ItemId,
+        options: CursorListOptions,
+    ) -> AppResult<PaginatedList<Checkout>>;
 }
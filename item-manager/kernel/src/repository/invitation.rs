@@ -0,0 +1,16 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::invitation::{
+    CreatedInvitation, Invitation,
+    event::{CreateInvitation, DeleteInvitation},
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait InvitationRepository: Send + Sync {
+    async fn create(&self, event: CreateInvitation) -> AppResult<CreatedInvitation>;
+    /// Lists every invitation, newest first, including expired and used up ones.
+    async fn find_all(&self) -> AppResult<Vec<Invitation>>;
+    async fn delete(&self, event: DeleteInvitation) -> AppResult<()>;
+}
@@ -1,18 +1,32 @@
+use std::pin::Pin;
+
 use async_trait::async_trait;
 use shared::error::AppResult;
+use tokio_stream::Stream;
 
 use crate::model::{
     id::ItemId,
-    item::{CreateItem, DeleteItem, Item, UpdateItem},
+    item::{CreateItem, DeleteItem, Item, RestoreItem, UpdateItem},
     list::{ListOptions, PaginatedList},
 };
 
+/// Items yielded one by one, so a listing of the whole table never has to be
+/// held in memory.
+pub type ItemStream = Pin<Box<dyn Stream<Item = AppResult<Item>> + Send>>;
+
 #[mockall::automock]
 #[async_trait]
 pub trait ItemRepository: Send + Sync {
     async fn create(&self, event: CreateItem) -> AppResult<()>;
+    /// Creates every item in a single transaction, or none of them.
+    async fn create_many(&self, events: Vec<CreateItem>) -> AppResult<()>;
     async fn find_all(&self, options: ListOptions) -> AppResult<PaginatedList<Item>>;
+    /// Every item, archived ones included, with its current checkout, oldest first.
+    fn stream_all(&self) -> ItemStream;
+    /// Resolves archived items as well.
     async fn find_by_id(&self, id: ItemId) -> AppResult<Option<Item>>;
     async fn update(&self, event: UpdateItem) -> AppResult<()>;
+    /// Archives the item; it is hidden from listings but keeps its history.
     async fn delete(&self, event: DeleteItem) -> AppResult<()>;
+    async fn restore(&self, event: RestoreItem) -> AppResult<()>;
 }
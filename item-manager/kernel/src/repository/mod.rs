@@ -1,5 +1,14 @@
+pub mod audit;
 pub mod auth;
+pub mod category;
 pub mod checkout;
 pub mod health;
+pub mod invitation;
 pub mod item;
+pub mod oidc;
+pub mod password_reset;
+pub mod personal_access_token;
+pub mod reservation;
+pub mod tag;
+pub mod two_factor;
 pub mod user;
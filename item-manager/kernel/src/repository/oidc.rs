@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{
+    id::UserId,
+    oidc::{
+        PendingOidcLogin,
+        event::{CreateOidcLoginRequest, SignInWithOidc},
+    },
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait OidcRepository: Send + Sync {
+    async fn create_login_request(&self, event: CreateOidcLoginRequest) -> AppResult<()>;
+    /// Removes and returns the pending sign-in for `state`, or `None` when it is unknown or
+    /// expired.
+    async fn take_login_request(&self, state: &str) -> AppResult<Option<PendingOidcLogin>>;
+    /// Resolves the identity to an account, linking or creating one as configured. Fails with
+    /// `UnauthenticatedError` when no account may be used, and with `ForbiddenOperation` when
+    /// the account is deactivated.
+    async fn sign_in(&self, event: SignInWithOidc) -> AppResult<UserId>;
+}
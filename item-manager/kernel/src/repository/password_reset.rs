@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{
+    id::UserId,
+    password_reset::{
+        PasswordResetToken,
+        event::{ConfirmPasswordReset, RequestPasswordReset},
+    },
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait PasswordResetRepository: Send + Sync {
+    /// Issues a token for the active account registered under the email address, replacing any
+    /// earlier one. Returns `None` when there is no such account or a token was issued moments
+    /// ago, so callers cannot tell whether the address is registered.
+    async fn create(&self, event: RequestPasswordReset) -> AppResult<Option<PasswordResetToken>>;
+    /// Sets the new password, consumes the token and signs the user out everywhere. Fails with
+    /// `UnprocessableEntity` for unknown, used or expired tokens.
+    async fn confirm(&self, event: ConfirmPasswordReset) -> AppResult<UserId>;
+}
@@ -0,0 +1,24 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{
+    id::UserId,
+    personal_access_token::{
+        CreatedPersonalAccessToken, PersonalAccessToken,
+        event::{CreatePersonalAccessToken, DeletePersonalAccessToken},
+    },
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait PersonalAccessTokenRepository: Send + Sync {
+    async fn create(
+        &self,
+        event: CreatePersonalAccessToken,
+    ) -> AppResult<CreatedPersonalAccessToken>;
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Vec<PersonalAccessToken>>;
+    async fn delete(&self, event: DeletePersonalAccessToken) -> AppResult<()>;
+    /// Resolves a presented secret and records its use. Returns `None` for unknown
+    /// or expired tokens and for tokens of deactivated users.
+    async fn authenticate(&self, secret: &str) -> AppResult<Option<PersonalAccessToken>>;
+}
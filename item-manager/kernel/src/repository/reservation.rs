@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{
+    id::{ItemId, ReservationId, UserId},
+    reservation::{
+        Reservation,
+        event::{CreateReservation, DeleteReservation},
+    },
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait ReservationRepository: Send + Sync {
+    async fn create(&self, event: CreateReservation) -> AppResult<ReservationId>;
+    async fn delete(&self, event: DeleteReservation) -> AppResult<()>;
+    async fn find_by_item_id(&self, item_id: ItemId) -> AppResult<Vec<Reservation>>;
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Reservation>>;
+}
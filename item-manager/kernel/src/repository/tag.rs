@@ -0,0 +1,20 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::tag::{
+    Tag,
+    event::{AttachTag, CreateTag, DeleteTag, DetachTag, UpdateTag},
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait TagRepository: Send + Sync {
+    async fn create(&self, event: CreateTag) -> AppResult<Tag>;
+    /// Lists every tag in alphabetical order.
+    async fn find_all(&self) -> AppResult<Vec<Tag>>;
+    async fn update(&self, event: UpdateTag) -> AppResult<Tag>;
+    async fn delete(&self, event: DeleteTag) -> AppResult<()>;
+    /// Adds the tag to the item; attaching it again has no effect.
+    async fn attach(&self, event: AttachTag) -> AppResult<()>;
+    async fn detach(&self, event: DetachTag) -> AppResult<()>;
+}
@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{
+    id::UserId,
+    two_factor::{
+        CompletedLoginChallenge, LoginChallenge, TotpSetup, TwoFactorStatus,
+        event::{
+            CompleteLoginChallenge, ConfirmTotp, CreateLoginChallenge, DisableTotp, SetUpTotp,
+        },
+    },
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait TwoFactorRepository: Send + Sync {
+    async fn find_status(&self, user_id: UserId) -> AppResult<TwoFactorStatus>;
+    /// Replaces any unconfirmed secret with a new one. Fails with `Conflict` once enabled.
+    async fn set_up(&self, event: SetUpTotp) -> AppResult<TotpSetup>;
+    /// Enables two-factor authentication and returns a fresh set of recovery codes. Fails with
+    /// `UnprocessableEntity` when the code does not match the pending secret.
+    async fn confirm(&self, event: ConfirmTotp) -> AppResult<Vec<String>>;
+    /// Fails with `ForbiddenOperation` when the user's role requires two-factor authentication.
+    async fn disable(&self, event: DisableTotp) -> AppResult<()>;
+    /// Starts the second login step for a user whose password was verified, or returns `None`
+    /// when they can be signed in right away.
+    async fn create_challenge(
+        &self,
+        event: CreateLoginChallenge,
+    ) -> AppResult<Option<LoginChallenge>>;
+    /// [`Self::set_up`] for the user of a challenge that requires setup.
+    async fn set_up_for_challenge(&self, token: &str) -> AppResult<TotpSetup>;
+    /// Consumes the challenge once the code matches. Fails with `UnauthenticatedError` for
+    /// wrong codes and unknown or expired challenges; a challenge only allows a few attempts.
+    async fn complete_challenge(
+        &self,
+        event: CompleteLoginChallenge,
+    ) -> AppResult<CompletedLoginChallenge>;
+}
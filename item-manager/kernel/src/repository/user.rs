@@ -1,10 +1,11 @@
 use crate::model::{
     id::UserId,
+    list::{PaginatedList, UserListOptions},
     user::{
         User,
         event::{
-            CreateUser, DeleteUser, UpdateUserEmail, UpdateUserName, UpdateUserPassword,
-            UpdateUserRole,
+            CreateUser, DeactivateUser, DeleteUser, ReactivateUser, UnlockUser, UpdateUserEmail,
+            UpdateUserName, UpdateUserPassword, UpdateUserRole,
         },
     },
 };
@@ -15,11 +16,20 @@ use shared::error::AppResult;
 #[async_trait]
 pub trait UserRepository: Send + Sync {
     async fn find_current_user(&self, current_user_id: UserId) -> AppResult<Option<User>>;
-    async fn find_all(&self) -> AppResult<Vec<User>>;
+    async fn find_all(&self, options: UserListOptions) -> AppResult<PaginatedList<User>>;
+    /// Fails with `UnprocessableEntity` when the invitation code is unknown, expired or used up.
     async fn create(&self, event: CreateUser) -> AppResult<User>;
     async fn update_password(&self, event: UpdateUserPassword) -> AppResult<()>;
     async fn update_role(&self, event: UpdateUserRole) -> AppResult<()>;
     async fn update_name(&self, event: UpdateUserName) -> AppResult<()>;
     async fn update_email(&self, event: UpdateUserEmail) -> AppResult<()>;
+    /// Fails with a conflict once the user has checkout history; use `deactivate` instead.
     async fn delete(&self, event: DeleteUser) -> AppResult<()>;
+    /// Signs the user out everywhere and drops their reservations. Fails while
+    /// they still have items checked out.
+    async fn deactivate(&self, event: DeactivateUser) -> AppResult<()>;
+    async fn reactivate(&self, event: ReactivateUser) -> AppResult<()>;
+    /// Lifts a login lockout on the user's email address. Fails with a conflict when no
+    /// failed attempts are recorded.
+    async fn unlock(&self, event: UnlockUser) -> AppResult<()>;
 }
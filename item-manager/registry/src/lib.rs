@@ -1,47 +1,140 @@
 use std::sync::Arc;
 
 use adapter::{
-    database::{ConnectionPool, model::auth::JwtSecret},
+    database::{
+        ConnectionPool,
+        model::{auth::JwtSecret, password::PasswordPolicy},
+    },
+    mailer::{file::FileMailer, smtp::SmtpMailer},
+    oidc::OpenIdConnectProvider,
     repository::{
-        auth::AuthRepositoryImpl, checkout::CheckoutRepositoryImpl,
-        health::HealthCheckRepositoryImpl, item::ItemRepositoryImpl, user::UserRepositoryImpl,
+        audit::AuditRepositoryImpl, auth::AuthRepositoryImpl,
+        category::CustomCategoryRepositoryImpl, checkout::CheckoutRepositoryImpl,
+        health::HealthCheckRepositoryImpl, invitation::InvitationRepositoryImpl,
+        item::ItemRepositoryImpl, oidc::OidcRepositoryImpl,
+        password_reset::PasswordResetRepositoryImpl,
+        personal_access_token::PersonalAccessTokenRepositoryImpl,
+        reservation::ReservationRepositoryImpl, tag::TagRepositoryImpl,
+        two_factor::TwoFactorRepositoryImpl, user::UserRepositoryImpl,
     },
 };
+use kernel::mailer::Mailer;
+use kernel::oidc::OidcProvider;
 use kernel::repository::{
-    auth::AuthRepository, checkout::CheckoutRepository, health::HealthCheckRepository,
-    item::ItemRepository, user::UserRepository,
+    audit::AuditRepository, auth::AuthRepository, category::CustomCategoryRepository,
+    checkout::CheckoutRepository, health::HealthCheckRepository, invitation::InvitationRepository,
+    item::ItemRepository, oidc::OidcRepository, password_reset::PasswordResetRepository,
+    personal_access_token::PersonalAccessTokenRepository, reservation::ReservationRepository,
+    tag::TagRepository, two_factor::TwoFactorRepository, user::UserRepository,
 };
-use shared::config::{AppConfig, WebConfig};
+use shared::config::{AppConfig, MailTransport, WebConfig};
 
 #[derive(Clone)]
 pub struct AppRegistryImpl {
     health_check_repository: Arc<dyn HealthCheckRepository>,
     item_repository: Arc<dyn ItemRepository>,
+    tag_repository: Arc<dyn TagRepository>,
+    custom_category_repository: Arc<dyn CustomCategoryRepository>,
     auth_repository: Arc<dyn AuthRepository>,
     user_repository: Arc<dyn UserRepository>,
     checkout_repository: Arc<dyn CheckoutRepository>,
+    reservation_repository: Arc<dyn ReservationRepository>,
+    audit_repository: Arc<dyn AuditRepository>,
+    personal_access_token_repository: Arc<dyn PersonalAccessTokenRepository>,
+    password_reset_repository: Arc<dyn PasswordResetRepository>,
+    invitation_repository: Arc<dyn InvitationRepository>,
+    two_factor_repository: Arc<dyn TwoFactorRepository>,
+    oidc_repository: Arc<dyn OidcRepository>,
+    mailer: Arc<dyn Mailer>,
+    oidc_provider: Option<Arc<dyn OidcProvider>>,
     web_config: WebConfig,
 }
 
 impl AppRegistryImpl {
-    pub fn new(pool: ConnectionPool, app_config: AppConfig) -> Self {
+    pub fn new(pool: ConnectionPool, app_config: AppConfig) -> anyhow::Result<Self> {
+        let password_policy = PasswordPolicy::new(app_config.password)?;
         let health_check_repository = Arc::new(HealthCheckRepositoryImpl::new(pool.clone()));
         let item_repository = Arc::new(ItemRepositoryImpl::new(pool.clone()));
+        let tag_repository = Arc::new(TagRepositoryImpl::new(pool.clone()));
+        let custom_category_repository = Arc::new(CustomCategoryRepositoryImpl::new(pool.clone()));
         let auth_repository = Arc::new(AuthRepositoryImpl::new(
             pool.clone(),
             JwtSecret::new(app_config.auth.secret),
             app_config.auth.ttl,
+            app_config.auth.refresh_ttl,
+            app_config.login_throttle,
+            password_policy.clone(),
+        ));
+        let user_repository = Arc::new(UserRepositoryImpl::new(
+            pool.clone(),
+            password_policy.clone(),
+        ));
+        let checkout_repository = Arc::new(CheckoutRepositoryImpl::new(
+            pool.clone(),
+            app_config.reservation.hold_ttl,
+            app_config.loan,
+        ));
+        let reservation_repository = Arc::new(ReservationRepositoryImpl::new(
+            pool.clone(),
+            app_config.reservation.hold_ttl,
+        ));
+        let audit_repository = Arc::new(AuditRepositoryImpl::new(pool.clone()));
+        let personal_access_token_repository =
+            Arc::new(PersonalAccessTokenRepositoryImpl::new(pool.clone()));
+        let password_reset_repository = Arc::new(PasswordResetRepositoryImpl::new(
+            pool.clone(),
+            app_config.auth.password_reset_ttl,
+            password_policy.clone(),
         ));
-        let user_repository = Arc::new(UserRepositoryImpl::new(pool.clone()));
-        let checkout_repository = Arc::new(CheckoutRepositoryImpl::new(pool.clone()));
-        Self {
+        let invitation_repository = Arc::new(InvitationRepositoryImpl::new(pool.clone()));
+        let two_factor_repository = Arc::new(TwoFactorRepositoryImpl::new(
+            pool.clone(),
+            app_config.auth.require_admin_two_factor,
+        ));
+        let oidc_repository = Arc::new(OidcRepositoryImpl::new(
+            pool.clone(),
+            app_config
+                .oidc
+                .as_ref()
+                .is_some_and(|oidc| oidc.allow_signup),
+            app_config
+                .oidc
+                .as_ref()
+                .is_some_and(|oidc| oidc.link_by_email),
+            password_policy,
+        ));
+        let mailer: Arc<dyn Mailer> = match &app_config.mail.transport {
+            MailTransport::Smtp(smtp) => Arc::new(SmtpMailer::new(&app_config.mail.from, smtp)?),
+            MailTransport::File { dir } => Arc::new(FileMailer::new(
+                app_config.mail.from.clone(),
+                dir.as_ref().map(Into::into),
+            )),
+        };
+        let oidc_provider = match &app_config.oidc {
+            Some(oidc) => {
+                Some(Arc::new(OpenIdConnectProvider::new(oidc)?) as Arc<dyn OidcProvider>)
+            }
+            None => None,
+        };
+        Ok(Self {
             health_check_repository,
             item_repository,
+            tag_repository,
+            custom_category_repository,
             auth_repository,
             user_repository,
             checkout_repository,
+            reservation_repository,
+            audit_repository,
+            personal_access_token_repository,
+            password_reset_repository,
+            invitation_repository,
+            two_factor_repository,
+            oidc_repository,
+            mailer,
+            oidc_provider,
             web_config: app_config.web,
-        }
+        })
     }
 }
 
@@ -49,9 +142,21 @@ impl AppRegistryImpl {
 pub trait AppRegistryExt {
     fn health_check_repository(&self) -> Arc<dyn HealthCheckRepository>;
     fn item_repository(&self) -> Arc<dyn ItemRepository>;
+    fn tag_repository(&self) -> Arc<dyn TagRepository>;
+    fn custom_category_repository(&self) -> Arc<dyn CustomCategoryRepository>;
     fn auth_repository(&self) -> Arc<dyn AuthRepository>;
     fn user_repository(&self) -> Arc<dyn UserRepository>;
     fn checkout_repository(&self) -> Arc<dyn CheckoutRepository>;
+    fn reservation_repository(&self) -> Arc<dyn ReservationRepository>;
+    fn audit_repository(&self) -> Arc<dyn AuditRepository>;
+    fn personal_access_token_repository(&self) -> Arc<dyn PersonalAccessTokenRepository>;
+    fn password_reset_repository(&self) -> Arc<dyn PasswordResetRepository>;
+    fn invitation_repository(&self) -> Arc<dyn InvitationRepository>;
+    fn two_factor_repository(&self) -> Arc<dyn TwoFactorRepository>;
+    fn oidc_repository(&self) -> Arc<dyn OidcRepository>;
+    fn mailer(&self) -> Arc<dyn Mailer>;
+    /// `None` when single sign-on is not configured.
+    fn oidc_provider(&self) -> Option<Arc<dyn OidcProvider>>;
     fn web_config(&self) -> WebConfig;
 }
 
@@ -64,6 +169,14 @@ impl AppRegistryExt for AppRegistryImpl {
         self.item_repository.clone()
     }
 
+    fn tag_repository(&self) -> Arc<dyn TagRepository> {
+        self.tag_repository.clone()
+    }
+
+    fn custom_category_repository(&self) -> Arc<dyn CustomCategoryRepository> {
+        self.custom_category_repository.clone()
+    }
+
     fn auth_repository(&self) -> Arc<dyn AuthRepository> {
         self.auth_repository.clone()
     }
@@ -76,6 +189,42 @@ impl AppRegistryExt for AppRegistryImpl {
         self.checkout_repository.clone()
     }
 
+    fn reservation_repository(&self) -> Arc<dyn ReservationRepository> {
+        self.reservation_repository.clone()
+    }
+
+    fn audit_repository(&self) -> Arc<dyn AuditRepository> {
+        self.audit_repository.clone()
+    }
+
+    fn personal_access_token_repository(&self) -> Arc<dyn PersonalAccessTokenRepository> {
+        self.personal_access_token_repository.clone()
+    }
+
+    fn password_reset_repository(&self) -> Arc<dyn PasswordResetRepository> {
+        self.password_reset_repository.clone()
+    }
+
+    fn invitation_repository(&self) -> Arc<dyn InvitationRepository> {
+        self.invitation_repository.clone()
+    }
+
+    fn two_factor_repository(&self) -> Arc<dyn TwoFactorRepository> {
+        self.two_factor_repository.clone()
+    }
+
+    fn oidc_repository(&self) -> Arc<dyn OidcRepository> {
+        self.oidc_repository.clone()
+    }
+
+    fn mailer(&self) -> Arc<dyn Mailer> {
+        self.mailer.clone()
+    }
+
+    fn oidc_provider(&self) -> Option<Arc<dyn OidcProvider>> {
+        self.oidc_provider.clone()
+    }
+
     fn web_config(&self) -> WebConfig {
         self.web_config.clone()
     }
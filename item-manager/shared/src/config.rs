@@ -1,9 +1,36 @@
 use anyhow::Context;
 
+const DEFAULT_REFRESH_TOKEN_TTL: u64 = 60 * 60 * 24 * 30;
+const DEFAULT_PASSWORD_RESET_TOKEN_TTL: u64 = 60 * 60;
+const DEFAULT_SMTP_PORT: u16 = 587;
+const DEFAULT_RESERVATION_HOLD_TTL: u64 = 60 * 60 * 48;
+const DEFAULT_GENERAL_LOAN_DAYS: u64 = 14;
+const DEFAULT_BOOK_LOAN_DAYS: u64 = 14;
+const DEFAULT_LAPTOP_LOAN_DAYS: u64 = 7;
+const DEFAULT_MAX_RENEWALS: u32 = 2;
+const DEFAULT_LOGIN_MAX_EMAIL_FAILURES: u32 = 5;
+const DEFAULT_LOGIN_MAX_IP_FAILURES: u32 = 20;
+const DEFAULT_LOGIN_BASE_LOCKOUT: u64 = 30;
+const DEFAULT_LOGIN_MAX_LOCKOUT: u64 = 60 * 60;
+const DEFAULT_LOGIN_FAILURE_WINDOW: u64 = 60 * 60 * 24;
+const DEFAULT_OIDC_SCOPES: &str = "email profile";
+const DEFAULT_PASSWORD_MIN_LENGTH: usize = 8;
+const DEFAULT_BCRYPT_COST: u32 = 12;
+// The minimum recommended by OWASP for Argon2id.
+const DEFAULT_ARGON2_MEMORY_KIB: u32 = 19 * 1024;
+const DEFAULT_ARGON2_ITERATIONS: u32 = 2;
+const DEFAULT_ARGON2_PARALLELISM: u32 = 1;
+
 pub struct AppConfig {
     pub database: DatabaseConfig,
     pub auth: AuthConfig,
     pub web: WebConfig,
+    pub reservation: ReservationConfig,
+    pub loan: LoanConfig,
+    pub login_throttle: LoginThrottleConfig,
+    pub mail: MailConfig,
+    pub oidc: Option<OidcConfig>,
+    pub password: PasswordConfig,
 }
 
 impl AppConfig {
@@ -19,21 +46,111 @@ impl AppConfig {
                 .context("AUTH_TOKEN_TTL")?
                 .parse()?,
             secret: std::env::var("JWT_SECRET").context("JWT_SECRET")?,
+            refresh_ttl: env_or("AUTH_REFRESH_TOKEN_TTL", DEFAULT_REFRESH_TOKEN_TTL)?,
+            password_reset_ttl: env_or(
+                "PASSWORD_RESET_TOKEN_TTL",
+                DEFAULT_PASSWORD_RESET_TOKEN_TTL,
+            )?,
+            require_admin_two_factor: env_or("REQUIRE_ADMIN_TWO_FACTOR", false)?,
         };
         let web = WebConfig {
             frontend_origin: std::env::var("FRONTEND_ORIGIN").context("FRONTEND_ORIGIN")?,
             access_token_cookie_name: std::env::var("ACCESS_TOKEN_COOKIE_NAME")
                 .unwrap_or_else(|_| "access_token".to_string()),
             access_token_cookie_max_age_seconds: auth.ttl,
+            refresh_token_cookie_name: std::env::var("REFRESH_TOKEN_COOKIE_NAME")
+                .unwrap_or_else(|_| "refresh_token".to_string()),
+            refresh_token_cookie_max_age_seconds: auth.refresh_ttl,
+            trust_forwarded_for: env_or("TRUST_X_FORWARDED_FOR", false)?,
+        };
+        let reservation = ReservationConfig {
+            hold_ttl: env_or("RESERVATION_HOLD_TTL", DEFAULT_RESERVATION_HOLD_TTL)?,
+        };
+        let loan = LoanConfig {
+            general_days: env_or("LOAN_PERIOD_DAYS_GENERAL", DEFAULT_GENERAL_LOAN_DAYS)?,
+            book_days: env_or("LOAN_PERIOD_DAYS_BOOK", DEFAULT_BOOK_LOAN_DAYS)?,
+            laptop_days: env_or("LOAN_PERIOD_DAYS_LAPTOP", DEFAULT_LAPTOP_LOAN_DAYS)?,
+            max_renewals: env_or("LOAN_MAX_RENEWALS", DEFAULT_MAX_RENEWALS)?,
+        };
+        let login_throttle = LoginThrottleConfig {
+            max_email_failures: env_or(
+                "LOGIN_MAX_FAILURES_PER_EMAIL",
+                DEFAULT_LOGIN_MAX_EMAIL_FAILURES,
+            )?,
+            max_ip_failures: env_or("LOGIN_MAX_FAILURES_PER_IP", DEFAULT_LOGIN_MAX_IP_FAILURES)?,
+            base_lockout: env_or("LOGIN_BASE_LOCKOUT", DEFAULT_LOGIN_BASE_LOCKOUT)?,
+            max_lockout: env_or("LOGIN_MAX_LOCKOUT", DEFAULT_LOGIN_MAX_LOCKOUT)?,
+            failure_window: env_or("LOGIN_FAILURE_WINDOW", DEFAULT_LOGIN_FAILURE_WINDOW)?,
+        };
+        let transport = match std::env::var("MAIL_TRANSPORT").as_deref() {
+            Ok("smtp") => MailTransport::Smtp(SmtpConfig {
+                host: std::env::var("SMTP_HOST").context("SMTP_HOST")?,
+                port: env_or("SMTP_PORT", DEFAULT_SMTP_PORT)?,
+                username: std::env::var("SMTP_USERNAME").ok(),
+                password: std::env::var("SMTP_PASSWORD").ok(),
+                security: env_or("SMTP_SECURITY", SmtpSecurity::StartTls)?,
+            }),
+            Ok("file") | Err(_) => MailTransport::File {
+                dir: std::env::var("MAIL_DIR").ok(),
+            },
+            Ok(other) => anyhow::bail!("MAIL_TRANSPORT: unknown transport `{other}`"),
+        };
+        let mail = MailConfig {
+            from: std::env::var("MAIL_FROM")
+                .unwrap_or_else(|_| "procon-manager <noreply@localhost>".to_string()),
+            transport,
+        };
+        let oidc = match std::env::var("OIDC_ISSUER_URL") {
+            Ok(issuer_url) => Some(OidcConfig {
+                issuer_url,
+                client_id: std::env::var("OIDC_CLIENT_ID").context("OIDC_CLIENT_ID")?,
+                client_secret: std::env::var("OIDC_CLIENT_SECRET").ok(),
+                redirect_url: std::env::var("OIDC_REDIRECT_URL").context("OIDC_REDIRECT_URL")?,
+                scopes: std::env::var("OIDC_SCOPES")
+                    .unwrap_or_else(|_| DEFAULT_OIDC_SCOPES.to_string())
+                    .split_whitespace()
+                    .map(Into::into)
+                    .collect(),
+                allow_signup: env_or("OIDC_ALLOW_SIGNUP", false)?,
+                link_by_email: env_or("OIDC_LINK_BY_EMAIL", true)?,
+            }),
+            Err(_) => None,
+        };
+        let password = PasswordConfig {
+            min_length: env_or("PASSWORD_MIN_LENGTH", DEFAULT_PASSWORD_MIN_LENGTH)?,
+            denylist_file: std::env::var("PASSWORD_DENYLIST_FILE").ok(),
+            history: env_or("PASSWORD_HISTORY", 0)?,
+            algorithm: env_or("PASSWORD_HASH_ALGORITHM", PasswordHashAlgorithm::Bcrypt)?,
+            bcrypt_cost: env_or("PASSWORD_BCRYPT_COST", DEFAULT_BCRYPT_COST)?,
+            argon2_memory_kib: env_or("PASSWORD_ARGON2_MEMORY_KIB", DEFAULT_ARGON2_MEMORY_KIB)?,
+            argon2_iterations: env_or("PASSWORD_ARGON2_ITERATIONS", DEFAULT_ARGON2_ITERATIONS)?,
+            argon2_parallelism: env_or("PASSWORD_ARGON2_PARALLELISM", DEFAULT_ARGON2_PARALLELISM)?,
         };
         Ok(Self {
             database,
             auth,
             web,
+            reservation,
+            loan,
+            login_throttle,
+            mail,
+            oidc,
+            password,
         })
     }
 }
 
+fn env_or<T>(key: &str, default: T) -> anyhow::Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    match std::env::var(key) {
+        Ok(value) => value.parse().with_context(|| key.to_string()),
+        Err(_) => Ok(default),
+    }
+}
+
 pub struct DatabaseConfig {
     pub host: String,
     pub username: String,
@@ -44,6 +161,11 @@ pub struct DatabaseConfig {
 pub struct AuthConfig {
     pub ttl: u64,
     pub secret: String,
+    pub refresh_ttl: u64,
+    /// How long, in seconds, a password reset link stays valid.
+    pub password_reset_ttl: u64,
+    /// Make administrators set up TOTP before their next login can complete.
+    pub require_admin_two_factor: bool,
 }
 
 #[derive(Clone)]
@@ -51,4 +173,156 @@ pub struct WebConfig {
     pub frontend_origin: String,
     pub access_token_cookie_name: String,
     pub access_token_cookie_max_age_seconds: u64,
+    pub refresh_token_cookie_name: String,
+    pub refresh_token_cookie_max_age_seconds: u64,
+    /// Take the client address for login throttling from `X-Forwarded-For`. Only enable this
+    /// behind a reverse proxy that overwrites the header.
+    pub trust_forwarded_for: bool,
+}
+
+#[derive(Clone)]
+pub struct ReservationConfig {
+    /// How long, in seconds, a returned item is held for the head of its reservation queue.
+    pub hold_ttl: u64,
+}
+
+/// Default loan periods, in days, applied to new checkouts and renewals per item category.
+#[derive(Clone)]
+pub struct LoanConfig {
+    /// Also applies to items of custom categories.
+    pub general_days: u64,
+    pub book_days: u64,
+    pub laptop_days: u64,
+    pub max_renewals: u32,
+}
+
+impl Default for LoanConfig {
+    fn default() -> Self {
+        Self {
+            general_days: DEFAULT_GENERAL_LOAN_DAYS,
+            book_days: DEFAULT_BOOK_LOAN_DAYS,
+            laptop_days: DEFAULT_LAPTOP_LOAN_DAYS,
+            max_renewals: DEFAULT_MAX_RENEWALS,
+        }
+    }
+}
+
+/// Failed login tracking. Once an email address or client IP reaches its failure limit, further
+/// attempts are refused for `base_lockout` seconds, doubling with every additional failure up to
+/// `max_lockout`. Counters reset after `failure_window` seconds without a failure.
+#[derive(Clone)]
+pub struct LoginThrottleConfig {
+    pub max_email_failures: u32,
+    pub max_ip_failures: u32,
+    pub base_lockout: u64,
+    pub max_lockout: u64,
+    pub failure_window: u64,
+}
+
+impl Default for LoginThrottleConfig {
+    fn default() -> Self {
+        Self {
+            max_email_failures: DEFAULT_LOGIN_MAX_EMAIL_FAILURES,
+            max_ip_failures: DEFAULT_LOGIN_MAX_IP_FAILURES,
+            base_lockout: DEFAULT_LOGIN_BASE_LOCKOUT,
+            max_lockout: DEFAULT_LOGIN_MAX_LOCKOUT,
+            failure_window: DEFAULT_LOGIN_FAILURE_WINDOW,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MailConfig {
+    /// Sender mailbox, e.g. `procon-manager <noreply@example.com>`.
+    pub from: String,
+    pub transport: MailTransport,
+}
+
+#[derive(Clone)]
+pub enum MailTransport {
+    Smtp(SmtpConfig),
+    /// Writes each mail to `dir`, or only logs it when no directory is set. Meant for
+    /// development, where mails would otherwise go nowhere.
+    File {
+        dir: Option<String>,
+    },
+}
+
+#[derive(Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub security: SmtpSecurity,
+}
+
+/// Rules for new passwords and how they are hashed. Stored hashes that do not match the
+/// configured algorithm or cost are replaced on the next successful login.
+#[derive(Clone)]
+pub struct PasswordConfig {
+    /// Minimum number of characters.
+    pub min_length: usize,
+    /// File with one refused password per line, such as a list of common or breached ones.
+    pub denylist_file: Option<String>,
+    /// How many of the most recent passwords, including the current one, cannot be chosen
+    /// again. `0` allows any.
+    pub history: u32,
+    pub algorithm: PasswordHashAlgorithm,
+    pub bcrypt_cost: u32,
+    pub argon2_memory_kib: u32,
+    pub argon2_iterations: u32,
+    pub argon2_parallelism: u32,
+}
+
+impl Default for PasswordConfig {
+    fn default() -> Self {
+        Self {
+            min_length: DEFAULT_PASSWORD_MIN_LENGTH,
+            denylist_file: None,
+            history: 0,
+            algorithm: PasswordHashAlgorithm::Bcrypt,
+            bcrypt_cost: DEFAULT_BCRYPT_COST,
+            argon2_memory_kib: DEFAULT_ARGON2_MEMORY_KIB,
+            argon2_iterations: DEFAULT_ARGON2_ITERATIONS,
+            argon2_parallelism: DEFAULT_ARGON2_PARALLELISM,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum PasswordHashAlgorithm {
+    Bcrypt,
+    Argon2id,
+}
+
+/// OpenID Connect single sign-on, enabled by setting `OIDC_ISSUER_URL`.
+#[derive(Clone)]
+pub struct OidcConfig {
+    /// The provider's endpoints are discovered from `<issuer_url>/.well-known/openid-configuration`.
+    pub issuer_url: String,
+    pub client_id: String,
+    /// Left unset for public clients, which rely on PKCE alone.
+    pub client_secret: Option<String>,
+    /// Registered with the provider; points at `/auth/oidc/callback` of this server.
+    pub redirect_url: String,
+    /// Requested in addition to `openid`.
+    pub scopes: Vec<String>,
+    /// Create an account with the `user` role for identities that match none. Otherwise only
+    /// existing members can sign in.
+    pub allow_signup: bool,
+    /// Link an identity to the account with the same email address on its first sign-in, if the
+    /// provider has verified the address.
+    pub link_by_email: bool,
+}
+
+#[derive(Clone, Copy, Debug, strum::EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Plain connection, only for relays on the same host.
+    None,
+    StartTls,
+    /// Implicit TLS, usually on port 465.
+    Tls,
 }
@@ -26,6 +26,8 @@ pub enum AppError {
     NoRowsAffectedError(String),
     #[error("{0}")]
     BcryptError(#[from] bcrypt::BcryptError),
+    #[error("Password hashing failed: {0}")]
+    PasswordHashError(String),
     #[error("{0}")]
     ConvertToUuidError(#[from] uuid::Error),
     #[error("Login failed.")]
@@ -36,10 +38,20 @@ pub enum AppError {
     ForbiddenOperation(String),
     #[error("{0}")]
     ConversionEntityError(String),
+    #[error("Failed to send mail: {0}")]
+    MailError(String),
+    #[error("Identity provider request failed: {0}")]
+    IdentityProviderError(String),
+    #[error("Too many failed login attempts. Try again in {retry_after} seconds.")]
+    TooManyRequests { retry_after: u64 },
 }
 
 impl axum::response::IntoResponse for AppError {
     fn into_response(self) -> Response {
+        let retry_after = match &self {
+            AppError::TooManyRequests { retry_after } => Some(*retry_after),
+            _ => None,
+        };
         let (status_code, message) = match self {
             AppError::UnprocessableEntity(message) => (StatusCode::UNPROCESSABLE_ENTITY, message),
             AppError::Conflict(message) => (StatusCode::CONFLICT, message),
@@ -52,11 +64,15 @@ impl axum::response::IntoResponse for AppError {
             ),
             AppError::ForbiddenOperation(message) => (StatusCode::FORBIDDEN, message),
             AppError::UnauthenticatedError => (StatusCode::UNAUTHORIZED, "Login failed.".into()),
+            e @ AppError::TooManyRequests { .. } => (StatusCode::TOO_MANY_REQUESTS, e.to_string()),
             e @ (AppError::TransactionError(_)
             | AppError::SpecificOperationError(_)
             | AppError::NoRowsAffectedError(_)
             | AppError::BcryptError(_)
-            | AppError::ConversionEntityError(_)) => {
+            | AppError::PasswordHashError(_)
+            | AppError::ConversionEntityError(_)
+            | AppError::MailError(_)
+            | AppError::IdentityProviderError(_)) => {
                 tracing::error!(
                     error.cause_chain = ?e,
                     error.message = %e,
@@ -75,6 +91,11 @@ impl axum::response::IntoResponse for AppError {
                 HeaderValue::from_static("Bearer"),
             );
         }
+        if let Some(retry_after) = retry_after {
+            response
+                .headers_mut()
+                .insert(axum::http::header::RETRY_AFTER, retry_after.into());
+        }
         response
     }
 }
@@ -60,7 +60,7 @@ async fn bootstrap() -> anyhow::Result<()> {
     let app_config = shared::config::AppConfig::new()?;
     let cors = cors(&app_config.web.frontend_origin)?;
     let pool = adapter::database::connect_database_with(&app_config.database);
-    let registry = Arc::new(registry::AppRegistryImpl::new(pool, app_config));
+    let registry = Arc::new(registry::AppRegistryImpl::new(pool, app_config)?);
 
     let app = axum::Router::new()
         .merge(v1::routes())
@@ -87,14 +87,17 @@ async fn bootstrap() -> anyhow::Result<()> {
 
     tracing::info!("Listening on {addr}");
 
-    axum::serve(listener, app)
-        .await
-        .context("Failed to start server")
-        .inspect_err(|e| {
-            tracing::error!(
-                error.cause_chain = ?e,
-                error.message = %e,
-                "Unexpected error"
-            );
-        })
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .await
+    .context("Failed to start server")
+    .inspect_err(|e| {
+        tracing::error!(
+            error.cause_chain = ?e,
+            error.message = %e,
+            "Unexpected error"
+        );
+    })
 }